@@ -80,6 +80,22 @@ fn bench_provider_count(c: &mut Criterion) {
     group.finish();
 }
 
+fn bench_catalog_access(c: &mut Criterion) {
+    let mut group = c.benchmark_group("catalog_access");
+
+    let registry = ProviderRegistry::new().unwrap();
+
+    group.bench_function("get_all_copy", |b| {
+        b.iter(|| black_box(registry.get_all().unwrap()))
+    });
+
+    group.bench_function("providers_snapshot", |b| {
+        b.iter(|| black_box(registry.providers()))
+    });
+
+    group.finish();
+}
+
 criterion_group!(
     benches,
     bench_load_providers,
@@ -87,6 +103,7 @@ criterion_group!(
     bench_model_search,
     bench_serialize_providers,
     bench_serialize_single_provider,
-    bench_provider_count
+    bench_provider_count,
+    bench_catalog_access
 );
 criterion_main!(benches);
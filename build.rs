@@ -0,0 +1,151 @@
+//! Build script embedding build metadata for the `/version` endpoint
+//!
+//! Sets `CRABRACE_GIT_SHA` and `CRABRACE_BUILD_TIMESTAMP` (Unix seconds).
+//! Both can be overridden with the `GIT_SHA` and `SOURCE_DATE_EPOCH`
+//! environment variables, e.g. for Docker builds without a `.git` directory.
+//!
+//! It also fails the build if an embedded provider config does not pass
+//! strict validation or is not registered in `src/providers/embedded.rs`,
+//! so a typo can never ship a binary that silently drops a provider.
+
+use std::path::Path;
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// The crate's own schema and checks, so build and runtime agree
+#[allow(dead_code)]
+#[path = "src/models/provider.rs"]
+mod provider;
+#[allow(dead_code)]
+#[path = "src/providers/validate.rs"]
+mod validate;
+
+use provider::{Model, ModelKind, Provider, RateLimits};
+
+const CONFIGS_DIR: &str = "src/providers/configs";
+const EMBEDDED: &str = "src/providers/embedded.rs";
+
+fn main() {
+    validate_provider_configs();
+
+    println!("cargo:rerun-if-env-changed=GIT_SHA");
+    println!("cargo:rerun-if-env-changed=SOURCE_DATE_EPOCH");
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/refs");
+
+    let git_sha = std::env::var("GIT_SHA")
+        .ok()
+        .filter(|sha| !sha.is_empty())
+        .or_else(|| {
+            Command::new("git")
+                .args(["rev-parse", "--short=12", "HEAD"])
+                .output()
+                .ok()
+                .filter(|output| output.status.success())
+                .and_then(|output| String::from_utf8(output.stdout).ok())
+                .map(|sha| sha.trim().to_string())
+        })
+        .unwrap_or_else(|| "unknown".to_string());
+
+    let build_timestamp = std::env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|epoch| epoch.parse::<u64>().ok())
+        .unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default()
+        });
+
+    println!("cargo:rustc-env=CRABRACE_GIT_SHA={}", git_sha);
+    println!(
+        "cargo:rustc-env=CRABRACE_BUILD_TIMESTAMP={}",
+        build_timestamp
+    );
+}
+
+/// Strictly validate every `configs/*.json`, exiting with a report of all problems
+fn validate_provider_configs() {
+    println!("cargo:rerun-if-changed={}", CONFIGS_DIR);
+    println!("cargo:rerun-if-changed={}", EMBEDDED);
+    println!("cargo:rerun-if-changed=src/models/provider.rs");
+    println!("cargo:rerun-if-changed=src/providers/validate.rs");
+
+    let embedded = std::fs::read_to_string(EMBEDDED).expect("read embedded provider list");
+    let mut files: Vec<_> = std::fs::read_dir(CONFIGS_DIR)
+        .expect("read provider configs directory")
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    files.sort();
+
+    let mut errors = Vec::new();
+    for path in &files {
+        let file = path.file_name().unwrap_or_default().to_string_lossy();
+        println!("cargo:rerun-if-changed={}", path.display());
+
+        if !embedded.contains(&format!("include_str!(\"configs/{}\")", file)) {
+            errors.push(format!(
+                "{}: not embedded (add it to EMBEDDED_CONFIGS in {})",
+                path.display(),
+                EMBEDDED
+            ));
+        }
+
+        let json = match std::fs::read_to_string(path) {
+            Ok(json) => json,
+            Err(e) => {
+                errors.push(format!("{}: {}", path.display(), e));
+                continue;
+            }
+        };
+        for problem in validate::check_config(&json) {
+            errors.push(format!(
+                "{}: {}",
+                location(path, &json, &problem.path),
+                problem
+            ));
+        }
+    }
+
+    if !errors.is_empty() {
+        eprintln!("error: invalid embedded provider configuration");
+        for error in &errors {
+            eprintln!("  {}", error);
+        }
+        std::process::exit(1);
+    }
+}
+
+/// `file:line` of the last key in a JSON path (best effort, falls back to line 1)
+fn location(path: &Path, json: &str, json_path: &str) -> String {
+    let key = json_path
+        .rsplit('.')
+        .next()
+        .map(|key| key.split('[').next().unwrap_or(key))
+        .filter(|key| !key.is_empty());
+
+    // Models are the only repeated objects, so start from the model's own `"id"`
+    let mut start = 0;
+    if let Some(index) = json_path
+        .strip_prefix("models[")
+        .and_then(|rest| rest.split(']').next())
+        .and_then(|index| index.parse::<usize>().ok())
+    {
+        let models = json.find("\"models\"").unwrap_or(0);
+        start = json[models..]
+            .match_indices("\"id\"")
+            .nth(index)
+            .map(|(offset, _)| models + offset)
+            .unwrap_or(models);
+        // Back up to the model's opening brace so keys before `id` are found too
+        start = json[..start].rfind('{').unwrap_or(start);
+    }
+
+    let offset = key
+        .and_then(|key| json[start..].find(&format!("\"{}\"", key)))
+        .map(|offset| start + offset)
+        .unwrap_or(start);
+    let line = json[..offset].matches('\n').count() + 1;
+    format!("{}:{}", path.display(), line)
+}
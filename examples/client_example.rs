@@ -1,5 +1,5 @@
 use anyhow::Result;
-use crabrace::CrabraceClient;
+use crabrace::{CostUsage, CrabraceClient};
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -53,13 +53,21 @@ async fn main() -> Result<()> {
             if model.supports_attachments {
                 capabilities.push("vision/attachments");
             }
+            if model.supports_tools {
+                capabilities.push("tools");
+            }
+            if model.supports_streaming {
+                capabilities.push("streaming");
+            }
 
             if !capabilities.is_empty() {
                 println!("     - Capabilities: {}", capabilities.join(", "));
             }
 
             // Calculate example cost (without caching)
-            let example_cost = model.calculate_cost(100_000, 50_000, false);
+            let example_cost = model
+                .calculate_cost(&CostUsage::tokens(100_000, 50_000))
+                .total();
             println!(
                 "     - Example cost (100k in, 50k out): ${:.4}",
                 example_cost
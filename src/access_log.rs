@@ -0,0 +1,221 @@
+//! Structured access logging
+//!
+//! Emits one JSON line per request (method, path, status, latency, bytes,
+//! client IP, request ID) to stdout or a file, independently of the
+//! application's tracing output.
+
+use anyhow::{Context, Result};
+use axum::body::HttpBody;
+use axum::extract::{ConnectInfo, Request, State};
+use axum::http::header;
+use axum::middleware::{self, Next};
+use axum::response::Response;
+use axum::Router;
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::fs::OpenOptions;
+use std::io::{LineWriter, Write};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Instant;
+
+use crate::api::request_id::X_REQUEST_ID;
+use crate::config::AccessLogConfig;
+
+/// One access log record
+#[derive(Debug, Clone, Serialize)]
+pub struct AccessLogEntry {
+    pub timestamp: DateTime<Utc>,
+    pub method: String,
+    /// Request path including the query string
+    pub path: String,
+    pub status: u16,
+    pub latency_ms: f64,
+    /// Response body size, when known up front
+    pub bytes: Option<u64>,
+    pub client_ip: Option<String>,
+    pub request_id: Option<String>,
+}
+
+/// Writes access log entries as JSON lines
+#[derive(Clone)]
+pub struct AccessLogger {
+    writer: Arc<Mutex<Box<dyn Write + Send>>>,
+}
+
+impl AccessLogger {
+    /// Log to an arbitrary writer
+    pub fn new(writer: impl Write + Send + 'static) -> Self {
+        Self {
+            writer: Arc::new(Mutex::new(Box::new(writer))),
+        }
+    }
+
+    /// Log to standard output
+    pub fn stdout() -> Self {
+        Self::new(std::io::stdout())
+    }
+
+    /// Append to a file, creating it if needed
+    pub fn file(path: &str) -> Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open access log file: {}", path))?;
+        Ok(Self::new(LineWriter::new(file)))
+    }
+
+    /// Build a logger from configuration (`None` when disabled)
+    pub fn from_config(config: &AccessLogConfig) -> Result<Option<Self>> {
+        if !config.enabled {
+            return Ok(None);
+        }
+
+        match &config.file {
+            Some(path) => Self::file(path).map(Some),
+            None => Ok(Some(Self::stdout())),
+        }
+    }
+
+    /// Write one entry
+    pub fn log(&self, entry: &AccessLogEntry) {
+        let mut writer = self.writer.lock();
+        let result = serde_json::to_writer(&mut *writer, entry)
+            .map_err(std::io::Error::from)
+            .and_then(|_| writer.write_all(b"\n"));
+        if let Err(e) = result {
+            tracing::warn!("Failed to write access log entry: {}", e);
+        }
+    }
+
+    /// Wrap a router so every request is logged
+    pub fn layer<S>(self, router: Router<S>) -> Router<S>
+    where
+        S: Clone + Send + Sync + 'static,
+    {
+        router.layer(middleware::from_fn_with_state(self, access_log))
+    }
+}
+
+/// Middleware recording one entry per request
+async fn access_log(State(logger): State<AccessLogger>, request: Request, next: Next) -> Response {
+    let start = Instant::now();
+    let method = request.method().to_string();
+    let path = request
+        .uri()
+        .path_and_query()
+        .map(|pq| pq.to_string())
+        .unwrap_or_else(|| request.uri().path().to_string());
+    let client_ip = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip().to_string());
+
+    let response = next.run(request).await;
+
+    let header_str = |name| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+    };
+    logger.log(&AccessLogEntry {
+        timestamp: Utc::now(),
+        method,
+        path,
+        status: response.status().as_u16(),
+        latency_ms: start.elapsed().as_secs_f64() * 1000.0,
+        bytes: response
+            .body()
+            .size_hint()
+            .exact()
+            .or_else(|| header_str(header::CONTENT_LENGTH).and_then(|len| len.parse().ok())),
+        client_ip,
+        request_id: header_str(X_REQUEST_ID).map(str::to_string),
+    });
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use axum::routing::get;
+    use tower::ServiceExt;
+
+    /// Writer collecting output in memory
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_access_log_entry() {
+        let buffer = Buffer::default();
+        let app = AccessLogger::new(buffer.clone())
+            .layer(Router::new().route("/hello", get(|| async { "hello" })));
+
+        let response = app
+            .oneshot(
+                Request::get("/hello?name=crab")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let output = String::from_utf8(buffer.0.lock().clone()).unwrap();
+        let entry: serde_json::Value = serde_json::from_str(output.trim()).unwrap();
+        assert_eq!(entry["method"], "GET");
+        assert_eq!(entry["path"], "/hello?name=crab");
+        assert_eq!(entry["status"], 200);
+        assert_eq!(entry["bytes"], 5);
+        assert!(entry["latency_ms"].as_f64().unwrap() >= 0.0);
+        assert!(entry["client_ip"].is_null());
+    }
+
+    #[test]
+    fn test_from_config_disabled() {
+        let config = AccessLogConfig::default();
+        assert!(AccessLogger::from_config(&config).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_file_output() {
+        let path = std::env::temp_dir().join(format!("crabrace-access-{}.log", std::process::id()));
+        let config = AccessLogConfig {
+            enabled: true,
+            file: Some(path.to_string_lossy().into_owned()),
+        };
+
+        let logger = AccessLogger::from_config(&config).unwrap().unwrap();
+        logger.log(&AccessLogEntry {
+            timestamp: Utc::now(),
+            method: "GET".to_string(),
+            path: "/v1/providers".to_string(),
+            status: 200,
+            latency_ms: 1.5,
+            bytes: None,
+            client_ip: Some("127.0.0.1".to_string()),
+            request_id: Some("abc".to_string()),
+        });
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(contents.ends_with('\n'));
+        assert!(contents.contains("\"request_id\":\"abc\""));
+    }
+}
@@ -0,0 +1,1019 @@
+//! Admin API for mutating the catalog at runtime
+//!
+//! Lets operators add a provider or model, or fix a price, without cutting a
+//! release. Every mutation is validated, bumps the registry version, and is
+//! optionally persisted to the overlay directory as `<provider id>.json`.
+//! Requests need credentials with the admin scope (see [`Authenticator::authorize_admin`]),
+//! and every mutation is recorded in the [audit log](crate::audit).
+//!
+//! [`Authenticator::authorize_admin`]: crate::auth::Authenticator::authorize_admin
+
+use axum::extract::{Path, Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post, put};
+use axum::Extension;
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use tracing::info;
+use utoipa::{IntoParams, ToSchema};
+
+use super::error::ErrorResponse;
+use super::{ApiError, ApiJson, ApiQuery, AppState, Pagination};
+use crate::audit::AuditEntry;
+use crate::auth::Principal;
+use crate::providers::registry::RegistrySnapshot;
+use crate::providers::validate::{self, Problem};
+use crate::{Model, Provider};
+
+/// Result of a provider mutation
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ProviderMutation {
+    /// Registry version after the mutation
+    pub registry_version: u64,
+
+    /// The provider as stored (or as removed)
+    pub provider: Provider,
+}
+
+/// Result of a model mutation
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ModelMutation {
+    /// Registry version after the mutation
+    pub registry_version: u64,
+
+    pub provider_id: String,
+
+    /// The model as stored (or as removed)
+    pub model: Model,
+}
+
+/// Result of a registry import
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RegistryImport {
+    /// Registry version after the import
+    pub registry_version: u64,
+
+    /// Hash of the catalog now being served
+    pub dataset_hash: String,
+
+    /// Whether the imported catalog differed from the one it replaced
+    pub changed: bool,
+
+    /// Number of providers now being served
+    pub provider_count: usize,
+}
+
+/// Build the admin routes (mounted under `/admin`)
+pub fn routes(state: AppState) -> Router<AppState> {
+    Router::new()
+        .route("/audit", get(list_audit))
+        .route("/registry/export", get(export_registry))
+        .route("/registry/import", post(import_registry))
+        .route("/providers", post(create_provider))
+        .route("/providers/:id", put(put_provider).delete(delete_provider))
+        .route("/providers/:id/models", post(create_model))
+        .route(
+            "/providers/:id/models/*model_id",
+            put(put_model).delete(delete_model),
+        )
+        .layer(axum::middleware::from_fn_with_state(state, require_admin))
+}
+
+/// Middleware requiring the admin scope
+async fn require_admin(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    let authorized = match &state.auth {
+        Some(auth) => auth.authorize_admin(request.extensions().get::<Principal>()),
+        None => Err(ApiError::forbidden("The admin API requires authentication")),
+    };
+
+    match authorized {
+        Ok(()) => next.run(request).await,
+        Err(err) => err.into_response(),
+    }
+}
+
+/// POST /admin/providers - Add a provider
+#[utoipa::path(
+    post,
+    path = "/admin/providers",
+    tag = "admin",
+    request_body = Provider,
+    responses(
+        (status = 201, description = "Provider created", body = ProviderMutation),
+        (status = 400, description = "Invalid provider", body = ErrorResponse),
+        (status = 409, description = "Provider already exists", body = ErrorResponse)
+    )
+)]
+pub async fn create_provider(
+    State(state): State<AppState>,
+    principal: Option<Extension<Principal>>,
+    ApiJson(provider): ApiJson<Provider>,
+) -> Result<Response, ApiError> {
+    validate_provider(&provider)?;
+    if lookup_provider(&state, &provider.id)?.is_some() {
+        return Err(ApiError::conflict(format!(
+            "Provider '{}' already exists",
+            provider.id
+        )));
+    }
+
+    let id = provider.id.clone();
+    state
+        .registry
+        .upsert_provider(provider.clone())
+        .map_err(registry_error)?;
+    persist(&state, &id)?;
+    audit(
+        &state,
+        principal,
+        "create_provider",
+        &id,
+        None,
+        None::<&Provider>,
+        Some(&provider),
+    );
+
+    info!("Admin created provider '{}'", id);
+    Ok((StatusCode::CREATED, provider_mutation(&state, provider)).into_response())
+}
+
+/// PUT /admin/providers/:id - Create or replace a provider
+#[utoipa::path(
+    put,
+    path = "/admin/providers/{id}",
+    tag = "admin",
+    params(("id" = String, Path, description = "Provider ID")),
+    request_body = Provider,
+    responses(
+        (status = 200, description = "Provider replaced", body = ProviderMutation),
+        (status = 201, description = "Provider created", body = ProviderMutation),
+        (status = 400, description = "Invalid provider", body = ErrorResponse)
+    )
+)]
+pub async fn put_provider(
+    State(state): State<AppState>,
+    principal: Option<Extension<Principal>>,
+    Path(provider_id): Path<String>,
+    ApiJson(provider): ApiJson<Provider>,
+) -> Result<Response, ApiError> {
+    if provider.id != provider_id {
+        return Err(ApiError::bad_request(format!(
+            "Body provider ID '{}' does not match path ID '{}'",
+            provider.id, provider_id
+        )));
+    }
+    validate_provider(&provider)?;
+
+    let previous = state
+        .registry
+        .upsert_provider(provider.clone())
+        .map_err(registry_error)?;
+    persist(&state, &provider_id)?;
+    let action = if previous.is_some() {
+        "update_provider"
+    } else {
+        "create_provider"
+    };
+    audit(
+        &state,
+        principal,
+        action,
+        &provider_id,
+        None,
+        previous.as_ref(),
+        Some(&provider),
+    );
+
+    info!("Admin replaced provider '{}'", provider_id);
+    let status = if previous.is_some() {
+        StatusCode::OK
+    } else {
+        StatusCode::CREATED
+    };
+    Ok((status, provider_mutation(&state, provider)).into_response())
+}
+
+/// DELETE /admin/providers/:id - Remove a provider
+#[utoipa::path(
+    delete,
+    path = "/admin/providers/{id}",
+    tag = "admin",
+    params(("id" = String, Path, description = "Provider ID")),
+    responses(
+        (status = 200, description = "Provider removed", body = ProviderMutation),
+        (status = 404, description = "Unknown provider", body = ErrorResponse)
+    )
+)]
+pub async fn delete_provider(
+    State(state): State<AppState>,
+    principal: Option<Extension<Principal>>,
+    Path(provider_id): Path<String>,
+) -> Result<Response, ApiError> {
+    let removed = state
+        .registry
+        .remove_provider(&provider_id)
+        .map_err(registry_error)?
+        .ok_or_else(|| ApiError::provider_not_found(&provider_id))?;
+    persist(&state, &provider_id)?;
+    audit(
+        &state,
+        principal,
+        "delete_provider",
+        &provider_id,
+        None,
+        Some(&removed),
+        None::<&Provider>,
+    );
+
+    info!("Admin removed provider '{}'", provider_id);
+    Ok(provider_mutation(&state, removed).into_response())
+}
+
+/// POST /admin/providers/:id/models - Add a model to a provider
+#[utoipa::path(
+    post,
+    path = "/admin/providers/{id}/models",
+    tag = "admin",
+    params(("id" = String, Path, description = "Provider ID")),
+    request_body = Model,
+    responses(
+        (status = 201, description = "Model created", body = ModelMutation),
+        (status = 400, description = "Invalid model", body = ErrorResponse),
+        (status = 404, description = "Unknown provider", body = ErrorResponse),
+        (status = 409, description = "Model already exists", body = ErrorResponse)
+    )
+)]
+pub async fn create_model(
+    State(state): State<AppState>,
+    principal: Option<Extension<Principal>>,
+    Path(provider_id): Path<String>,
+    ApiJson(model): ApiJson<Model>,
+) -> Result<Response, ApiError> {
+    validate_model(&model)?;
+    let conflict = || {
+        ApiError::conflict(format!(
+            "Model '{}' already exists for provider '{}'",
+            model.id, provider_id
+        ))
+    };
+    if lookup_provider(&state, &provider_id)?
+        .is_some_and(|provider| provider.get_model(&model.id).is_some())
+    {
+        return Err(conflict());
+    }
+
+    let stored = model.clone();
+    let inserted = state
+        .registry
+        .update_provider(&provider_id, |provider| {
+            if provider.get_model(&stored.id).is_some() {
+                return false;
+            }
+            provider.models.push(stored);
+            true
+        })
+        .map_err(registry_error)?
+        .ok_or_else(|| ApiError::provider_not_found(&provider_id))?;
+
+    if !inserted {
+        return Err(conflict());
+    }
+    persist(&state, &provider_id)?;
+    audit(
+        &state,
+        principal,
+        "create_model",
+        &provider_id,
+        Some(&model.id),
+        None::<&Model>,
+        Some(&model),
+    );
+
+    info!("Admin added model '{}' to '{}'", model.id, provider_id);
+    Ok((
+        StatusCode::CREATED,
+        model_mutation(&state, provider_id, model),
+    )
+        .into_response())
+}
+
+/// PUT /admin/providers/:id/models/*model_id - Create or replace a model
+#[utoipa::path(
+    put,
+    path = "/admin/providers/{id}/models/{model_id}",
+    tag = "admin",
+    params(
+        ("id" = String, Path, description = "Provider ID"),
+        ("model_id" = String, Path, description = "Model ID (may contain slashes)")
+    ),
+    request_body = Model,
+    responses(
+        (status = 200, description = "Model replaced", body = ModelMutation),
+        (status = 201, description = "Model created", body = ModelMutation),
+        (status = 400, description = "Invalid model", body = ErrorResponse),
+        (status = 404, description = "Unknown provider", body = ErrorResponse)
+    )
+)]
+pub async fn put_model(
+    State(state): State<AppState>,
+    principal: Option<Extension<Principal>>,
+    Path((provider_id, model_id)): Path<(String, String)>,
+    ApiJson(model): ApiJson<Model>,
+) -> Result<Response, ApiError> {
+    if model.id != model_id {
+        return Err(ApiError::bad_request(format!(
+            "Body model ID '{}' does not match path ID '{}'",
+            model.id, model_id
+        )));
+    }
+    validate_model(&model)?;
+
+    let stored = model.clone();
+    let previous = state
+        .registry
+        .update_provider(&provider_id, |provider| {
+            match provider.models.iter_mut().find(|m| m.id == stored.id) {
+                Some(existing) => Some(std::mem::replace(existing, stored)),
+                None => {
+                    provider.models.push(stored);
+                    None
+                }
+            }
+        })
+        .map_err(registry_error)?
+        .ok_or_else(|| ApiError::provider_not_found(&provider_id))?;
+    persist(&state, &provider_id)?;
+    let action = if previous.is_some() {
+        "update_model"
+    } else {
+        "create_model"
+    };
+    audit(
+        &state,
+        principal,
+        action,
+        &provider_id,
+        Some(&model_id),
+        previous.as_ref(),
+        Some(&model),
+    );
+
+    info!("Admin replaced model '{}' in '{}'", model_id, provider_id);
+    let status = if previous.is_some() {
+        StatusCode::OK
+    } else {
+        StatusCode::CREATED
+    };
+    Ok((status, model_mutation(&state, provider_id, model)).into_response())
+}
+
+/// DELETE /admin/providers/:id/models/*model_id - Remove a model
+#[utoipa::path(
+    delete,
+    path = "/admin/providers/{id}/models/{model_id}",
+    tag = "admin",
+    params(
+        ("id" = String, Path, description = "Provider ID"),
+        ("model_id" = String, Path, description = "Model ID (may contain slashes)")
+    ),
+    responses(
+        (status = 200, description = "Model removed", body = ModelMutation),
+        (status = 404, description = "Unknown provider or model", body = ErrorResponse)
+    )
+)]
+pub async fn delete_model(
+    State(state): State<AppState>,
+    principal: Option<Extension<Principal>>,
+    Path((provider_id, model_id)): Path<(String, String)>,
+) -> Result<Response, ApiError> {
+    super::providers::find_model(&state.registry, &provider_id, &model_id)?;
+
+    let removed = state
+        .registry
+        .update_provider(&provider_id, |provider| {
+            let position = provider.models.iter().position(|m| m.id == model_id)?;
+            Some(provider.models.remove(position))
+        })
+        .map_err(registry_error)?
+        .ok_or_else(|| ApiError::provider_not_found(&provider_id))?
+        .ok_or_else(|| ApiError::model_not_found(&provider_id, &model_id))?;
+    persist(&state, &provider_id)?;
+    audit(
+        &state,
+        principal,
+        "delete_model",
+        &provider_id,
+        Some(&model_id),
+        Some(&removed),
+        None::<&Model>,
+    );
+
+    info!("Admin removed model '{}' from '{}'", model_id, provider_id);
+    Ok(model_mutation(&state, provider_id, removed).into_response())
+}
+
+/// Query parameters for GET /admin/audit
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AuditQuery {
+    /// Only entries for this provider
+    pub provider_id: Option<String>,
+
+    /// Only entries by this actor
+    pub actor: Option<String>,
+}
+
+/// GET /admin/audit - Recent admin operations, newest first
+#[utoipa::path(
+    get,
+    path = "/admin/audit",
+    tag = "admin",
+    params(AuditQuery, Pagination),
+    responses(
+        (status = 200, description = "Audit entries", body = Vec<AuditEntry>),
+        (status = 400, description = "Invalid query parameters", body = ErrorResponse)
+    )
+)]
+pub async fn list_audit(
+    State(state): State<AppState>,
+    ApiQuery(query): ApiQuery<AuditQuery>,
+    ApiQuery(page): ApiQuery<Pagination>,
+) -> Result<Response, ApiError> {
+    let entries: Vec<AuditEntry> = state
+        .audit
+        .entries()
+        .into_iter()
+        .filter(|e| {
+            query
+                .provider_id
+                .as_ref()
+                .map_or(true, |p| *p == e.provider_id)
+        })
+        .filter(|e| query.actor.as_ref().map_or(true, |a| *a == e.actor))
+        .collect();
+    page.respond(entries)
+}
+
+/// GET /admin/registry/export - The whole catalog as a self-contained snapshot
+#[utoipa::path(
+    get,
+    path = "/admin/registry/export",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Catalog snapshot", body = RegistrySnapshot)
+    )
+)]
+pub async fn export_registry(State(state): State<AppState>) -> Result<Response, ApiError> {
+    let snapshot = state.registry.snapshot().map_err(|e| {
+        tracing::error!("Failed to export registry: {}", e);
+        ApiError::internal("Failed to export registry")
+    })?;
+
+    let disposition = format!(
+        "attachment; filename=\"crabrace-snapshot-v{}.json\"",
+        snapshot.registry_version
+    );
+    Ok(([(header::CONTENT_DISPOSITION, disposition)], Json(snapshot)).into_response())
+}
+
+/// POST /admin/registry/import - Atomically replace the catalog with a snapshot
+///
+/// The snapshot must hash to its `dataset_hash` and every provider must pass
+/// the same validation as the other admin endpoints. Providers are written
+/// to (and providers no longer present removed from) the overlay directory.
+#[utoipa::path(
+    post,
+    path = "/admin/registry/import",
+    tag = "admin",
+    request_body = RegistrySnapshot,
+    responses(
+        (status = 200, description = "Catalog replaced", body = RegistryImport),
+        (status = 400, description = "Invalid snapshot", body = ErrorResponse)
+    )
+)]
+pub async fn import_registry(
+    State(state): State<AppState>,
+    principal: Option<Extension<Principal>>,
+    ApiJson(snapshot): ApiJson<RegistrySnapshot>,
+) -> Result<Response, ApiError> {
+    snapshot
+        .verify()
+        .map_err(|e| ApiError::bad_request(e.to_string()))?;
+
+    let mut ids = HashSet::new();
+    for provider in &snapshot.providers {
+        validate_provider(provider)?;
+        if !ids.insert(provider.id.as_str()) {
+            return Err(ApiError::bad_request(format!(
+                "Duplicate provider ID '{}'",
+                provider.id
+            )));
+        }
+    }
+
+    let before = state.registry.snapshot().map_err(registry_error)?;
+    let changed = state
+        .registry
+        .replace_all(snapshot.providers.clone())
+        .map_err(registry_error)?;
+
+    // Persist every provider that was or now is in the catalog
+    let affected: HashSet<&str> = before
+        .providers
+        .iter()
+        .map(|p| p.id.as_str())
+        .chain(ids.iter().copied())
+        .collect();
+    for id in affected {
+        persist(&state, id)?;
+    }
+
+    let summary = |snapshot: &RegistrySnapshot| {
+        serde_json::json!({
+            "dataset_hash": snapshot.dataset_hash,
+            "providers": snapshot.providers.iter().map(|p| &p.id).collect::<Vec<_>>(),
+        })
+    };
+    audit(
+        &state,
+        principal,
+        "import_registry",
+        "*",
+        None,
+        Some(&summary(&before)),
+        Some(&summary(&snapshot)),
+    );
+
+    info!(
+        "Admin imported a registry snapshot with {} providers (changed: {})",
+        snapshot.providers.len(),
+        changed
+    );
+    Ok(Json(RegistryImport {
+        registry_version: state.registry.version(),
+        dataset_hash: state.registry.dataset_hash(),
+        changed,
+        provider_count: state.registry.count(),
+    })
+    .into_response())
+}
+
+/// Record a mutation in the audit log
+fn audit<T: Serialize>(
+    state: &AppState,
+    principal: Option<Extension<Principal>>,
+    action: &str,
+    provider_id: &str,
+    model_id: Option<&str>,
+    before: Option<&T>,
+    after: Option<&T>,
+) {
+    let to_value = |value: Option<&T>| value.and_then(|v| serde_json::to_value(v).ok());
+    let actor = principal.map_or_else(|| "anonymous".to_string(), |Extension(p)| p.actor());
+
+    state.audit.record(AuditEntry::new(
+        actor,
+        action,
+        provider_id,
+        model_id.map(str::to_string),
+        to_value(before),
+        to_value(after),
+        state.registry.version(),
+    ));
+}
+
+fn provider_mutation(state: &AppState, provider: Provider) -> Json<ProviderMutation> {
+    Json(ProviderMutation {
+        registry_version: state.registry.version(),
+        provider,
+    })
+}
+
+fn model_mutation(state: &AppState, provider_id: String, model: Model) -> Json<ModelMutation> {
+    Json(ModelMutation {
+        registry_version: state.registry.version(),
+        provider_id,
+        model,
+    })
+}
+
+/// Exact lookup: admin routes address providers by ID, never by alias
+fn lookup_provider(state: &AppState, provider_id: &str) -> Result<Option<Provider>, ApiError> {
+    state
+        .registry
+        .get_by_canonical_id(provider_id)
+        .map_err(registry_error)
+}
+
+fn registry_error(e: anyhow::Error) -> ApiError {
+    tracing::error!("Failed to update registry: {}", e);
+    ApiError::internal("Failed to update registry")
+}
+
+/// Write the provider's current state to the overlay directory, or remove
+/// its file when the provider no longer exists
+fn persist(state: &AppState, provider_id: &str) -> Result<(), ApiError> {
+    let Some(dir) = &state.overlay_dir else {
+        return Ok(());
+    };
+    let path = dir.join(format!("{}.json", provider_id));
+
+    let result = match lookup_provider(state, provider_id)? {
+        Some(provider) => std::fs::create_dir_all(dir).and_then(|()| {
+            let json = serde_json::to_string_pretty(&provider)?;
+            std::fs::write(&path, json)
+        }),
+        None => match std::fs::remove_file(&path) {
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            other => other,
+        },
+    };
+
+    result.map_err(|e| {
+        tracing::error!("Failed to persist {}: {}", path.display(), e);
+        ApiError::internal("Change applied but could not be persisted")
+    })
+}
+
+/// Check a provider beyond what deserialization enforces
+fn validate_provider(provider: &Provider) -> Result<(), ApiError> {
+    reject_first(validate::provider_problems(provider))
+}
+
+/// Check a model beyond what deserialization enforces
+fn validate_model(model: &Model) -> Result<(), ApiError> {
+    reject_first(validate::model_problems(model, ""))
+}
+
+fn reject_first(problems: Vec<Problem>) -> Result<(), ApiError> {
+    match problems.into_iter().next() {
+        Some(problem) => Err(ApiError::bad_request(problem.message)),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::test_support::{body_json, send_with};
+    use crate::api::AppState;
+    use crate::auth::{Authenticator, X_API_KEY};
+    use crate::config::AuthConfig;
+    use crate::providers::registry::ProviderRegistry;
+    use crate::Config;
+    use axum::body::Body;
+    use axum::http::{header, Method, Request, StatusCode};
+    use axum::response::Response;
+    use serde_json::{json, Value};
+    use std::sync::Arc;
+
+    const KEY: &str = "admin-key";
+
+    fn config() -> Config {
+        let mut config = Config::default();
+        config.admin.enabled = true;
+        config.security.auth = AuthConfig {
+            enabled: true,
+            keys: vec![KEY.to_string()],
+            ..Default::default()
+        };
+        config
+    }
+
+    fn state() -> AppState {
+        let auth = Authenticator::from_config(&config().security.auth).unwrap();
+        AppState::new(Arc::new(ProviderRegistry::new().unwrap())).with_auth(auth)
+    }
+
+    async fn request(state: &AppState, method: Method, uri: &str, body: Option<Value>) -> Response {
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header(X_API_KEY, KEY)
+            .header(header::CONTENT_TYPE, "application/json");
+        let body = body.map_or_else(Body::empty, |b| Body::from(b.to_string()));
+        send_with(&config(), state.clone(), request.body(body).unwrap()).await
+    }
+
+    fn model(id: &str, cost: f64) -> Value {
+        json!({
+            "id": id,
+            "name": "Test Model",
+            "cost_per_1m_in": cost,
+            "cost_per_1m_out": cost * 2.0,
+            "context_window": 128000,
+            "default_max_tokens": 4096
+        })
+    }
+
+    #[tokio::test]
+    async fn test_provider_lifecycle() {
+        let state = state();
+        let provider = json!({
+            "name": "Acme",
+            "id": "acme",
+            "type": "openai",
+            "models": [model("acme-1", 1.0)]
+        });
+
+        let response = request(
+            &state,
+            Method::POST,
+            "/admin/providers",
+            Some(provider.clone()),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+        assert_eq!(body_json(response).await["registry_version"], 2);
+
+        let response = request(&state, Method::POST, "/admin/providers", Some(provider)).await;
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+
+        let response = request(&state, Method::GET, "/v1/providers/acme", None).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = request(&state, Method::DELETE, "/admin/providers/acme", None).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(state.registry.version(), 3);
+
+        let response = request(&state, Method::DELETE, "/admin/providers/acme", None).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_model_mutations() {
+        let state = state();
+        let uri = "/admin/providers/openrouter/models";
+
+        let response = request(
+            &state,
+            Method::POST,
+            uri,
+            Some(model("acme/new-model", 1.0)),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        // Fix the price; slashes in model IDs are supported
+        let response = request(
+            &state,
+            Method::PUT,
+            "/admin/providers/openrouter/models/acme/new-model",
+            Some(model("acme/new-model", 0.5)),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let stored = state
+            .registry
+            .get_model("openrouter", "acme/new-model")
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.cost_per_1m_in, 0.5);
+
+        let response = request(
+            &state,
+            Method::DELETE,
+            "/admin/providers/openrouter/models/acme/new-model",
+            None,
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(body_json(response).await["model"]["cost_per_1m_in"], 0.5);
+
+        let response = request(
+            &state,
+            Method::POST,
+            "/admin/providers/nope/models",
+            Some(model("x", 1.0)),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_audit_log() {
+        let state = state();
+
+        request(
+            &state,
+            Method::PUT,
+            "/admin/providers/anthropic/models/claude-sonnet-4-5-20250929",
+            Some(json!({
+                "id": "claude-sonnet-4-5-20250929",
+                "name": "Claude Sonnet 4.5",
+                "cost_per_1m_in": 2.5,
+                "cost_per_1m_out": 15.0,
+                "cost_per_1m_in_cached": 3.75,
+                "cost_per_1m_out_cached": 0.3,
+                "cost_per_1m_in_batch": 1.5,
+                "cost_per_1m_out_batch": 7.5,
+                "context_window": 200000,
+                "default_max_tokens": 50000,
+                "max_output_tokens": 64000,
+                "can_reason": true,
+                "has_reasoning_efforts": false,
+                "supports_attachments": true,
+                "supports_tools": true,
+                "supports_parallel_tool_calls": true,
+                "supports_structured_output": true,
+                "supports_streaming": true,
+                "input_modalities": ["text", "image", "pdf"],
+                "knowledge_cutoff": "2025-01-01",
+                "released_at": "2025-09-29",
+                "family": "claude-sonnet",
+                "previous_model_id": "claude-3-5-sonnet-20241022",
+                "tags": ["frontier", "coding"],
+                "benchmarks": {"swe_bench_verified": 77.2, "gpqa_diamond": 83.4},
+                "tokenizer": "claude"
+            })),
+        )
+        .await;
+        request(
+            &state,
+            Method::POST,
+            "/admin/providers/openai/models",
+            Some(model("gpt-test", 1.0)),
+        )
+        .await;
+
+        let response = request(&state, Method::GET, "/admin/audit", None).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let entries = body_json(response).await;
+        let entries = entries.as_array().unwrap();
+        assert_eq!(entries.len(), 2);
+
+        // Newest first, attributed to the key fingerprint
+        assert_eq!(entries[0]["action"], "create_model");
+        assert!(entries[0]["actor"].as_str().unwrap().starts_with("key:"));
+        assert!(entries[0]["before"].is_null());
+
+        assert_eq!(entries[1]["action"], "update_model");
+        assert_eq!(entries[1]["before"]["cost_per_1m_in"], 3.0);
+        assert_eq!(entries[1]["after"]["cost_per_1m_in"], 2.5);
+        assert_eq!(entries[1]["changed_fields"], json!(["/cost_per_1m_in"]));
+        assert!(entries[1]["request_id"].is_string());
+
+        let response = request(&state, Method::GET, "/admin/audit?provider_id=openai", None).await;
+        assert_eq!(body_json(response).await.as_array().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_validation() {
+        let state = state();
+
+        let response = request(
+            &state,
+            Method::POST,
+            "/admin/providers/anthropic/models",
+            Some(model("bad", -1.0)),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = request(
+            &state,
+            Method::PUT,
+            "/admin/providers/anthropic/models/other",
+            Some(model("bad", 1.0)),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = request(
+            &state,
+            Method::POST,
+            "/admin/providers",
+            Some(json!({"name": "Evil", "id": "../evil", "type": "openai"})),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        // Schema violations are rejected by the extractor
+        let response = request(
+            &state,
+            Method::POST,
+            "/admin/providers",
+            Some(json!({"name": "Acme"})),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(state.registry.version(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_requires_credentials() {
+        let response = send_with(
+            &config(),
+            state(),
+            Request::delete("/admin/providers/anthropic")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        // Not mounted unless enabled
+        let mut disabled = config();
+        disabled.admin.enabled = false;
+        let response = send_with(
+            &disabled,
+            state(),
+            Request::delete("/admin/providers/anthropic")
+                .header(X_API_KEY, KEY)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_registry_export_import() {
+        let staging = state();
+        let response = request(
+            &staging,
+            Method::PUT,
+            "/admin/providers/openrouter/models/acme/new-model",
+            Some(model("acme/new-model", 1.0)),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+        staging.registry.remove_provider("xai").unwrap();
+
+        let response = request(&staging, Method::GET, "/admin/registry/export", None).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.headers()[header::CONTENT_DISPOSITION]
+            .to_str()
+            .unwrap()
+            .starts_with("attachment"));
+        let snapshot = body_json(response).await;
+        assert_eq!(snapshot["dataset_hash"], staging.registry.dataset_hash());
+
+        // Tampered snapshots are rejected
+        let mut tampered = snapshot.clone();
+        tampered["providers"][0]["name"] = json!("Tampered");
+        let prod = state();
+        let response = request(
+            &prod,
+            Method::POST,
+            "/admin/registry/import",
+            Some(tampered),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = request(
+            &prod,
+            Method::POST,
+            "/admin/registry/import",
+            Some(snapshot),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = body_json(response).await;
+        assert_eq!(body["changed"], true);
+        assert_eq!(body["provider_count"], 17);
+        assert_eq!(
+            prod.registry.dataset_hash(),
+            staging.registry.dataset_hash()
+        );
+        assert!(prod
+            .registry
+            .get_model("openrouter", "acme/new-model")
+            .unwrap()
+            .is_some());
+
+        let entry = &prod.audit.entries()[0];
+        assert_eq!(entry.action, "import_registry");
+        assert_eq!(entry.changed_fields, vec!["/dataset_hash", "/providers"]);
+    }
+
+    #[tokio::test]
+    async fn test_overlay_persistence() {
+        let dir = std::env::temp_dir().join(format!("crabrace-admin-{}", std::process::id()));
+        let state = state().with_overlay_dir(Some(dir.clone()));
+
+        let response = request(
+            &state,
+            Method::POST,
+            "/admin/providers/anthropic/models",
+            Some(model("claude-test", 1.0)),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        let reloaded = ProviderRegistry::new().unwrap();
+        reloaded.load_dir(&dir).unwrap();
+        assert!(reloaded
+            .get_model("anthropic", "claude-test")
+            .unwrap()
+            .is_some());
+
+        let response = request(&state, Method::DELETE, "/admin/providers/anthropic", None).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(!dir.join("anthropic.json").exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,191 @@
+use axum::body::Bytes;
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+use axum::response::Response;
+use parking_lot::Mutex;
+use tower_http::set_header::SetResponseHeaderLayer;
+
+use super::ApiError;
+use crate::config::CacheConfig;
+
+/// Build the `Cache-Control` value for catalog responses
+pub fn cache_control_value(config: &CacheConfig) -> Option<HeaderValue> {
+    if !config.enabled {
+        return None;
+    }
+
+    let mut value = format!("public, max-age={}", config.max_age_seconds);
+    if let Some(swr) = config.stale_while_revalidate_seconds {
+        value.push_str(&format!(", stale-while-revalidate={}", swr));
+    }
+
+    HeaderValue::from_str(&value).ok()
+}
+
+/// Build a layer setting `Cache-Control` on successful and `304 Not Modified` responses only
+///
+/// Error responses are left uncached so that a transient 404 or 500 is not
+/// pinned in a CDN until `max-age` expires.
+pub fn build_cache_control_layer(
+    config: &CacheConfig,
+) -> Option<SetResponseHeaderLayer<impl Fn(&Response) -> Option<HeaderValue> + Clone>> {
+    let value = cache_control_value(config)?;
+
+    Some(SetResponseHeaderLayer::if_not_present(
+        header::CACHE_CONTROL,
+        move |response: &Response| {
+            let status = response.status();
+            (status.is_success() || status == StatusCode::NOT_MODIFIED).then(|| value.clone())
+        },
+    ))
+}
+
+/// A response body serialized from one version of the catalog
+#[derive(Debug, Clone)]
+pub struct CachedBody {
+    /// Dataset hash of the catalog the body was serialized from
+    pub hash: String,
+
+    pub body: Bytes,
+
+    /// Number of items in the body, for `X-Total-Count`
+    pub count: usize,
+}
+
+impl CachedBody {
+    /// Strong `ETag` identifying the body
+    pub fn etag(&self) -> HeaderValue {
+        HeaderValue::from_str(&format!("\"{}\"", self.hash))
+            .unwrap_or_else(|_| HeaderValue::from_static("\"\""))
+    }
+}
+
+/// Response body serialized once per catalog version
+///
+/// Holds the body for the last dataset hash it was asked for; a request
+/// for another hash re-serializes it. Concurrent rebuilds wait for the
+/// first one instead of serializing the catalog in parallel.
+#[derive(Debug, Default)]
+pub struct BodyCache {
+    entry: Mutex<Option<CachedBody>>,
+}
+
+impl BodyCache {
+    /// The body for `hash`, built with `build` (returning the body and its item count) on a miss
+    pub fn get_or_build(
+        &self,
+        hash: &str,
+        build: impl FnOnce() -> Result<(Bytes, usize), ApiError>,
+    ) -> Result<CachedBody, ApiError> {
+        let mut entry = self.entry.lock();
+        if let Some(cached) = entry.as_ref().filter(|cached| cached.hash == hash) {
+            return Ok(cached.clone());
+        }
+
+        let (body, count) = build()?;
+        let cached = CachedBody {
+            hash: hash.to_string(),
+            body,
+            count,
+        };
+        *entry = Some(cached.clone());
+        Ok(cached)
+    }
+}
+
+/// Whether the request's `If-None-Match` matches `etag`
+///
+/// Uses weak comparison, as required for `If-None-Match`.
+pub fn not_modified(headers: &HeaderMap, etag: &HeaderValue) -> bool {
+    let Ok(etag) = etag.to_str() else {
+        return false;
+    };
+    headers
+        .get_all(header::IF_NONE_MATCH)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(|tag| tag.trim())
+        .any(|tag| tag == "*" || tag.trim_start_matches("W/") == etag)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::test_support::{app_with_config, get_with};
+    use crate::Config;
+    use axum::http::StatusCode;
+
+    #[test]
+    fn test_cache_control_disabled() {
+        assert!(cache_control_value(&CacheConfig::default()).is_none());
+    }
+
+    #[test]
+    fn test_body_cache() {
+        let cache = BodyCache::default();
+        let build = |body: &'static str| move || Ok((Bytes::from_static(body.as_bytes()), 1));
+
+        assert_eq!(
+            cache.get_or_build("a", build("first")).unwrap().body,
+            "first"
+        );
+        assert_eq!(
+            cache.get_or_build("a", build("second")).unwrap().body,
+            "first"
+        );
+        assert_eq!(
+            cache.get_or_build("b", build("third")).unwrap().body,
+            "third"
+        );
+    }
+
+    #[test]
+    fn test_not_modified() {
+        let etag = HeaderValue::from_static("\"abc\"");
+        let matches = |value: &'static str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(header::IF_NONE_MATCH, HeaderValue::from_static(value));
+            not_modified(&headers, &etag)
+        };
+
+        assert!(matches("\"abc\""));
+        assert!(matches("W/\"abc\""));
+        assert!(matches("\"old\", \"abc\""));
+        assert!(matches("*"));
+        assert!(!matches("\"old\""));
+        assert!(!not_modified(&HeaderMap::new(), &etag));
+    }
+
+    #[test]
+    fn test_cache_control_value() {
+        let config = CacheConfig {
+            enabled: true,
+            max_age_seconds: 600,
+            stale_while_revalidate_seconds: Some(60),
+        };
+        assert_eq!(
+            cache_control_value(&config).unwrap(),
+            "public, max-age=600, stale-while-revalidate=60"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cache_control_on_catalog_responses() {
+        let mut config = Config::default();
+        config.server.cache.enabled = true;
+        let app = app_with_config(&config);
+
+        let response = get_with(app.clone(), "/providers").await;
+        assert_eq!(
+            response.headers()[header::CACHE_CONTROL],
+            "public, max-age=300"
+        );
+
+        let response = get_with(app.clone(), "/providers/nonexistent").await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert!(response.headers().get(header::CACHE_CONTROL).is_none());
+
+        let response = get_with(app, "/health").await;
+        assert!(response.headers().get(header::CACHE_CONTROL).is_none());
+    }
+}
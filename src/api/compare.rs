@@ -0,0 +1,233 @@
+use axum::extract::State;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use super::error::ErrorResponse;
+use super::estimate::{price, CurrencyQuery, EstimateRequest, EstimateResponse, Usage};
+use super::providers::find_model;
+use super::{ApiError, ApiQuery, AppState};
+use crate::ProviderModel;
+
+/// Largest number of models accepted in one comparison
+pub const MAX_COMPARE_MODELS: usize = 20;
+
+/// Query parameters for GET /models/compare
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct CompareQuery {
+    /// Comma-separated `provider_id/model_id` pairs (model IDs may contain slashes)
+    #[serde(default)]
+    pub ids: String,
+
+    /// Sample workload: uncached input tokens
+    #[serde(default = "default_workload_input_tokens")]
+    pub input_tokens: u64,
+
+    /// Sample workload: output tokens
+    #[serde(default = "default_workload_output_tokens")]
+    pub output_tokens: u64,
+
+    /// Sample workload: cached input tokens
+    #[serde(default)]
+    pub cached_input_tokens: u64,
+
+    /// Sample workload: reasoning tokens
+    #[serde(default)]
+    pub reasoning_tokens: u64,
+
+    /// Sample workload: price at batch API rates, where the model has them
+    #[serde(default)]
+    pub batch: bool,
+}
+
+fn default_workload_input_tokens() -> u64 {
+    1_000_000
+}
+
+fn default_workload_output_tokens() -> u64 {
+    1_000_000
+}
+
+/// One column of the comparison matrix
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ComparedModel {
+    /// The model, with its provider ID
+    #[serde(flatten)]
+    pub model: ProviderModel,
+
+    /// Cost of the sample workload on this model
+    pub workload_cost: EstimateResponse,
+}
+
+/// Side-by-side comparison of several models
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ComparisonResponse {
+    /// Models in the order they were requested
+    pub models: Vec<ComparedModel>,
+
+    /// `provider_id/model_id` of the model with the lowest workload cost
+    pub cheapest: Option<String>,
+
+    /// `provider_id/model_id` of the model with the largest context window
+    pub largest_context_window: Option<String>,
+}
+
+impl CompareQuery {
+    /// Split `ids` into `(provider_id, model_id)` pairs
+    fn model_refs(&self) -> Result<Vec<(&str, &str)>, ApiError> {
+        let refs = self
+            .ids
+            .split(',')
+            .map(str::trim)
+            .filter(|id| !id.is_empty())
+            .map(|id| match id.split_once('/') {
+                Some((provider, model)) if !provider.is_empty() && !model.is_empty() => {
+                    Ok((provider, model))
+                }
+                _ => Err(ApiError::bad_request(format!(
+                    "Invalid model reference '{}', expected 'provider_id/model_id'",
+                    id
+                ))),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if refs.is_empty() {
+            return Err(ApiError::bad_request("Query parameter 'ids' is required"));
+        }
+        if refs.len() > MAX_COMPARE_MODELS {
+            return Err(ApiError::bad_request(format!(
+                "At most {} models can be compared at once",
+                MAX_COMPARE_MODELS
+            )));
+        }
+        Ok(refs)
+    }
+}
+
+fn qualified_id(entry: &ComparedModel) -> String {
+    format!("{}/{}", entry.model.provider_id, entry.model.model.id)
+}
+
+/// GET /models/compare - Compare models side by side
+///
+/// Each model is priced against the sample workload given by the token
+/// query parameters (1M input and 1M output tokens by default), in USD
+/// unless `currency` names another configured currency.
+#[utoipa::path(
+    get,
+    path = "/v1/models/compare",
+    tag = "models",
+    params(CompareQuery, CurrencyQuery),
+    responses(
+        (status = 200, description = "Comparison matrix", body = ComparisonResponse),
+        (status = 400, description = "Missing or malformed model references or unknown currency", body = ErrorResponse),
+        (status = 404, description = "Unknown provider or model", body = ErrorResponse)
+    )
+)]
+pub async fn compare_models(
+    State(state): State<AppState>,
+    ApiQuery(query): ApiQuery<CompareQuery>,
+    ApiQuery(currency): ApiQuery<CurrencyQuery>,
+) -> Result<Response, ApiError> {
+    let conversion = currency.conversion(&state.currencies)?;
+    let models = query
+        .model_refs()?
+        .into_iter()
+        .map(|(provider_id, model_id)| {
+            let model = find_model(&state.registry, provider_id, model_id)?;
+            let workload = EstimateRequest {
+                provider_id: provider_id.to_string(),
+                model_id: model_id.to_string(),
+                usage: Usage {
+                    input_tokens: query.input_tokens,
+                    output_tokens: query.output_tokens,
+                    reasoning_tokens: query.reasoning_tokens,
+                    cached_input_tokens: query.cached_input_tokens,
+                    batch: query.batch,
+                    ..Usage::default()
+                },
+            };
+            Ok(ComparedModel {
+                workload_cost: price(&model, &workload).convert(&conversion),
+                model: ProviderModel {
+                    provider_id: provider_id.to_string(),
+                    model,
+                },
+            })
+        })
+        .collect::<Result<Vec<_>, ApiError>>()?;
+
+    let cheapest = models
+        .iter()
+        .min_by(|a, b| {
+            a.workload_cost
+                .total_cost
+                .total_cmp(&b.workload_cost.total_cost)
+        })
+        .map(qualified_id);
+    let largest_context_window = models
+        .iter()
+        .max_by_key(|m| m.model.model.context_window)
+        .map(qualified_id);
+
+    Ok(Json(ComparisonResponse {
+        models,
+        cheapest,
+        largest_context_window,
+    })
+    .into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::test_support::get_json;
+    use axum::http::StatusCode;
+
+    #[tokio::test]
+    async fn test_compare_models() {
+        let (status, body) = get_json(
+            "/v1/models/compare?ids=anthropic/claude-sonnet-4-5-20250929,chutes/deepseek-ai/DeepSeek-R1-0528",
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let models = body["models"].as_array().unwrap();
+        assert_eq!(models.len(), 2);
+        assert_eq!(models[0]["provider_id"], "anthropic");
+        assert_eq!(models[0]["workload_cost"]["total_cost"], 18.0);
+        assert_eq!(models[1]["id"], "deepseek-ai/DeepSeek-R1-0528");
+        assert_eq!(models[0]["supports_structured_output"], true);
+        assert_eq!(models[1]["supports_structured_output"], false);
+        assert!(body["cheapest"].is_string());
+        assert!(body["largest_context_window"].is_string());
+    }
+
+    #[tokio::test]
+    async fn test_compare_custom_workload() {
+        let (status, body) = get_json(
+            "/v1/models/compare?ids=anthropic/claude-sonnet-4-5-20250929&input_tokens=0&output_tokens=2000000",
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["models"][0]["workload_cost"]["total_cost"], 30.0);
+    }
+
+    #[tokio::test]
+    async fn test_compare_requires_ids() {
+        let (status, body) = get_json("/v1/models/compare").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["code"], "bad_request");
+
+        let (status, _) = get_json("/v1/models/compare?ids=anthropic").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_compare_unknown_model() {
+        let (status, body) = get_json("/v1/models/compare?ids=anthropic/nope").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["code"], "model_not_found");
+    }
+}
@@ -0,0 +1,201 @@
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use utoipa::ToSchema;
+
+/// Structured JSON error returned by API handlers
+///
+/// Serialized as `{"error": "<message>", "code": "<machine readable code>"}`,
+/// plus a `request_id` when rendered inside a request.
+#[derive(Debug, Clone)]
+pub struct ApiError {
+    status: StatusCode,
+    code: &'static str,
+    message: String,
+}
+
+/// JSON body of an error response
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ErrorResponse {
+    /// Human readable error message
+    pub error: String,
+
+    /// Machine readable error code (e.g. `provider_not_found`)
+    pub code: String,
+
+    /// ID of the failed request, matching the `X-Request-Id` response header
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+}
+
+impl ApiError {
+    /// Create a new API error
+    pub fn new(status: StatusCode, code: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            code,
+            message: message.into(),
+        }
+    }
+
+    /// 400 for malformed request parameters
+    pub fn bad_request(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, "bad_request", message)
+    }
+
+    /// 401 for missing or invalid credentials
+    pub fn unauthorized(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::UNAUTHORIZED, "unauthorized", message)
+    }
+
+    /// 403 for valid credentials lacking a required scope
+    pub fn forbidden(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::FORBIDDEN, "forbidden", message)
+    }
+
+    /// 404 for a path no route serves
+    pub fn not_found(path: &str) -> Self {
+        Self::new(
+            StatusCode::NOT_FOUND,
+            "not_found",
+            format!("No resource at '{}'", path),
+        )
+    }
+
+    /// 404 for an unknown provider ID
+    pub fn provider_not_found(provider_id: &str) -> Self {
+        Self::new(
+            StatusCode::NOT_FOUND,
+            "provider_not_found",
+            format!("Provider '{}' not found", provider_id),
+        )
+    }
+
+    /// 404 for an unknown model within a known provider
+    pub fn model_not_found(provider_id: &str, model_id: &str) -> Self {
+        Self::new(
+            StatusCode::NOT_FOUND,
+            "model_not_found",
+            format!(
+                "Model '{}' not found for provider '{}'",
+                model_id, provider_id
+            ),
+        )
+    }
+
+    /// 404 for a tenant that is not configured
+    pub fn tenant_not_found(tenant: &str) -> Self {
+        Self::new(
+            StatusCode::NOT_FOUND,
+            "tenant_not_found",
+            format!("Tenant '{}' not found", tenant),
+        )
+    }
+
+    /// 404 for a model ID no provider offers
+    pub fn model_not_offered(model_id: &str) -> Self {
+        Self::new(
+            StatusCode::NOT_FOUND,
+            "model_not_found",
+            format!("Model '{}' is not offered by any provider", model_id),
+        )
+    }
+
+    /// 405 for a known path requested with an unsupported method
+    pub fn method_not_allowed(method: &axum::http::Method) -> Self {
+        Self::new(
+            StatusCode::METHOD_NOT_ALLOWED,
+            "method_not_allowed",
+            format!("Method {} is not allowed on this route", method),
+        )
+    }
+
+    /// 410 for a changelog request older than the retained changes
+    pub fn changes_expired(since: u64, oldest: u64) -> Self {
+        Self::new(
+            StatusCode::GONE,
+            "changes_expired",
+            format!(
+                "Changes since version {} are no longer retained (oldest: {}); re-fetch the catalog",
+                since, oldest
+            ),
+        )
+    }
+
+    /// 409 for a create that collides with an existing resource
+    pub fn conflict(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::CONFLICT, "conflict", message)
+    }
+
+    /// 413 for a request body over the configured limit
+    pub fn payload_too_large(max_bytes: usize) -> Self {
+        Self::new(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            "payload_too_large",
+            format!("Request body exceeds the {} byte limit", max_bytes),
+        )
+    }
+
+    /// 408 for a request whose handler exceeded the configured time budget
+    pub fn request_timeout(timeout: Duration) -> Self {
+        Self::new(
+            StatusCode::REQUEST_TIMEOUT,
+            "request_timeout",
+            format!("Request did not complete within {}s", timeout.as_secs_f64()),
+        )
+    }
+
+    /// 500 for unexpected internal failures
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::INTERNAL_SERVER_ERROR, "internal_error", message)
+    }
+
+    /// HTTP status of this error
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    /// Machine readable error code
+    pub fn code(&self) -> &'static str {
+        self.code
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let body = ErrorResponse {
+            error: self.message,
+            code: self.code.to_string(),
+            request_id: super::request_id::current(),
+        };
+        (self.status, Json(body)).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_provider_not_found() {
+        let err = ApiError::provider_not_found("nope");
+        assert_eq!(err.status(), StatusCode::NOT_FOUND);
+        assert_eq!(err.code(), "provider_not_found");
+    }
+
+    #[test]
+    fn test_model_not_found() {
+        let err = ApiError::model_not_found("anthropic", "nope");
+        assert_eq!(err.status(), StatusCode::NOT_FOUND);
+        assert_eq!(err.code(), "model_not_found");
+    }
+
+    #[test]
+    fn test_internal_error() {
+        let err = ApiError::internal("boom");
+        assert_eq!(err.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(err.into_response().status(), 500);
+    }
+}
@@ -0,0 +1,408 @@
+use axum::extract::State;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use super::error::ErrorResponse;
+use super::providers::find_model;
+use super::{ApiError, ApiJson, ApiQuery, AppState};
+use crate::currency::{Conversion, ExchangeRates, BASE_CURRENCY};
+use crate::models::estimate::validate;
+pub use crate::models::estimate::{price, EstimateRequest, EstimateResponse, Usage};
+use crate::providers::registry::ProviderRegistry;
+
+/// Largest number of items accepted in a single batch estimate
+pub const MAX_BATCH_SIZE: usize = 10_000;
+
+/// Query parameter selecting the currency of reported costs
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct CurrencyQuery {
+    /// ISO 4217 code of the currency to report costs in (`USD` by default)
+    pub currency: Option<String>,
+}
+
+impl CurrencyQuery {
+    /// Conversion to the requested currency; 400 when its rate is unknown
+    pub fn conversion(&self, rates: &ExchangeRates) -> Result<Conversion, ApiError> {
+        let currency = self.currency.as_deref().unwrap_or(BASE_CURRENCY);
+        rates.conversion(currency).ok_or_else(|| {
+            ApiError::bad_request(format!(
+                "Unknown currency '{}' (available: {})",
+                currency,
+                rates.currencies().join(", ")
+            ))
+        })
+    }
+}
+
+/// Estimate body: a single request or a batch
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[serde(untagged)]
+pub enum EstimateBody {
+    Single(EstimateRequest),
+    Batch(Vec<EstimateRequest>),
+}
+
+/// Result of a batch estimate
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct BatchEstimateResponse {
+    /// Per-item breakdowns, in request order
+    pub estimates: Vec<EstimateResponse>,
+
+    /// Sum of all `total_cost` values
+    pub total_cost: f64,
+
+    pub currency: String,
+}
+
+/// Look up the requested model and price the request
+pub fn estimate(
+    registry: &ProviderRegistry,
+    request: &EstimateRequest,
+) -> Result<EstimateResponse, ApiError> {
+    let model = find_model(registry, &request.provider_id, &request.model_id)?;
+    validate(&model, request).map_err(|e| ApiError::bad_request(e.to_string()))?;
+    Ok(price(&model, request))
+}
+
+impl EstimateResponse {
+    /// Convert every cost from USD
+    pub fn convert(mut self, conversion: &Conversion) -> Self {
+        for cost in [
+            &mut self.input_cost,
+            &mut self.output_cost,
+            &mut self.cached_input_cost,
+            &mut self.reasoning_cost,
+            &mut self.image_cost,
+            &mut self.audio_cost,
+            &mut self.surcharge_cost,
+            &mut self.total_cost,
+        ] {
+            *cost = conversion.apply(*cost);
+        }
+        self.currency = conversion.currency.clone();
+        self
+    }
+}
+
+/// POST /estimate - Estimate the cost of token usage
+///
+/// Accepts a single request object or an array of requests (batch mode).
+/// Costs are in USD unless `currency` names another configured currency.
+#[utoipa::path(
+    post,
+    path = "/v1/estimate",
+    tag = "pricing",
+    params(CurrencyQuery),
+    request_body = EstimateBody,
+    responses(
+        (status = 200, description = "Cost breakdown (single) or batch result (array input)", content(
+            (EstimateResponse = "application/json"),
+            (BatchEstimateResponse = "application/json")
+        )),
+        (status = 400, description = "Invalid request body or unknown currency", body = ErrorResponse),
+        (status = 404, description = "Unknown provider or model", body = ErrorResponse)
+    )
+)]
+pub async fn estimate_cost(
+    State(state): State<AppState>,
+    ApiQuery(query): ApiQuery<CurrencyQuery>,
+    ApiJson(body): ApiJson<EstimateBody>,
+) -> Result<Response, ApiError> {
+    let conversion = query.conversion(&state.currencies)?;
+    match body {
+        EstimateBody::Single(request) => {
+            let estimate = estimate(&state.registry, &request)?.convert(&conversion);
+            Ok(Json(estimate).into_response())
+        }
+        EstimateBody::Batch(requests) => {
+            if requests.len() > MAX_BATCH_SIZE {
+                return Err(ApiError::bad_request(format!(
+                    "Batch size must not exceed {}",
+                    MAX_BATCH_SIZE
+                )));
+            }
+
+            let estimates = requests
+                .iter()
+                .map(|request| Ok(estimate(&state.registry, request)?.convert(&conversion)))
+                .collect::<Result<Vec<_>, ApiError>>()?;
+            let total_cost = estimates.iter().map(|e| e.total_cost).sum();
+
+            Ok(Json(BatchEstimateResponse {
+                estimates,
+                total_cost,
+                currency: conversion.currency,
+            })
+            .into_response())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::test_support::{body_json, post_json, send_with};
+    use crate::api::AppState;
+    use crate::currency::ExchangeRates;
+    use crate::providers::registry::ProviderRegistry;
+    use crate::Config;
+    use axum::body::Body;
+    use axum::http::header::CONTENT_TYPE;
+    use axum::http::{Request, StatusCode};
+    use serde_json::json;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_estimate_single() {
+        let response = post_json(
+            "/v1/estimate",
+            json!({
+                "provider_id": "anthropic",
+                "model_id": "claude-sonnet-4-5-20250929",
+                "input_tokens": 1_000_000,
+                "output_tokens": 1_000_000
+            }),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = body_json(response).await;
+        assert_eq!(body["input_cost"], 3.0);
+        assert_eq!(body["output_cost"], 15.0);
+        assert_eq!(body["total_cost"], 18.0);
+        assert_eq!(body["currency"], "USD");
+    }
+
+    #[tokio::test]
+    async fn test_estimate_batch() {
+        let item = json!({
+            "provider_id": "anthropic",
+            "model_id": "claude-sonnet-4-5-20250929",
+            "input_tokens": 1_000_000
+        });
+        let response = post_json("/v1/estimate", json!([item, item])).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = body_json(response).await;
+        assert_eq!(body["estimates"].as_array().unwrap().len(), 2);
+        assert_eq!(body["total_cost"], 6.0);
+    }
+
+    #[tokio::test]
+    async fn test_estimate_images() {
+        let request = |quality: &str| {
+            json!({
+                "provider_id": "openai",
+                "model_id": "gpt-image-1",
+                "input_tokens": 1_000_000,
+                "images": 4,
+                "image_size": "1536x1024",
+                "image_quality": quality
+            })
+        };
+        let response = post_json("/v1/estimate", request("high")).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = body_json(response).await;
+        assert_eq!(body["images"], 4);
+        assert_eq!(body["input_cost"], 5.0);
+        assert_eq!(body["image_cost"], 1.0);
+        assert_eq!(body["total_cost"], 6.0);
+
+        let response = post_json("/v1/estimate", request("ultra")).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(body_json(response).await["code"], "bad_request");
+    }
+
+    #[tokio::test]
+    async fn test_estimate_audio() {
+        let response = post_json(
+            "/v1/estimate",
+            json!({ "provider_id": "openai", "model_id": "whisper-1", "audio_minutes": 100.0 }),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = body_json(response).await;
+        assert_eq!(body["audio_cost"], 0.6);
+        assert_eq!(body["total_cost"], 0.6);
+
+        let response = post_json(
+            "/v1/estimate",
+            json!({ "provider_id": "openai", "model_id": "tts-1", "characters": 200_000 }),
+        )
+        .await;
+        assert_eq!(body_json(response).await["audio_cost"], 3.0);
+
+        let response = post_json(
+            "/v1/estimate",
+            json!({ "provider_id": "openai", "model_id": "tts-1", "audio_minutes": 1.0 }),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_estimate_batch_pricing() {
+        let response = post_json(
+            "/v1/estimate",
+            json!({
+                "provider_id": "anthropic",
+                "model_id": "claude-sonnet-4-5-20250929",
+                "input_tokens": 1_000_000,
+                "output_tokens": 1_000_000,
+                "batch": true
+            }),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = body_json(response).await;
+        assert_eq!(body["batch"], true);
+        assert_eq!(body["input_cost"], 1.5);
+        assert_eq!(body["output_cost"], 7.5);
+        assert_eq!(body["total_cost"], 9.0);
+
+        let response = post_json(
+            "/v1/estimate",
+            json!({ "provider_id": "openai", "model_id": "tts-1", "batch": true }),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_estimate_reasoning() {
+        let response = post_json(
+            "/v1/estimate",
+            json!({
+                "provider_id": "anthropic",
+                "model_id": "claude-sonnet-4-5-20250929",
+                "output_tokens": 1_000_000,
+                "reasoning_tokens": 1_000_000
+            }),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = body_json(response).await;
+        assert_eq!(body["output_cost"], 15.0);
+        assert_eq!(body["reasoning_cost"], 15.0);
+        assert_eq!(body["total_cost"], 30.0);
+    }
+
+    #[tokio::test]
+    async fn test_estimate_surcharges() {
+        let response = post_json(
+            "/v1/estimate",
+            json!({
+                "provider_id": "openrouter",
+                "model_id": "openai/o3-deep-research",
+                "input_tokens": 100_000,
+                "requests": 1,
+                "tool_calls": 25
+            }),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = body_json(response).await;
+        assert_eq!(body["tool_calls"], 25);
+        assert_eq!(body["input_cost"], 1.0);
+        assert_eq!(body["surcharge_cost"], 0.25);
+        assert_eq!(body["total_cost"], 1.25);
+    }
+
+    #[tokio::test]
+    async fn test_estimate_from_text() {
+        let response = post_json(
+            "/v1/estimate",
+            json!({
+                "provider_id": "anthropic",
+                "model_id": "claude-sonnet-4-5-20250929",
+                "input_tokens": 1000,
+                "input_text": "a".repeat(70),
+                "output_text": ""
+            }),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = body_json(response).await;
+        // 70 characters at 3.5 characters per token
+        assert_eq!(body["input_tokens"], 1020);
+        assert_eq!(body["output_tokens"], 0);
+    }
+
+    #[tokio::test]
+    async fn test_estimate_currency() {
+        let mut config = Config::default();
+        config.currency.rates.insert("eur".to_string(), 0.75);
+        let registry = Arc::new(ProviderRegistry::new().unwrap());
+        let state = AppState::new(registry).with_currencies(Arc::new(
+            ExchangeRates::from_config(&config.currency).unwrap(),
+        ));
+        let request = |uri: &str| {
+            Request::post(uri)
+                .header(CONTENT_TYPE, "application/json")
+                .body(Body::from(
+                    json!({
+                        "provider_id": "anthropic",
+                        "model_id": "claude-sonnet-4-5-20250929",
+                        "input_tokens": 1_000_000,
+                        "output_tokens": 1_000_000
+                    })
+                    .to_string(),
+                ))
+                .unwrap()
+        };
+
+        let response =
+            send_with(&config, state.clone(), request("/v1/estimate?currency=EUR")).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = body_json(response).await;
+        assert_eq!(body["input_cost"], 2.25);
+        assert_eq!(body["output_cost"], 11.25);
+        assert_eq!(body["total_cost"], 13.5);
+        assert_eq!(body["currency"], "EUR");
+
+        let response = send_with(&config, state, request("/v1/estimate?currency=JPY")).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert!(body_json(response).await["error"]
+            .as_str()
+            .unwrap()
+            .contains("available: EUR, USD"));
+    }
+
+    #[tokio::test]
+    async fn test_estimate_unknown_model() {
+        let response = post_json(
+            "/v1/estimate",
+            json!({ "provider_id": "anthropic", "model_id": "nope" }),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(body_json(response).await["code"], "model_not_found");
+    }
+
+    #[tokio::test]
+    async fn test_estimate_token_overflow() {
+        let response = post_json(
+            "/v1/estimate",
+            json!({
+                "provider_id": "anthropic",
+                "model_id": "claude-sonnet-4-5-20250929",
+                "input_tokens": u64::MAX,
+                "input_text": "Hello, world"
+            }),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = body_json(response).await;
+        assert_eq!(body["code"], "bad_request");
+        assert_eq!(body["error"], "Token counts are too large");
+    }
+
+    #[tokio::test]
+    async fn test_estimate_invalid_body() {
+        let response = post_json("/v1/estimate", json!({ "input_tokens": 5 })).await;
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(body_json(response).await["code"], "bad_request");
+    }
+}
@@ -0,0 +1,151 @@
+use axum::extract::State;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use utoipa::{IntoParams, ToSchema};
+
+use super::error::ErrorResponse;
+use super::{ApiError, ApiQuery, AppState, CatalogFilter};
+use crate::Model;
+
+/// Query parameters for GET /models/families, on top of [`CatalogFilter`]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct FamilyQuery {
+    /// Only include this family (e.g. `claude-sonnet`)
+    pub family: Option<String>,
+}
+
+/// A provider's models of one family
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ModelFamily {
+    /// Family name (e.g. `gpt-4o`)
+    pub family: String,
+
+    /// ID of the provider offering the models
+    pub provider_id: String,
+
+    /// ID of the newest active member, if any
+    pub latest_model_id: Option<String>,
+
+    /// Member IDs, newest first
+    pub model_ids: Vec<String>,
+}
+
+/// Number of predecessors of a model within its family
+fn generation(model: &Model, members: &[&Model]) -> usize {
+    let mut generation = 0;
+    let mut current = model;
+    while let Some(previous) = current
+        .previous_model_id
+        .as_deref()
+        .and_then(|id| members.iter().find(|m| m.id == id))
+    {
+        generation += 1;
+        // Stop on cycles
+        if generation >= members.len() {
+            break;
+        }
+        current = previous;
+    }
+    generation
+}
+
+/// Order a family's members newest first: by release date, then lineage
+fn newest_first(members: &mut Vec<&Model>) {
+    let generations: BTreeMap<&str, usize> = members
+        .iter()
+        .map(|m| (m.id.as_str(), generation(m, members)))
+        .collect();
+    members.sort_by(|a, b| {
+        (b.released_at, generations[b.id.as_str()])
+            .cmp(&(a.released_at, generations[a.id.as_str()]))
+            .then_with(|| a.id.cmp(&b.id))
+    });
+}
+
+/// GET /models/families - Model families and their latest members
+///
+/// Groups each provider's models by `family`, so clients pinned to a dated
+/// snapshot can find its newest sibling. Supports the same filters as
+/// `/models` (see [`CatalogFilter`]); models without a family are left out.
+#[utoipa::path(
+    get,
+    path = "/v1/models/families",
+    tag = "models",
+    params(CatalogFilter, FamilyQuery),
+    responses(
+        (status = 200, description = "Families, sorted by family then provider", body = [ModelFamily]),
+        (status = 400, description = "Invalid query parameters", body = ErrorResponse)
+    )
+)]
+pub async fn list_families(
+    State(state): State<AppState>,
+    ApiQuery(filter): ApiQuery<CatalogFilter>,
+    ApiQuery(query): ApiQuery<FamilyQuery>,
+) -> Result<Response, ApiError> {
+    let providers = filter.apply(&state.registry.providers());
+
+    let mut groups: BTreeMap<(&str, &str), Vec<&Model>> = BTreeMap::new();
+    for provider in &providers {
+        for model in &provider.models {
+            let Some(family) = model.family.as_deref() else {
+                continue;
+            };
+            if query.family.as_deref().is_some_and(|f| f != family) {
+                continue;
+            }
+            groups
+                .entry((family, provider.id.as_str()))
+                .or_default()
+                .push(model);
+        }
+    }
+
+    let families: Vec<ModelFamily> = groups
+        .into_iter()
+        .map(|((family, provider_id), mut members)| {
+            newest_first(&mut members);
+            ModelFamily {
+                family: family.to_string(),
+                provider_id: provider_id.to_string(),
+                latest_model_id: members.iter().find(|m| m.is_active()).map(|m| m.id.clone()),
+                model_ids: members.iter().map(|m| m.id.clone()).collect(),
+            }
+        })
+        .collect();
+
+    Ok(Json(families).into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::test_support::get_json;
+    use axum::http::StatusCode;
+
+    #[tokio::test]
+    async fn test_list_families() {
+        let (status, body) = get_json("/models/families?family=claude-sonnet").await;
+        assert_eq!(status, StatusCode::OK);
+        let families = body.as_array().unwrap();
+        assert!(families.iter().all(|f| f["family"] == "claude-sonnet"));
+        let anthropic = families
+            .iter()
+            .find(|f| f["provider_id"] == "anthropic")
+            .unwrap();
+        assert_eq!(anthropic["latest_model_id"], "claude-sonnet-4-5-20250929");
+        assert_eq!(anthropic["model_ids"].as_array().unwrap().len(), 1);
+
+        // Retired members are listed on request, after the current one
+        let (_, body) = get_json(
+            "/models/families?family=claude-sonnet&type=anthropic&include_deprecated=true",
+        )
+        .await;
+        assert_eq!(
+            body[0]["model_ids"],
+            serde_json::json!(["claude-sonnet-4-5-20250929", "claude-3-5-sonnet-20241022"])
+        );
+        assert_eq!(body[0]["latest_model_id"], "claude-sonnet-4-5-20250929");
+    }
+}
@@ -0,0 +1,446 @@
+use chrono::NaiveDate;
+use serde::Deserialize;
+use std::sync::Arc;
+use utoipa::IntoParams;
+
+use crate::{Modality, Model, ModelKind, Provider};
+
+/// Catalog filters accepted as query parameters on listing endpoints
+///
+/// Provider-level filters select which providers are returned; model-level
+/// filters prune each provider's `models` array. When any model-level filter
+/// is set, providers left without matching models are dropped. Deprecated
+/// and retired models (by `lifecycle`) are left out unless
+/// `include_deprecated` is set, and only chat
+/// models are listed unless `model_kind` asks for another kind.
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct CatalogFilter {
+    /// Only include providers of this type (e.g. `openai`)
+    #[serde(rename = "type")]
+    pub provider_type: Option<String>,
+
+    /// Only include models of this kind (`chat` by default, `embedding`, `rerank`, `image` or `audio`)
+    #[param(inline)]
+    pub model_kind: Option<ModelKind>,
+
+    /// Only include models with (or without) reasoning support
+    pub can_reason: Option<bool>,
+
+    /// Only include models with (or without) attachment support
+    pub supports_attachments: Option<bool>,
+
+    /// Only include models with (or without) tool calling support
+    pub supports_tools: Option<bool>,
+
+    /// Only include models with (or without) schema-constrained output
+    pub supports_structured_output: Option<bool>,
+
+    /// Only include models with (or without) streaming support
+    pub supports_streaming: Option<bool>,
+
+    /// Only include models with (or without) published, redistributable weights
+    pub open_weights: Option<bool>,
+
+    /// Only include open-weights models under this license (e.g. `apache-2.0`)
+    pub license: Option<String>,
+
+    /// Only include models with at least this context window
+    pub min_context_window: Option<u64>,
+
+    /// Only include models accepting this kind of input (`text`, `image`, `audio`, `video` or `pdf`)
+    #[param(inline)]
+    pub input_modality: Option<Modality>,
+
+    /// Only include models producing this kind of output
+    #[param(inline)]
+    pub output_modality: Option<Modality>,
+
+    /// Only include models whose training data ends on or after this date (e.g. `2024-06-01`)
+    #[param(value_type = Option<String>, format = Date)]
+    pub min_knowledge_cutoff: Option<NaiveDate>,
+
+    /// Only include models released on or after this date (e.g. `2025-07-01`)
+    #[param(value_type = Option<String>, format = Date)]
+    pub released_after: Option<NaiveDate>,
+
+    /// Only include models served from this region (e.g. `eu-west-1`)
+    pub region: Option<String>,
+
+    /// Only include models tagged with this tag, directly or through their provider (e.g. `coding`)
+    pub tag: Option<String>,
+
+    /// Only include models with a published score on this benchmark (e.g. `swe_bench_verified`);
+    /// also the benchmark `sort=benchmark` orders by
+    pub benchmark: Option<String>,
+
+    /// With `benchmark`, only include models scoring at least this much (percent)
+    pub min_benchmark_score: Option<f64>,
+
+    /// Also include deprecated and retired models
+    #[serde(default)]
+    pub include_deprecated: bool,
+}
+
+/// Query parameter opting in to deprecated models on endpoints without [`CatalogFilter`]
+#[derive(Debug, Clone, Copy, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DeprecationFilter {
+    /// Also include deprecated and retired models
+    #[serde(default)]
+    pub include_deprecated: bool,
+}
+
+impl DeprecationFilter {
+    /// Drop deprecated and retired models unless they were asked for
+    pub fn retain(&self, models: &mut Vec<Model>) {
+        if !self.include_deprecated {
+            models.retain(Model::is_active);
+        }
+    }
+}
+
+impl CatalogFilter {
+    /// Whether any model-level filter is set
+    pub fn has_model_filters(&self) -> bool {
+        self.model_kind.is_some()
+            || self.can_reason.is_some()
+            || self.supports_attachments.is_some()
+            || self.supports_tools.is_some()
+            || self.supports_structured_output.is_some()
+            || self.supports_streaming.is_some()
+            || self.open_weights.is_some()
+            || self.license.is_some()
+            || self.min_context_window.is_some()
+            || self.input_modality.is_some()
+            || self.output_modality.is_some()
+            || self.min_knowledge_cutoff.is_some()
+            || self.released_after.is_some()
+            || self.region.is_some()
+            || self.tag.is_some()
+            || self.benchmark.is_some()
+    }
+
+    /// Whether no filter is set, so only deprecated models are dropped
+    pub fn is_default(&self) -> bool {
+        self.provider_type.is_none() && !self.has_model_filters() && !self.include_deprecated
+    }
+
+    /// Check whether a provider passes the provider-level filters
+    pub fn matches_provider(&self, provider: &Provider) -> bool {
+        if let Some(provider_type) = &self.provider_type {
+            if !provider.provider_type.eq_ignore_ascii_case(provider_type) {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Check whether a model passes the model-level filters
+    pub fn matches_model(&self, model: &Model) -> bool {
+        if !model.is_active() && !self.include_deprecated {
+            return false;
+        }
+
+        if model.model_kind != self.model_kind.unwrap_or_default() {
+            return false;
+        }
+
+        if let Some(can_reason) = self.can_reason {
+            if model.can_reason != can_reason {
+                return false;
+            }
+        }
+
+        if let Some(supports_attachments) = self.supports_attachments {
+            if model.supports_attachments != supports_attachments {
+                return false;
+            }
+        }
+
+        if let Some(supports_tools) = self.supports_tools {
+            if model.supports_tools != supports_tools {
+                return false;
+            }
+        }
+
+        if let Some(supports_structured_output) = self.supports_structured_output {
+            if model.supports_structured_output != supports_structured_output {
+                return false;
+            }
+        }
+
+        if let Some(supports_streaming) = self.supports_streaming {
+            if model.supports_streaming != supports_streaming {
+                return false;
+            }
+        }
+
+        if let Some(open_weights) = self.open_weights {
+            if model.open_weights != open_weights {
+                return false;
+            }
+        }
+
+        if let Some(license) = &self.license {
+            if !model
+                .license
+                .as_deref()
+                .is_some_and(|l| l.eq_ignore_ascii_case(license))
+            {
+                return false;
+            }
+        }
+
+        if let Some(min_context_window) = self.min_context_window {
+            if model.context_window < min_context_window {
+                return false;
+            }
+        }
+
+        if let Some(modality) = self.input_modality {
+            if !model.accepts(modality) {
+                return false;
+            }
+        }
+
+        if let Some(modality) = self.output_modality {
+            if !model.produces(modality) {
+                return false;
+            }
+        }
+
+        if let Some(min) = self.min_knowledge_cutoff {
+            if model.knowledge_cutoff.map_or(true, |cutoff| cutoff < min) {
+                return false;
+            }
+        }
+
+        if let Some(after) = self.released_after {
+            if model.released_at.map_or(true, |released| released < after) {
+                return false;
+            }
+        }
+
+        if let Some(benchmark) = &self.benchmark {
+            let min = self.min_benchmark_score.unwrap_or(f64::NEG_INFINITY);
+            if !model.benchmark(benchmark).is_some_and(|score| score >= min) {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Check whether one of a provider's models passes the model-level filters,
+    /// including those that depend on the provider (region and tag)
+    pub fn matches(&self, provider: &Provider, model: &Model) -> bool {
+        if let Some(region) = &self.region {
+            if !provider.available_in(model, region) {
+                return false;
+            }
+        }
+
+        if let Some(tag) = &self.tag {
+            if !provider.has_tag(model, tag) {
+                return false;
+            }
+        }
+
+        self.matches_model(model)
+    }
+
+    /// Apply the filters to a list of providers
+    ///
+    /// Providers whose models all pass are shared with the input; only
+    /// providers that lose models are copied.
+    pub fn apply(&self, providers: &[Arc<Provider>]) -> Vec<Arc<Provider>> {
+        let drop_empty = self.has_model_filters();
+
+        providers
+            .iter()
+            .filter(|p| self.matches_provider(p))
+            .filter_map(|provider| {
+                let provider = if provider.models.iter().all(|m| self.matches(provider, m)) {
+                    Arc::clone(provider)
+                } else {
+                    let mut pruned = Provider::clone(provider);
+                    pruned.models.retain(|m| self.matches(provider, m));
+                    Arc::new(pruned)
+                };
+                if drop_empty && provider.models.is_empty() {
+                    return None;
+                }
+                Some(provider)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn provider(id: &str, provider_type: &str, models: Vec<Model>) -> Arc<Provider> {
+        Arc::new(
+            Provider::new(id.to_string(), id.to_string(), provider_type.to_string())
+                .with_models(models),
+        )
+    }
+
+    fn model(id: &str, context_window: u64, can_reason: bool) -> Model {
+        let mut model = Model::new(
+            id.to_string(),
+            id.to_string(),
+            1.0,
+            2.0,
+            context_window,
+            1000,
+        );
+        model.can_reason = can_reason;
+        model
+    }
+
+    #[test]
+    fn test_empty_filter_keeps_everything() {
+        let providers = vec![
+            provider("a", "openai", vec![]),
+            provider("b", "anthropic", vec![]),
+        ];
+        assert_eq!(CatalogFilter::default().apply(&providers).len(), 2);
+    }
+
+    #[test]
+    fn test_filter_by_type() {
+        let providers = vec![
+            provider("a", "openai", vec![]),
+            provider("b", "anthropic", vec![]),
+        ];
+        let filter = CatalogFilter {
+            provider_type: Some("openai".to_string()),
+            ..Default::default()
+        };
+        let result = filter.apply(&providers);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].id, "a");
+    }
+
+    #[test]
+    fn test_model_filters_prune_models_and_providers() {
+        let providers = vec![
+            provider(
+                "a",
+                "openai",
+                vec![model("small", 8_000, false), model("big", 200_000, true)],
+            ),
+            provider("b", "openai", vec![model("tiny", 4_000, false)]),
+        ];
+        let filter = CatalogFilter {
+            min_context_window: Some(100_000),
+            can_reason: Some(true),
+            ..Default::default()
+        };
+        let result = filter.apply(&providers);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].models.len(), 1);
+        assert_eq!(result[0].models[0].id, "big");
+        assert_eq!(providers[0].models.len(), 2);
+    }
+
+    #[test]
+    fn test_modality_filters() {
+        let mut audio = model("audio", 8_000, false);
+        audio.input_modalities.push(Modality::Audio);
+        let providers = vec![provider(
+            "a",
+            "openai",
+            vec![model("text", 8_000, false), audio],
+        )];
+
+        let filter = CatalogFilter {
+            input_modality: Some(Modality::Audio),
+            ..Default::default()
+        };
+        let result = filter.apply(&providers);
+        assert_eq!(result[0].models.len(), 1);
+        assert_eq!(result[0].models[0].id, "audio");
+
+        let filter = CatalogFilter {
+            output_modality: Some(Modality::Image),
+            ..Default::default()
+        };
+        assert!(filter.apply(&providers).is_empty());
+    }
+
+    #[test]
+    fn test_region_filter() {
+        let mut eu = model("eu", 8_000, false);
+        eu.regions = vec!["eu-west-1".to_string()];
+        let mut regional = Provider::new("a".to_string(), "a".to_string(), "bedrock".to_string());
+        regional.regions = vec!["us-east-1".to_string()];
+        let providers = vec![
+            Arc::new(regional.with_models(vec![model("us", 8_000, false), eu])),
+            provider("b", "openai", vec![model("anywhere", 8_000, false)]),
+        ];
+
+        let filter = CatalogFilter {
+            region: Some("EU-WEST-1".to_string()),
+            ..Default::default()
+        };
+        let result = filter.apply(&providers);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].models.len(), 1);
+        assert_eq!(result[0].models[0].id, "eu");
+
+        let filter = CatalogFilter {
+            region: Some("us-east-1".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(filter.apply(&providers)[0].models[0].id, "us");
+    }
+
+    #[test]
+    fn test_tag_filter() {
+        let mut coding = model("coder", 8_000, false);
+        coding.tags = vec!["coding".to_string()];
+        let mut local = Provider::new("b".to_string(), "b".to_string(), "ollama".to_string());
+        local.tags = vec!["local".to_string()];
+        let providers = vec![
+            provider("a", "openai", vec![model("chat", 8_000, false), coding]),
+            Arc::new(local.with_models(vec![model("llama", 8_000, false)])),
+        ];
+
+        let filter = CatalogFilter {
+            tag: Some("Coding".to_string()),
+            ..Default::default()
+        };
+        let result = filter.apply(&providers);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].models.len(), 1);
+        assert_eq!(result[0].models[0].id, "coder");
+
+        let filter = CatalogFilter {
+            tag: Some("local".to_string()),
+            ..Default::default()
+        };
+        let result = filter.apply(&providers);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].id, "b");
+    }
+
+    #[test]
+    fn test_unpruned_providers_are_shared() {
+        let mut deprecated = model("old", 8_000, false);
+        deprecated.deprecated = true;
+        let providers = vec![
+            provider("a", "openai", vec![model("new", 8_000, false)]),
+            provider("b", "openai", vec![model("new", 8_000, false), deprecated]),
+        ];
+
+        let result = CatalogFilter::default().apply(&providers);
+        assert!(Arc::ptr_eq(&result[0], &providers[0]));
+        assert!(!Arc::ptr_eq(&result[1], &providers[1]));
+        assert_eq!(result[1].models.len(), 1);
+    }
+}
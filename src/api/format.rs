@@ -0,0 +1,486 @@
+use axum::async_trait;
+use axum::body::{Body, Bytes};
+use axum::extract::FromRequestParts;
+use axum::http::request::Parts;
+use axum::http::{header, HeaderMap, HeaderValue};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use futures_util::stream;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::sync::Arc;
+
+use super::pagination::X_TOTAL_COUNT;
+use super::{ApiError, Pagination};
+use crate::{ImagePrice, Modality, Provider, ProviderModel, RateLimits};
+
+/// Media type for newline-delimited JSON
+pub const NDJSON: &str = "application/x-ndjson";
+
+/// Media type for CSV
+pub const CSV: &str = "text/csv; charset=utf-8";
+
+/// Media type for YAML
+pub const YAML: &str = "application/yaml";
+
+/// Media type for TOML
+pub const TOML: &str = "application/toml";
+
+/// TOML documents cannot have a top-level array, so lists are wrapped
+#[derive(Serialize)]
+struct TomlList<'a, T> {
+    data: &'a [T],
+}
+
+/// Representation negotiated from the `Accept` header for listing endpoints
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ResponseFormat {
+    /// A single JSON document (default)
+    #[default]
+    Json,
+    /// One JSON document per line, streamed as items are serialized
+    Ndjson,
+    /// Comma-separated values with a header row
+    Csv,
+    /// A single YAML document
+    Yaml,
+    /// A single TOML document (lists are wrapped in a `data` array of tables)
+    Toml,
+}
+
+impl ResponseFormat {
+    /// Pick the first supported media type listed in the `Accept` header
+    ///
+    /// Unknown or missing `Accept` values fall back to JSON.
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let Some(accept) = headers
+            .get(header::ACCEPT)
+            .and_then(|value| value.to_str().ok())
+        else {
+            return Self::Json;
+        };
+
+        accept
+            .split(',')
+            .filter_map(|media| {
+                let media = media.split(';').next()?.trim().to_ascii_lowercase();
+                match media.as_str() {
+                    "application/json" => Some(Self::Json),
+                    "application/x-ndjson" | "application/ndjson" => Some(Self::Ndjson),
+                    "text/csv" => Some(Self::Csv),
+                    "application/yaml" | "application/x-yaml" | "text/yaml" => Some(Self::Yaml),
+                    "application/toml" => Some(Self::Toml),
+                    _ => None,
+                }
+            })
+            .next()
+            .unwrap_or_default()
+    }
+
+    /// Render a listing in this format, applying pagination
+    ///
+    /// Always sets the `X-Total-Count` header. Document formats (JSON, YAML,
+    /// TOML) wrap paginated results in an envelope; line-oriented formats
+    /// (NDJSON, CSV) write the items of the requested page without one.
+    pub fn respond_list<T>(self, page: &Pagination, items: Vec<T>) -> Result<Response, ApiError>
+    where
+        T: Serialize + CsvRecord + Send + 'static,
+    {
+        page.validate()?;
+        let total = HeaderValue::from(items.len());
+
+        let mut response = match self {
+            Self::Json => return page.respond(items),
+            Self::Ndjson => ndjson_response(page_items(page, items)),
+            Self::Csv => csv_response(&page_items(page, items))?,
+            Self::Yaml | Self::Toml if page.is_requested() => {
+                self.respond_one(&page.paginate(items))?
+            }
+            Self::Yaml => self.respond_one(&items)?,
+            Self::Toml => self.respond_one(&TomlList { data: &items })?,
+        };
+        response.headers_mut().insert(X_TOTAL_COUNT, total);
+        Ok(response)
+    }
+
+    /// Render a single document in this format
+    ///
+    /// Line-oriented formats do not apply to single resources and fall back
+    /// to JSON.
+    pub fn respond_one<T: Serialize>(self, value: &T) -> Result<Response, ApiError> {
+        let (content_type, body) = match self {
+            Self::Yaml => (
+                YAML,
+                serde_yaml::to_string(value).map_err(|e| render_error("YAML", e))?,
+            ),
+            Self::Toml => (
+                TOML,
+                toml::to_string(value).map_err(|e| render_error("TOML", e))?,
+            ),
+            Self::Json | Self::Ndjson | Self::Csv => return Ok(Json(value).into_response()),
+        };
+
+        Ok((
+            [(header::CONTENT_TYPE, HeaderValue::from_static(content_type))],
+            body,
+        )
+            .into_response())
+    }
+}
+
+fn render_error(format: &str, error: impl std::fmt::Display) -> ApiError {
+    tracing::error!("Failed to render {}: {}", format, error);
+    ApiError::internal(format!("Failed to render {}", format))
+}
+
+/// Items of the requested page, without an envelope
+fn page_items<T>(page: &Pagination, items: Vec<T>) -> Vec<T> {
+    if page.is_requested() {
+        page.paginate(items).data
+    } else {
+        items
+    }
+}
+
+/// Flat tabular representation used for CSV output
+pub trait CsvRecord {
+    /// Column names, written as the header row
+    fn csv_header() -> &'static [&'static str];
+
+    /// Values for one row, in the same order as [`CsvRecord::csv_header`]
+    fn csv_record(&self) -> Vec<String>;
+}
+
+fn opt<T: ToString>(value: &Option<T>) -> String {
+    value.as_ref().map(ToString::to_string).unwrap_or_default()
+}
+
+/// Modalities as one `;`-separated cell (e.g. `text;image`)
+fn modalities(modalities: &[Modality]) -> String {
+    let names: Vec<_> = modalities.iter().map(|m| m.as_str()).collect();
+    names.join(";")
+}
+
+/// Image price tiers as one `;`-separated cell (e.g. `1024x1024/low=0.011;1024x1024/high=0.167`)
+fn image_prices(tiers: &[ImagePrice]) -> String {
+    let tiers: Vec<_> = tiers
+        .iter()
+        .map(|tier| match &tier.quality {
+            Some(quality) => format!("{}/{}={}", tier.size, quality, tier.cost),
+            None => format!("{}={}", tier.size, tier.cost),
+        })
+        .collect();
+    tiers.join(";")
+}
+
+/// Rate limits as `tier=500rpm/30000tpm` entries separated by `;`
+fn rate_limits(limits: &[RateLimits]) -> String {
+    let limits: Vec<_> = limits
+        .iter()
+        .map(|limits| {
+            let values: Vec<_> = [
+                (limits.requests_per_minute, "rpm"),
+                (limits.tokens_per_minute, "tpm"),
+                (limits.tokens_per_day, "tpd"),
+            ]
+            .iter()
+            .filter_map(|(value, unit)| value.map(|value| format!("{}{}", value, unit)))
+            .collect();
+            match &limits.tier {
+                Some(tier) => format!("{}={}", tier, values.join("/")),
+                None => values.join("/"),
+            }
+        })
+        .collect();
+    limits.join(";")
+}
+
+/// Benchmark scores as `benchmark=score` entries separated by `;`, sorted by benchmark
+fn benchmarks(scores: &HashMap<String, f64>) -> String {
+    let mut scores: Vec<_> = scores
+        .iter()
+        .map(|(benchmark, score)| format!("{}={}", benchmark, score))
+        .collect();
+    scores.sort();
+    scores.join(";")
+}
+
+impl CsvRecord for ProviderModel {
+    fn csv_header() -> &'static [&'static str] {
+        &[
+            "provider_id",
+            "id",
+            "name",
+            "cost_per_1m_in",
+            "cost_per_1m_out",
+            "cost_per_1m_in_cached",
+            "cost_per_1m_out_cached",
+            "cost_per_1m_in_batch",
+            "cost_per_1m_out_batch",
+            "cost_per_1m_reasoning",
+            "cost_per_image",
+            "cost_per_audio_minute",
+            "cost_per_1m_characters",
+            "cost_per_request",
+            "cost_per_tool_call",
+            "context_window",
+            "default_max_tokens",
+            "max_output_tokens",
+            "model_kind",
+            "family",
+            "previous_model_id",
+            "base_model_id",
+            "variant",
+            "quantization",
+            "open_weights",
+            "license",
+            "tags",
+            "benchmarks",
+            "dimensions",
+            "max_input_tokens",
+            "tokenizer",
+            "voices",
+            "languages",
+            "rate_limits",
+            "regions",
+            "median_ttft_ms",
+            "median_tokens_per_second",
+            "can_reason",
+            "has_reasoning_efforts",
+            "default_reasoning_effort",
+            "supports_attachments",
+            "supports_tools",
+            "supports_parallel_tool_calls",
+            "supports_structured_output",
+            "supports_streaming",
+            "input_modalities",
+            "output_modalities",
+            "knowledge_cutoff",
+            "released_at",
+            "lifecycle",
+            "deprecated",
+            "deprecated_at",
+            "retires_at",
+            "deprecation_date",
+            "replacement_model_id",
+        ]
+    }
+
+    fn csv_record(&self) -> Vec<String> {
+        let model = &self.model;
+        vec![
+            self.provider_id.clone(),
+            model.id.clone(),
+            model.name.clone(),
+            model.cost_per_1m_in.to_string(),
+            model.cost_per_1m_out.to_string(),
+            opt(&model.cost_per_1m_in_cached),
+            opt(&model.cost_per_1m_out_cached),
+            opt(&model.cost_per_1m_in_batch),
+            opt(&model.cost_per_1m_out_batch),
+            opt(&model.cost_per_1m_reasoning),
+            image_prices(&model.cost_per_image),
+            opt(&model.cost_per_audio_minute),
+            opt(&model.cost_per_1m_characters),
+            opt(&model.cost_per_request),
+            opt(&model.cost_per_tool_call),
+            model.context_window.to_string(),
+            model.default_max_tokens.to_string(),
+            opt(&model.max_output_tokens),
+            model.model_kind.to_string(),
+            opt(&model.family),
+            opt(&model.previous_model_id),
+            opt(&model.base_model_id),
+            opt(&model.variant),
+            opt(&model.quantization),
+            model.open_weights.to_string(),
+            opt(&model.license),
+            model.tags.join(";"),
+            benchmarks(&model.benchmarks),
+            opt(&model.dimensions),
+            opt(&model.max_input_tokens),
+            opt(&model.tokenizer),
+            model.voices.join(";"),
+            model.languages.join(";"),
+            rate_limits(&model.limits),
+            model.regions.join(";"),
+            opt(&model.median_ttft_ms),
+            opt(&model.median_tokens_per_second),
+            model.can_reason.to_string(),
+            model.has_reasoning_efforts.to_string(),
+            opt(&model.default_reasoning_effort),
+            model.supports_attachments.to_string(),
+            model.supports_tools.to_string(),
+            model.supports_parallel_tool_calls.to_string(),
+            model.supports_structured_output.to_string(),
+            model.supports_streaming.to_string(),
+            modalities(&model.input_modalities),
+            modalities(&model.output_modalities),
+            opt(&model.knowledge_cutoff),
+            opt(&model.released_at),
+            model.lifecycle.to_string(),
+            model.deprecated.to_string(),
+            opt(&model.deprecated_at),
+            opt(&model.retires_at),
+            opt(&model.deprecation_date),
+            opt(&model.replacement_model_id),
+        ]
+    }
+}
+
+impl<T: CsvRecord> CsvRecord for Arc<T> {
+    fn csv_header() -> &'static [&'static str] {
+        T::csv_header()
+    }
+
+    fn csv_record(&self) -> Vec<String> {
+        T::csv_record(self)
+    }
+}
+
+impl CsvRecord for Provider {
+    fn csv_header() -> &'static [&'static str] {
+        &[
+            "id",
+            "name",
+            "type",
+            "api_endpoint",
+            "default_large_model_id",
+            "default_small_model_id",
+            "model_count",
+        ]
+    }
+
+    fn csv_record(&self) -> Vec<String> {
+        vec![
+            self.id.clone(),
+            self.name.clone(),
+            self.provider_type.clone(),
+            opt(&self.api_endpoint),
+            opt(&self.default_large_model_id),
+            opt(&self.default_small_model_id),
+            self.models.len().to_string(),
+        ]
+    }
+}
+
+/// Render items as CSV with a header row
+fn csv_response<T: CsvRecord>(items: &[T]) -> Result<Response, ApiError> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+
+    let write = |writer: &mut csv::Writer<Vec<u8>>| -> csv::Result<()> {
+        writer.write_record(T::csv_header())?;
+        for item in items {
+            writer.write_record(item.csv_record())?;
+        }
+        writer.flush()?;
+        Ok(())
+    };
+    write(&mut writer).map_err(|e| render_error("CSV", e))?;
+
+    let body = writer.into_inner().map_err(|e| render_error("CSV", e))?;
+
+    Ok((
+        [(header::CONTENT_TYPE, HeaderValue::from_static(CSV))],
+        body,
+    )
+        .into_response())
+}
+
+/// Stream items as newline-delimited JSON
+fn ndjson_response<T>(items: Vec<T>) -> Response
+where
+    T: Serialize + Send + 'static,
+{
+    let lines = stream::iter(items.into_iter().map(|item| {
+        let mut line = serde_json::to_vec(&item)?;
+        line.push(b'\n');
+        Ok::<_, serde_json::Error>(Bytes::from(line))
+    }));
+
+    (
+        [(header::CONTENT_TYPE, HeaderValue::from_static(NDJSON))],
+        Body::from_stream(lines),
+    )
+        .into_response()
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for ResponseFormat
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(Self::from_headers(&parts.headers))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn accept(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::ACCEPT, HeaderValue::from_str(value).unwrap());
+        headers
+    }
+
+    #[test]
+    fn test_negotiation() {
+        assert_eq!(
+            ResponseFormat::from_headers(&HeaderMap::new()),
+            ResponseFormat::Json
+        );
+        assert_eq!(
+            ResponseFormat::from_headers(&accept("*/*")),
+            ResponseFormat::Json
+        );
+        assert_eq!(
+            ResponseFormat::from_headers(&accept("application/x-ndjson")),
+            ResponseFormat::Ndjson
+        );
+        assert_eq!(
+            ResponseFormat::from_headers(&accept("text/html, application/ndjson;q=0.9")),
+            ResponseFormat::Ndjson
+        );
+        assert_eq!(
+            ResponseFormat::from_headers(&accept("application/json, application/x-ndjson")),
+            ResponseFormat::Json
+        );
+        assert_eq!(
+            ResponseFormat::from_headers(&accept("text/csv")),
+            ResponseFormat::Csv
+        );
+        assert_eq!(
+            ResponseFormat::from_headers(&accept("application/x-yaml")),
+            ResponseFormat::Yaml
+        );
+        assert_eq!(
+            ResponseFormat::from_headers(&accept("application/toml")),
+            ResponseFormat::Toml
+        );
+    }
+
+    #[tokio::test]
+    async fn test_csv_quoting() {
+        let provider = Provider::new(
+            "Name, with comma".to_string(),
+            "test".to_string(),
+            "openai".to_string(),
+        );
+        let response = csv_response(&[provider]).unwrap();
+        assert_eq!(response.headers()[header::CONTENT_TYPE], CSV);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        assert_eq!(
+            body.lines().nth(1).unwrap(),
+            r#"test,"Name, with comma",openai,,,,0"#
+        );
+    }
+}
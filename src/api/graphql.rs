@@ -0,0 +1,676 @@
+//! GraphQL query endpoint (enabled with the `graphql` feature)
+//!
+//! Exposes providers and models as a graph so clients can request exactly the
+//! nested shape they need, e.g. all vision-capable models together with their
+//! provider's `apiEndpoint`.
+//!
+//! Models link back to their provider, so queries can nest without end;
+//! their depth and complexity are capped by the `[graphql]` configuration.
+
+use async_graphql::{Context, EmptyMutation, EmptySubscription, Object, Schema};
+use axum::extract::State;
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Json};
+use std::sync::Arc;
+
+use super::{AppState, CatalogFilter, DeprecationFilter};
+use crate::config::GraphqlConfig;
+use crate::providers::registry::ProviderRegistry;
+use crate::{ImagePrice, Model, Provider, RateLimits};
+
+/// GraphQL schema type served by the endpoint
+pub type CrabraceSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+/// Complexity of a list of objects, relative to one of its items
+const LIST_COMPLEXITY: usize = 10;
+
+/// Build the schema with the configured limits; it is stateless, the
+/// registry is attached per request
+pub fn schema(config: &GraphqlConfig) -> CrabraceSchema {
+    Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .limit_depth(config.max_depth)
+        .limit_complexity(config.max_complexity)
+        .finish()
+}
+
+/// Root query type
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// All providers, optionally filtered like `GET /providers`
+    // One argument per query parameter of the REST endpoint
+    #[allow(clippy::too_many_arguments)]
+    #[graphql(complexity = "LIST_COMPLEXITY * child_complexity")]
+    async fn providers(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(name = "type")] provider_type: Option<String>,
+        model_kind: Option<String>,
+        can_reason: Option<bool>,
+        supports_attachments: Option<bool>,
+        supports_tools: Option<bool>,
+        supports_structured_output: Option<bool>,
+        supports_streaming: Option<bool>,
+        open_weights: Option<bool>,
+        license: Option<String>,
+        min_context_window: Option<u64>,
+        input_modality: Option<String>,
+        output_modality: Option<String>,
+        min_knowledge_cutoff: Option<String>,
+        released_after: Option<String>,
+        region: Option<String>,
+        tag: Option<String>,
+        benchmark: Option<String>,
+        min_benchmark_score: Option<f64>,
+        #[graphql(default)] include_deprecated: bool,
+    ) -> async_graphql::Result<Vec<ProviderObject>> {
+        let filter = CatalogFilter {
+            provider_type,
+            model_kind: model_kind.map(|k| k.parse()).transpose()?,
+            can_reason,
+            supports_attachments,
+            supports_tools,
+            supports_structured_output,
+            supports_streaming,
+            open_weights,
+            license,
+            min_context_window,
+            input_modality: input_modality.map(|m| m.parse()).transpose()?,
+            output_modality: output_modality.map(|m| m.parse()).transpose()?,
+            min_knowledge_cutoff: min_knowledge_cutoff.map(|d| d.parse()).transpose()?,
+            released_after: released_after.map(|d| d.parse()).transpose()?,
+            region,
+            tag,
+            benchmark,
+            min_benchmark_score,
+            include_deprecated,
+        };
+        Ok(filter
+            .apply(&registry(ctx)?.providers())
+            .into_iter()
+            .map(ProviderObject)
+            .collect())
+    }
+
+    /// A single provider by ID
+    async fn provider(
+        &self,
+        ctx: &Context<'_>,
+        id: String,
+        #[graphql(default)] include_deprecated: bool,
+    ) -> async_graphql::Result<Option<ProviderObject>> {
+        let filter = DeprecationFilter { include_deprecated };
+        Ok(registry(ctx)?.get_by_id(&id)?.map(|mut provider| {
+            filter.retain(&mut provider.models);
+            ProviderObject(Arc::new(provider))
+        }))
+    }
+
+    /// Models across all providers, optionally filtered like `GET /models`
+    #[allow(clippy::too_many_arguments)]
+    #[graphql(complexity = "LIST_COMPLEXITY * child_complexity")]
+    async fn models(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(name = "type")] provider_type: Option<String>,
+        model_kind: Option<String>,
+        can_reason: Option<bool>,
+        supports_attachments: Option<bool>,
+        supports_tools: Option<bool>,
+        supports_structured_output: Option<bool>,
+        supports_streaming: Option<bool>,
+        open_weights: Option<bool>,
+        license: Option<String>,
+        min_context_window: Option<u64>,
+        input_modality: Option<String>,
+        output_modality: Option<String>,
+        min_knowledge_cutoff: Option<String>,
+        released_after: Option<String>,
+        region: Option<String>,
+        tag: Option<String>,
+        benchmark: Option<String>,
+        min_benchmark_score: Option<f64>,
+        #[graphql(default)] include_deprecated: bool,
+    ) -> async_graphql::Result<Vec<ModelObject>> {
+        let filter = CatalogFilter {
+            provider_type,
+            model_kind: model_kind.map(|k| k.parse()).transpose()?,
+            can_reason,
+            supports_attachments,
+            supports_tools,
+            supports_structured_output,
+            supports_streaming,
+            open_weights,
+            license,
+            min_context_window,
+            input_modality: input_modality.map(|m| m.parse()).transpose()?,
+            output_modality: output_modality.map(|m| m.parse()).transpose()?,
+            min_knowledge_cutoff: min_knowledge_cutoff.map(|d| d.parse()).transpose()?,
+            released_after: released_after.map(|d| d.parse()).transpose()?,
+            region,
+            tag,
+            benchmark,
+            min_benchmark_score,
+            include_deprecated,
+        };
+        Ok(filter
+            .apply(&registry(ctx)?.providers())
+            .iter()
+            .flat_map(|provider| {
+                provider.models.iter().map(|model| ModelObject {
+                    provider_id: provider.id.clone(),
+                    model: model.clone(),
+                })
+            })
+            .collect())
+    }
+}
+
+fn registry<'a>(ctx: &Context<'a>) -> async_graphql::Result<&'a Arc<ProviderRegistry>> {
+    ctx.data::<Arc<ProviderRegistry>>()
+}
+
+/// GraphQL view of a [`Provider`]
+pub struct ProviderObject(Arc<Provider>);
+
+#[Object(name = "Provider")]
+impl ProviderObject {
+    async fn id(&self) -> &str {
+        &self.0.id
+    }
+
+    async fn name(&self) -> &str {
+        &self.0.name
+    }
+
+    #[graphql(name = "type")]
+    async fn provider_type(&self) -> &str {
+        &self.0.provider_type
+    }
+
+    async fn api_endpoint(&self) -> Option<&str> {
+        self.0.api_endpoint.as_deref()
+    }
+
+    async fn default_large_model_id(&self) -> Option<&str> {
+        self.0.default_large_model_id.as_deref()
+    }
+
+    async fn default_small_model_id(&self) -> Option<&str> {
+        self.0.default_small_model_id.as_deref()
+    }
+
+    /// Rate limits shared by the provider's models, by account tier
+    async fn limits(&self) -> Vec<RateLimitsObject> {
+        self.0
+            .limits
+            .iter()
+            .cloned()
+            .map(RateLimitsObject)
+            .collect()
+    }
+
+    /// Regions every model is served from, unless the model lists its own
+    async fn regions(&self) -> &[String] {
+        &self.0.regions
+    }
+
+    /// Curation tags applying to every model
+    async fn tags(&self) -> &[String] {
+        &self.0.tags
+    }
+
+    #[graphql(complexity = "LIST_COMPLEXITY * child_complexity")]
+    async fn models(&self) -> Vec<ModelObject> {
+        self.0
+            .models
+            .iter()
+            .map(|model| ModelObject {
+                provider_id: self.0.id.clone(),
+                model: model.clone(),
+            })
+            .collect()
+    }
+}
+
+/// GraphQL view of an [`ImagePrice`]
+pub struct ImagePriceObject(ImagePrice);
+
+#[Object(name = "ImagePrice")]
+impl ImagePriceObject {
+    async fn size(&self) -> &str {
+        &self.0.size
+    }
+
+    async fn quality(&self) -> Option<&str> {
+        self.0.quality.as_deref()
+    }
+
+    async fn cost(&self) -> f64 {
+        self.0.cost
+    }
+}
+
+/// GraphQL view of [`RateLimits`]
+pub struct RateLimitsObject(RateLimits);
+
+#[Object(name = "RateLimits")]
+impl RateLimitsObject {
+    async fn tier(&self) -> Option<&str> {
+        self.0.tier.as_deref()
+    }
+
+    async fn requests_per_minute(&self) -> Option<u64> {
+        self.0.requests_per_minute
+    }
+
+    async fn tokens_per_minute(&self) -> Option<u64> {
+        self.0.tokens_per_minute
+    }
+
+    async fn tokens_per_day(&self) -> Option<u64> {
+        self.0.tokens_per_day
+    }
+}
+
+/// A model's score on one benchmark
+pub struct BenchmarkScoreObject {
+    benchmark: String,
+    score: f64,
+}
+
+#[Object(name = "BenchmarkScore")]
+impl BenchmarkScoreObject {
+    async fn benchmark(&self) -> &str {
+        &self.benchmark
+    }
+
+    /// Score, in percent
+    async fn score(&self) -> f64 {
+        self.score
+    }
+}
+
+/// GraphQL view of a [`Model`] together with its provider
+pub struct ModelObject {
+    provider_id: String,
+    model: Model,
+}
+
+#[Object(name = "Model")]
+impl ModelObject {
+    async fn id(&self) -> &str {
+        &self.model.id
+    }
+
+    async fn name(&self) -> &str {
+        &self.model.name
+    }
+
+    /// What the model is for (`chat`, `embedding`, `rerank`, `image` or `audio`)
+    async fn model_kind(&self) -> &str {
+        self.model.model_kind.as_str()
+    }
+
+    /// Product line across snapshots (e.g. `claude-sonnet`)
+    async fn family(&self) -> Option<&str> {
+        self.model.family.as_deref()
+    }
+
+    /// ID of the model this one succeeds
+    async fn previous_model_id(&self) -> Option<&str> {
+        self.model.previous_model_id.as_deref()
+    }
+
+    /// ID of the reference model this one is a serving variant of
+    async fn base_model_id(&self) -> Option<&str> {
+        self.model.base_model_id.as_deref()
+    }
+
+    /// How this variant is served (e.g. `speculative`)
+    async fn variant(&self) -> Option<&str> {
+        self.model.variant.as_deref()
+    }
+
+    /// Precision the weights are served at (e.g. `fp8`)
+    async fn quantization(&self) -> Option<&str> {
+        self.model.quantization.as_deref()
+    }
+
+    /// Whether the weights are published and redistributable
+    async fn open_weights(&self) -> bool {
+        self.model.open_weights
+    }
+
+    /// License of the weights (e.g. `apache-2.0`)
+    async fn license(&self) -> Option<&str> {
+        self.model.license.as_deref()
+    }
+
+    /// Curation tags (e.g. `coding`), not including the provider's
+    async fn tags(&self) -> &[String] {
+        &self.model.tags
+    }
+
+    /// Published benchmark scores, sorted by benchmark
+    async fn benchmarks(&self) -> Vec<BenchmarkScoreObject> {
+        let mut benchmarks: Vec<BenchmarkScoreObject> = self
+            .model
+            .benchmarks
+            .iter()
+            .map(|(benchmark, score)| BenchmarkScoreObject {
+                benchmark: benchmark.clone(),
+                score: *score,
+            })
+            .collect();
+        benchmarks.sort_by(|a, b| a.benchmark.cmp(&b.benchmark));
+        benchmarks
+    }
+
+    async fn provider_id(&self) -> &str {
+        &self.provider_id
+    }
+
+    /// The provider offering this model
+    async fn provider(&self, ctx: &Context<'_>) -> async_graphql::Result<Option<ProviderObject>> {
+        Ok(registry(ctx)?
+            .get_by_id(&self.provider_id)?
+            .map(|provider| ProviderObject(Arc::new(provider))))
+    }
+
+    async fn cost_per_1m_in(&self) -> f64 {
+        self.model.cost_per_1m_in
+    }
+
+    async fn cost_per_1m_out(&self) -> f64 {
+        self.model.cost_per_1m_out
+    }
+
+    async fn cost_per_1m_in_cached(&self) -> Option<f64> {
+        self.model.cost_per_1m_in_cached
+    }
+
+    async fn cost_per_1m_out_cached(&self) -> Option<f64> {
+        self.model.cost_per_1m_out_cached
+    }
+
+    /// Input price through the batch API
+    async fn cost_per_1m_in_batch(&self) -> Option<f64> {
+        self.model.cost_per_1m_in_batch
+    }
+
+    /// Output price through the batch API
+    async fn cost_per_1m_out_batch(&self) -> Option<f64> {
+        self.model.cost_per_1m_out_batch
+    }
+
+    /// Reasoning token price, when it differs from the output price
+    async fn cost_per_1m_reasoning(&self) -> Option<f64> {
+        self.model.cost_per_1m_reasoning
+    }
+
+    /// Cost per generated image, by size and quality tier
+    async fn cost_per_image(&self) -> Vec<ImagePriceObject> {
+        self.model
+            .cost_per_image
+            .iter()
+            .cloned()
+            .map(ImagePriceObject)
+            .collect()
+    }
+
+    async fn cost_per_audio_minute(&self) -> Option<f64> {
+        self.model.cost_per_audio_minute
+    }
+
+    async fn cost_per_1m_characters(&self) -> Option<f64> {
+        self.model.cost_per_1m_characters
+    }
+
+    async fn cost_per_request(&self) -> Option<f64> {
+        self.model.cost_per_request
+    }
+
+    async fn cost_per_tool_call(&self) -> Option<f64> {
+        self.model.cost_per_tool_call
+    }
+
+    async fn context_window(&self) -> u64 {
+        self.model.context_window
+    }
+
+    async fn default_max_tokens(&self) -> u64 {
+        self.model.default_max_tokens
+    }
+
+    /// Hard cap on output tokens per request
+    async fn max_output_tokens(&self) -> Option<u64> {
+        self.model.max_output_tokens
+    }
+
+    async fn dimensions(&self) -> Option<u64> {
+        self.model.dimensions
+    }
+
+    async fn max_input_tokens(&self) -> Option<u64> {
+        self.model.max_input_tokens
+    }
+
+    /// Tokenizer the model counts tokens with (e.g. `o200k_base`)
+    async fn tokenizer(&self) -> Option<&str> {
+        self.model.tokenizer.as_deref()
+    }
+
+    async fn voices(&self) -> &[String] {
+        &self.model.voices
+    }
+
+    async fn languages(&self) -> &[String] {
+        &self.model.languages
+    }
+
+    /// Rate limits by account tier, when they differ from the provider's
+    async fn limits(&self) -> Vec<RateLimitsObject> {
+        self.model
+            .limits
+            .iter()
+            .cloned()
+            .map(RateLimitsObject)
+            .collect()
+    }
+
+    /// Regions the model is served from, when they differ from the provider's
+    async fn regions(&self) -> &[String] {
+        &self.model.regions
+    }
+
+    /// Median time to first token, in milliseconds
+    async fn median_ttft_ms(&self) -> Option<u64> {
+        self.model.median_ttft_ms
+    }
+
+    /// Median output speed, in tokens per second
+    async fn median_tokens_per_second(&self) -> Option<f64> {
+        self.model.median_tokens_per_second
+    }
+
+    async fn can_reason(&self) -> bool {
+        self.model.can_reason
+    }
+
+    async fn has_reasoning_efforts(&self) -> bool {
+        self.model.has_reasoning_efforts
+    }
+
+    async fn default_reasoning_effort(&self) -> Option<&str> {
+        self.model.default_reasoning_effort.as_deref()
+    }
+
+    async fn supports_attachments(&self) -> bool {
+        self.model.supports_attachments
+    }
+
+    async fn supports_tools(&self) -> bool {
+        self.model.supports_tools
+    }
+
+    async fn supports_parallel_tool_calls(&self) -> bool {
+        self.model.supports_parallel_tool_calls
+    }
+
+    async fn supports_structured_output(&self) -> bool {
+        self.model.supports_structured_output
+    }
+
+    async fn supports_streaming(&self) -> bool {
+        self.model.supports_streaming
+    }
+
+    /// Kinds of content the model accepts (`text`, `image`, `audio`, `video`, `pdf`)
+    async fn input_modalities(&self) -> Vec<&str> {
+        self.model
+            .input_modalities
+            .iter()
+            .map(|m| m.as_str())
+            .collect()
+    }
+
+    /// Kinds of content the model produces
+    async fn output_modalities(&self) -> Vec<&str> {
+        self.model
+            .output_modalities
+            .iter()
+            .map(|m| m.as_str())
+            .collect()
+    }
+
+    async fn deprecated(&self) -> bool {
+        self.model.deprecated
+    }
+
+    /// Date the model's training data ends (`YYYY-MM-DD`)
+    async fn knowledge_cutoff(&self) -> Option<String> {
+        self.model.knowledge_cutoff.map(|date| date.to_string())
+    }
+
+    /// Release date (`YYYY-MM-DD`)
+    async fn released_at(&self) -> Option<String> {
+        self.model.released_at.map(|date| date.to_string())
+    }
+
+    /// Date the model is deprecated from (`YYYY-MM-DD`)
+    async fn deprecated_at(&self) -> Option<String> {
+        self.model.deprecated_at.map(|date| date.to_string())
+    }
+
+    /// Retirement date (`YYYY-MM-DD`)
+    async fn retires_at(&self) -> Option<String> {
+        self.model.retires_at.map(|date| date.to_string())
+    }
+
+    /// Retirement date (`YYYY-MM-DD`); same as `retiresAt`
+    async fn deprecation_date(&self) -> Option<String> {
+        self.model.deprecation_date.map(|date| date.to_string())
+    }
+
+    /// `active`, `deprecated` or `retired`
+    async fn lifecycle(&self) -> &str {
+        self.model.lifecycle.as_str()
+    }
+
+    async fn replacement_model_id(&self) -> Option<&str> {
+        self.model.replacement_model_id.as_deref()
+    }
+}
+
+/// POST /graphql - Execute a GraphQL query
+pub async fn graphql(
+    State(state): State<AppState>,
+    Extension(schema): Extension<CrabraceSchema>,
+    Json(request): Json<async_graphql::Request>,
+) -> Response {
+    let response = schema.execute(request.data(state.registry.clone())).await;
+    Json(response).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::test_support::{app_with_config, body_json};
+    use crate::Config;
+    use axum::body::Body;
+    use axum::http::{header, Request, StatusCode};
+    use tower::ServiceExt;
+
+    async fn query(query: &str) -> serde_json::Value {
+        query_with(&Config::default(), query).await
+    }
+
+    async fn query_with(config: &Config, query: &str) -> serde_json::Value {
+        let request = Request::post("/v1/graphql")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(
+                serde_json::json!({ "query": query }).to_string(),
+            ))
+            .unwrap();
+        let response = app_with_config(config).oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        body_json(response).await
+    }
+
+    #[tokio::test]
+    async fn test_query_provider() {
+        let body = query(r#"{ provider(id: "anthropic") { id name models { id } } }"#).await;
+        assert_eq!(body["data"]["provider"]["id"], "anthropic");
+        assert!(!body["data"]["provider"]["models"]
+            .as_array()
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_query_models_with_provider() {
+        let body =
+            query("{ models(supportsAttachments: true) { id provider { id apiEndpoint } } }").await;
+        let models = body["data"]["models"].as_array().unwrap();
+        assert!(!models.is_empty());
+        assert!(models.iter().all(|m| m["provider"]["id"].is_string()));
+    }
+
+    #[tokio::test]
+    async fn test_query_error() {
+        let body = query("{ unknownField }").await;
+        assert!(body["errors"].is_array());
+    }
+
+    #[tokio::test]
+    async fn test_query_limits() {
+        // Models and providers link to each other without end
+        let mut nested = "id".to_string();
+        for _ in 0..6 {
+            nested = format!("models {{ provider {{ {} }} }}", nested);
+        }
+        let deep = format!("{{ providers {{ {} }} }}", nested);
+        let body = query(&deep).await;
+        assert!(body["data"].is_null());
+        assert_eq!(body["errors"][0]["message"], "Query is too complex.");
+
+        let mut config = Config::default();
+        config.graphql.max_complexity = 1_000_000_000;
+        let body = query_with(&config, &deep).await;
+        assert_eq!(body["errors"][0]["message"], "Query is nested too deep.");
+
+        // Lists multiply the complexity of their items
+        let body =
+            query("{ providers { models { provider { models { provider { id } } } } } }").await;
+        assert_eq!(body["errors"][0]["message"], "Query is too complex.");
+        let body = query("{ providers { models { provider { id } } } }").await;
+        assert!(body["errors"].is_null());
+
+        config.graphql.max_depth = 2;
+        let body = query_with(&config, r#"{ provider(id: "openai") { models { id } } }"#).await;
+        assert_eq!(body["errors"][0]["message"], "Query is nested too deep.");
+        let body = query_with(&config, r#"{ provider(id: "openai") { id } }"#).await;
+        assert_eq!(body["data"]["provider"]["id"], "openai");
+    }
+}
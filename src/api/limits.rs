@@ -0,0 +1,170 @@
+//! Request body size, time and method restrictions
+//!
+//! Oversized bodies are rejected with 413 before reaching a handler, requests
+//! that take longer than `server.timeout_seconds` are cut off with 408, and
+//! requests using a method a route does not serve get a JSON 405 (with the
+//! `Allow` header axum computes), so error bodies stay consistent with the
+//! rest of the API.
+
+use axum::extract::{Request, State};
+use axum::http::header;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use std::time::Duration;
+
+use super::ApiError;
+
+/// Middleware rejecting requests whose declared `Content-Length` exceeds `max_bytes`
+///
+/// Chunked bodies without a length are capped by `DefaultBodyLimit` when
+/// extracted instead.
+pub async fn limit_body_size(
+    State(max_bytes): State<usize>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let content_length = request
+        .headers()
+        .get(header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<u64>().ok());
+
+    match content_length {
+        Some(length) if length > max_bytes as u64 => {
+            ApiError::payload_too_large(max_bytes).into_response()
+        }
+        _ => next.run(request).await,
+    }
+}
+
+/// Middleware aborting requests that take longer than `timeout`
+///
+/// The handler future is dropped when the budget runs out, so no further
+/// work is done on behalf of the client.
+pub async fn limit_duration(
+    State(timeout): State<Duration>,
+    request: Request,
+    next: Next,
+) -> Response {
+    match tokio::time::timeout(timeout, next.run(request)).await {
+        Ok(response) => response,
+        Err(_) => {
+            tracing::warn!("Request exceeded the {:?} timeout", timeout);
+            ApiError::request_timeout(timeout).into_response()
+        }
+    }
+}
+
+/// Fallback for known paths requested with an unsupported method
+pub async fn method_not_allowed(request: Request) -> ApiError {
+    ApiError::method_not_allowed(request.method())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::test_support::{body_json, get_with, send, send_with};
+    use crate::api::AppState;
+    use crate::providers::registry::ProviderRegistry;
+    use crate::Config;
+    use axum::body::Body;
+    use axum::http::{header, Request, StatusCode};
+    use axum::routing::get;
+    use axum::Router;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_method_not_allowed() {
+        let response = send(
+            Request::delete("/v1/providers")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert!(response.headers()[header::ALLOW]
+            .to_str()
+            .unwrap()
+            .contains("GET"));
+
+        let body = body_json(response).await;
+        assert_eq!(body["code"], "method_not_allowed");
+        assert!(body["request_id"].is_string());
+
+        let response = send(Request::get("/v1/estimate").body(Body::empty()).unwrap()).await;
+        assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+
+        // Unknown paths are still 404
+        let response = send(Request::delete("/v1/nope").body(Body::empty()).unwrap()).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_request_timeout() {
+        let app = Router::new()
+            .route(
+                "/slow",
+                get(|| async {
+                    tokio::time::sleep(Duration::from_secs(5)).await;
+                    "done"
+                }),
+            )
+            .route("/fast", get(|| async { "done" }))
+            .layer(axum::middleware::from_fn_with_state(
+                Duration::from_millis(20),
+                super::limit_duration,
+            ));
+
+        let response = get_with(app.clone(), "/slow").await;
+        assert_eq!(response.status(), StatusCode::REQUEST_TIMEOUT);
+        assert_eq!(body_json(response).await["code"], "request_timeout");
+
+        let response = get_with(app, "/fast").await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_payload_too_large() {
+        let mut config = Config::default();
+        config.server.max_body_bytes = 64;
+        let state = AppState::new(Arc::new(ProviderRegistry::new().unwrap()));
+
+        let body = serde_json::json!({
+            "provider_id": "anthropic",
+            "model_id": "claude-sonnet-4-5-20250929",
+            "input_tokens": 1000,
+        })
+        .to_string();
+        assert!(body.len() > 64);
+
+        // Declared length
+        let response = send_with(
+            &config,
+            state.clone(),
+            Request::post("/v1/estimate")
+                .header(header::CONTENT_TYPE, "application/json")
+                .header(header::CONTENT_LENGTH, body.len())
+                .body(Body::from(body.clone()))
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(body_json(response).await["code"], "payload_too_large");
+
+        // Streamed body without a length
+        let stream = futures_util::stream::once(async move {
+            Ok::<_, std::io::Error>(axum::body::Bytes::from(body))
+        });
+        let response = send_with(
+            &config,
+            state,
+            Request::post("/v1/estimate")
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from_stream(stream))
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(body_json(response).await["code"], "payload_too_large");
+    }
+}
@@ -0,0 +1,494 @@
+//! HTTP API for the Crabrace server
+//!
+//! This module contains the route table, shared application state and the
+//! request handlers. Request ID handling is part of the router; other
+//! middleware (CORS, security headers, compression) is layered on top of the
+//! router by the binary.
+
+pub mod admin;
+pub mod cache;
+pub mod compare;
+pub mod error;
+pub mod estimate;
+pub mod extract;
+pub mod families;
+pub mod filter;
+pub mod format;
+#[cfg(feature = "graphql")]
+pub mod graphql;
+pub mod limits;
+pub mod models;
+pub mod openapi;
+pub mod pagination;
+pub mod providers;
+pub mod recommend;
+pub mod request_id;
+pub mod sort;
+pub mod stats;
+pub mod system;
+pub mod tenant;
+
+pub use error::ApiError;
+pub use extract::{ApiJson, ApiQuery};
+pub use filter::{CatalogFilter, DeprecationFilter};
+pub use format::ResponseFormat;
+pub use pagination::Pagination;
+pub use sort::SortParams;
+
+use axum::extract::DefaultBodyLimit;
+use axum::http::{HeaderName, HeaderValue};
+use axum::routing::{get, post};
+use axum::Router;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tower::Layer;
+use tower_http::set_header::SetResponseHeaderLayer;
+use tracing::info;
+
+use crate::audit::AuditLog;
+use crate::auth::{self, Authenticator};
+use crate::config::RouteGroup;
+use crate::currency::ExchangeRates;
+use crate::providers::registry::ProviderRegistry;
+use crate::providers::tenants::Tenants;
+use crate::Config;
+
+/// Application state shared across handlers
+#[derive(Clone)]
+pub struct AppState {
+    pub registry: Arc<ProviderRegistry>,
+
+    /// Request authentication (`None` when disabled)
+    pub auth: Option<Arc<Authenticator>>,
+
+    /// Directory where admin mutations are persisted (`None` keeps them in memory)
+    pub overlay_dir: Option<PathBuf>,
+
+    /// Record of admin operations
+    pub audit: Arc<AuditLog>,
+
+    /// Tenant catalogs served under `/v1/t/{tenant}`
+    pub tenants: Arc<Tenants>,
+
+    /// Exchange rates for costs reported in other currencies than USD
+    pub currencies: Arc<ExchangeRates>,
+
+    /// Serialized default `GET /providers` body for `registry`
+    pub providers_body: Arc<cache::BodyCache>,
+}
+
+impl AppState {
+    /// Create application state around a provider registry
+    pub fn new(registry: Arc<ProviderRegistry>) -> Self {
+        Self {
+            registry,
+            auth: None,
+            overlay_dir: None,
+            audit: Arc::new(AuditLog::default()),
+            tenants: Arc::new(Tenants::default()),
+            currencies: Arc::default(),
+            providers_body: Arc::default(),
+        }
+    }
+
+    /// Serve tenant catalogs alongside the main one
+    pub fn with_tenants(mut self, tenants: Tenants) -> Self {
+        self.tenants = Arc::new(tenants);
+        self
+    }
+
+    /// Convert reported costs with the given exchange rates
+    pub fn with_currencies(mut self, currencies: Arc<ExchangeRates>) -> Self {
+        self.currencies = currencies;
+        self
+    }
+
+    /// Require authentication on every non-exempt route
+    pub fn with_auth(mut self, auth: Option<Authenticator>) -> Self {
+        self.auth = auth.map(Arc::new);
+        self
+    }
+
+    /// Record admin operations in the given audit log
+    pub fn with_audit(mut self, audit: AuditLog) -> Self {
+        self.audit = Arc::new(audit);
+        self
+    }
+
+    /// Persist admin mutations to an overlay directory
+    pub fn with_overlay_dir(mut self, overlay_dir: Option<PathBuf>) -> Self {
+        self.overlay_dir = overlay_dir;
+        self
+    }
+}
+
+/// Prefix for the current API version
+pub const API_V1_PREFIX: &str = "/v1";
+
+/// Header marking responses served from deprecated, unversioned routes
+pub const DEPRECATION: HeaderName = HeaderName::from_static("deprecation");
+
+/// Build the versioned API routes (mounted under [`API_V1_PREFIX`])
+fn api_routes(config: &Config, state: &AppState) -> Router<AppState> {
+    let mut catalog = Router::new()
+        .route("/providers", get(providers::list_providers))
+        .route("/providers/version", get(providers::catalog_version))
+        .route("/providers/changes", get(providers::list_changes))
+        .route("/providers/:id", get(providers::get_provider))
+        .route(
+            "/providers/:id/models",
+            get(providers::list_provider_models),
+        )
+        .route(
+            "/providers/:id/models/*model_id",
+            get(providers::get_provider_model),
+        )
+        .route("/models", get(models::list_models))
+        .route("/models.csv", get(models::list_models_csv))
+        .route("/models/search", get(models::search_models))
+        .route("/models/compare", get(compare::compare_models))
+        .route("/models/families", get(families::list_families))
+        .route("/models/*path", get(models::model_offers))
+        .route("/stats", get(stats::stats));
+
+    // Cache-Control applies to catalog data only
+    if let Some(cache_layer) = cache::build_cache_control_layer(&config.server.cache) {
+        catalog = catalog.layer(cache_layer);
+    }
+
+    #[cfg(feature = "graphql")]
+    {
+        catalog = catalog.route(
+            "/graphql",
+            post(graphql::graphql).layer(axum::Extension(graphql::schema(&config.graphql))),
+        );
+    }
+
+    catalog
+        .layer(axum::middleware::map_response_with_state(
+            state.clone(),
+            providers::catalog_version_header,
+        ))
+        .route("/providers/events", get(providers::catalog_events))
+        .route("/estimate", post(estimate::estimate_cost))
+        .route("/recommend", post(recommend::recommend))
+}
+
+/// Build the router for the primary listener (`server.host:server.port`)
+pub fn router(config: &Config, state: AppState) -> Router {
+    router_for(config, state, &config.server.primary_routes())
+}
+
+/// Build a router serving only the given route groups
+pub fn router_for(config: &Config, state: AppState, routes: &[RouteGroup]) -> Router {
+    let mut app = Router::new();
+
+    if routes.contains(&RouteGroup::Api) {
+        let api = api_routes(config, &state);
+
+        // Each tenant gets the same routes over its own catalog
+        let mut v1 = api.clone();
+        for (name, registry) in state.tenants.iter() {
+            let tenant_state = AppState {
+                registry: registry.clone(),
+                providers_body: Arc::default(),
+                ..state.clone()
+            };
+            v1 = v1.nest(
+                &format!("/t/{}", name),
+                api_routes(config, &tenant_state).with_state(tenant_state),
+            );
+        }
+        app = app.nest(API_V1_PREFIX, v1);
+
+        // Unversioned Catwalk-style routes are kept as deprecated aliases
+        if config.server.legacy_routes {
+            app = app.merge(api.layer(SetResponseHeaderLayer::overriding(
+                DEPRECATION,
+                HeaderValue::from_static("true"),
+            )));
+            info!("Legacy unversioned API routes enabled (deprecated)");
+        }
+
+        if config.server.cache.enabled {
+            info!(
+                "Cache-Control enabled on catalog responses: max-age={}s",
+                config.server.cache.max_age_seconds
+            );
+        }
+    }
+
+    if routes.contains(&RouteGroup::Health) {
+        app = app
+            .route("/health", get(system::health))
+            .route("/version", get(system::version));
+
+        // Kubernetes-style probes; readiness also reports configuration errors
+        let config_error = config.validate().err().map(|e| e.to_string());
+        app = app
+            .route(&config.health.liveness_path, get(system::livez))
+            .route(
+                &config.health.readiness_path,
+                get(move |state| system::readyz(state, config_error.clone())),
+            );
+    }
+
+    // API documentation
+    let base_path = config.server.base_path().to_string();
+    if config.docs.enabled && routes.contains(&RouteGroup::Docs) {
+        let doc_base_path = base_path.clone();
+        app = app.route(
+            "/openapi.json",
+            get(move || openapi::openapi_json(doc_base_path.clone())),
+        );
+        if config.docs.swagger_ui {
+            app = app
+                .route("/docs", get(openapi::swagger_ui))
+                .route("/docs/:file", get(openapi::swagger_ui_asset));
+            info!("Swagger UI enabled at /docs");
+        }
+    }
+
+    // Runtime catalog mutations (admin scope required)
+    if config.admin.enabled && routes.contains(&RouteGroup::Admin) {
+        app = app.nest("/admin", admin::routes(state.clone()));
+        info!("Admin API enabled at /admin");
+    }
+
+    if config.metrics.enabled && routes.contains(&RouteGroup::Metrics) {
+        app = app.route(&config.metrics.path, get(system::metrics));
+        info!("Metrics endpoint enabled at {}", config.metrics.path);
+    }
+
+    // JSON 405 for every route registered above, then body size and time limits
+    let max_body_bytes = config.server.max_body_bytes;
+    app = app
+        .method_not_allowed_fallback(limits::method_not_allowed)
+        .layer(DefaultBodyLimit::max(max_body_bytes))
+        .layer(axum::middleware::from_fn_with_state(
+            max_body_bytes,
+            limits::limit_body_size,
+        ))
+        .layer(axum::middleware::from_fn_with_state(
+            Duration::from_secs(config.server.timeout_seconds),
+            limits::limit_duration,
+        ));
+
+    // Per-request metrics (outside the limits so rejections are counted)
+    if config.metrics.enabled {
+        app = app.layer(axum::middleware::from_fn(
+            crate::metrics::track_http_metrics,
+        ));
+    }
+
+    // Authentication runs inside the request ID layer so 401s carry the ID
+    if let Some(auth) = state.auth.clone() {
+        let exempt_paths: Arc<[String]> = config.auth_exempt_paths().into();
+        app = app.layer(axum::middleware::from_fn_with_state(
+            (auth, exempt_paths),
+            auth::require_auth,
+        ));
+    }
+
+    // Mount everything under the configured prefix (e.g. behind a shared ingress)
+    if !base_path.is_empty() {
+        app = Router::new().nest(&base_path, app);
+        info!("Serving all routes under {}", base_path);
+    }
+
+    let mut app: Router = app.with_state(state.clone());
+
+    // Tenant selection rewrites the path, so it has to run before routing
+    if routes.contains(&RouteGroup::Api) && !state.tenants.is_empty() {
+        let routing =
+            tenant::TenantRouting::new(format!("{}{}", base_path, API_V1_PREFIX), &state.tenants);
+        let select = axum::middleware::map_request_with_state(routing, tenant::select_tenant);
+        app = Router::new().fallback_service(select.layer(app));
+        info!("Tenant catalogs enabled: {:?}", state.tenants.names());
+    }
+
+    request_id::layer(app)
+}
+
+#[cfg(test)]
+pub(crate) mod test_support {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use axum::response::Response;
+    use tower::ServiceExt;
+
+    /// Build a router over the embedded registry with default configuration
+    pub fn app() -> Router {
+        app_with_config(&Config::default())
+    }
+
+    /// Build a router over the embedded registry with the given configuration
+    pub fn app_with_config(config: &Config) -> Router {
+        let registry = Arc::new(ProviderRegistry::new().unwrap());
+        router(config, AppState::new(registry))
+    }
+
+    /// Send a request through a fresh router
+    pub async fn send(request: Request<Body>) -> Response {
+        app().oneshot(request).await.unwrap()
+    }
+
+    /// Application state over the embedded registry with authentication
+    pub fn state_with_auth(auth: Authenticator) -> AppState {
+        let registry = Arc::new(ProviderRegistry::new().unwrap());
+        AppState::new(registry).with_auth(Some(auth))
+    }
+
+    /// Send a request through a router built from the given config and state
+    pub async fn send_with(config: &Config, state: AppState, request: Request<Body>) -> Response {
+        router(config, state).oneshot(request).await.unwrap()
+    }
+
+    /// Issue a GET request against the given router
+    pub async fn get_with(app: Router, uri: &str) -> Response {
+        app.oneshot(Request::get(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    /// Issue a GET request against a fresh router
+    pub async fn get(uri: &str) -> Response {
+        send(Request::get(uri).body(Body::empty()).unwrap()).await
+    }
+
+    /// Issue a GET request and parse the response body as JSON
+    pub async fn get_json(uri: &str) -> (StatusCode, serde_json::Value) {
+        let response = get(uri).await;
+        let status = response.status();
+        (status, body_json(response).await)
+    }
+
+    /// Issue a POST request with a JSON body
+    pub async fn post_json(uri: &str, body: serde_json::Value) -> Response {
+        send(
+            Request::post(uri)
+                .header(axum::http::header::CONTENT_TYPE, "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+        )
+        .await
+    }
+
+    /// Issue a GET request with an `Accept` header
+    pub async fn get_accept(uri: &str, accept: &str) -> Response {
+        send(
+            Request::get(uri)
+                .header(axum::http::header::ACCEPT, accept)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+    }
+
+    /// Read a response body as text
+    pub async fn body_text(response: Response) -> String {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    /// Read a response body as JSON
+    pub async fn body_json(response: Response) -> serde_json::Value {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::test_support::{app_with_config, body_json, get, get_with};
+    use super::{router_for, AppState, DEPRECATION};
+    use crate::config::{ListenerConfig, RouteGroup};
+    use crate::providers::registry::ProviderRegistry;
+    use crate::Config;
+    use axum::http::StatusCode;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_v1_routes() {
+        let response = get("/v1/providers").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.headers().get(DEPRECATION).is_none());
+
+        let response = get("/v1/providers/anthropic/models").await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_legacy_routes_are_deprecated() {
+        let response = get("/providers").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[DEPRECATION], "true");
+    }
+
+    #[tokio::test]
+    async fn test_listener_route_groups() {
+        let mut config = Config::default();
+        config.server.listeners.push(ListenerConfig {
+            address: "127.0.0.1:9090".to_string(),
+            routes: vec![RouteGroup::Metrics],
+        });
+
+        // The primary listener no longer serves metrics
+        let primary = app_with_config(&config);
+        let response = get_with(primary.clone(), "/v1/providers").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = get_with(primary, "/metrics").await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let registry = Arc::new(ProviderRegistry::new().unwrap());
+        let internal = router_for(&config, AppState::new(registry), &[RouteGroup::Metrics]);
+        let response = get_with(internal.clone(), "/metrics").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = get_with(internal, "/v1/providers").await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_base_path() {
+        let mut config = Config::default();
+        config.server.base_path = "/crabrace/".to_string();
+        let app = app_with_config(&config);
+
+        let response = get_with(app.clone(), "/crabrace/v1/providers").await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = get_with(app.clone(), "/crabrace/health").await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = get_with(app.clone(), "/v1/providers").await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert!(response
+            .headers()
+            .contains_key(super::request_id::X_REQUEST_ID));
+
+        let response = get_with(app, "/crabrace/openapi.json").await;
+        assert_eq!(body_json(response).await["servers"][0]["url"], "/crabrace");
+    }
+
+    #[tokio::test]
+    async fn test_legacy_routes_disabled() {
+        let mut config = Config::default();
+        config.server.legacy_routes = false;
+        let app = app_with_config(&config);
+
+        let response = get_with(app.clone(), "/providers").await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let response = get_with(app.clone(), "/v1/providers").await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = get_with(app, "/health").await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
@@ -0,0 +1,536 @@
+use axum::extract::{Path, State};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+use super::error::ErrorResponse;
+use super::{ApiError, ApiQuery, AppState, CatalogFilter, Pagination, ResponseFormat, SortParams};
+use crate::providers::search::SearchMode;
+use crate::ProviderModel;
+
+/// GET /models - Returns a flat list of all models across providers
+///
+/// Each model carries an embedded `provider_id`. Supports the same filters as
+/// `/providers` (see [`CatalogFilter`]) and supports [`SortParams`],
+/// [`Pagination`], NDJSON streaming via `Accept: application/x-ndjson` and
+/// CSV export via `Accept: text/csv`.
+#[utoipa::path(
+    get,
+    path = "/v1/models",
+    tag = "models",
+    params(CatalogFilter, SortParams, Pagination),
+    responses(
+        (status = 200, description = "Flat list of models", content(
+            (Vec<ProviderModel> = "application/json"),
+            (ProviderModel = "application/x-ndjson"),
+            (String = "text/csv"),
+            (Vec<ProviderModel> = "application/yaml"),
+            (String = "application/toml")
+        )),
+        (status = 400, description = "Invalid query parameters", body = ErrorResponse)
+    )
+)]
+pub async fn list_models(
+    State(state): State<AppState>,
+    format: ResponseFormat,
+    ApiQuery(filter): ApiQuery<CatalogFilter>,
+    ApiQuery(sort): ApiQuery<SortParams>,
+    ApiQuery(page): ApiQuery<Pagination>,
+) -> Result<Response, ApiError> {
+    models_response(&state, format, &filter, &sort, &page)
+}
+
+/// GET /models.csv - The flat model catalog as CSV
+///
+/// Equivalent to `GET /models` with `Accept: text/csv`, for spreadsheet tools
+/// that cannot set request headers.
+#[utoipa::path(
+    get,
+    path = "/v1/models.csv",
+    tag = "models",
+    params(CatalogFilter, SortParams, Pagination),
+    responses(
+        (status = 200, description = "Flat list of models", body = String, content_type = "text/csv"),
+        (status = 400, description = "Invalid query parameters", body = ErrorResponse)
+    )
+)]
+pub async fn list_models_csv(
+    State(state): State<AppState>,
+    ApiQuery(filter): ApiQuery<CatalogFilter>,
+    ApiQuery(sort): ApiQuery<SortParams>,
+    ApiQuery(page): ApiQuery<Pagination>,
+) -> Result<Response, ApiError> {
+    models_response(&state, ResponseFormat::Csv, &filter, &sort, &page)
+}
+
+fn models_response(
+    state: &AppState,
+    format: ResponseFormat,
+    filter: &CatalogFilter,
+    sort: &SortParams,
+    page: &Pagination,
+) -> Result<Response, ApiError> {
+    let mut models: Vec<ProviderModel> = filter
+        .apply(&state.registry.providers())
+        .iter()
+        .flat_map(|provider| {
+            provider.models.iter().map(|model| ProviderModel {
+                provider_id: provider.id.clone(),
+                model: model.clone(),
+            })
+        })
+        .collect();
+    sort.sort_models(&mut models)?;
+
+    format.respond_list(page, models)
+}
+
+/// Query parameters for GET /models/search
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SearchQuery {
+    /// Text to search for in model IDs and names
+    #[serde(default)]
+    pub q: String,
+
+    /// Matching mode: `substring` (default), `prefix` or `fuzzy` (best match first)
+    #[serde(default)]
+    #[param(inline)]
+    pub mode: SearchMode,
+
+    /// Also include deprecated models
+    #[serde(default)]
+    pub include_deprecated: bool,
+}
+
+/// GET /models/search - Search models across all providers
+#[utoipa::path(
+    get,
+    path = "/v1/models/search",
+    tag = "models",
+    params(SearchQuery),
+    responses(
+        (status = 200, description = "Matching models", body = [ProviderModel]),
+        (status = 400, description = "Missing search query", body = ErrorResponse)
+    )
+)]
+pub async fn search_models(
+    State(state): State<AppState>,
+    ApiQuery(query): ApiQuery<SearchQuery>,
+) -> Result<Response, ApiError> {
+    if query.q.trim().is_empty() {
+        return Err(ApiError::bad_request("Query parameter 'q' is required"));
+    }
+
+    let mut results = state.registry.search(&query.q, query.mode).map_err(|e| {
+        tracing::error!("Failed to search models for '{}': {}", query.q, e);
+        ApiError::internal("Failed to search models")
+    })?;
+    if !query.include_deprecated {
+        results.retain(|result| result.model.is_active());
+    }
+
+    Ok(Json(results).into_response())
+}
+
+/// GET /models/*model_id/offers - Every provider offering a model ID
+///
+/// The model ID is matched as a wildcard because it may contain slashes
+/// (e.g. `deepseek/deepseek-r1`), so the `/offers` suffix is checked here.
+#[utoipa::path(
+    get,
+    path = "/v1/models/{model_id}/offers",
+    tag = "models",
+    params(("model_id" = String, Path, description = "Model ID (may contain slashes)")),
+    responses(
+        (status = 200, description = "Providers offering the model, in catalog order", body = [ProviderModel]),
+        (status = 404, description = "No provider offers the model", body = ErrorResponse)
+    )
+)]
+pub async fn model_offers(
+    State(state): State<AppState>,
+    Path(path): Path<String>,
+) -> Result<Response, ApiError> {
+    let Some(model_id) = path.strip_suffix("/offers") else {
+        return Err(ApiError::not_found(&format!("/v1/models/{}", path)));
+    };
+
+    let offers = state.registry.find_model(model_id).map_err(|e| {
+        tracing::error!("Failed to find offers for model '{}': {}", model_id, e);
+        ApiError::internal("Failed to retrieve model offers")
+    })?;
+    if offers.is_empty() {
+        return Err(ApiError::model_not_offered(model_id));
+    }
+
+    Ok(Json(offers).into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::test_support::{body_json, body_text, get, get_accept, get_json};
+    use axum::http::StatusCode;
+
+    #[tokio::test]
+    async fn test_list_models() {
+        let (status, body) = get_json("/models").await;
+        assert_eq!(status, StatusCode::OK);
+        let models = body.as_array().unwrap();
+        assert!(models.len() > 100);
+        assert!(models.iter().all(|m| m["provider_id"].is_string()));
+    }
+
+    #[tokio::test]
+    async fn test_list_models_filtered() {
+        let (status, body) = get_json("/models?type=anthropic&can_reason=true").await;
+        assert_eq!(status, StatusCode::OK);
+        let models = body.as_array().unwrap();
+        assert!(!models.is_empty());
+        assert!(models.iter().all(|m| m["can_reason"] == true));
+        assert!(models.iter().all(|m| m["provider_id"] == "anthropic"));
+    }
+
+    #[tokio::test]
+    async fn test_list_models_without_tools() {
+        let (status, body) = get_json("/models?supports_tools=false").await;
+        assert_eq!(status, StatusCode::OK);
+        let ids: Vec<_> = body
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|m| m["id"].as_str().unwrap())
+            .collect();
+        assert!(ids.contains(&"deepseek-reasoner"));
+        assert!(!ids.contains(&"claude-sonnet-4-5-20250929"));
+    }
+
+    #[tokio::test]
+    async fn test_list_models_by_kind() {
+        let (_, body) = get_json("/models").await;
+        let models = body.as_array().unwrap();
+        assert!(models.iter().all(|m| m["model_kind"] == "chat"));
+
+        let (status, body) = get_json("/models?model_kind=embedding").await;
+        assert_eq!(status, StatusCode::OK);
+        let models = body.as_array().unwrap();
+        assert_eq!(models.len(), 3);
+        assert!(models
+            .iter()
+            .all(|m| m["dimensions"].is_u64() && m["cost_per_1m_out"] == 0.0));
+    }
+
+    #[tokio::test]
+    async fn test_list_models_by_knowledge_cutoff() {
+        let (status, body) = get_json("/models?min_knowledge_cutoff=2024-06-01").await;
+        assert_eq!(status, StatusCode::OK);
+        let models = body.as_array().unwrap();
+        assert!(!models.is_empty());
+        assert!(models
+            .iter()
+            .all(|m| m["knowledge_cutoff"].as_str().unwrap() >= "2024-06-01"));
+        assert!(models
+            .iter()
+            .any(|m| m["id"] == "claude-sonnet-4-5-20250929"));
+        assert!(!models.iter().any(|m| m["id"] == "gpt-4o-2024-11-20"));
+
+        let (status, _) = get_json("/models?min_knowledge_cutoff=June").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_list_models_released_after() {
+        let (status, body) = get_json("/models?released_after=2025-04-01").await;
+        assert_eq!(status, StatusCode::OK);
+        let mut ids: Vec<&str> = body
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|m| m["id"].as_str().unwrap())
+            .collect();
+        ids.sort_unstable();
+        assert_eq!(ids, ["claude-sonnet-4-5-20250929", "gpt-5", "o3"]);
+    }
+
+    #[tokio::test]
+    async fn test_list_models_by_region() {
+        let (status, body) = get_json("/models?region=eu-west-1").await;
+        assert_eq!(status, StatusCode::OK);
+        let mut ids: Vec<&str> = body
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|m| m["id"].as_str().unwrap())
+            .collect();
+        ids.sort_unstable();
+        assert_eq!(ids, ["amazon.nova-lite-v1:0", "amazon.nova-pro-v1:0"]);
+
+        // Vertex AI models inherit the provider's regions
+        let (_, body) = get_json("/models?region=europe-west4").await;
+        let models = body.as_array().unwrap();
+        assert_eq!(models.len(), 2);
+        assert!(models.iter().all(|m| m["provider_id"] == "vertexai"));
+    }
+
+    #[tokio::test]
+    async fn test_list_models_by_license() {
+        let (status, body) = get_json("/models?open_weights=true&license=LLAMA3.3").await;
+        assert_eq!(status, StatusCode::OK);
+        let models = body.as_array().unwrap();
+        assert!(models
+            .iter()
+            .any(|m| m["id"] == "llama-3.3-70b-versatile" && m["provider_id"] == "groq"));
+        assert!(models
+            .iter()
+            .all(|m| m["open_weights"] == true && m["license"] == "llama3.3"));
+
+        let (_, body) = get_json("/models?type=anthropic&open_weights=true").await;
+        assert!(body.as_array().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_list_models_by_tag() {
+        let (status, body) = get_json("/models?tag=coding&type=anthropic").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body[0]["id"], "claude-sonnet-4-5-20250929");
+        assert_eq!(body[0]["tags"], serde_json::json!(["frontier", "coding"]));
+
+        // Provider tags apply to every model
+        let (_, body) = get_json("/models?tag=local").await;
+        let models = body.as_array().unwrap();
+        assert!(!models.is_empty());
+        assert!(models
+            .iter()
+            .all(|m| m["provider_id"] == "ollama" || m["provider_id"] == "lmstudio"));
+    }
+
+    #[tokio::test]
+    async fn test_list_model_variants() {
+        let (status, body) = get_json("/providers/groq/models").await;
+        assert_eq!(status, StatusCode::OK);
+        let models = body.as_array().unwrap();
+        let specdec = models
+            .iter()
+            .find(|m| m["id"] == "llama-3.3-70b-specdec")
+            .unwrap();
+        assert_eq!(specdec["base_model_id"], "llama-3.3-70b-versatile");
+        assert_eq!(specdec["variant"], "speculative");
+
+        // Reference models carry no variant metadata
+        let reference = models
+            .iter()
+            .find(|m| m["id"] == "llama-3.3-70b-versatile")
+            .unwrap();
+        assert!(reference.get("variant").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_list_models_without_streaming() {
+        let (status, body) = get_json("/models?supports_streaming=false").await;
+        assert_eq!(status, StatusCode::OK);
+        let models = body.as_array().unwrap();
+        assert_eq!(models.len(), 1);
+        assert_eq!(models[0]["id"], "openai/o3-pro");
+    }
+
+    #[tokio::test]
+    async fn test_list_models_by_modality() {
+        let (status, body) = get_json("/models?input_modality=audio").await;
+        assert_eq!(status, StatusCode::OK);
+        let models = body.as_array().unwrap();
+        assert!(!models.is_empty());
+        assert!(models.iter().all(|m| m["input_modalities"]
+            .as_array()
+            .unwrap()
+            .contains(&"audio".into())));
+
+        let (status, body) = get_json("/models?output_modality=smell").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["code"], "bad_request");
+    }
+
+    #[tokio::test]
+    async fn test_list_models_paginated() {
+        let response = get("/models?limit=10").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let total: usize = response.headers()["x-total-count"]
+            .to_str()
+            .unwrap()
+            .parse()
+            .unwrap();
+
+        let body = body_json(response).await;
+        assert_eq!(body["data"].as_array().unwrap().len(), 10);
+        assert_eq!(body["pagination"]["total"], total);
+    }
+
+    #[tokio::test]
+    async fn test_list_models_invalid_limit() {
+        let (status, body) = get_json("/models?limit=0").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["code"], "bad_request");
+    }
+
+    #[tokio::test]
+    async fn test_list_models_sorted_by_cost() {
+        let (status, body) = get_json("/models?sort=cost_per_1m_in&order=asc").await;
+        assert_eq!(status, StatusCode::OK);
+        let costs: Vec<f64> = body
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|m| m["cost_per_1m_in"].as_f64().unwrap())
+            .collect();
+        assert!(costs.windows(2).all(|w| w[0] <= w[1]));
+    }
+
+    #[tokio::test]
+    async fn test_list_models_by_benchmark() {
+        let (status, body) = get_json(
+            "/models?benchmark=GPQA_DIAMOND&min_benchmark_score=80&sort=benchmark&order=desc",
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let ids: Vec<_> = body
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|m| m["id"].as_str().unwrap())
+            .collect();
+        assert_eq!(ids, ["gpt-5", "claude-sonnet-4-5-20250929", "o3"]);
+
+        let (status, _) = get_json("/models?sort=benchmark").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_list_models_by_throughput() {
+        let (status, body) =
+            get_json("/models?sort=median_tokens_per_second&order=desc&limit=4").await;
+        assert_eq!(status, StatusCode::OK);
+        let speeds: Vec<_> = body["data"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|m| m["median_tokens_per_second"].as_f64().unwrap())
+            .collect();
+        assert_eq!(speeds, [3000.0, 2200.0, 2100.0, 1665.0]);
+        assert_eq!(body["data"][0]["provider_id"], "cerebras");
+    }
+
+    #[tokio::test]
+    async fn test_list_models_sort_by_model_count_rejected() {
+        let (status, _) = get_json("/models?sort=model_count").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_list_models_ndjson() {
+        let response = get_accept("/models?type=anthropic", "application/x-ndjson").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = body_text(response).await;
+        assert!(body.lines().count() > 1);
+        for line in body.lines() {
+            let model: serde_json::Value = serde_json::from_str(line).unwrap();
+            assert_eq!(model["provider_id"], "anthropic");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_list_models_csv() {
+        let response = get_accept("/models?type=anthropic", "text/csv").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.headers()["content-type"]
+            .to_str()
+            .unwrap()
+            .starts_with("text/csv"));
+
+        let body = body_text(response).await;
+        let mut lines = body.lines();
+        assert!(lines
+            .next()
+            .unwrap()
+            .starts_with("provider_id,id,name,cost_per_1m_in"));
+        assert!(lines.all(|line| line.starts_with("anthropic,")));
+    }
+
+    #[tokio::test]
+    async fn test_models_csv_route() {
+        let response = get("/v1/models.csv?limit=5").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(body_text(response).await.lines().count(), 6);
+    }
+
+    #[tokio::test]
+    async fn test_search_models() {
+        let (status, body) = get_json("/models/search?q=gpt-4").await;
+        assert_eq!(status, StatusCode::OK);
+        let results = body.as_array().unwrap();
+        assert!(!results.is_empty());
+        assert!(results.iter().all(|r| r["provider_id"].is_string()));
+        assert!(results.iter().any(|r| r["provider_id"] == "openai"));
+    }
+
+    #[tokio::test]
+    async fn test_search_modes() {
+        let (status, body) = get_json("/v1/models/search?q=gpt-5&mode=prefix").await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.as_array().unwrap().iter().all(|r| r["id"]
+            .as_str()
+            .unwrap()
+            .to_lowercase()
+            .starts_with("gpt-5")
+            || r["name"]
+                .as_str()
+                .unwrap()
+                .to_lowercase()
+                .starts_with("gpt-5")));
+
+        let (status, body) = get_json("/v1/models/search?q=gpt5mini&mode=fuzzy").await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.as_array().unwrap()[0]["id"]
+            .as_str()
+            .unwrap()
+            .starts_with("gpt-5-mini"));
+
+        let (status, body) = get_json("/v1/models/search?q=gpt&mode=regex").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["code"], "bad_request");
+    }
+
+    #[tokio::test]
+    async fn test_search_models_requires_query() {
+        let (status, body) = get_json("/models/search").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["code"], "bad_request");
+    }
+
+    #[tokio::test]
+    async fn test_model_offers() {
+        let (status, body) = get_json("/v1/models/gpt-5/offers").await;
+        assert_eq!(status, StatusCode::OK);
+        let providers: Vec<&str> = body
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|o| o["provider_id"].as_str().unwrap())
+            .collect();
+        assert_eq!(providers, vec!["openai", "aihubmix"]);
+
+        // Model IDs with slashes
+        let (status, body) = get_json("/v1/models/openai/gpt-oss-120b/offers").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body.as_array().unwrap().len(), 2);
+
+        let (status, body) = get_json("/v1/models/no-such-model/offers").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["code"], "model_not_found");
+
+        // Only the /offers sub-resource exists
+        let (status, body) = get_json("/v1/models/gpt-5").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["code"], "not_found");
+        assert_eq!(body["error"], "No resource at '/v1/models/gpt-5'");
+        assert!(body["request_id"].is_string());
+    }
+}
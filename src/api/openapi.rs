@@ -0,0 +1,231 @@
+use axum::extract::Path;
+use axum::http::{header, StatusCode};
+use axum::response::{Html, IntoResponse, Response};
+use axum::Json;
+use once_cell::sync::Lazy;
+use std::sync::Arc;
+use utoipa::openapi::server::Server;
+use utoipa::OpenApi;
+
+use super::admin::{ModelMutation, ProviderMutation, RegistryImport};
+use super::compare::{ComparedModel, ComparisonResponse};
+use super::error::ErrorResponse;
+use super::estimate::{
+    BatchEstimateResponse, EstimateBody, EstimateRequest, EstimateResponse, Usage,
+};
+use super::families::ModelFamily;
+use super::pagination::PageInfo;
+use super::providers::{CatalogVersion, ChangesResponse};
+use super::recommend::{RecommendRequest, RecommendResponse, Recommendation};
+use super::sort::{SortKey, SortOrder};
+use super::{admin, compare, estimate, families, models, providers, recommend, stats};
+use crate::audit::AuditEntry;
+use crate::providers::changelog::{Change, ChangeKind, Prices};
+use crate::providers::registry::RegistrySnapshot;
+use crate::providers::stats::{CatalogStats, ModelSummary, ProviderStats};
+use crate::{Model, Provider, ProviderModel};
+
+/// OpenAPI document describing the versioned HTTP API
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Crabrace API",
+        description = "AI provider and model catalog"
+    ),
+    paths(
+        providers::list_providers,
+        providers::catalog_version,
+        providers::list_changes,
+        providers::catalog_events,
+        providers::get_provider,
+        providers::list_provider_models,
+        providers::get_provider_model,
+        models::list_models,
+        models::list_models_csv,
+        models::search_models,
+        models::model_offers,
+        compare::compare_models,
+        families::list_families,
+        recommend::recommend,
+        stats::stats,
+        estimate::estimate_cost,
+        admin::list_audit,
+        admin::export_registry,
+        admin::import_registry,
+        admin::create_provider,
+        admin::put_provider,
+        admin::delete_provider,
+        admin::create_model,
+        admin::put_model,
+        admin::delete_model,
+    ),
+    components(schemas(
+        Provider,
+        Model,
+        ProviderModel,
+        ErrorResponse,
+        PageInfo,
+        EstimateRequest,
+        Usage,
+        EstimateResponse,
+        EstimateBody,
+        BatchEstimateResponse,
+        ComparedModel,
+        ComparisonResponse,
+        ModelFamily,
+        RecommendRequest,
+        Recommendation,
+        RecommendResponse,
+        CatalogStats,
+        ModelSummary,
+        ProviderStats,
+        SortKey,
+        SortOrder,
+        ProviderMutation,
+        ModelMutation,
+        AuditEntry,
+        RegistrySnapshot,
+        RegistryImport,
+        Change,
+        ChangeKind,
+        Prices,
+        ChangesResponse,
+        CatalogVersion
+    )),
+    tags(
+        (name = "providers", description = "Provider catalog"),
+        (name = "models", description = "Cross-provider model queries"),
+        (name = "pricing", description = "Cost estimation"),
+        (name = "admin", description = "Runtime catalog mutations (admin scope required)")
+    )
+)]
+pub struct ApiDoc;
+
+/// OpenAPI document for a server mounted at `base_path` (`""` for the root)
+///
+/// A non-root base path is advertised as the document's server URL so
+/// clients and Swagger UI resolve the `/v1/...` paths under the prefix.
+pub fn document(base_path: &str) -> utoipa::openapi::OpenApi {
+    let mut doc = ApiDoc::openapi();
+    if !base_path.is_empty() {
+        doc.servers = Some(vec![Server::new(base_path)]);
+    }
+    doc
+}
+
+/// GET /openapi.json - OpenAPI 3.1 specification
+pub async fn openapi_json(base_path: String) -> Response {
+    Json(document(&base_path)).into_response()
+}
+
+/// GET /docs - Swagger UI rendering `/openapi.json`
+///
+/// The page loads its assets from `/docs/{file}`, served from the copy of
+/// Swagger UI bundled into the binary, so it works without internet access.
+pub async fn swagger_ui() -> Response {
+    Html(SWAGGER_UI_HTML).into_response()
+}
+
+/// Settings injected into the bundled `swagger-initializer.js` (the `/docs`
+/// page configures Swagger UI inline instead)
+static SWAGGER_UI_CONFIG: Lazy<Arc<utoipa_swagger_ui::Config<'static>>> =
+    Lazy::new(|| Arc::new(utoipa_swagger_ui::Config::from("openapi.json")));
+
+/// GET /docs/{file} - Bundled Swagger UI asset
+pub async fn swagger_ui_asset(Path(file): Path<String>) -> Response {
+    match utoipa_swagger_ui::serve(&file, SWAGGER_UI_CONFIG.clone()) {
+        Ok(Some(asset)) => (
+            [(header::CONTENT_TYPE, asset.content_type)],
+            asset.bytes.into_owned(),
+        )
+            .into_response(),
+        Ok(None) => StatusCode::NOT_FOUND.into_response(),
+        Err(error) => {
+            tracing::error!("Failed to serve Swagger UI file '{}': {}", file, error);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+const SWAGGER_UI_HTML: &str = r##"<!DOCTYPE html>
+<html lang="en">
+<head>
+  <meta charset="utf-8" />
+  <title>Crabrace API</title>
+  <link rel="stylesheet" href="docs/swagger-ui.css" />
+</head>
+<body>
+  <div id="swagger-ui"></div>
+  <script src="docs/swagger-ui-bundle.js"></script>
+  <script>
+    window.onload = () => {
+      window.ui = SwaggerUIBundle({ url: "openapi.json", dom_id: "#swagger-ui" });
+    };
+  </script>
+</body>
+</html>
+"##;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::test_support::{app_with_config, body_text, get, get_json, get_with};
+    use crate::Config;
+    use axum::http::StatusCode;
+
+    #[test]
+    fn test_openapi_document() {
+        let doc = ApiDoc::openapi();
+        assert!(doc.paths.paths.contains_key("/v1/providers"));
+        assert!(doc
+            .paths
+            .paths
+            .contains_key("/v1/providers/{id}/models/{model_id}"));
+    }
+
+    #[tokio::test]
+    async fn test_openapi_endpoint() {
+        let (status, body) = get_json("/openapi.json").await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body["openapi"].as_str().unwrap().starts_with("3.1"));
+        assert!(body["components"]["schemas"]["Provider"].is_object());
+    }
+
+    #[test]
+    fn test_document_base_path() {
+        assert!(document("").servers.is_none());
+
+        let doc = serde_json::to_value(document("/crabrace")).unwrap();
+        assert_eq!(doc["servers"][0]["url"], "/crabrace");
+    }
+
+    #[tokio::test]
+    async fn test_swagger_ui_disabled_by_default() {
+        let response = get("/docs").await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_swagger_ui_enabled() {
+        let mut config = Config::default();
+        config.docs.swagger_ui = true;
+        let response = get_with(app_with_config(&config), "/docs").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let page = body_text(response).await;
+        assert!(!page.contains("https://"));
+
+        // Assets are served from the binary
+        for (file, content_type) in [
+            ("swagger-ui.css", "text/css"),
+            ("swagger-ui-bundle.js", "text/javascript"),
+        ] {
+            assert!(page.contains(&format!("docs/{}", file)));
+            let response = get_with(app_with_config(&config), &format!("/docs/{}", file)).await;
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(response.headers()[header::CONTENT_TYPE], content_type);
+        }
+
+        let response = get_with(app_with_config(&config), "/docs/missing.js").await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
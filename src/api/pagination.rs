@@ -0,0 +1,171 @@
+use axum::http::{HeaderName, HeaderValue};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use super::ApiError;
+
+/// Header carrying the total number of items before pagination
+pub const X_TOTAL_COUNT: HeaderName = HeaderName::from_static("x-total-count");
+
+/// Largest page size a client may request
+pub const MAX_PAGE_LIMIT: usize = 1000;
+
+/// Pagination query parameters (`?limit=&offset=`)
+///
+/// When neither parameter is supplied, listings are returned as a bare JSON
+/// array for compatibility with Catwalk clients. Otherwise the response is
+/// wrapped in a [`PageEnvelope`].
+#[derive(Debug, Clone, Copy, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct Pagination {
+    /// Maximum number of items to return
+    pub limit: Option<usize>,
+
+    /// Number of items to skip
+    pub offset: Option<usize>,
+}
+
+/// Pagination metadata included in paginated responses
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct PageInfo {
+    /// Total number of items across all pages
+    pub total: usize,
+
+    /// Page size used for this response
+    pub limit: usize,
+
+    /// Offset of the first item in this response
+    pub offset: usize,
+
+    /// Offset of the next page, if there is one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_offset: Option<usize>,
+}
+
+/// Response envelope for paginated listings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PageEnvelope<T> {
+    /// Items on this page
+    pub data: Vec<T>,
+
+    /// Pagination metadata
+    pub pagination: PageInfo,
+}
+
+impl Pagination {
+    /// Whether the client asked for a paginated response
+    pub fn is_requested(&self) -> bool {
+        self.limit.is_some() || self.offset.is_some()
+    }
+
+    /// Validate the requested page parameters
+    pub fn validate(&self) -> Result<(), ApiError> {
+        match self.limit {
+            Some(0) => Err(ApiError::bad_request("'limit' must be greater than 0")),
+            Some(limit) if limit > MAX_PAGE_LIMIT => Err(ApiError::bad_request(format!(
+                "'limit' must not exceed {}",
+                MAX_PAGE_LIMIT
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    /// Slice a list of items into the requested page
+    pub fn paginate<T>(&self, items: Vec<T>) -> PageEnvelope<T> {
+        let total = items.len();
+        let offset = self.offset.unwrap_or(0).min(total);
+        let limit = self.limit.unwrap_or(MAX_PAGE_LIMIT);
+
+        let data: Vec<T> = items.into_iter().skip(offset).take(limit).collect();
+        let end = offset + data.len();
+        let next_offset = (end < total).then_some(end);
+
+        PageEnvelope {
+            data,
+            pagination: PageInfo {
+                total,
+                limit,
+                offset,
+                next_offset,
+            },
+        }
+    }
+
+    /// Render a listing, paginating it when requested
+    ///
+    /// Always sets the `X-Total-Count` header.
+    pub fn respond<T: Serialize>(&self, items: Vec<T>) -> Result<Response, ApiError> {
+        self.validate()?;
+        let total = HeaderValue::from(items.len());
+
+        let mut response = if self.is_requested() {
+            Json(self.paginate(items)).into_response()
+        } else {
+            Json(items).into_response()
+        };
+        response.headers_mut().insert(X_TOTAL_COUNT, total);
+
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paginate() {
+        let page = Pagination {
+            limit: Some(2),
+            offset: Some(1),
+        }
+        .paginate(vec![1, 2, 3, 4]);
+
+        assert_eq!(page.data, vec![2, 3]);
+        assert_eq!(page.pagination.total, 4);
+        assert_eq!(page.pagination.next_offset, Some(3));
+    }
+
+    #[test]
+    fn test_paginate_last_page() {
+        let page = Pagination {
+            limit: Some(10),
+            offset: Some(2),
+        }
+        .paginate(vec![1, 2, 3]);
+
+        assert_eq!(page.data, vec![3]);
+        assert_eq!(page.pagination.next_offset, None);
+    }
+
+    #[test]
+    fn test_paginate_offset_past_end() {
+        let page = Pagination {
+            limit: None,
+            offset: Some(10),
+        }
+        .paginate(vec![1, 2, 3]);
+
+        assert!(page.data.is_empty());
+        assert_eq!(page.pagination.offset, 3);
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(Pagination::default().validate().is_ok());
+        assert!(Pagination {
+            limit: Some(0),
+            offset: None
+        }
+        .validate()
+        .is_err());
+        assert!(Pagination {
+            limit: Some(MAX_PAGE_LIMIT + 1),
+            offset: None
+        }
+        .validate()
+        .is_err());
+    }
+}
@@ -0,0 +1,799 @@
+use axum::body::Bytes;
+use axum::extract::{Path, State};
+use axum::http::{header, HeaderMap, HeaderName, HeaderValue, StatusCode};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use chrono::{DateTime, Utc};
+use futures_util::stream::{self, Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+use utoipa::{IntoParams, ToSchema};
+
+use super::cache;
+use super::error::ErrorResponse;
+use super::pagination::X_TOTAL_COUNT;
+use super::{
+    ApiError, ApiQuery, AppState, CatalogFilter, DeprecationFilter, Pagination, ResponseFormat,
+    SortParams,
+};
+use crate::providers::changelog::Change;
+use crate::providers::registry::ProviderRegistry;
+use crate::{Lifecycle, Model, Provider};
+
+/// GET /providers - Returns all AI providers and their models
+///
+/// Supports the filters described by [`CatalogFilter`], [`SortParams`] and
+/// [`Pagination`] as query parameters. Send `Accept: application/x-ndjson`
+/// to stream one provider per line, `Accept: text/csv` for a CSV summary, or
+/// `application/yaml` / `application/toml` for those document formats.
+///
+/// The unfiltered JSON listing is serialized once per catalog version and
+/// carries an `ETag`; send it back in `If-None-Match` to get a 304 while the
+/// catalog is unchanged.
+#[utoipa::path(
+    get,
+    path = "/v1/providers",
+    tag = "providers",
+    params(CatalogFilter, SortParams, Pagination),
+    responses(
+        (status = 200, description = "List of providers", content(
+            (Vec<Provider> = "application/json"),
+            (Provider = "application/x-ndjson"),
+            (String = "text/csv"),
+            (Vec<Provider> = "application/yaml"),
+            (String = "application/toml")
+        )),
+        (status = 304, description = "Catalog unchanged since the `If-None-Match` ETag"),
+        (status = 400, description = "Invalid query parameters", body = ErrorResponse)
+    )
+)]
+pub async fn list_providers(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    format: ResponseFormat,
+    ApiQuery(filter): ApiQuery<CatalogFilter>,
+    ApiQuery(sort): ApiQuery<SortParams>,
+    ApiQuery(page): ApiQuery<Pagination>,
+) -> Result<Response, ApiError> {
+    if format == ResponseFormat::Json
+        && filter.is_default()
+        && sort.sort.is_none()
+        && !page.is_requested()
+    {
+        return cached_providers(&state, &filter, &headers);
+    }
+
+    let mut providers = filter.apply(&state.registry.providers());
+    sort.sort_providers(&mut providers)?;
+
+    info!(
+        "Returned {} providers with {} total models",
+        providers.len(),
+        providers.iter().map(|p| p.models.len()).sum::<usize>()
+    );
+    format.respond_list(&page, providers)
+}
+
+/// Serve the default provider listing from the per-version body cache
+fn cached_providers(
+    state: &AppState,
+    filter: &CatalogFilter,
+    headers: &HeaderMap,
+) -> Result<Response, ApiError> {
+    let (providers, hash) = state.registry.providers_with_hash();
+    let cached = state.providers_body.get_or_build(&hash, || {
+        let providers = filter.apply(&providers);
+        let body = serde_json::to_vec(&providers).map_err(|e| {
+            tracing::error!("Failed to serialize providers: {}", e);
+            ApiError::internal("Failed to retrieve providers")
+        })?;
+        Ok((Bytes::from(body), providers.len()))
+    })?;
+
+    let etag = cached.etag();
+    if cache::not_modified(headers, &etag) {
+        return Ok((StatusCode::NOT_MODIFIED, [(header::ETAG, etag)]).into_response());
+    }
+
+    info!("Returned {} providers from the cached body", cached.count);
+    Ok((
+        [
+            (
+                header::CONTENT_TYPE,
+                HeaderValue::from_static("application/json"),
+            ),
+            (header::ETAG, etag),
+            (X_TOTAL_COUNT, HeaderValue::from(cached.count)),
+        ],
+        cached.body,
+    )
+        .into_response())
+}
+
+/// Header carrying the catalog's content hash on catalog responses
+pub const X_CATALOG_VERSION: HeaderName = HeaderName::from_static("x-catalog-version");
+
+/// Add [`X_CATALOG_VERSION`] to a catalog response
+pub async fn catalog_version_header(
+    State(state): State<AppState>,
+    mut response: Response,
+) -> Response {
+    if let Ok(value) = HeaderValue::from_str(&state.registry.dataset_hash()) {
+        response.headers_mut().insert(X_CATALOG_VERSION, value);
+    }
+    response
+}
+
+/// Fingerprint of the catalog being served
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CatalogVersion {
+    /// Hex-encoded SHA-256 of the catalog, also sent as `X-Catalog-Version`
+    pub hash: String,
+
+    /// Registry version, incremented by every change
+    pub version: u64,
+
+    /// When the catalog was loaded or last changed
+    #[schema(value_type = String, format = DateTime)]
+    pub loaded_at: DateTime<Utc>,
+}
+
+/// GET /providers/version - Returns the fingerprint of the current catalog
+///
+/// A cheap staleness check: compare `hash` with the one from the last full
+/// fetch (or its `X-Catalog-Version` header) before downloading `/providers`.
+#[utoipa::path(
+    get,
+    path = "/v1/providers/version",
+    tag = "providers",
+    responses(
+        (status = 200, description = "Catalog fingerprint", body = CatalogVersion)
+    )
+)]
+pub async fn catalog_version(State(state): State<AppState>) -> Json<CatalogVersion> {
+    Json(CatalogVersion::of(&state.registry))
+}
+
+impl CatalogVersion {
+    fn of(registry: &ProviderRegistry) -> Self {
+        Self {
+            hash: registry.dataset_hash(),
+            version: registry.version(),
+            loaded_at: registry.loaded_at(),
+        }
+    }
+
+    /// Server-sent event announcing this catalog version
+    fn event(&self) -> Result<Event, axum::Error> {
+        Event::default()
+            .event("catalog")
+            .id(self.version.to_string())
+            .json_data(self)
+    }
+}
+
+/// GET /providers/events - Streams catalog versions as server-sent events
+///
+/// Sends a `catalog` event carrying the current [`CatalogVersion`] on
+/// connect, then one after every change (reloads, syncs, admin edits), so
+/// long-lived clients know when to re-fetch `/providers` or ask
+/// `/providers/changes` for the delta. Changes made faster than a client
+/// reads coalesce into the latest version.
+#[utoipa::path(
+    get,
+    path = "/v1/providers/events",
+    tag = "providers",
+    responses(
+        (status = 200, description = "Stream of `catalog` events", content_type = "text/event-stream", body = CatalogVersion)
+    )
+)]
+pub async fn catalog_events(
+    State(state): State<AppState>,
+) -> Sse<impl Stream<Item = Result<Event, axum::Error>>> {
+    let mut updates = state.registry.subscribe();
+    updates.borrow_and_update();
+    let current = CatalogVersion::of(&state.registry).event();
+
+    let changes = stream::unfold(updates, |mut updates| async move {
+        updates.changed().await.ok()?;
+        let update = updates.borrow_and_update().clone();
+        let version = CatalogVersion {
+            hash: update.dataset_hash,
+            version: update.version,
+            loaded_at: update.updated_at,
+        };
+        Some((version.event(), updates))
+    });
+    Sse::new(stream::once(async { current }).chain(changes)).keep_alive(KeepAlive::default())
+}
+
+/// Query parameters for GET /providers/changes
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ChangesQuery {
+    /// Registry version the client already has; changes after it are returned
+    pub since: u64,
+}
+
+/// Catalog changes since a registry version
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ChangesResponse {
+    /// The `since` version that was requested
+    pub since: u64,
+
+    /// Current registry version; pass it as `since` on the next request
+    pub version: u64,
+
+    /// Additions, removals and price changes, oldest first
+    pub changes: Vec<Change>,
+}
+
+/// GET /providers/changes - Returns the catalog changes since a registry version
+///
+/// Lists provider and model additions and removals and model price changes,
+/// so caches can apply deltas instead of re-downloading the catalog. Other
+/// edits (e.g. a model's name) bump the version without a change entry.
+/// Only the most recent changes are kept; older `since` values get a 410
+/// and the client should re-fetch `/providers`.
+#[utoipa::path(
+    get,
+    path = "/v1/providers/changes",
+    tag = "providers",
+    params(ChangesQuery),
+    responses(
+        (status = 200, description = "Changes since the given version", body = ChangesResponse),
+        (status = 400, description = "Missing or invalid `since`", body = ErrorResponse),
+        (status = 410, description = "Changes since that version are no longer retained", body = ErrorResponse)
+    )
+)]
+pub async fn list_changes(
+    State(state): State<AppState>,
+    ApiQuery(query): ApiQuery<ChangesQuery>,
+) -> Result<Response, ApiError> {
+    let version = state.registry.version();
+    let changes = state
+        .registry
+        .changes_since(query.since)
+        .map_err(|oldest| ApiError::changes_expired(query.since, oldest))?;
+
+    Ok(Json(ChangesResponse {
+        since: query.since,
+        version,
+        changes,
+    })
+    .into_response())
+}
+
+/// GET /providers/:id - Returns a single provider and its models
+///
+/// Deprecated models are left out unless `include_deprecated=true`.
+#[utoipa::path(
+    get,
+    path = "/v1/providers/{id}",
+    tag = "providers",
+    params(("id" = String, Path, description = "Provider ID"), DeprecationFilter),
+    responses(
+        (status = 200, description = "The provider", content(
+            (Provider = "application/json"),
+            (Provider = "application/yaml"),
+            (Provider = "application/toml")
+        )),
+        (status = 404, description = "Unknown provider", body = ErrorResponse)
+    )
+)]
+pub async fn get_provider(
+    State(state): State<AppState>,
+    format: ResponseFormat,
+    Path(provider_id): Path<String>,
+    ApiQuery(deprecation): ApiQuery<DeprecationFilter>,
+) -> Result<Response, ApiError> {
+    let provider = state.registry.get_by_id(&provider_id).map_err(|e| {
+        tracing::error!("Failed to get provider '{}': {}", provider_id, e);
+        ApiError::internal("Failed to retrieve provider")
+    })?;
+
+    match provider {
+        Some(mut provider) => {
+            deprecation.retain(&mut provider.models);
+            format.respond_one(&provider)
+        }
+        None => Err(ApiError::provider_not_found(&provider_id)),
+    }
+}
+
+/// GET /providers/:id/models - Returns the models offered by a provider
+///
+/// Deprecated models are left out unless `include_deprecated=true`.
+#[utoipa::path(
+    get,
+    path = "/v1/providers/{id}/models",
+    tag = "providers",
+    params(("id" = String, Path, description = "Provider ID"), DeprecationFilter),
+    responses(
+        (status = 200, description = "Models offered by the provider", body = [Model]),
+        (status = 404, description = "Unknown provider", body = ErrorResponse)
+    )
+)]
+pub async fn list_provider_models(
+    State(state): State<AppState>,
+    Path(provider_id): Path<String>,
+    ApiQuery(deprecation): ApiQuery<DeprecationFilter>,
+) -> Result<Response, ApiError> {
+    let provider = state.registry.get_by_id(&provider_id).map_err(|e| {
+        tracing::error!("Failed to get provider '{}': {}", provider_id, e);
+        ApiError::internal("Failed to retrieve provider")
+    })?;
+
+    match provider {
+        Some(mut provider) => {
+            deprecation.retain(&mut provider.models);
+            Ok(Json(provider.models).into_response())
+        }
+        None => Err(ApiError::provider_not_found(&provider_id)),
+    }
+}
+
+/// GET /providers/:id/models/*model_id - Returns a single model
+///
+/// The model ID is matched as a wildcard because aggregator providers use
+/// IDs containing slashes (e.g. `deepseek-ai/DeepSeek-R1-0528`). Deprecated
+/// models are still returned, with a `Warning` header saying so.
+#[utoipa::path(
+    get,
+    path = "/v1/providers/{id}/models/{model_id}",
+    tag = "providers",
+    params(
+        ("id" = String, Path, description = "Provider ID"),
+        ("model_id" = String, Path, description = "Model ID (may contain slashes)")
+    ),
+    responses(
+        (status = 200, description = "The model", body = Model),
+        (status = 404, description = "Unknown provider or model", body = ErrorResponse)
+    )
+)]
+pub async fn get_provider_model(
+    State(state): State<AppState>,
+    Path((provider_id, model_id)): Path<(String, String)>,
+) -> Result<Response, ApiError> {
+    let model = find_model(&state.registry, &provider_id, &model_id)?;
+    let warning = deprecation_warning(&model);
+    let mut response = Json(model).into_response();
+    if let Some(warning) = warning {
+        response.headers_mut().insert(header::WARNING, warning);
+    }
+    Ok(response)
+}
+
+/// `Warning: 299` value for a deprecated or retired model (RFC 7234 miscellaneous persistent warning)
+fn deprecation_warning(model: &Model) -> Option<HeaderValue> {
+    if model.is_active() {
+        return None;
+    }
+
+    let mut text = match (model.lifecycle, model.retires_at) {
+        (Lifecycle::Retired, Some(date)) => format!("Model '{}' was retired on {}", model.id, date),
+        (_, Some(date)) => format!(
+            "Model '{}' is deprecated (retirement date {})",
+            model.id, date
+        ),
+        (_, None) => format!("Model '{}' is deprecated", model.id),
+    };
+    if let Some(replacement) = &model.replacement_model_id {
+        text.push_str(&format!("; use '{}' instead", replacement));
+    }
+    HeaderValue::from_str(&format!("299 - \"{}\"", text.replace('"', "'"))).ok()
+}
+
+/// Look up a model, mapping a miss to the matching 404 error
+///
+/// Distinguishes an unknown provider from an unknown model within a known
+/// provider.
+pub(crate) fn find_model(
+    registry: &ProviderRegistry,
+    provider_id: &str,
+    model_id: &str,
+) -> Result<Model, ApiError> {
+    let model = registry.get_model(provider_id, model_id).map_err(|e| {
+        tracing::error!(
+            "Failed to get model '{}' for provider '{}': {}",
+            model_id,
+            provider_id,
+            e
+        );
+        ApiError::internal("Failed to retrieve model")
+    })?;
+
+    if let Some(model) = model {
+        return Ok(model);
+    }
+
+    match registry.get_by_id(provider_id) {
+        Ok(Some(_)) => Err(ApiError::model_not_found(provider_id, model_id)),
+        Ok(None) => Err(ApiError::provider_not_found(provider_id)),
+        Err(e) => {
+            tracing::error!("Failed to get provider '{}': {}", provider_id, e);
+            Err(ApiError::internal("Failed to retrieve provider"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::router;
+    use crate::api::test_support::{body_json, body_text, get, get_accept, get_json, get_with};
+    use crate::Config;
+    use axum::body::Body;
+    use axum::http::Request;
+    use std::sync::Arc;
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_list_providers() {
+        let (status, body) = get_json("/providers").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body.as_array().unwrap().len(), 18);
+    }
+
+    #[tokio::test]
+    async fn test_list_providers_cached_body() {
+        let registry = Arc::new(ProviderRegistry::new().unwrap());
+        let app = router(&Config::default(), AppState::new(registry.clone()));
+
+        let response = get_with(app.clone(), "/providers").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let etag = response.headers()[header::ETAG].clone();
+        assert_eq!(etag, format!("\"{}\"", registry.dataset_hash()).as_str());
+        assert_eq!(response.headers()[X_TOTAL_COUNT], "18");
+        let cached = body_json(response).await;
+
+        // Same body as the uncached path
+        let response = get_with(app.clone(), "/providers?sort=id&order=asc").await;
+        assert!(response.headers().get(header::ETAG).is_none());
+        let mut sorted = cached.as_array().unwrap().clone();
+        sorted.sort_by(|a, b| a["id"].as_str().cmp(&b["id"].as_str()));
+        assert_eq!(body_json(response).await.as_array().unwrap(), &sorted);
+
+        let conditional = |etag: HeaderValue| {
+            Request::get("/providers")
+                .header(header::IF_NONE_MATCH, etag)
+                .body(Body::empty())
+                .unwrap()
+        };
+        let response = app
+            .clone()
+            .oneshot(conditional(etag.clone()))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(response.headers()[header::ETAG], etag);
+
+        // A catalog change invalidates the body and its ETag
+        registry.remove_provider("openai").unwrap();
+        let response = app.oneshot(conditional(etag.clone())).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_ne!(response.headers()[header::ETAG], etag);
+        assert_eq!(body_json(response).await.as_array().unwrap().len(), 17);
+    }
+
+    #[tokio::test]
+    async fn test_get_provider() {
+        let (status, body) = get_json("/providers/anthropic").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["id"], "anthropic");
+        assert!(!body["models"].as_array().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_get_provider_not_found() {
+        let (status, body) = get_json("/providers/nonexistent").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["code"], "provider_not_found");
+        assert!(body["error"].as_str().unwrap().contains("nonexistent"));
+    }
+
+    #[tokio::test]
+    async fn test_list_provider_models() {
+        let (status, body) = get_json("/providers/anthropic/models").await;
+        assert_eq!(status, StatusCode::OK);
+        let models = body.as_array().unwrap();
+        assert!(models
+            .iter()
+            .any(|m| m["id"] == "claude-sonnet-4-5-20250929"));
+    }
+
+    #[tokio::test]
+    async fn test_deprecated_models() {
+        let ids = |body: &serde_json::Value| -> Vec<String> {
+            body.as_array()
+                .unwrap()
+                .iter()
+                .map(|m| m["id"].as_str().unwrap().to_string())
+                .collect()
+        };
+        let opus = "claude-3-opus-20240229".to_string();
+
+        // Listings leave deprecated models out unless asked
+        let (_, body) = get_json("/providers/anthropic/models").await;
+        assert!(!ids(&body).contains(&opus));
+        let (_, body) = get_json("/providers/anthropic/models?include_deprecated=true").await;
+        assert!(ids(&body).contains(&opus));
+        let (_, body) = get_json("/providers/anthropic").await;
+        assert!(!ids(&body["models"]).contains(&opus));
+        let (_, body) = get_json("/models?type=anthropic").await;
+        assert!(!ids(&body).contains(&opus));
+        let (_, body) = get_json("/models?type=anthropic&include_deprecated=true").await;
+        assert!(ids(&body).contains(&opus));
+        let (_, body) = get_json("/models/search?q=claude-3-opus").await;
+        assert!(!ids(&body).contains(&opus));
+        let (_, body) = get_json("/models/search?q=claude-3-opus&include_deprecated=true").await;
+        assert!(ids(&body).contains(&opus));
+
+        // Direct fetches still work, with a warning
+        let response = get(&format!("/providers/anthropic/models/{}", opus)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let warning = response.headers()["warning"].to_str().unwrap().to_string();
+        assert!(warning.starts_with("299 - \""));
+        assert!(warning.contains("2026-01-05"));
+        assert!(warning.contains("claude-sonnet-4-5-20250929"));
+        let body = body_json(response).await;
+        assert_eq!(body["deprecated"], true);
+        assert_eq!(body["deprecation_date"], "2026-01-05");
+        assert_eq!(body["retires_at"], "2026-01-05");
+        assert_eq!(body["lifecycle"], "retired");
+
+        let response = get("/providers/anthropic/models/claude-sonnet-4-5-20250929").await;
+        assert!(!response.headers().contains_key("warning"));
+    }
+
+    #[tokio::test]
+    async fn test_provider_aliases() {
+        let (status, body) = get_json("/providers/google").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["id"], "gemini");
+
+        let (status, body) = get_json("/providers/aws/models").await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(!body.as_array().unwrap().is_empty());
+
+        let model_id = body[0]["id"].as_str().unwrap();
+        let (status, body) = get_json(&format!("/providers/aws/models/{}", model_id)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["id"], model_id);
+    }
+
+    #[tokio::test]
+    async fn test_catalog_version() {
+        let (status, body) = get_json("/providers/version").await;
+        assert_eq!(status, StatusCode::OK);
+        let hash = body["hash"].as_str().unwrap();
+        assert_eq!(hash.len(), 64);
+        assert!(body["loaded_at"].is_string());
+
+        // Every catalog response carries the same hash
+        for uri in ["/providers", "/providers/openai", "/models/search?q=gpt"] {
+            let response = get(uri).await;
+            assert_eq!(response.headers()["x-catalog-version"], hash, "{}", uri);
+        }
+        assert!(!get("/health")
+            .await
+            .headers()
+            .contains_key("x-catalog-version"));
+    }
+
+    #[tokio::test]
+    async fn test_list_changes() {
+        use crate::api::{router, test_support::get_with, AppState};
+        use crate::config::Config;
+        use crate::providers::registry::ProviderRegistry;
+        use std::sync::Arc;
+
+        let registry = Arc::new(ProviderRegistry::new().unwrap());
+        let since = registry.version();
+        registry.remove_provider("xai").unwrap();
+        registry
+            .update_provider("openai", |p| p.models[0].cost_per_1m_in = 0.5)
+            .unwrap();
+        let app = router(&Config::default(), AppState::new(registry.clone()));
+
+        let response = get_with(app.clone(), &format!("/providers/changes?since={}", since)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = body_json(response).await;
+        assert_eq!(body["version"], since + 2);
+        let changes = body["changes"].as_array().unwrap();
+        assert_eq!(changes[0]["kind"], "provider_removed");
+        assert_eq!(changes[0]["provider_id"], "xai");
+        let (price, removed) = changes[1..].split_last().unwrap();
+        assert!(removed.iter().all(|c| c["kind"] == "model_removed"));
+
+        assert_eq!(price["kind"], "price_changed");
+        assert_eq!(price["version"], since + 2);
+        assert_eq!(price["new_prices"]["cost_per_1m_in"], 0.5);
+
+        let response = get_with(
+            app.clone(),
+            &format!("/providers/changes?since={}", since + 2),
+        )
+        .await;
+        let body = body_json(response).await;
+        assert!(body["changes"].as_array().unwrap().is_empty());
+
+        let response = get_with(app, "/providers/changes").await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_catalog_events() {
+        let registry = Arc::new(ProviderRegistry::new().unwrap());
+        let version = registry.version();
+        let app = router(&Config::default(), AppState::new(registry.clone()));
+
+        let response = get_with(app, "/v1/providers/events").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["content-type"], "text/event-stream");
+        let mut events = response
+            .into_body()
+            .into_data_stream()
+            .map(|event| String::from_utf8(event.unwrap().to_vec()).unwrap());
+
+        let event = events.next().await.unwrap();
+        assert!(event.starts_with("event: catalog\n"), "{}", event);
+        assert!(event.contains(&format!("id: {}\n", version)));
+        assert!(event.contains(&registry.dataset_hash()));
+
+        registry.remove_provider("xai").unwrap();
+        let event = events.next().await.unwrap();
+        assert!(event.contains(&format!("\"version\":{}", version + 1)));
+        assert!(event.contains(&registry.dataset_hash()));
+    }
+
+    #[tokio::test]
+    async fn test_list_provider_models_unknown_provider() {
+        let (status, body) = get_json("/providers/nonexistent/models").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["code"], "provider_not_found");
+    }
+
+    #[tokio::test]
+    async fn test_get_provider_model() {
+        let (status, body) =
+            get_json("/providers/anthropic/models/claude-sonnet-4-5-20250929").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["id"], "claude-sonnet-4-5-20250929");
+    }
+
+    #[tokio::test]
+    async fn test_get_provider_model_with_slash() {
+        let (status, body) =
+            get_json("/providers/chutes/models/deepseek-ai/DeepSeek-R1-0528").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["id"], "deepseek-ai/DeepSeek-R1-0528");
+
+        // As sent by the client, with the slash percent-encoded
+        let (status, body) =
+            get_json("/providers/chutes/models/deepseek-ai%2FDeepSeek-R1-0528").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["id"], "deepseek-ai/DeepSeek-R1-0528");
+    }
+
+    #[tokio::test]
+    async fn test_get_provider_model_not_found() {
+        let (status, body) = get_json("/providers/anthropic/models/nonexistent").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["code"], "model_not_found");
+
+        let (status, body) = get_json("/providers/nonexistent/models/nonexistent").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["code"], "provider_not_found");
+    }
+
+    #[tokio::test]
+    async fn test_list_providers_filtered_by_type() {
+        let (status, body) = get_json("/providers?type=anthropic").await;
+        assert_eq!(status, StatusCode::OK);
+        let providers = body.as_array().unwrap();
+        assert!(!providers.is_empty());
+        assert!(providers.iter().all(|p| p["type"] == "anthropic"));
+    }
+
+    #[tokio::test]
+    async fn test_list_providers_filtered_by_model_capabilities() {
+        let (status, body) = get_json("/providers?can_reason=true&min_context_window=200000").await;
+        assert_eq!(status, StatusCode::OK);
+        for provider in body.as_array().unwrap() {
+            let models = provider["models"].as_array().unwrap();
+            assert!(!models.is_empty());
+            for model in models {
+                assert_eq!(model["can_reason"], true);
+                assert!(model["context_window"].as_u64().unwrap() >= 200_000);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_list_providers_invalid_filter() {
+        let (status, body) = get_json("/providers?can_reason=maybe").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["code"], "bad_request");
+    }
+
+    #[tokio::test]
+    async fn test_list_providers_paginated() {
+        let response = get("/providers?limit=5&offset=5").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["x-total-count"], "18");
+
+        let body = body_json(response).await;
+        assert_eq!(body["data"].as_array().unwrap().len(), 5);
+        assert_eq!(body["pagination"]["total"], 18);
+        assert_eq!(body["pagination"]["offset"], 5);
+        assert_eq!(body["pagination"]["next_offset"], 10);
+    }
+
+    #[tokio::test]
+    async fn test_list_providers_sorted() {
+        let (status, body) = get_json("/providers?sort=model_count&order=desc").await;
+        assert_eq!(status, StatusCode::OK);
+        let counts: Vec<usize> = body
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|p| p["models"].as_array().unwrap().len())
+            .collect();
+        assert!(counts.windows(2).all(|w| w[0] >= w[1]));
+    }
+
+    #[tokio::test]
+    async fn test_list_providers_ndjson() {
+        let response = get_accept("/providers?limit=3", "application/x-ndjson").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["content-type"], "application/x-ndjson");
+        assert_eq!(response.headers()["x-total-count"], "18");
+
+        let body = body_text(response).await;
+        let lines: Vec<&str> = body.lines().collect();
+        assert_eq!(lines.len(), 3);
+        for line in lines {
+            let provider: serde_json::Value = serde_json::from_str(line).unwrap();
+            assert!(provider["id"].is_string());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_list_providers_yaml() {
+        let response = get_accept("/providers?type=anthropic", "application/yaml").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["content-type"], "application/yaml");
+
+        let providers: Vec<crate::Provider> =
+            serde_yaml::from_str(&body_text(response).await).unwrap();
+        assert_eq!(providers.len(), 1);
+        assert_eq!(providers[0].id, "anthropic");
+    }
+
+    #[tokio::test]
+    async fn test_list_providers_toml() {
+        let response = get_accept("/providers", "application/toml").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["content-type"], "application/toml");
+
+        let document: toml::Value = toml::from_str(&body_text(response).await).unwrap();
+        assert_eq!(document["data"].as_array().unwrap().len(), 18);
+    }
+
+    #[tokio::test]
+    async fn test_get_provider_toml() {
+        let response = get_accept("/providers/openai", "application/toml").await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let provider: crate::Provider = toml::from_str(&body_text(response).await).unwrap();
+        assert_eq!(provider.id, "openai");
+    }
+
+    #[tokio::test]
+    async fn test_list_providers_invalid_sort() {
+        let (status, _) = get_json("/providers?sort=bogus").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+}
@@ -0,0 +1,302 @@
+use axum::extract::State;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::error::ErrorResponse;
+use super::estimate::{price, CurrencyQuery, EstimateRequest, Usage};
+use super::{ApiError, ApiJson, ApiQuery, AppState, CatalogFilter};
+use crate::ProviderModel;
+
+/// Default number of recommendations returned
+pub const DEFAULT_RECOMMEND_LIMIT: usize = 10;
+
+/// Largest number of recommendations returned
+pub const MAX_RECOMMEND_LIMIT: usize = 100;
+
+/// Constraints for POST /recommend
+///
+/// All constraints are optional; candidates must satisfy every constraint
+/// that is set.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(default)]
+pub struct RecommendRequest {
+    /// Maximum cost per 1M input tokens (USD)
+    pub max_cost_per_1m_in: Option<f64>,
+
+    /// Maximum cost per 1M output tokens (USD)
+    pub max_cost_per_1m_out: Option<f64>,
+
+    /// Minimum context window in tokens
+    pub min_context_window: Option<u64>,
+
+    /// Earliest acceptable end of the training data
+    #[schema(value_type = Option<String>, format = Date)]
+    pub min_knowledge_cutoff: Option<NaiveDate>,
+
+    /// Require reasoning support
+    pub needs_reasoning: bool,
+
+    /// Require attachment (image) support
+    pub needs_attachments: bool,
+
+    /// Require tool (function) calling support
+    pub needs_tools: bool,
+
+    /// Require schema-constrained (structured) output
+    pub needs_structured_output: bool,
+
+    /// Require streaming support
+    pub needs_streaming: bool,
+
+    /// Require published, redistributable weights
+    pub needs_open_weights: bool,
+
+    /// Only consider these provider IDs
+    pub providers: Option<Vec<String>>,
+
+    /// Only consider models served from this region (e.g. `eu-west-1`)
+    pub region: Option<String>,
+
+    /// Input tokens of the workload used for ranking (default 1M)
+    pub input_tokens: Option<u64>,
+
+    /// Output tokens of the workload used for ranking (default 1M)
+    pub output_tokens: Option<u64>,
+
+    /// Maximum number of candidates to return (default 10, max 100)
+    pub limit: Option<usize>,
+}
+
+/// A ranked candidate model
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Recommendation {
+    /// 1-based rank (1 is the best match)
+    pub rank: usize,
+
+    /// The candidate model, with its provider ID
+    #[serde(flatten)]
+    pub model: ProviderModel,
+
+    /// Cost of the ranking workload on this model, in the response's `currency`
+    pub estimated_cost: f64,
+}
+
+/// Result of POST /recommend
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RecommendResponse {
+    /// Candidates, cheapest first
+    pub recommendations: Vec<Recommendation>,
+
+    /// Number of models that satisfied the constraints before `limit` was applied
+    pub total_candidates: usize,
+
+    /// Currency of `estimated_cost` (ISO 4217 code)
+    pub currency: String,
+}
+
+impl RecommendRequest {
+    /// Validate the request and return the effective limit
+    fn limit(&self) -> Result<usize, ApiError> {
+        match self.limit {
+            Some(0) => Err(ApiError::bad_request("limit must be greater than 0")),
+            Some(limit) if limit > MAX_RECOMMEND_LIMIT => Err(ApiError::bad_request(format!(
+                "limit must not exceed {}",
+                MAX_RECOMMEND_LIMIT
+            ))),
+            Some(limit) => Ok(limit),
+            None => Ok(DEFAULT_RECOMMEND_LIMIT),
+        }
+    }
+
+    /// Capability constraints expressed as a catalog filter
+    fn filter(&self) -> CatalogFilter {
+        CatalogFilter {
+            provider_type: None,
+            model_kind: None,
+            can_reason: self.needs_reasoning.then_some(true),
+            supports_attachments: self.needs_attachments.then_some(true),
+            supports_tools: self.needs_tools.then_some(true),
+            supports_structured_output: self.needs_structured_output.then_some(true),
+            supports_streaming: self.needs_streaming.then_some(true),
+            open_weights: self.needs_open_weights.then_some(true),
+            license: None,
+            min_context_window: self.min_context_window,
+            input_modality: None,
+            output_modality: None,
+            min_knowledge_cutoff: self.min_knowledge_cutoff,
+            released_after: None,
+            region: self.region.clone(),
+            tag: None,
+            benchmark: None,
+            min_benchmark_score: None,
+            include_deprecated: false,
+        }
+    }
+
+    /// Check the pricing and provider constraints
+    fn matches(&self, candidate: &ProviderModel) -> bool {
+        if let Some(providers) = &self.providers {
+            if !providers.iter().any(|p| p == &candidate.provider_id) {
+                return false;
+            }
+        }
+
+        if let Some(max) = self.max_cost_per_1m_in {
+            if candidate.model.cost_per_1m_in > max {
+                return false;
+            }
+        }
+
+        if let Some(max) = self.max_cost_per_1m_out {
+            if candidate.model.cost_per_1m_out > max {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+/// POST /recommend - Rank models satisfying a set of constraints
+///
+/// Candidates are ranked by the cost of the workload given by
+/// `input_tokens` / `output_tokens`, with larger context windows breaking ties.
+/// Price constraints are in USD; `estimated_cost` is reported in `currency`.
+#[utoipa::path(
+    post,
+    path = "/v1/recommend",
+    tag = "models",
+    params(CurrencyQuery),
+    request_body = RecommendRequest,
+    responses(
+        (status = 200, description = "Ranked candidate models", body = RecommendResponse),
+        (status = 400, description = "Invalid constraints or unknown currency", body = ErrorResponse)
+    )
+)]
+pub async fn recommend(
+    State(state): State<AppState>,
+    ApiQuery(currency): ApiQuery<CurrencyQuery>,
+    ApiJson(request): ApiJson<RecommendRequest>,
+) -> Result<Response, ApiError> {
+    let limit = request.limit()?;
+    let conversion = currency.conversion(&state.currencies)?;
+
+    let mut candidates: Vec<(ProviderModel, f64)> = request
+        .filter()
+        .apply(&state.registry.providers())
+        .iter()
+        .flat_map(|provider| {
+            provider.models.iter().map(|model| ProviderModel {
+                provider_id: provider.id.clone(),
+                model: model.clone(),
+            })
+        })
+        .filter(|candidate| request.matches(candidate))
+        .map(|candidate| {
+            let workload = EstimateRequest {
+                provider_id: candidate.provider_id.clone(),
+                model_id: candidate.model.id.clone(),
+                usage: Usage {
+                    input_tokens: request.input_tokens.unwrap_or(1_000_000),
+                    output_tokens: request.output_tokens.unwrap_or(1_000_000),
+                    ..Usage::default()
+                },
+            };
+            let cost = price(&candidate.model, &workload).total_cost;
+            (candidate, cost)
+        })
+        .collect();
+
+    candidates.sort_by(|(a, a_cost), (b, b_cost)| {
+        a_cost
+            .total_cmp(b_cost)
+            .then_with(|| b.model.context_window.cmp(&a.model.context_window))
+    });
+
+    let total_candidates = candidates.len();
+    let recommendations = candidates
+        .into_iter()
+        .take(limit)
+        .enumerate()
+        .map(|(i, (model, estimated_cost))| Recommendation {
+            rank: i + 1,
+            model,
+            estimated_cost: conversion.apply(estimated_cost),
+        })
+        .collect();
+
+    Ok(Json(RecommendResponse {
+        recommendations,
+        total_candidates,
+        currency: conversion.currency,
+    })
+    .into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::test_support::{body_json, post_json};
+    use axum::http::StatusCode;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_recommend_with_constraints() {
+        let response = post_json(
+            "/v1/recommend",
+            json!({
+                "max_cost_per_1m_out": 20.0,
+                "min_context_window": 128000,
+                "needs_reasoning": true,
+                "limit": 5
+            }),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = body_json(response).await;
+        let recommendations = body["recommendations"].as_array().unwrap();
+        assert!(!recommendations.is_empty());
+        assert!(recommendations.len() <= 5);
+        assert!(body["total_candidates"].as_u64().unwrap() >= recommendations.len() as u64);
+
+        for (i, r) in recommendations.iter().enumerate() {
+            assert_eq!(r["rank"], i + 1);
+            assert_eq!(r["can_reason"], true);
+            assert!(r["cost_per_1m_out"].as_f64().unwrap() <= 20.0);
+            assert!(r["context_window"].as_u64().unwrap() >= 128_000);
+        }
+
+        let costs: Vec<f64> = recommendations
+            .iter()
+            .map(|r| r["estimated_cost"].as_f64().unwrap())
+            .collect();
+        assert!(costs.windows(2).all(|w| w[0] <= w[1]));
+    }
+
+    #[tokio::test]
+    async fn test_recommend_restricted_to_providers() {
+        let response = post_json(
+            "/v1/recommend",
+            json!({ "providers": ["anthropic"], "needs_attachments": true }),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = body_json(response).await;
+        let recommendations = body["recommendations"].as_array().unwrap();
+        assert!(!recommendations.is_empty());
+        assert!(recommendations
+            .iter()
+            .all(|r| r["provider_id"] == "anthropic" && r["supports_attachments"] == true));
+    }
+
+    #[tokio::test]
+    async fn test_recommend_invalid_limit() {
+        let response = post_json("/v1/recommend", json!({ "limit": 0 })).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(body_json(response).await["code"], "bad_request");
+    }
+}
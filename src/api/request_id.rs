@@ -0,0 +1,99 @@
+//! `X-Request-Id` handling
+//!
+//! Every request gets an ID: the client's `X-Request-Id` header when present,
+//! otherwise a generated UUID. The ID is echoed in the response, attached to
+//! a tracing span wrapping the handler, and included in JSON error bodies.
+
+use axum::extract::Request;
+use axum::http::HeaderName;
+use axum::middleware::{self, Next};
+use axum::response::Response;
+use axum::Router;
+use tower_http::request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer};
+use tracing::Instrument;
+
+/// Request ID header name
+pub const X_REQUEST_ID: HeaderName = HeaderName::from_static("x-request-id");
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// ID of the request currently being handled, if any
+pub fn current() -> Option<String> {
+    REQUEST_ID.try_with(|id| id.clone()).ok()
+}
+
+/// Run the rest of the stack with the request ID in scope and on a span
+async fn scope_request_id(request: Request, next: Next) -> Response {
+    let request_id = request
+        .headers()
+        .get(&X_REQUEST_ID)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default()
+        .to_string();
+
+    let span = tracing::info_span!("request", request_id = %request_id);
+    REQUEST_ID
+        .scope(request_id, next.run(request).instrument(span))
+        .await
+}
+
+/// Add request ID generation, propagation and scoping to a router
+pub fn layer<S>(router: Router<S>) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    router
+        .layer(PropagateRequestIdLayer::new(X_REQUEST_ID))
+        .layer(middleware::from_fn(scope_request_id))
+        .layer(SetRequestIdLayer::new(X_REQUEST_ID, MakeRequestUuid))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::X_REQUEST_ID;
+    use crate::api::test_support::{body_json, get, send};
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+
+    #[tokio::test]
+    async fn test_request_id_generated() {
+        let response = get("/health").await;
+        let request_id = response.headers()[X_REQUEST_ID].to_str().unwrap();
+        assert_eq!(request_id.len(), 36);
+    }
+
+    #[tokio::test]
+    async fn test_request_id_propagated() {
+        let response = send(
+            Request::get("/v1/providers")
+                .header(X_REQUEST_ID, "abc-123")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(response.headers()[X_REQUEST_ID], "abc-123");
+    }
+
+    #[tokio::test]
+    async fn test_request_id_in_error_body() {
+        let response = send(
+            Request::get("/v1/providers/nonexistent")
+                .header(X_REQUEST_ID, "abc-123")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(response.headers()[X_REQUEST_ID], "abc-123");
+        assert_eq!(body_json(response).await["request_id"], "abc-123");
+    }
+
+    #[tokio::test]
+    async fn test_request_id_on_not_found_route() {
+        let response = get("/nope").await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert!(response.headers().contains_key(X_REQUEST_ID));
+    }
+}
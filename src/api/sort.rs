@@ -0,0 +1,301 @@
+use serde::Deserialize;
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use utoipa::{IntoParams, ToSchema};
+
+use super::ApiError;
+use crate::{Model, Provider, ProviderModel};
+
+/// Field to sort listings by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SortKey {
+    Id,
+    Name,
+    ModelCount,
+    #[serde(rename = "cost_per_1m_in")]
+    CostPer1mIn,
+    #[serde(rename = "cost_per_1m_out")]
+    CostPer1mOut,
+    ContextWindow,
+    Benchmark,
+    MedianTtftMs,
+    MedianTokensPerSecond,
+}
+
+/// Sort direction
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SortOrder {
+    #[default]
+    Asc,
+    Desc,
+}
+
+/// Sorting query parameters (`?sort=cost_per_1m_in&order=asc`)
+///
+/// Providers are sorted by their cheapest model for price keys, by their
+/// largest context window for `context_window`, by their best score for
+/// `benchmark` and by their fastest model for `median_ttft_ms` and
+/// `median_tokens_per_second`. `model_count` only applies to providers.
+/// Models without latency data sort last in ascending `median_ttft_ms` and
+/// descending `median_tokens_per_second` order.
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SortParams {
+    /// Field to sort by; listings keep catalog order when absent
+    pub sort: Option<SortKey>,
+
+    /// Sort direction (defaults to ascending)
+    #[serde(default)]
+    pub order: SortOrder,
+
+    /// Benchmark to sort by with `sort=benchmark`, shared with the
+    /// [`CatalogFilter`](super::CatalogFilter) parameter of the same name
+    #[param(ignore)]
+    pub benchmark: Option<String>,
+}
+
+impl SortParams {
+    fn apply_order(&self, ordering: Ordering) -> Ordering {
+        match self.order {
+            SortOrder::Asc => ordering,
+            SortOrder::Desc => ordering.reverse(),
+        }
+    }
+
+    /// Benchmark to sort by, when sorting by benchmark
+    fn benchmark(&self) -> Result<&str, ApiError> {
+        self.benchmark
+            .as_deref()
+            .ok_or_else(|| ApiError::bad_request("Sort key 'benchmark' requires a benchmark"))
+    }
+
+    /// Sort providers in place
+    pub fn sort_providers<P: Borrow<Provider>>(&self, providers: &mut [P]) -> Result<(), ApiError> {
+        let Some(key) = self.sort else {
+            return Ok(());
+        };
+        let benchmark = if key == SortKey::Benchmark {
+            self.benchmark()?
+        } else {
+            ""
+        };
+
+        providers.sort_by(|a, b| {
+            let (a, b): (&Provider, &Provider) = (a.borrow(), b.borrow());
+            let ordering = match key {
+                SortKey::Id => a.id.cmp(&b.id),
+                SortKey::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+                SortKey::ModelCount => a.models.len().cmp(&b.models.len()),
+                SortKey::CostPer1mIn => {
+                    min_cost(a, |m| m.cost_per_1m_in).total_cmp(&min_cost(b, |m| m.cost_per_1m_in))
+                }
+                SortKey::CostPer1mOut => min_cost(a, |m| m.cost_per_1m_out)
+                    .total_cmp(&min_cost(b, |m| m.cost_per_1m_out)),
+                SortKey::ContextWindow => max_context(a).cmp(&max_context(b)),
+                SortKey::Benchmark => best_score(a, benchmark).total_cmp(&best_score(b, benchmark)),
+                SortKey::MedianTtftMs => min_ttft(a).cmp(&min_ttft(b)),
+                SortKey::MedianTokensPerSecond => max_speed(a).total_cmp(&max_speed(b)),
+            };
+            self.apply_order(ordering)
+        });
+
+        Ok(())
+    }
+
+    /// Sort models in place
+    pub fn sort_models(&self, models: &mut [ProviderModel]) -> Result<(), ApiError> {
+        let Some(key) = self.sort else {
+            return Ok(());
+        };
+
+        if key == SortKey::ModelCount {
+            return Err(ApiError::bad_request(
+                "Sort key 'model_count' is only supported for providers",
+            ));
+        }
+        let benchmark = if key == SortKey::Benchmark {
+            self.benchmark()?
+        } else {
+            ""
+        };
+
+        models.sort_by(|a, b| {
+            let (a, b) = (&a.model, &b.model);
+            let ordering = match key {
+                SortKey::Id => a.id.cmp(&b.id),
+                SortKey::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+                SortKey::CostPer1mIn => a.cost_per_1m_in.total_cmp(&b.cost_per_1m_in),
+                SortKey::CostPer1mOut => a.cost_per_1m_out.total_cmp(&b.cost_per_1m_out),
+                SortKey::ContextWindow => a.context_window.cmp(&b.context_window),
+                SortKey::Benchmark => score(a, benchmark).total_cmp(&score(b, benchmark)),
+                SortKey::MedianTtftMs => ttft(a).cmp(&ttft(b)),
+                SortKey::MedianTokensPerSecond => speed(a).total_cmp(&speed(b)),
+                SortKey::ModelCount => Ordering::Equal,
+            };
+            self.apply_order(ordering)
+        });
+
+        Ok(())
+    }
+}
+
+/// Cheapest price across a provider's models (providers without models sort last)
+fn min_cost(provider: &Provider, price: impl Fn(&Model) -> f64) -> f64 {
+    provider
+        .models
+        .iter()
+        .map(price)
+        .min_by(f64::total_cmp)
+        .unwrap_or(f64::INFINITY)
+}
+
+/// Score of a model on a benchmark (unscored models sort first)
+fn score(model: &Model, benchmark: &str) -> f64 {
+    model.benchmark(benchmark).unwrap_or(f64::NEG_INFINITY)
+}
+
+/// Best score on a benchmark across a provider's models
+fn best_score(provider: &Provider, benchmark: &str) -> f64 {
+    provider
+        .models
+        .iter()
+        .map(|m| score(m, benchmark))
+        .max_by(f64::total_cmp)
+        .unwrap_or(f64::NEG_INFINITY)
+}
+
+/// Median time to first token of a model (models without data sort last)
+fn ttft(model: &Model) -> u64 {
+    model.median_ttft_ms.unwrap_or(u64::MAX)
+}
+
+/// Median output speed of a model (models without data sort first)
+fn speed(model: &Model) -> f64 {
+    model.median_tokens_per_second.unwrap_or(f64::NEG_INFINITY)
+}
+
+/// Lowest median time to first token across a provider's models
+fn min_ttft(provider: &Provider) -> u64 {
+    provider.models.iter().map(ttft).min().unwrap_or(u64::MAX)
+}
+
+/// Highest median output speed across a provider's models
+fn max_speed(provider: &Provider) -> f64 {
+    provider
+        .models
+        .iter()
+        .map(speed)
+        .max_by(f64::total_cmp)
+        .unwrap_or(f64::NEG_INFINITY)
+}
+
+/// Largest context window across a provider's models
+fn max_context(provider: &Provider) -> u64 {
+    provider
+        .models
+        .iter()
+        .map(|m| m.context_window)
+        .max()
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: &str, cost_in: f64, context_window: u64) -> ProviderModel {
+        ProviderModel {
+            provider_id: "test".to_string(),
+            model: Model::new(
+                id.to_string(),
+                id.to_string(),
+                cost_in,
+                1.0,
+                context_window,
+                1000,
+            ),
+        }
+    }
+
+    #[test]
+    fn test_sort_models_by_cost() {
+        let mut models = vec![
+            entry("b", 3.0, 10),
+            entry("a", 1.0, 20),
+            entry("c", 2.0, 30),
+        ];
+        let params = SortParams {
+            sort: Some(SortKey::CostPer1mIn),
+            order: SortOrder::Asc,
+            ..SortParams::default()
+        };
+        params.sort_models(&mut models).unwrap();
+        let ids: Vec<_> = models.iter().map(|m| m.model.id.as_str()).collect();
+        assert_eq!(ids, vec!["a", "c", "b"]);
+    }
+
+    #[test]
+    fn test_sort_models_desc() {
+        let mut models = vec![
+            entry("b", 3.0, 10),
+            entry("a", 1.0, 20),
+            entry("c", 2.0, 30),
+        ];
+        let params = SortParams {
+            sort: Some(SortKey::ContextWindow),
+            order: SortOrder::Desc,
+            ..SortParams::default()
+        };
+        params.sort_models(&mut models).unwrap();
+        let ids: Vec<_> = models.iter().map(|m| m.model.id.as_str()).collect();
+        assert_eq!(ids, vec!["c", "a", "b"]);
+    }
+
+    #[test]
+    fn test_sort_models_by_latency() {
+        let mut models = vec![
+            entry("none", 1.0, 1),
+            entry("slow", 1.0, 1),
+            entry("fast", 1.0, 1),
+        ];
+        models[1].model.median_ttft_ms = Some(900);
+        models[2].model.median_ttft_ms = Some(150);
+        let params = SortParams {
+            sort: Some(SortKey::MedianTtftMs),
+            order: SortOrder::Asc,
+            ..SortParams::default()
+        };
+        params.sort_models(&mut models).unwrap();
+        let ids: Vec<_> = models.iter().map(|m| m.model.id.as_str()).collect();
+        // Models without latency data come last
+        assert_eq!(ids, vec!["fast", "slow", "none"]);
+    }
+
+    #[test]
+    fn test_sort_models_rejects_model_count() {
+        let params = SortParams {
+            sort: Some(SortKey::ModelCount),
+            order: SortOrder::Asc,
+            ..SortParams::default()
+        };
+        assert!(params.sort_models(&mut []).is_err());
+    }
+
+    #[test]
+    fn test_sort_providers_by_model_count() {
+        let one = Provider::new("One".into(), "one".into(), "t".into())
+            .with_model(entry("a", 1.0, 1).model);
+        let none = Provider::new("None".into(), "none".into(), "t".into());
+        let mut providers = vec![one, none];
+
+        let params = SortParams {
+            sort: Some(SortKey::ModelCount),
+            order: SortOrder::Asc,
+            ..SortParams::default()
+        };
+        params.sort_providers(&mut providers).unwrap();
+        assert_eq!(providers[0].id, "none");
+    }
+}
@@ -0,0 +1,48 @@
+use axum::extract::State;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+
+use super::{ApiError, AppState};
+use crate::providers::stats::CatalogStats;
+
+/// GET /stats - Aggregate statistics over the catalog
+#[utoipa::path(
+    get,
+    path = "/v1/stats",
+    tag = "providers",
+    responses(
+        (status = 200, description = "Catalog statistics", body = CatalogStats)
+    )
+)]
+pub async fn stats(State(state): State<AppState>) -> Result<Response, ApiError> {
+    let stats = state.registry.stats().map_err(|e| {
+        tracing::error!("Failed to compute catalog stats: {}", e);
+        ApiError::internal("Failed to compute catalog statistics")
+    })?;
+
+    Ok(Json(stats).into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::test_support::get_json;
+    use axum::http::StatusCode;
+
+    #[tokio::test]
+    async fn test_stats() {
+        let (status, body) = get_json("/v1/stats").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["provider_count"], 18);
+        assert!(body["model_count"].as_u64().unwrap() > 100);
+        assert!(body["cheapest_model"]["provider_id"].is_string());
+        assert!(body["most_expensive_model"]["cost_per_1m_out"].is_number());
+
+        let anthropic = body["providers"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|p| p["id"] == "anthropic")
+            .unwrap();
+        assert!(anthropic["median_cost_per_1m_in"].as_f64().unwrap() > 0.0);
+    }
+}
@@ -0,0 +1,281 @@
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use chrono::{DateTime, Utc};
+use prometheus::{Encoder, TextEncoder};
+use serde::{Deserialize, Serialize};
+
+use super::{ApiError, ApiQuery, AppState};
+use crate::providers::registry::LoadFailure;
+
+/// Crate version the binary was built from
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Git commit the binary was built from (`unknown` outside a checkout)
+pub const GIT_SHA: &str = env!("CRABRACE_GIT_SHA");
+
+/// Build time as Unix seconds (see `build.rs`)
+const BUILD_TIMESTAMP: &str = env!("CRABRACE_BUILD_TIMESTAMP");
+
+/// Build and dataset information returned by GET /version
+#[derive(Debug, Serialize)]
+pub struct VersionInfo {
+    pub version: &'static str,
+    pub git_sha: &'static str,
+    /// RFC 3339 build time
+    pub build_timestamp: Option<DateTime<Utc>>,
+    pub dataset: DatasetInfo,
+}
+
+/// Identifies the provider catalog a running instance is serving
+#[derive(Debug, Serialize)]
+pub struct DatasetInfo {
+    /// Hex-encoded SHA-256 of the serialized catalog
+    pub hash: String,
+    /// Registry version, incremented by every admin mutation
+    pub version: u64,
+    pub provider_count: usize,
+    pub model_count: usize,
+}
+
+/// Build time parsed from [`BUILD_TIMESTAMP`]
+fn build_timestamp() -> Option<DateTime<Utc>> {
+    BUILD_TIMESTAMP
+        .parse()
+        .ok()
+        .and_then(|secs| DateTime::from_timestamp(secs, 0))
+}
+
+/// Query parameters for GET /health
+#[derive(Debug, Default, Deserialize)]
+pub struct HealthQuery {
+    /// Return the full per-provider integrity report as JSON
+    #[serde(default)]
+    pub verbose: bool,
+}
+
+/// GET /health - Health check endpoint
+///
+/// Verifies catalog integrity (every embedded config parsed, no provider
+/// without models, default model IDs resolve). Returns `OK`, or 503 when the
+/// catalog is unhealthy; `?verbose=true` returns the full report as JSON.
+/// Unresolved default model IDs only mark the catalog as degraded.
+pub async fn health(
+    State(state): State<AppState>,
+    ApiQuery(query): ApiQuery<HealthQuery>,
+) -> Result<Response, ApiError> {
+    let report = state.registry.check_integrity().map_err(|e| {
+        tracing::error!("Failed to check registry integrity: {}", e);
+        ApiError::internal("Failed to check registry integrity")
+    })?;
+
+    let status = if report.is_serving() {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    if query.verbose {
+        return Ok((status, Json(report)).into_response());
+    }
+
+    let body = if report.is_serving() {
+        "OK"
+    } else {
+        "UNHEALTHY"
+    };
+    Ok((status, body).into_response())
+}
+
+/// GET /livez - Liveness probe (the process is up and serving requests)
+pub async fn livez() -> Response {
+    (StatusCode::OK, "OK").into_response()
+}
+
+/// Result of the readiness checks returned by GET /readyz
+#[derive(Debug, Serialize)]
+pub struct ReadinessReport {
+    /// `ready` or `not_ready`
+    pub status: &'static str,
+    pub checks: ReadinessChecks,
+
+    /// Embedded provider configurations that failed to parse
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub failed_providers: Vec<LoadFailure>,
+
+    /// Configuration validation error, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub config_error: Option<String>,
+}
+
+/// Individual readiness checks
+#[derive(Debug, Serialize)]
+pub struct ReadinessChecks {
+    /// The registry holds at least one provider
+    pub registry_loaded: bool,
+
+    /// Every embedded provider configuration parsed
+    pub providers_parsed: bool,
+
+    /// The configuration passed validation
+    pub config_valid: bool,
+}
+
+/// GET /readyz - Readiness probe
+///
+/// Returns 503 with the failing checks until the registry is loaded, every
+/// expected provider parsed, and the configuration is valid.
+pub async fn readyz(State(state): State<AppState>, config_error: Option<String>) -> Response {
+    let failed_providers = state.registry.load_failures();
+    let checks = ReadinessChecks {
+        registry_loaded: state.registry.count() > 0,
+        providers_parsed: failed_providers.is_empty(),
+        config_valid: config_error.is_none(),
+    };
+    let ready = checks.registry_loaded && checks.providers_parsed && checks.config_valid;
+
+    let report = ReadinessReport {
+        status: if ready { "ready" } else { "not_ready" },
+        checks,
+        failed_providers,
+        config_error,
+    };
+    let status = if ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(report)).into_response()
+}
+
+/// GET /version - Build and dataset information
+pub async fn version(State(state): State<AppState>) -> Json<VersionInfo> {
+    Json(VersionInfo {
+        version: VERSION,
+        git_sha: GIT_SHA,
+        build_timestamp: build_timestamp(),
+        dataset: DatasetInfo {
+            hash: state.registry.dataset_hash(),
+            version: state.registry.version(),
+            provider_count: state.registry.count(),
+            model_count: state.registry.model_count(),
+        },
+    })
+}
+
+/// GET /metrics - Prometheus metrics endpoint
+pub async fn metrics() -> Response {
+    let encoder = TextEncoder::new();
+    let metric_families = prometheus::gather();
+    let mut buffer = Vec::new();
+
+    match encoder.encode(&metric_families, &mut buffer) {
+        Ok(_) => (
+            StatusCode::OK,
+            [(
+                axum::http::header::CONTENT_TYPE,
+                "text/plain; version=0.0.4",
+            )],
+            buffer,
+        )
+            .into_response(),
+        Err(e) => {
+            tracing::error!("Failed to encode metrics: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to encode metrics",
+            )
+                .into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::test_support::{app_with_config, body_text, get, get_json, get_with};
+    use crate::Config;
+    use axum::http::StatusCode;
+
+    #[tokio::test]
+    async fn test_health() {
+        let response = get("/health").await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_health_verbose() {
+        let (status, body) = get_json("/health?verbose=true").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_ne!(body["status"], "unhealthy");
+        assert!(body["load_failures"].as_array().unwrap().is_empty());
+
+        let providers = body["providers"].as_array().unwrap();
+        assert_eq!(providers.len(), 18);
+        let anthropic = providers.iter().find(|p| p["id"] == "anthropic").unwrap();
+        assert_eq!(anthropic["status"], "ok");
+    }
+
+    #[tokio::test]
+    async fn test_livez() {
+        let response = get("/livez").await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_readyz() {
+        let (status, body) = get_json("/readyz").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], "ready");
+        assert_eq!(body["checks"]["registry_loaded"], true);
+        assert_eq!(body["checks"]["providers_parsed"], true);
+        assert_eq!(body["checks"]["config_valid"], true);
+    }
+
+    #[tokio::test]
+    async fn test_readyz_invalid_config() {
+        let mut config = Config::default();
+        config.logging.level = "loud".to_string();
+
+        let response = get_with(app_with_config(&config), "/readyz").await;
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[tokio::test]
+    async fn test_custom_probe_paths() {
+        let mut config = Config::default();
+        config.health.liveness_path = "/healthz/live".to_string();
+        config.health.readiness_path = "/healthz/ready".to_string();
+        let app = app_with_config(&config);
+
+        let response = get_with(app.clone(), "/healthz/live").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = get_with(app.clone(), "/healthz/ready").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = get_with(app, "/livez").await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_version() {
+        let (status, body) = get_json("/version").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["version"], env!("CARGO_PKG_VERSION"));
+        assert!(body["git_sha"].is_string());
+        assert!(body["build_timestamp"].is_string());
+        assert_eq!(body["dataset"]["hash"].as_str().unwrap().len(), 64);
+        assert_eq!(body["dataset"]["provider_count"], 18);
+    }
+
+    #[tokio::test]
+    async fn test_metrics() {
+        get("/v1/providers").await;
+
+        let response = get("/metrics").await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = body_text(response).await;
+        assert!(body.contains("crabrace_http_requests_total"));
+        assert!(body.contains("crabrace_http_request_duration_seconds_bucket"));
+    }
+}
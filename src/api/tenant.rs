@@ -0,0 +1,158 @@
+//! Tenant selection
+//!
+//! Tenant catalogs are served under `/v1/t/{tenant}`. Clients that cannot
+//! change their base URL send an `X-Tenant` header instead: `/v1` requests
+//! carrying it are rewritten to the tenant's routes before routing.
+//! `X-Tenant: default` selects the main catalog, and unknown tenants get a
+//! JSON 404 either way.
+
+use axum::extract::{Request, State};
+use axum::http::{HeaderName, Uri};
+use std::sync::Arc;
+
+use super::ApiError;
+use crate::providers::tenants::{Tenants, DEFAULT_TENANT};
+
+/// Tenant selection header
+pub const X_TENANT: HeaderName = HeaderName::from_static("x-tenant");
+
+/// Where tenant routes are mounted and which tenants exist
+#[derive(Clone)]
+pub struct TenantRouting {
+    /// Path of the versioned API, base path included (e.g. `/crabrace/v1`)
+    prefix: String,
+    names: Arc<Vec<String>>,
+}
+
+impl TenantRouting {
+    pub fn new(prefix: impl Into<String>, tenants: &Tenants) -> Self {
+        Self {
+            prefix: prefix.into(),
+            names: Arc::new(tenants.names().into_iter().map(str::to_string).collect()),
+        }
+    }
+
+    fn exists(&self, tenant: &str) -> bool {
+        self.names.iter().any(|name| name == tenant)
+    }
+}
+
+/// Middleware routing `X-Tenant` requests to the tenant's routes and rejecting unknown tenants
+pub async fn select_tenant(
+    State(routing): State<TenantRouting>,
+    mut request: Request,
+) -> Result<Request, ApiError> {
+    let path = request.uri().path();
+    let Some(rest) = path
+        .strip_prefix(routing.prefix.as_str())
+        .filter(|rest| rest.starts_with('/'))
+    else {
+        return Ok(request);
+    };
+
+    // Explicit tenant path; the header is ignored
+    if let Some(tenant_path) = rest.strip_prefix("/t/") {
+        let tenant = tenant_path.split('/').next().unwrap_or_default();
+        if !routing.exists(tenant) {
+            return Err(ApiError::tenant_not_found(tenant));
+        }
+        return Ok(request);
+    }
+
+    let Some(tenant) = request.headers().get(&X_TENANT) else {
+        return Ok(request);
+    };
+    let tenant = String::from_utf8_lossy(tenant.as_bytes())
+        .trim()
+        .to_string();
+    if tenant == DEFAULT_TENANT {
+        return Ok(request);
+    }
+    if !routing.exists(&tenant) {
+        return Err(ApiError::tenant_not_found(&tenant));
+    }
+
+    // Tenant names are validated at startup, so the new path is always valid
+    let mut path = format!("{}/t/{}{}", routing.prefix, tenant, rest);
+    if let Some(query) = request.uri().query() {
+        path = format!("{}?{}", path, query);
+    }
+    let mut parts = request.uri().clone().into_parts();
+    parts.path_and_query = Some(
+        path.parse()
+            .map_err(|e| ApiError::internal(format!("Invalid tenant path: {}", e)))?,
+    );
+    *request.uri_mut() = Uri::from_parts(parts)
+        .map_err(|e| ApiError::internal(format!("Invalid tenant path: {}", e)))?;
+    Ok(request)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::test_support::{body_json, get_with};
+    use crate::api::{router, AppState};
+    use crate::config::TenantConfig;
+    use crate::providers::registry::ProviderRegistry;
+    use crate::Config;
+    use axum::body::Body;
+    use axum::http::StatusCode;
+    use axum::Router;
+    use tower::ServiceExt;
+
+    fn app() -> Router {
+        let mut config = Config::default();
+        config.tenants.insert(
+            "team-a".to_string(),
+            TenantConfig {
+                enabled: vec!["anthropic".to_string()],
+                ..TenantConfig::default()
+            },
+        );
+        let registry = Arc::new(ProviderRegistry::new().unwrap());
+        let tenants = Tenants::build(&registry, &config).unwrap();
+        router(&config, AppState::new(registry).with_tenants(tenants))
+    }
+
+    async fn get_as(app: Router, uri: &str, tenant: &str) -> axum::response::Response {
+        let request = Request::get(uri)
+            .header(X_TENANT, tenant)
+            .body(Body::empty())
+            .unwrap();
+        app.oneshot(request).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_tenant_path() {
+        let response = get_with(app(), "/v1/t/team-a/providers").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let providers = body_json(response).await;
+        assert_eq!(providers.as_array().unwrap().len(), 1);
+        assert_eq!(providers[0]["id"], "anthropic");
+
+        let response = get_with(app(), "/v1/t/team-a/providers/openai").await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let response = get_with(app(), "/v1/t/team-b/providers").await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(body_json(response).await["code"], "tenant_not_found");
+
+        // The main catalog is unchanged
+        let response = get_with(app(), "/v1/providers/openai").await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_tenant_header() {
+        let response = get_as(app(), "/v1/providers?sort=name", "team-a").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(body_json(response).await.as_array().unwrap().len(), 1);
+
+        let response = get_as(app(), "/v1/providers/openai", "default").await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = get_as(app(), "/v1/providers", "../admin").await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(body_json(response).await["code"], "tenant_not_found");
+    }
+}
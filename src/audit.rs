@@ -0,0 +1,277 @@
+//! Audit log for admin operations
+//!
+//! Every catalog mutation is recorded with who made it, when, and the state
+//! of the changed resource before and after. Entries are appended as JSON
+//! lines to an optional file (never rewritten) and the most recent ones are
+//! kept in memory for `GET /admin/audit`.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, LineWriter, Write};
+use utoipa::ToSchema;
+
+use crate::config::AuditConfig;
+
+/// One audited admin operation
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct AuditEntry {
+    #[schema(value_type = String, format = DateTime)]
+    pub timestamp: DateTime<Utc>,
+
+    /// Caller identity (token subject or API key fingerprint)
+    pub actor: String,
+
+    /// Operation, e.g. `create_provider` or `update_model`
+    pub action: String,
+
+    pub provider_id: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_id: Option<String>,
+
+    /// Resource state before the change (`null` when created)
+    #[schema(value_type = Object)]
+    pub before: Option<Value>,
+
+    /// Resource state after the change (`null` when removed)
+    #[schema(value_type = Object)]
+    pub after: Option<Value>,
+
+    /// JSON pointers of the fields that differ between `before` and `after`
+    pub changed_fields: Vec<String>,
+
+    /// Registry version after the change
+    pub registry_version: u64,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+}
+
+impl AuditEntry {
+    /// Create an entry, computing the changed fields from the two states
+    pub fn new(
+        actor: impl Into<String>,
+        action: impl Into<String>,
+        provider_id: impl Into<String>,
+        model_id: Option<String>,
+        before: Option<Value>,
+        after: Option<Value>,
+        registry_version: u64,
+    ) -> Self {
+        let mut changed_fields = Vec::new();
+        diff(
+            "",
+            before.as_ref().unwrap_or(&Value::Null),
+            after.as_ref().unwrap_or(&Value::Null),
+            &mut changed_fields,
+        );
+
+        Self {
+            timestamp: Utc::now(),
+            actor: actor.into(),
+            action: action.into(),
+            provider_id: provider_id.into(),
+            model_id,
+            before,
+            after,
+            changed_fields,
+            registry_version,
+            request_id: crate::api::request_id::current(),
+        }
+    }
+}
+
+/// Collect JSON pointers of the leaves that differ between two values
+///
+/// Objects are compared key by key; arrays and scalars are compared whole.
+fn diff(path: &str, before: &Value, after: &Value, changes: &mut Vec<String>) {
+    match (before, after) {
+        (Value::Object(a), Value::Object(b)) => {
+            let mut keys: Vec<&String> = a.keys().chain(b.keys()).collect();
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                let child = format!("{}/{}", path, key.replace('~', "~0").replace('/', "~1"));
+                diff(
+                    &child,
+                    a.get(key).unwrap_or(&Value::Null),
+                    b.get(key).unwrap_or(&Value::Null),
+                    changes,
+                );
+            }
+        }
+        _ if before != after => changes.push(if path.is_empty() {
+            "/".to_string()
+        } else {
+            path.to_string()
+        }),
+        _ => {}
+    }
+}
+
+/// Append-only audit log with an in-memory window of recent entries
+pub struct AuditLog {
+    recent: RwLock<VecDeque<AuditEntry>>,
+    max_entries: usize,
+    writer: Option<Mutex<LineWriter<File>>>,
+}
+
+impl AuditLog {
+    /// Keep up to `max_entries` entries in memory only
+    pub fn in_memory(max_entries: usize) -> Self {
+        Self {
+            recent: RwLock::new(VecDeque::new()),
+            max_entries,
+            writer: None,
+        }
+    }
+
+    /// Build an audit log from configuration
+    ///
+    /// With a file, previous entries are read back so the review endpoint
+    /// covers history from before a restart.
+    pub fn from_config(config: &AuditConfig) -> Result<Self> {
+        let mut log = Self::in_memory(config.max_entries);
+        let Some(path) = &config.file else {
+            return Ok(log);
+        };
+
+        if let Ok(file) = File::open(path) {
+            let mut recent = VecDeque::new();
+            for line in BufReader::new(file).lines() {
+                let line = line.with_context(|| format!("Failed to read audit log: {}", path))?;
+                match serde_json::from_str::<AuditEntry>(&line) {
+                    Ok(entry) => {
+                        recent.push_back(entry);
+                        if recent.len() > log.max_entries {
+                            recent.pop_front();
+                        }
+                    }
+                    Err(e) => tracing::warn!("Skipping unreadable audit log line: {}", e),
+                }
+            }
+            log.recent = RwLock::new(recent);
+        }
+
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open audit log file: {}", path))?;
+        log.writer = Some(Mutex::new(LineWriter::new(file)));
+        Ok(log)
+    }
+
+    /// Record an entry
+    pub fn record(&self, entry: AuditEntry) {
+        if let Some(writer) = &self.writer {
+            let mut writer = writer.lock();
+            let result = serde_json::to_writer(&mut *writer, &entry)
+                .map_err(std::io::Error::from)
+                .and_then(|_| writer.write_all(b"\n"));
+            if let Err(e) = result {
+                tracing::error!("Failed to write audit log entry: {}", e);
+            }
+        }
+
+        tracing::info!(
+            actor = %entry.actor,
+            action = %entry.action,
+            provider_id = %entry.provider_id,
+            "Audited admin operation"
+        );
+
+        let mut recent = self.recent.write();
+        recent.push_back(entry);
+        if recent.len() > self.max_entries {
+            recent.pop_front();
+        }
+    }
+
+    /// Retained entries, newest first
+    pub fn entries(&self) -> Vec<AuditEntry> {
+        self.recent.read().iter().rev().cloned().collect()
+    }
+}
+
+impl Default for AuditLog {
+    fn default() -> Self {
+        Self::in_memory(AuditConfig::default().max_entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn entry(action: &str, version: u64) -> AuditEntry {
+        AuditEntry::new(
+            "key:test",
+            action,
+            "anthropic",
+            None,
+            None,
+            Some(json!({})),
+            version,
+        )
+    }
+
+    #[test]
+    fn test_changed_fields() {
+        let entry = AuditEntry::new(
+            "alice",
+            "update_model",
+            "anthropic",
+            Some("claude".into()),
+            Some(json!({"id": "claude", "cost_per_1m_in": 3.0, "tags": ["a"]})),
+            Some(json!({"id": "claude", "cost_per_1m_in": 2.5, "tags": ["a", "b"], "new": true})),
+            2,
+        );
+        assert_eq!(
+            entry.changed_fields,
+            vec!["/cost_per_1m_in", "/new", "/tags"]
+        );
+
+        let created = AuditEntry::new("alice", "create_model", "x", None, None, Some(json!(1)), 3);
+        assert_eq!(created.changed_fields, vec!["/"]);
+    }
+
+    #[test]
+    fn test_retention() {
+        let log = AuditLog::in_memory(2);
+        for version in 1..=3 {
+            log.record(entry("update_provider", version));
+        }
+
+        let versions: Vec<u64> = log.entries().iter().map(|e| e.registry_version).collect();
+        assert_eq!(versions, vec![3, 2]);
+    }
+
+    #[test]
+    fn test_file_is_appended_and_reloaded() {
+        let path = std::env::temp_dir().join(format!("crabrace-audit-{}.log", std::process::id()));
+        let config = AuditConfig {
+            file: Some(path.to_string_lossy().into_owned()),
+            ..Default::default()
+        };
+
+        AuditLog::from_config(&config)
+            .unwrap()
+            .record(entry("create_provider", 2));
+        let log = AuditLog::from_config(&config).unwrap();
+        log.record(entry("delete_provider", 3));
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(contents.lines().count(), 2);
+
+        let actions: Vec<String> = log.entries().into_iter().map(|e| e.action).collect();
+        assert_eq!(actions, vec!["delete_provider", "create_provider"]);
+    }
+}
@@ -0,0 +1,416 @@
+//! JWT validation against a shared secret, a JWKS URL or an OIDC issuer
+
+use anyhow::{Context, Result};
+use jsonwebtoken::jwk::{Jwk, JwkSet};
+use jsonwebtoken::{Algorithm, DecodingKey, Validation};
+use parking_lot::RwLock;
+use serde::Deserialize;
+use std::time::{Duration, Instant};
+
+use crate::config::JwtConfig;
+
+/// Claims read from a validated token
+#[derive(Debug, Deserialize)]
+struct Claims {
+    #[serde(default)]
+    sub: Option<String>,
+
+    /// OAuth 2.0 space-delimited scopes
+    #[serde(default)]
+    scope: Option<String>,
+
+    /// Scopes as used by some providers (string or array)
+    #[serde(default)]
+    scp: Option<serde_json::Value>,
+}
+
+impl Claims {
+    fn scopes(&self) -> Vec<String> {
+        let mut scopes: Vec<String> = self
+            .scope
+            .iter()
+            .flat_map(|s| s.split_whitespace())
+            .map(str::to_string)
+            .collect();
+
+        match &self.scp {
+            Some(serde_json::Value::String(s)) => {
+                scopes.extend(s.split_whitespace().map(str::to_string))
+            }
+            Some(serde_json::Value::Array(values)) => {
+                scopes.extend(values.iter().filter_map(|v| v.as_str()).map(str::to_string))
+            }
+            _ => {}
+        }
+
+        scopes
+    }
+}
+
+/// A successfully validated token
+#[derive(Debug, Clone)]
+pub struct TokenInfo {
+    pub subject: Option<String>,
+    pub scopes: Vec<String>,
+}
+
+/// Where verification keys come from
+enum KeySource {
+    /// HMAC shared secret and the one HS256/HS384/HS512 algorithm accepted
+    Secret(DecodingKey, Algorithm),
+    /// Key set fetched from a URL, or discovered from the issuer when `None`
+    Jwks { url: Option<String> },
+}
+
+impl std::fmt::Debug for KeySource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KeySource::Secret(_, algorithm) => write!(f, "Secret(.., {:?})", algorithm),
+            KeySource::Jwks { url } => f.debug_struct("Jwks").field("url", url).finish(),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct CachedJwks {
+    url: String,
+    keys: JwkSet,
+    fetched: Instant,
+}
+
+/// Validates bearer JWTs
+#[derive(Debug)]
+pub struct JwtValidator {
+    issuer: Option<String>,
+    audience: Option<String>,
+    source: KeySource,
+    refresh_interval: Duration,
+    jwks: RwLock<Option<CachedJwks>>,
+    http: reqwest::Client,
+}
+
+impl JwtValidator {
+    /// Build a validator from configuration (`None` when disabled)
+    pub fn from_config(config: &JwtConfig) -> Result<Option<Self>> {
+        if !config.enabled {
+            return Ok(None);
+        }
+
+        let source = match (&config.secret, &config.jwks_url, &config.issuer) {
+            (Some(secret), _, _) => {
+                let algorithm = match config.algorithm.as_str() {
+                    "HS256" => Algorithm::HS256,
+                    "HS384" => Algorithm::HS384,
+                    "HS512" => Algorithm::HS512,
+                    other => anyhow::bail!(
+                        "Unsupported JWT algorithm '{}' for a shared secret (expected HS256, HS384 or HS512)",
+                        other
+                    ),
+                };
+                KeySource::Secret(DecodingKey::from_secret(secret.as_bytes()), algorithm)
+            }
+            (None, Some(url), _) => KeySource::Jwks {
+                url: Some(url.clone()),
+            },
+            (None, None, Some(_)) => KeySource::Jwks { url: None },
+            (None, None, None) => anyhow::bail!(
+                "JWT authentication requires a secret, a jwks_url or an issuer for OIDC discovery"
+            ),
+        };
+
+        Ok(Some(Self {
+            issuer: config.issuer.clone(),
+            audience: config.audience.clone(),
+            source,
+            refresh_interval: Duration::from_secs(config.jwks_refresh_seconds),
+            jwks: RwLock::new(None),
+            http: reqwest::Client::builder()
+                .timeout(Duration::from_secs(10))
+                .build()?,
+        }))
+    }
+
+    /// Validate a token's signature and registered claims
+    pub async fn validate(&self, token: &str) -> Result<TokenInfo> {
+        let header = jsonwebtoken::decode_header(token).context("Malformed token")?;
+
+        let (key, algorithm) = match &self.source {
+            // The token's own `alg` header is never trusted with a shared secret
+            KeySource::Secret(key, algorithm) => (key.clone(), *algorithm),
+            KeySource::Jwks { .. } => {
+                let jwk = self.find_jwk(header.kid.as_deref()).await?;
+                let algorithm = match &jwk.common.key_algorithm {
+                    Some(alg) => alg.to_string().parse::<Algorithm>()?,
+                    None => header.alg,
+                };
+                (DecodingKey::from_jwk(&jwk)?, algorithm)
+            }
+        };
+
+        let mut validation = Validation::new(algorithm);
+        match &self.issuer {
+            Some(issuer) => validation.set_issuer(&[issuer]),
+            None => validation.iss = None,
+        }
+        match &self.audience {
+            Some(audience) => validation.set_audience(&[audience]),
+            None => validation.validate_aud = false,
+        }
+
+        let data = jsonwebtoken::decode::<Claims>(token, &key, &validation)
+            .context("Token validation failed")?;
+
+        Ok(TokenInfo {
+            scopes: data.claims.scopes(),
+            subject: data.claims.sub,
+        })
+    }
+
+    /// Find the signing key, refetching the key set when the ID is unknown
+    async fn find_jwk(&self, kid: Option<&str>) -> Result<Jwk> {
+        let lookup = |jwks: &JwkSet| match kid {
+            Some(kid) => jwks.find(kid).cloned(),
+            None if jwks.keys.len() == 1 => jwks.keys.first().cloned(),
+            None => None,
+        };
+
+        let stale = {
+            let cached = self.jwks.read();
+            if let Some(cached) = cached.as_ref() {
+                if let Some(jwk) = lookup(&cached.keys) {
+                    return Ok(jwk);
+                }
+            }
+            cached
+                .as_ref()
+                .map_or(true, |c| c.fetched.elapsed() >= self.refresh_interval)
+        };
+
+        // Unknown key: refetch at most once per refresh interval (key rotation)
+        if !stale {
+            anyhow::bail!("No matching signing key");
+        }
+
+        let url = self.jwks_url().await?;
+        let keys: JwkSet = self
+            .http
+            .get(&url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await
+            .with_context(|| format!("Failed to fetch JWKS from {}", url))?;
+        let jwk = lookup(&keys);
+
+        *self.jwks.write() = Some(CachedJwks {
+            url,
+            keys,
+            fetched: Instant::now(),
+        });
+
+        jwk.context("No matching signing key")
+    }
+
+    /// JWKS location: configured, previously discovered, or via OIDC discovery
+    async fn jwks_url(&self) -> Result<String> {
+        if let KeySource::Jwks { url: Some(url) } = &self.source {
+            return Ok(url.clone());
+        }
+        if let Some(cached) = self.jwks.read().as_ref() {
+            return Ok(cached.url.clone());
+        }
+
+        #[derive(Deserialize)]
+        struct Discovery {
+            jwks_uri: String,
+        }
+
+        let issuer = self.issuer.as_deref().context("No issuer configured")?;
+        let discovery_url = format!(
+            "{}/.well-known/openid-configuration",
+            issuer.trim_end_matches('/')
+        );
+        let discovery: Discovery = self
+            .http
+            .get(&discovery_url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await
+            .with_context(|| format!("OIDC discovery failed for {}", issuer))?;
+
+        Ok(discovery.jwks_uri)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use jsonwebtoken::{encode, EncodingKey, Header};
+    use serde_json::json;
+
+    pub const SECRET: &str = "test-secret";
+
+    /// Sign claims with HS256 and the given key ID
+    pub fn token(secret: &str, kid: Option<&str>, claims: serde_json::Value) -> String {
+        token_with(Algorithm::HS256, secret, kid, claims)
+    }
+
+    fn token_with(
+        algorithm: Algorithm,
+        secret: &str,
+        kid: Option<&str>,
+        claims: serde_json::Value,
+    ) -> String {
+        let mut header = Header::new(algorithm);
+        header.kid = kid.map(str::to_string);
+        encode(
+            &header,
+            &claims,
+            &EncodingKey::from_secret(secret.as_bytes()),
+        )
+        .unwrap()
+    }
+
+    pub fn exp() -> u64 {
+        jsonwebtoken::get_current_timestamp() + 600
+    }
+
+    fn config() -> JwtConfig {
+        JwtConfig {
+            enabled: true,
+            secret: Some(SECRET.to_string()),
+            issuer: Some("https://issuer.example".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_requires_key_source() {
+        let config = JwtConfig {
+            enabled: true,
+            ..Default::default()
+        };
+        assert!(JwtValidator::from_config(&config).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_validate_secret() {
+        let validator = JwtValidator::from_config(&config()).unwrap().unwrap();
+
+        let valid = token(
+            SECRET,
+            None,
+            json!({"sub": "alice", "iss": "https://issuer.example", "exp": exp(), "scope": "read admin"}),
+        );
+        let info = validator.validate(&valid).await.unwrap();
+        assert_eq!(info.subject.as_deref(), Some("alice"));
+        assert_eq!(info.scopes, vec!["read", "admin"]);
+
+        let wrong_issuer = token(
+            SECRET,
+            None,
+            json!({"iss": "https://evil.example", "exp": exp()}),
+        );
+        assert!(validator.validate(&wrong_issuer).await.is_err());
+
+        let expired = token(
+            SECRET,
+            None,
+            json!({"iss": "https://issuer.example", "exp": 1}),
+        );
+        assert!(validator.validate(&expired).await.is_err());
+
+        let wrong_key = token(
+            "other",
+            None,
+            json!({"iss": "https://issuer.example", "exp": exp()}),
+        );
+        assert!(validator.validate(&wrong_key).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_secret_algorithm() {
+        let claims = json!({"iss": "https://issuer.example", "exp": exp()});
+        let hs384 = token_with(Algorithm::HS384, SECRET, None, claims.clone());
+
+        // Only the configured algorithm is accepted, whatever the token claims
+        let validator = JwtValidator::from_config(&config()).unwrap().unwrap();
+        assert!(validator.validate(&hs384).await.is_err());
+
+        let validator = JwtValidator::from_config(&JwtConfig {
+            algorithm: "HS384".to_string(),
+            ..config()
+        })
+        .unwrap()
+        .unwrap();
+        validator.validate(&hs384).await.unwrap();
+        assert!(validator
+            .validate(&token(SECRET, None, claims))
+            .await
+            .is_err());
+
+        for algorithm in ["RS256", "none", "hs256"] {
+            let error = JwtValidator::from_config(&JwtConfig {
+                algorithm: algorithm.to_string(),
+                ..config()
+            })
+            .unwrap_err();
+            assert!(error.to_string().contains("Unsupported JWT algorithm"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_validate_oidc_discovery() {
+        let mut server = mockito::Server::new_async().await;
+        let issuer = server.url();
+
+        let discovery = server
+            .mock("GET", "/.well-known/openid-configuration")
+            .with_body(json!({ "jwks_uri": format!("{}/jwks.json", issuer) }).to_string())
+            .create_async()
+            .await;
+        // Symmetric JWK so the test needs no key generation ("dGVzdC1zZWNyZXQ" = "test-secret")
+        let jwks = server
+            .mock("GET", "/jwks.json")
+            .with_body(
+                json!({ "keys": [{ "kty": "oct", "kid": "k1", "alg": "HS256", "k": "dGVzdC1zZWNyZXQ" }] })
+                    .to_string(),
+            )
+            .expect(1)
+            .create_async()
+            .await;
+
+        let validator = JwtValidator::from_config(&JwtConfig {
+            enabled: true,
+            issuer: Some(issuer.clone()),
+            ..Default::default()
+        })
+        .unwrap()
+        .unwrap();
+
+        let claims = json!({"iss": issuer, "exp": exp(), "scp": ["catalog:read"]});
+        let info = validator
+            .validate(&token(SECRET, Some("k1"), claims.clone()))
+            .await
+            .unwrap();
+        assert_eq!(info.scopes, vec!["catalog:read"]);
+
+        // Cached key set is reused
+        validator
+            .validate(&token(SECRET, Some("k1"), claims.clone()))
+            .await
+            .unwrap();
+
+        // Unknown key IDs are rejected without refetching inside the refresh interval
+        assert!(validator
+            .validate(&token(SECRET, Some("k2"), claims))
+            .await
+            .is_err());
+
+        discovery.assert_async().await;
+        jwks.assert_async().await;
+    }
+}
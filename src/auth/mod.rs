@@ -0,0 +1,495 @@
+//! API authentication
+//!
+//! Requests present a key either as `Authorization: Bearer <key>` or in the
+//! `X-Api-Key` header. Keys come from `[security.auth]`: inline, from a key
+//! file (one key per line) or from an environment variable (comma separated).
+//!
+//! When `[security.auth.jwt]` is enabled, bearer tokens shaped like a JWT are
+//! validated against the configured issuer instead, and their scopes decide
+//! access to read-only catalog routes versus admin endpoints.
+
+pub mod jwt;
+
+use anyhow::{Context, Result};
+use axum::extract::{Request, State};
+use axum::http::{header, HeaderMap, HeaderName, HeaderValue};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+
+use crate::api::ApiError;
+use crate::config::AuthConfig;
+use jwt::JwtValidator;
+
+/// Header carrying an API key
+pub const X_API_KEY: HeaderName = HeaderName::from_static("x-api-key");
+
+/// How a request authenticated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthMethod {
+    ApiKey,
+    Jwt,
+}
+
+/// Authenticated caller, stored in request extensions by [`require_auth`]
+#[derive(Debug, Clone)]
+pub struct Principal {
+    pub method: AuthMethod,
+
+    /// Token subject (`sub` claim), or `key:<fingerprint>` for API keys
+    pub subject: Option<String>,
+
+    /// Granted scopes; `None` means unrestricted (API keys)
+    pub scopes: Option<Vec<String>>,
+}
+
+impl Principal {
+    /// Identity recorded in audit logs
+    pub fn actor(&self) -> String {
+        match (&self.subject, self.method) {
+            (Some(subject), _) => subject.clone(),
+            (None, AuthMethod::Jwt) => "jwt:unknown".to_string(),
+            (None, AuthMethod::ApiKey) => "key:unknown".to_string(),
+        }
+    }
+
+    /// Whether the caller was granted `scope`
+    pub fn has_scope(&self, scope: &str) -> bool {
+        self.scopes
+            .as_ref()
+            .map_or(true, |scopes| scopes.iter().any(|s| s == scope))
+    }
+}
+
+/// Validates request credentials
+#[derive(Debug)]
+pub struct Authenticator {
+    keys: Vec<String>,
+    jwt: Option<JwtValidator>,
+    read_scope: Option<String>,
+    admin_scope: String,
+}
+
+impl Authenticator {
+    /// Build an authenticator from configuration (`None` when disabled)
+    pub fn from_config(config: &AuthConfig) -> Result<Option<Self>> {
+        if !config.enabled {
+            return Ok(None);
+        }
+
+        let mut keys = config.keys.clone();
+
+        if let Some(path) = &config.keys_file {
+            let contents = std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read API key file: {}", path))?;
+            keys.extend(
+                contents
+                    .lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                    .map(str::to_string),
+            );
+        }
+
+        if let Some(var) = &config.keys_env {
+            if let Ok(value) = std::env::var(var) {
+                keys.extend(
+                    value
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|key| !key.is_empty())
+                        .map(str::to_string),
+                );
+            }
+        }
+
+        keys.retain(|key| !key.is_empty());
+        let jwt = JwtValidator::from_config(&config.jwt)?;
+        if keys.is_empty() && jwt.is_none() {
+            anyhow::bail!("Authentication is enabled but no API keys are configured");
+        }
+
+        Ok(Some(Self {
+            keys,
+            jwt,
+            read_scope: config.jwt.read_scope.clone(),
+            admin_scope: config.jwt.admin_scope.clone(),
+        }))
+    }
+
+    /// Number of configured keys
+    pub fn key_count(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Whether bearer JWTs are accepted
+    pub fn jwt_enabled(&self) -> bool {
+        self.jwt.is_some()
+    }
+
+    /// Check the credentials carried by a request
+    ///
+    /// Fails with 403 when a token lacks the configured read scope.
+    pub async fn authenticate(&self, headers: &HeaderMap) -> Result<Principal, ApiError> {
+        let credential =
+            presented_key(headers).ok_or_else(|| ApiError::unauthorized("Missing API key"))?;
+
+        if let (Some(jwt), Credential::Bearer(token)) = (&self.jwt, credential) {
+            if looks_like_jwt(token) {
+                let info = jwt.validate(token).await.map_err(|e| {
+                    tracing::debug!("Rejected bearer token: {:#}", e);
+                    ApiError::unauthorized("Invalid bearer token")
+                })?;
+                let principal = Principal {
+                    method: AuthMethod::Jwt,
+                    subject: info.subject,
+                    scopes: Some(info.scopes),
+                };
+
+                if let Some(scope) = &self.read_scope {
+                    if !principal.has_scope(scope) {
+                        return Err(ApiError::forbidden(format!(
+                            "Token is missing the '{}' scope",
+                            scope
+                        )));
+                    }
+                }
+                return Ok(principal);
+            }
+        }
+
+        let key = credential.value();
+        if self
+            .keys
+            .iter()
+            .any(|valid| constant_time_eq(valid.as_bytes(), key.as_bytes()))
+        {
+            Ok(Principal {
+                method: AuthMethod::ApiKey,
+                subject: Some(format!("key:{}", key_fingerprint(key))),
+                scopes: None,
+            })
+        } else {
+            Err(ApiError::unauthorized("Invalid API key"))
+        }
+    }
+
+    /// Check that a caller may use admin and mutation endpoints
+    ///
+    /// API keys are unrestricted; tokens need the configured admin scope.
+    pub fn authorize_admin(&self, principal: Option<&Principal>) -> Result<(), ApiError> {
+        match principal {
+            Some(principal) if principal.has_scope(&self.admin_scope) => Ok(()),
+            Some(_) => Err(ApiError::forbidden(format!(
+                "Token is missing the '{}' scope",
+                self.admin_scope
+            ))),
+            None => Err(ApiError::unauthorized("Authentication required")),
+        }
+    }
+}
+
+/// A credential presented by a request
+#[derive(Debug, Clone, Copy)]
+enum Credential<'a> {
+    Bearer(&'a str),
+    ApiKey(&'a str),
+}
+
+impl<'a> Credential<'a> {
+    fn value(self) -> &'a str {
+        match self {
+            Credential::Bearer(value) | Credential::ApiKey(value) => value,
+        }
+    }
+}
+
+/// Extract the credential from `Authorization: Bearer` or `X-Api-Key`
+fn presented_key(headers: &HeaderMap) -> Option<Credential<'_>> {
+    let bearer = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim)
+        .filter(|key| !key.is_empty())
+        .map(Credential::Bearer);
+
+    bearer.or_else(|| {
+        headers
+            .get(&X_API_KEY)
+            .and_then(|value| value.to_str().ok())
+            .map(str::trim)
+            .filter(|key| !key.is_empty())
+            .map(Credential::ApiKey)
+    })
+}
+
+/// Short, non-reversible identifier for an API key (first 8 hex digits of its SHA-256)
+fn key_fingerprint(key: &str) -> String {
+    Sha256::digest(key.as_bytes())
+        .iter()
+        .take(4)
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Compact JWS serialization: three dot-separated segments
+fn looks_like_jwt(token: &str) -> bool {
+    token.split('.').count() == 3
+}
+
+/// Compare two byte strings without short-circuiting on the first mismatch
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Middleware rejecting unauthenticated requests with 401 (or 403 for
+/// insufficient scope) and recording the [`Principal`] otherwise
+///
+/// Requests to the exempt paths (see [`Config::auth_exempt_paths`](crate::Config::auth_exempt_paths))
+/// pass through.
+pub async fn require_auth(
+    State((auth, exempt_paths)): State<(Arc<Authenticator>, Arc<[String]>)>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let path = request.uri().path();
+    if exempt_paths.iter().any(|exempt| exempt == path) {
+        return next.run(request).await;
+    }
+
+    match auth.authenticate(request.headers()).await {
+        Ok(principal) => {
+            request.extensions_mut().insert(principal);
+            next.run(request).await
+        }
+        Err(err) => (
+            [(header::WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"))],
+            err,
+        )
+            .into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::test_support::{body_json, send_with, state_with_auth};
+    use crate::config::JwtConfig;
+    use crate::Config;
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use serde_json::json;
+
+    fn config(keys: &[&str]) -> AuthConfig {
+        AuthConfig {
+            enabled: true,
+            keys: keys.iter().map(|k| k.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_disabled() {
+        assert!(Authenticator::from_config(&AuthConfig::default())
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_enabled_without_keys() {
+        assert!(Authenticator::from_config(&config(&[])).is_err());
+    }
+
+    #[test]
+    fn test_keys_file() {
+        let path = std::env::temp_dir().join(format!("crabrace-keys-{}", std::process::id()));
+        std::fs::write(&path, "# comment\nkey-one\n\n  key-two  \n").unwrap();
+
+        let auth = Authenticator::from_config(&AuthConfig {
+            keys_file: Some(path.to_string_lossy().into_owned()),
+            ..config(&[])
+        })
+        .unwrap()
+        .unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(auth.key_count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_authenticate() {
+        let auth = Authenticator::from_config(&config(&["secret"]))
+            .unwrap()
+            .unwrap();
+
+        let mut headers = HeaderMap::new();
+        assert_eq!(
+            auth.authenticate(&headers).await.unwrap_err().code(),
+            "unauthorized"
+        );
+
+        headers.insert(header::AUTHORIZATION, "Bearer secret".parse().unwrap());
+        let principal = auth.authenticate(&headers).await.unwrap();
+        assert_eq!(principal.method, AuthMethod::ApiKey);
+        assert!(principal.has_scope("crabrace:admin"));
+        assert_eq!(principal.actor(), "key:2bb80d53");
+
+        headers.insert(header::AUTHORIZATION, "Bearer wrong".parse().unwrap());
+        assert!(auth.authenticate(&headers).await.is_err());
+
+        let mut headers = HeaderMap::new();
+        headers.insert(X_API_KEY, "secret".parse().unwrap());
+        assert!(auth.authenticate(&headers).await.is_ok());
+    }
+
+    fn jwt_config(read_scope: Option<&str>) -> AuthConfig {
+        AuthConfig {
+            jwt: JwtConfig {
+                enabled: true,
+                secret: Some(jwt::tests::SECRET.to_string()),
+                read_scope: read_scope.map(str::to_string),
+                ..Default::default()
+            },
+            ..config(&[])
+        }
+    }
+
+    fn bearer(token: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::AUTHORIZATION,
+            format!("Bearer {}", token).parse().unwrap(),
+        );
+        headers
+    }
+
+    #[tokio::test]
+    async fn test_authenticate_jwt() {
+        let auth = Authenticator::from_config(&jwt_config(Some("catalog:read")))
+            .unwrap()
+            .unwrap();
+        assert_eq!(auth.key_count(), 0);
+
+        let token = jwt::tests::token(
+            jwt::tests::SECRET,
+            None,
+            json!({"sub": "svc", "exp": jwt::tests::exp(), "scope": "catalog:read"}),
+        );
+        let principal = auth.authenticate(&bearer(&token)).await.unwrap();
+        assert_eq!(principal.method, AuthMethod::Jwt);
+        assert_eq!(principal.subject.as_deref(), Some("svc"));
+        assert_eq!(
+            auth.authorize_admin(Some(&principal)).unwrap_err().code(),
+            "forbidden"
+        );
+
+        let admin = jwt::tests::token(
+            jwt::tests::SECRET,
+            None,
+            json!({"exp": jwt::tests::exp(), "scope": "catalog:read crabrace:admin"}),
+        );
+        let principal = auth.authenticate(&bearer(&admin)).await.unwrap();
+        assert!(auth.authorize_admin(Some(&principal)).is_ok());
+
+        let no_scope =
+            jwt::tests::token(jwt::tests::SECRET, None, json!({"exp": jwt::tests::exp()}));
+        let err = auth.authenticate(&bearer(&no_scope)).await.unwrap_err();
+        assert_eq!(err.status(), StatusCode::FORBIDDEN);
+
+        let forged = jwt::tests::token(
+            "forged",
+            None,
+            json!({"exp": jwt::tests::exp(), "scope": "catalog:read"}),
+        );
+        let err = auth.authenticate(&bearer(&forged)).await.unwrap_err();
+        assert_eq!(err.status(), StatusCode::UNAUTHORIZED);
+
+        assert!(auth.authorize_admin(None).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_middleware_jwt() {
+        let auth = Authenticator::from_config(&jwt_config(None))
+            .unwrap()
+            .unwrap();
+        let token = jwt::tests::token(jwt::tests::SECRET, None, json!({"exp": jwt::tests::exp()}));
+
+        let response = send_with(
+            &Config::default(),
+            state_with_auth(auth),
+            Request::get("/v1/providers")
+                .header(header::AUTHORIZATION, format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_middleware() {
+        let auth = Authenticator::from_config(&config(&["secret"]))
+            .unwrap()
+            .unwrap();
+        let state = state_with_auth(auth);
+        let config = Config::default();
+
+        let response = send_with(
+            &config,
+            state.clone(),
+            Request::get("/v1/providers").body(Body::empty()).unwrap(),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(response.headers()[header::WWW_AUTHENTICATE], "Bearer");
+        let body = body_json(response).await;
+        assert_eq!(body["code"], "unauthorized");
+        assert!(body["request_id"].is_string());
+
+        let response = send_with(
+            &config,
+            state.clone(),
+            Request::get("/v1/providers")
+                .header(X_API_KEY, "secret")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // Probes and metrics are exempt by default
+        let response = send_with(
+            &config,
+            state.clone(),
+            Request::get("/health").body(Body::empty()).unwrap(),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // Wherever they are served
+        let mut config = Config::default();
+        config.health.liveness_path = "/k8s/live".to_string();
+        config.metrics.path = "/internal/metrics".to_string();
+        for path in ["/k8s/live", "/internal/metrics", "/readyz"] {
+            let response = send_with(
+                &config,
+                state.clone(),
+                Request::get(path).body(Body::empty()).unwrap(),
+            )
+            .await;
+            assert_eq!(response.status(), StatusCode::OK, "{}", path);
+        }
+
+        // Unless the exempt paths are set explicitly
+        config.security.auth.exempt_paths = Some(vec!["/health".to_string()]);
+        let response = send_with(
+            &config,
+            state,
+            Request::get("/k8s/live").body(Body::empty()).unwrap(),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+}
@@ -0,0 +1,586 @@
+//! Synchronous Crabrace client (feature `blocking`)
+//!
+//! Mirrors [`crate::CrabraceClient`] on top of `reqwest::blocking`, for CLI
+//! tools and build scripts that do not run an async runtime. Retries, the
+//! response cache, conditional requests, credentials and offline fallbacks
+//! behave exactly as in the async client.
+//!
+//! Like every `reqwest::blocking` client, it must not be used from within an
+//! async runtime.
+
+use anyhow::Result;
+use bytes::Bytes;
+use reqwest::blocking::{Client as HttpClient, RequestBuilder, Response};
+use reqwest::header::{ACCEPT, IF_NONE_MATCH};
+use reqwest::{Method, StatusCode};
+use serde::Serialize;
+use std::io::{BufRead, BufReader};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::builder::{CrabraceClientBuilder, DEFAULT_USER_AGENT};
+use crate::cache::ResponseCache;
+use crate::fallback::Fallback;
+use crate::intercept::{Interceptor, Interceptors};
+use crate::stream::SseParser;
+use crate::watch::Watcher;
+use crate::{api_url, etag_of, read_providers, retry, stream, Credential};
+use crate::{
+    CatalogRevision, EstimateRequest, EstimateResponse, Fetched, Model, Provider, RetryPolicy,
+    Usage,
+};
+
+/// Blocking Crabrace HTTP client
+///
+/// # Example
+///
+/// ```no_run
+/// use crabrace::blocking::CrabraceClient;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let client = CrabraceClient::new("http://localhost:8080");
+/// for provider in client.get_providers()? {
+///     println!("Provider: {} ({} models)", provider.name, provider.models.len());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct CrabraceClient {
+    base_url: String,
+    http_client: HttpClient,
+    retry: RetryPolicy,
+    cache: ResponseCache,
+    pub(crate) credential: Option<Credential>,
+    pub(crate) fallback: Fallback,
+    pub(crate) interceptors: Interceptors,
+}
+
+impl CrabraceClient {
+    /// Create a new blocking client
+    pub fn new(base_url: impl Into<String>) -> Self {
+        let http_client = HttpClient::builder()
+            .user_agent(DEFAULT_USER_AGENT)
+            .build()
+            .expect("Failed to build HTTP client");
+        Self::with_client(base_url, http_client)
+    }
+
+    /// Start building a client with custom timeouts, headers, user-agent or
+    /// proxy
+    ///
+    /// Finish with [`CrabraceClientBuilder::build_blocking`].
+    pub fn builder(base_url: impl Into<String>) -> CrabraceClientBuilder {
+        CrabraceClientBuilder::new(base_url)
+    }
+
+    /// Create a new client with a custom blocking HTTP client
+    pub fn with_client(base_url: impl Into<String>, http_client: HttpClient) -> Self {
+        Self {
+            base_url: base_url.into(),
+            http_client,
+            retry: RetryPolicy::default(),
+            cache: ResponseCache::new(Duration::ZERO),
+            credential: None,
+            fallback: Fallback::default(),
+            interceptors: Interceptors::default(),
+        }
+    }
+
+    /// Replace the retry policy
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Cache successful responses in memory for `ttl`
+    pub fn with_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.cache = ResponseCache::new(ttl);
+        self
+    }
+
+    /// Authenticate every request with an API key, sent as `X-Api-Key`
+    pub fn with_api_key(mut self, key: impl Into<String>) -> Self {
+        self.credential = Some(Credential::ApiKey(key.into()));
+        self
+    }
+
+    /// Authenticate every request with a bearer token (an API key or a JWT)
+    pub fn with_bearer_token(mut self, token: impl Into<String>) -> Self {
+        self.credential = Some(Credential::Bearer(token.into()));
+        self
+    }
+
+    /// Answer from a snapshot file when the server cannot be reached
+    pub fn with_snapshot_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.fallback.snapshot = Some(path.into());
+        self
+    }
+
+    /// Answer from the catalog compiled into the crate when the server
+    /// cannot be reached
+    #[cfg(feature = "embedded-data")]
+    pub fn with_embedded_fallback(mut self) -> Self {
+        self.fallback.embedded = true;
+        self
+    }
+
+    /// Run `interceptor` around every request (see [`crate::intercept`])
+    pub fn with_interceptor(mut self, interceptor: impl Interceptor + 'static) -> Self {
+        self.interceptors.push(interceptor);
+        self
+    }
+
+    /// Drop every cached response and ETag
+    pub fn invalidate(&self) {
+        self.cache.clear();
+    }
+
+    /// Request carrying the client's credential
+    fn request(&self, method: Method, url: &str) -> RequestBuilder {
+        let request = self.http_client.request(method, url);
+        match &self.credential {
+            Some(Credential::ApiKey(key)) => request.header("x-api-key", key.as_str()),
+            Some(Credential::Bearer(token)) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+
+    /// Send a request, retrying failures the retry policy allows
+    ///
+    /// Only idempotent requests may be sent this way.
+    fn send(&self, request: RequestBuilder) -> Result<Response> {
+        let mut attempt = 1;
+        loop {
+            let request = request.try_clone().expect("Request bodies are buffered");
+            let result = self.execute(request, attempt);
+            let retries_left = attempt < self.retry.max_attempts;
+            let delay = match &result {
+                Ok(response) if retries_left && self.retry.retries_status(response.status()) => {
+                    self.retry
+                        .delay(attempt, retry::retry_after(response.headers()))
+                }
+                Err(e) if retries_left && self.retry.retries_error(e) => {
+                    self.retry.delay(attempt, None)
+                }
+                _ => return Ok(result?),
+            };
+            std::thread::sleep(delay);
+            attempt += 1;
+        }
+    }
+
+    /// Send one attempt of a request through the client's interceptors
+    fn execute(&self, request: RequestBuilder, attempt: u32) -> reqwest::Result<Response> {
+        let mut request = request.build()?;
+        let (method, url) = (request.method().clone(), request.url().clone());
+        let sent_at = self
+            .interceptors
+            .before(&method, &url, request.headers_mut(), attempt);
+        let result = self.http_client.execute(request);
+        let outcome = result.as_ref().map(|response| response.status());
+        self.interceptors
+            .after(&method, &url, attempt, outcome, sent_at);
+        result
+    }
+
+    /// Status and body of a POST request with a JSON body
+    fn post(&self, url: &str, body: &impl Serialize) -> Result<(StatusCode, Bytes)> {
+        let response = self.send(self.request(Method::POST, url).json(body))?;
+        let status = response.status();
+        Ok((status, response.bytes()?))
+    }
+
+    /// Status and body of a GET request, from the cache when fresh or not
+    /// modified since its ETag
+    fn fetch(&self, url: &str) -> Result<(StatusCode, Bytes)> {
+        if let Some(body) = self.cache.get(url) {
+            return Ok((StatusCode::OK, body));
+        }
+        self.revalidate(url)
+    }
+
+    /// Status and body of a GET request, from the cache only if not
+    /// modified since its ETag
+    fn revalidate(&self, url: &str) -> Result<(StatusCode, Bytes)> {
+        let mut etag = self.cache.etag(url);
+        loop {
+            let mut request = self.request(Method::GET, url);
+            if let Some(etag) = &etag {
+                request = request.header(IF_NONE_MATCH, etag);
+            }
+            let response = self.send(request)?;
+            let status = response.status();
+            if status == StatusCode::NOT_MODIFIED && etag.is_some() {
+                match self.cache.revalidate(url) {
+                    Some(body) => return Ok((StatusCode::OK, body)),
+                    // Invalidated in the meantime: ask for the body again
+                    None => {
+                        etag = None;
+                        continue;
+                    }
+                }
+            }
+
+            let new_etag = etag_of(response.headers());
+            let body = response.bytes()?;
+            if status.is_success() {
+                self.cache.insert(url, body.clone(), new_etag);
+            }
+            return Ok((status, body));
+        }
+    }
+
+    /// Get all available AI providers and their models
+    pub fn get_providers(&self) -> Result<Vec<Provider>> {
+        Ok(self.get_providers_with_freshness()?.value)
+    }
+
+    /// Get all providers, along with where they came from
+    pub fn get_providers_with_freshness(&self) -> Result<Fetched<Vec<Provider>>> {
+        let url = format!("{}/v1/providers", self.base_url);
+        // Cached copies are not news for the snapshot file
+        if let Some(body) = self.cache.get(&url) {
+            return read_providers(StatusCode::OK, &body).map(Fetched::live);
+        }
+        let providers = self.fallback.providers_from(self.revalidate(&url))?;
+        if providers.freshness.is_live() {
+            let _ = self.fallback.save(&providers.value);
+        }
+        Ok(providers)
+    }
+
+    /// Iterate over all providers, parsed one at a time as they arrive
+    ///
+    /// See [`crate::CrabraceClient::get_providers_stream`].
+    pub fn get_providers_stream(&self) -> impl Iterator<Item = Result<Provider>> {
+        let providers: Box<dyn Iterator<Item = Result<Provider>> + Send> =
+            match self.open_providers_stream() {
+                Ok(ProvidersStream::Ndjson(response)) => Box::new(
+                    serde_json::Deserializer::from_reader(response)
+                        .into_iter()
+                        .map(|provider| Ok(provider?)),
+                ),
+                Ok(ProvidersStream::Loaded(providers)) => Box::new(providers.into_iter().map(Ok)),
+                Err(e) => Box::new(std::iter::once(Err(e))),
+            };
+        providers
+    }
+
+    /// Request the providers as NDJSON, or read them whole when the server
+    /// answers otherwise
+    fn open_providers_stream(&self) -> Result<ProvidersStream> {
+        let url = format!("{}/v1/providers", self.base_url);
+        let request = self
+            .request(Method::GET, &url)
+            .header(ACCEPT, stream::NDJSON);
+        let result = match self.send(request) {
+            Ok(response)
+                if response.status().is_success() && stream::is_ndjson(response.headers()) =>
+            {
+                return Ok(ProvidersStream::Ndjson(response));
+            }
+            Ok(response) => {
+                let status = response.status();
+                response
+                    .bytes()
+                    .map(|body| (status, body))
+                    .map_err(Into::into)
+            }
+            Err(e) => Err(e),
+        };
+        let providers = self.fallback.providers_from(result)?.value;
+        Ok(ProvidersStream::Loaded(providers))
+    }
+
+    /// Follow the catalog: yield it now, then again every time it changes
+    ///
+    /// See [`crate::CrabraceClient::watch`]. Each call to `next` blocks
+    /// until the catalog changes or the connection fails.
+    pub fn watch(&self) -> impl Iterator<Item = Result<CatalogRevision>> + '_ {
+        let mut watcher = Watcher::default();
+        let mut events: Option<(std::io::Lines<BufReader<Response>>, SseParser)> = None;
+        std::iter::from_fn(move || loop {
+            let Some((lines, parser)) = events.as_mut() else {
+                if let Some(delay) = watcher.backoff(&self.retry) {
+                    std::thread::sleep(delay);
+                }
+                match self.open_events() {
+                    Ok(response) => {
+                        events = Some((BufReader::new(response).lines(), SseParser::default()))
+                    }
+                    Err(e) => {
+                        watcher.disconnected();
+                        return Some(Err(e));
+                    }
+                }
+                continue;
+            };
+
+            let event = match lines.next() {
+                Some(Ok(line)) => match parser.line(&line) {
+                    Some(event) => event,
+                    None => continue,
+                },
+                Some(Err(e)) if !is_timeout(&e) => {
+                    watcher.disconnected();
+                    events = None;
+                    return Some(Err(e.into()));
+                }
+                // Closed by the server, a proxy or the request timeout
+                _ => {
+                    watcher.disconnected();
+                    events = None;
+                    continue;
+                }
+            };
+            let announcement = match watcher.announced(&event) {
+                Ok(Some(announcement)) => announcement,
+                Ok(None) => continue,
+                Err(e) => return Some(Err(e)),
+            };
+
+            let url = format!("{}/v1/providers", self.base_url);
+            let providers = self
+                .revalidate(&url)
+                .and_then(|(status, body)| read_providers(status, &body));
+            return match providers {
+                Ok(providers) => {
+                    let _ = self.fallback.save(&providers);
+                    Some(Ok(watcher.revision(announcement, providers)))
+                }
+                // Reconnecting announces the version again
+                Err(e) => {
+                    watcher.disconnected();
+                    events = None;
+                    Some(Err(e))
+                }
+            };
+        })
+    }
+
+    /// Connect to the change feed
+    fn open_events(&self) -> Result<Response> {
+        let url = format!("{}/v1/providers/events", self.base_url);
+        let request = self
+            .request(Method::GET, &url)
+            .header(ACCEPT, stream::EVENT_STREAM);
+        // Reconnections have their own backoff
+        let response = self.execute(request, 1)?;
+        if !response.status().is_success() {
+            anyhow::bail!("Failed to watch the catalog: HTTP {}", response.status());
+        }
+        if !stream::is_event_stream(response.headers()) {
+            anyhow::bail!("Failed to watch the catalog: the server sent no event stream");
+        }
+        Ok(response)
+    }
+
+    /// Get a single provider by ID (`None` if the server returned 404)
+    pub fn get_provider(&self, id: &str) -> Result<Option<Provider>> {
+        Ok(self.get_provider_with_freshness(id)?.value)
+    }
+
+    /// Get a single provider by ID, along with where it came from
+    pub fn get_provider_with_freshness(&self, id: &str) -> Result<Fetched<Option<Provider>>> {
+        let url = api_url(&self.base_url, &["v1", "providers", id]);
+        self.fallback.provider_from(id, self.fetch(&url))
+    }
+
+    /// Get a single model of a provider (`None` if the server returned 404)
+    pub fn get_model(&self, provider_id: &str, model_id: &str) -> Result<Option<Model>> {
+        Ok(self.get_model_with_freshness(provider_id, model_id)?.value)
+    }
+
+    /// Get a single model of a provider, along with where it came from
+    pub fn get_model_with_freshness(
+        &self,
+        provider_id: &str,
+        model_id: &str,
+    ) -> Result<Fetched<Option<Model>>> {
+        let url = api_url(
+            &self.base_url,
+            &["v1", "providers", provider_id, "models", model_id],
+        );
+        self.fallback
+            .model_from(provider_id, model_id, self.fetch(&url))
+    }
+
+    /// Estimate what some usage of a model costs, in USD
+    pub fn estimate_cost(
+        &self,
+        provider_id: &str,
+        model_id: &str,
+        usage: Usage,
+    ) -> Result<EstimateResponse> {
+        Ok(self
+            .estimate_cost_with_freshness(provider_id, model_id, usage)?
+            .value)
+    }
+
+    /// Estimate what some usage of a model costs, along with where its
+    /// prices came from
+    pub fn estimate_cost_with_freshness(
+        &self,
+        provider_id: &str,
+        model_id: &str,
+        usage: Usage,
+    ) -> Result<Fetched<EstimateResponse>> {
+        let url = format!("{}/v1/estimate", self.base_url);
+        let request = EstimateRequest {
+            provider_id: provider_id.to_string(),
+            model_id: model_id.to_string(),
+            usage,
+        };
+        self.fallback
+            .estimate_from(&request, self.post(&url, &request))
+    }
+
+    /// Check if the Crabrace server is healthy
+    pub fn health_check(&self) -> Result<bool> {
+        let url = format!("{}/health", self.base_url);
+        let response = self.execute(self.request(Method::GET, &url), 1)?;
+        Ok(response.status().is_success())
+    }
+}
+
+/// Whether reading a response body stopped on the client's request timeout
+fn is_timeout(error: &std::io::Error) -> bool {
+    error
+        .get_ref()
+        .and_then(|inner| inner.downcast_ref::<reqwest::Error>())
+        .is_some_and(reqwest::Error::is_timeout)
+}
+
+/// Source of [`CrabraceClient::get_providers_stream`]
+enum ProvidersStream {
+    /// An NDJSON response, read as it arrives
+    Ndjson(Response),
+
+    /// Providers read whole, from a JSON array or a fallback catalog
+    Loaded(Vec<Provider>),
+}
+
+impl Default for CrabraceClient {
+    fn default() -> Self {
+        Self::new("http://localhost:8080")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blocking_client() {
+        let mut server = mockito::Server::new();
+        let providers = server
+            .mock("GET", "/v1/providers")
+            .match_header("x-api-key", "secret")
+            .with_header("content-type", "application/json")
+            .with_body(r#"[{"name":"Test","id":"test","type":"test","models":[]}]"#)
+            .expect(1)
+            .create();
+        server
+            .mock("GET", "/v1/providers/missing")
+            .with_status(404)
+            .create();
+        let model = server
+            .mock("GET", "/v1/providers/test/models/org%2Fmodel%3F1")
+            .with_status(404)
+            .create();
+
+        let client = CrabraceClient::builder(server.url())
+            .api_key("secret")
+            .cache_ttl(Duration::from_secs(60))
+            .build_blocking()
+            .unwrap();
+        for _ in 0..2 {
+            assert_eq!(client.get_providers().unwrap()[0].id, "test");
+        }
+        providers.assert();
+        assert!(client.get_provider("missing").unwrap().is_none());
+        // IDs are percent-encoded into a single path segment
+        assert!(client.get_model("test", "org/model?1").unwrap().is_none());
+        model.assert();
+        assert!(!format!("{:?}", client).contains("secret"));
+    }
+
+    #[test]
+    fn test_blocking_providers_stream() {
+        let mut server = mockito::Server::new();
+        server
+            .mock("GET", "/v1/providers")
+            .match_header("accept", "application/x-ndjson")
+            .with_header("content-type", "application/x-ndjson")
+            .with_body(concat!(
+                r#"{"name":"One","id":"one","type":"test","models":[]}"#,
+                "\n",
+                r#"{"name":"Two","id":"two","type":"test","models":[]}"#,
+                "\n",
+            ))
+            .create();
+
+        let client = CrabraceClient::new(server.url());
+        let ids: Vec<String> = client
+            .get_providers_stream()
+            .map(|provider| provider.unwrap().id)
+            .collect();
+        assert_eq!(ids, vec!["one", "two"]);
+    }
+
+    #[test]
+    fn test_blocking_watch() {
+        let mut server = mockito::Server::new();
+        server
+            .mock("GET", "/v1/providers/events")
+            .with_header("content-type", "text/event-stream")
+            .with_body(concat!(
+                "event: catalog\r\ndata: {\"version\":1,\"hash\":\"a\"}\r\n\r\n",
+                "event: catalog\r\ndata: {\"version\":2,\"hash\":\"b\"}\r\n\r\n",
+            ))
+            .create();
+        server
+            .mock("GET", "/v1/providers")
+            .with_body(r#"[{"name":"One","id":"one","type":"test","models":[]}]"#)
+            .create();
+
+        let client = CrabraceClient::new(server.url());
+        let versions: Vec<u64> = client
+            .watch()
+            .take(2)
+            .map(|revision| revision.unwrap().version)
+            .collect();
+        assert_eq!(versions, vec![1, 2]);
+
+        // Servers without a change feed
+        server
+            .mock("GET", "/v1/providers/events")
+            .with_status(404)
+            .create();
+        let error = client.watch().next().unwrap().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Failed to watch the catalog: HTTP 404 Not Found"
+        );
+    }
+
+    #[test]
+    fn test_blocking_retries() {
+        let mut server = mockito::Server::new();
+        let failures = server
+            .mock("GET", "/v1/providers")
+            .with_status(503)
+            .expect(2)
+            .create();
+
+        let client = CrabraceClient::new(server.url()).with_retry(RetryPolicy {
+            max_attempts: 2,
+            base_delay: Duration::from_millis(1),
+            ..RetryPolicy::default()
+        });
+        let error = client.get_providers().unwrap_err();
+        assert!(error.to_string().contains("HTTP 503"));
+        failures.assert();
+    }
+}
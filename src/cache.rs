@@ -0,0 +1,125 @@
+//! In-memory response cache for [`CrabraceClient`](crate::CrabraceClient)
+//!
+//! Successful catalog responses are kept by URL for a fixed TTL, so hot paths
+//! calling `get_providers()` per request only reach the server once per TTL.
+//! Responses carrying an `ETag` are kept past their TTL: the next request
+//! sends `If-None-Match` and a 304 revalidates the cached body for another
+//! TTL. Clones of a client share its cache.
+
+use bytes::Bytes;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+// `std::time::Instant` panics in browsers
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+#[cfg(target_arch = "wasm32")]
+use web_time::Instant;
+
+/// A cached response body
+#[derive(Debug, Clone)]
+struct Entry {
+    body: Bytes,
+    etag: Option<String>,
+    fetched_at: Instant,
+}
+
+/// Response bodies by URL, fresh for `ttl` after they were fetched
+#[derive(Debug, Clone)]
+pub(crate) struct ResponseCache {
+    ttl: Duration,
+    entries: Arc<Mutex<HashMap<String, Entry>>>,
+}
+
+impl ResponseCache {
+    pub(crate) fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Arc::default(),
+        }
+    }
+
+    /// Body cached for `url`, if still fresh
+    pub(crate) fn get(&self, url: &str) -> Option<Bytes> {
+        self.entries
+            .lock()
+            .get(url)
+            .filter(|entry| entry.fetched_at.elapsed() < self.ttl)
+            .map(|entry| entry.body.clone())
+    }
+
+    /// ETag of the body cached for `url`, to revalidate it with
+    pub(crate) fn etag(&self, url: &str) -> Option<String> {
+        self.entries.lock().get(url)?.etag.clone()
+    }
+
+    /// Cache the body fetched for `url`
+    ///
+    /// Without a TTL only bodies with an ETag are kept, as nothing else
+    /// could ever be served from the cache.
+    pub(crate) fn insert(&self, url: &str, body: Bytes, etag: Option<String>) {
+        let mut entries = self.entries.lock();
+        if etag.is_none() && self.ttl.is_zero() {
+            entries.remove(url);
+            return;
+        }
+        let entry = Entry {
+            body,
+            etag,
+            fetched_at: Instant::now(),
+        };
+        entries.insert(url.to_string(), entry);
+    }
+
+    /// Body cached for `url` after the server answered 304 Not Modified,
+    /// fresh again for another TTL
+    pub(crate) fn revalidate(&self, url: &str) -> Option<Bytes> {
+        let mut entries = self.entries.lock();
+        let entry = entries.get_mut(url)?;
+        entry.fetched_at = Instant::now();
+        Some(entry.body.clone())
+    }
+
+    /// Drop every cached response
+    pub(crate) fn clear(&self) {
+        self.entries.lock().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ttl() {
+        let cache = ResponseCache::new(Duration::from_secs(60));
+        cache.insert("/v1/providers", Bytes::from_static(b"[]"), None);
+        assert_eq!(cache.get("/v1/providers").unwrap(), "[]");
+        assert!(cache.get("/v1/providers/openai").is_none());
+
+        // Clones share entries
+        cache.clone().clear();
+        assert!(cache.get("/v1/providers").is_none());
+
+        let expired = ResponseCache::new(Duration::ZERO);
+        expired.insert("/v1/providers", Bytes::from_static(b"[]"), None);
+        assert!(expired.get("/v1/providers").is_none());
+        assert!(expired.revalidate("/v1/providers").is_none());
+    }
+
+    #[test]
+    fn test_etag() {
+        let cache = ResponseCache::new(Duration::ZERO);
+        let etag = Some("\"v1\"".to_string());
+        cache.insert("/v1/providers", Bytes::from_static(b"[]"), etag.clone());
+        assert!(cache.get("/v1/providers").is_none());
+        assert_eq!(cache.etag("/v1/providers"), etag);
+        assert_eq!(cache.revalidate("/v1/providers").unwrap(), "[]");
+
+        // A new body without an ETag replaces the old one
+        cache.insert("/v1/providers", Bytes::from_static(b"[{}]"), None);
+        assert!(cache.etag("/v1/providers").is_none());
+    }
+}
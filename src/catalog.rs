@@ -0,0 +1,225 @@
+//! Queries over a list of providers
+//!
+//! [`CatalogExt`] adds the lookups most consumers of
+//! [`CrabraceClient::get_providers`](crate::CrabraceClient::get_providers)
+//! end up writing by hand (find a model, pick the cheapest one meeting some
+//! requirement, group by capability or provider type) to `Vec<Provider>` and
+//! `&[Provider]`.
+
+use std::collections::BTreeMap;
+
+use crate::{Model, Provider};
+
+/// A feature a model may support, for [`CatalogExt::by_capability`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Capability {
+    /// Extended reasoning (`can_reason`)
+    Reasoning,
+
+    /// A configurable reasoning effort (`has_reasoning_efforts`)
+    ReasoningEffort,
+
+    /// Image or document inputs (`supports_attachments`)
+    Attachments,
+
+    /// Tool calling (`supports_tools`)
+    Tools,
+
+    /// Several tool calls per response (`supports_parallel_tool_calls`)
+    ParallelToolCalls,
+
+    /// Responses constrained to a JSON schema (`supports_structured_output`)
+    StructuredOutput,
+
+    /// Streamed responses (`supports_streaming`)
+    Streaming,
+}
+
+impl Capability {
+    /// Every capability, in order
+    pub const ALL: [Capability; 7] = [
+        Capability::Reasoning,
+        Capability::ReasoningEffort,
+        Capability::Attachments,
+        Capability::Tools,
+        Capability::ParallelToolCalls,
+        Capability::StructuredOutput,
+        Capability::Streaming,
+    ];
+
+    /// Whether `model` has this capability
+    pub fn of(self, model: &Model) -> bool {
+        match self {
+            Capability::Reasoning => model.can_reason,
+            Capability::ReasoningEffort => model.has_reasoning_efforts,
+            Capability::Attachments => model.supports_attachments,
+            Capability::Tools => model.supports_tools,
+            Capability::ParallelToolCalls => model.supports_parallel_tool_calls,
+            Capability::StructuredOutput => model.supports_structured_output,
+            Capability::Streaming => model.supports_streaming,
+        }
+    }
+}
+
+/// Query helpers for a list of providers
+///
+/// Models are returned along with the provider offering them, in catalog
+/// order.
+///
+/// # Example
+///
+/// ```
+/// use crabrace::{CatalogExt, Provider};
+///
+/// fn pick(providers: &[Provider]) {
+///     if let Some((provider, model)) = providers.find_model("gpt-4o") {
+///         println!("{} offers {}", provider.name, model.name);
+///     }
+///     if let Some((provider, model)) = providers.cheapest_with(|model| model.can_reason) {
+///         println!("Cheapest reasoning model: {}/{}", provider.id, model.id);
+///     }
+/// }
+/// ```
+pub trait CatalogExt {
+    /// Every model of every provider
+    fn models(&self) -> impl Iterator<Item = (&Provider, &Model)>;
+
+    /// First model with the given ID, or qualified ID (`provider/model`)
+    fn find_model(&self, id: &str) -> Option<(&Provider, &Model)>;
+
+    /// Model with the lowest combined input and output price per 1M tokens
+    /// among those matching `filter` (the first one on ties)
+    fn cheapest_with(&self, filter: impl Fn(&Model) -> bool) -> Option<(&Provider, &Model)>;
+
+    /// Models by capability (models without any are left out)
+    fn by_capability(&self) -> BTreeMap<Capability, Vec<(&Provider, &Model)>>;
+
+    /// Providers by provider type (e.g. `openai` for every OpenAI-compatible API)
+    fn providers_by_type(&self) -> BTreeMap<&str, Vec<&Provider>>;
+}
+
+impl CatalogExt for [Provider] {
+    fn models(&self) -> impl Iterator<Item = (&Provider, &Model)> {
+        self.iter()
+            .flat_map(|provider| provider.models.iter().map(move |model| (provider, model)))
+    }
+
+    fn find_model(&self, id: &str) -> Option<(&Provider, &Model)> {
+        // Model IDs may contain slashes themselves (e.g. on OpenRouter)
+        self.models().find(|(_, model)| model.id == id).or_else(|| {
+            let (provider_id, model_id) = id.split_once('/')?;
+            self.models()
+                .find(|(provider, model)| provider.id == provider_id && model.id == model_id)
+        })
+    }
+
+    fn cheapest_with(&self, filter: impl Fn(&Model) -> bool) -> Option<(&Provider, &Model)> {
+        let price = |model: &Model| model.cost_per_1m_in + model.cost_per_1m_out;
+        self.models()
+            .filter(|(_, model)| filter(model))
+            .reduce(|cheapest, candidate| {
+                if price(candidate.1) < price(cheapest.1) {
+                    candidate
+                } else {
+                    cheapest
+                }
+            })
+    }
+
+    fn by_capability(&self) -> BTreeMap<Capability, Vec<(&Provider, &Model)>> {
+        let mut groups: BTreeMap<_, Vec<_>> = BTreeMap::new();
+        for (provider, model) in self.models() {
+            for capability in Capability::ALL {
+                if capability.of(model) {
+                    groups
+                        .entry(capability)
+                        .or_default()
+                        .push((provider, model));
+                }
+            }
+        }
+        groups
+    }
+
+    fn providers_by_type(&self) -> BTreeMap<&str, Vec<&Provider>> {
+        let mut groups: BTreeMap<_, Vec<_>> = BTreeMap::new();
+        for provider in self {
+            groups
+                .entry(provider.provider_type.as_str())
+                .or_default()
+                .push(provider);
+        }
+        groups
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn provider(id: &str, provider_type: &str, models: Vec<Model>) -> Provider {
+        Provider {
+            models,
+            ..Provider::new(id.to_string(), id.to_string(), provider_type.to_string())
+        }
+    }
+
+    fn model(id: &str, cost_in: f64, cost_out: f64, can_reason: bool) -> Model {
+        Model {
+            can_reason,
+            ..Model::new(
+                id.to_string(),
+                id.to_string(),
+                cost_in,
+                cost_out,
+                128_000,
+                4096,
+            )
+        }
+    }
+
+    #[test]
+    fn test_catalog_queries() {
+        let providers = [
+            provider(
+                "openai",
+                "openai",
+                vec![
+                    model("gpt-4o", 2.5, 10.0, false),
+                    model("o3", 2.0, 8.0, true),
+                ],
+            ),
+            provider(
+                "openrouter",
+                "openai",
+                vec![model("openai/gpt-4o", 2.5, 10.0, false)],
+            ),
+            provider(
+                "deepseek",
+                "deepseek",
+                vec![model("deepseek-reasoner", 0.55, 2.19, true)],
+            ),
+        ];
+
+        assert_eq!(providers.models().count(), 4);
+        let (provider, _) = providers.find_model("gpt-4o").unwrap();
+        assert_eq!(provider.id, "openai");
+        let (provider, _) = providers.find_model("openrouter/openai/gpt-4o").unwrap();
+        assert_eq!(provider.id, "openrouter");
+        assert!(providers.find_model("openai/missing").is_none());
+
+        let (_, model) = providers.cheapest_with(|model| model.can_reason).unwrap();
+        assert_eq!(model.id, "deepseek-reasoner");
+        let (_, model) = providers.cheapest_with(|model| !model.can_reason).unwrap();
+        assert_eq!(model.id, "gpt-4o");
+        assert!(providers.cheapest_with(|_| false).is_none());
+
+        let by_capability = providers.by_capability();
+        assert_eq!(by_capability[&Capability::Reasoning].len(), 2);
+        assert!(!by_capability.contains_key(&Capability::Tools));
+
+        let by_type = providers.providers_by_type();
+        assert_eq!(by_type["openai"].len(), 2);
+        assert_eq!(by_type["deepseek"][0].id, "deepseek");
+    }
+}
@@ -0,0 +1,202 @@
+//! Client metrics through the [`metrics`] facade
+//!
+//! [`ClientMetrics`] is an [`Interceptor`] recording every attempt of a
+//! client with whatever `metrics` recorder the application installed
+//! (Prometheus, StatsD, OpenTelemetry...), so calls to crabrace show up on
+//! its own dashboards:
+//!
+//! - `crabrace_client_requests_total{method, endpoint, outcome}`: attempts,
+//!   by `outcome` (`success` or an [`Exchange::error_kind`])
+//! - `crabrace_client_errors_total{method, endpoint, kind}`: failed
+//!   attempts, by [`Exchange::error_kind`]
+//! - `crabrace_client_retries_total{method, endpoint}`: attempts after the
+//!   first
+//! - `crabrace_client_request_duration_seconds{method, endpoint}`: latency
+//!   histogram, up to the response headers
+//!
+//! `endpoint` is the route template ([`Exchange::endpoint`]), not the
+//! path, so provider and model IDs don't multiply series.
+
+use metrics::{counter, describe_counter, describe_histogram, histogram, Unit};
+
+use crate::intercept::{Exchange, Interceptor};
+
+const REQUESTS: &str = "crabrace_client_requests_total";
+const ERRORS: &str = "crabrace_client_errors_total";
+const RETRIES: &str = "crabrace_client_retries_total";
+const DURATION: &str = "crabrace_client_request_duration_seconds";
+
+/// Records client attempts with the global `metrics` recorder
+///
+/// # Example
+///
+/// ```
+/// use crabrace::client_metrics::ClientMetrics;
+/// use crabrace::CrabraceClient;
+///
+/// // After installing a recorder
+/// ClientMetrics::describe();
+/// let client = CrabraceClient::new("http://localhost:8080").with_interceptor(ClientMetrics);
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ClientMetrics;
+
+impl ClientMetrics {
+    /// Register the units and descriptions of the client metrics with the
+    /// installed recorder (optional, for exporters showing them)
+    pub fn describe() {
+        describe_counter!(REQUESTS, Unit::Count, "Requests sent to crabrace");
+        describe_counter!(ERRORS, Unit::Count, "Failed requests to crabrace");
+        describe_counter!(RETRIES, Unit::Count, "Retried requests to crabrace");
+        describe_histogram!(DURATION, Unit::Seconds, "Latency of requests to crabrace");
+    }
+}
+
+impl Interceptor for ClientMetrics {
+    fn on_response(&self, exchange: &Exchange<'_>) {
+        let method = exchange.method.as_str().to_string();
+        let endpoint = exchange.endpoint();
+        let kind = exchange.error_kind();
+
+        counter!(
+            REQUESTS,
+            "method" => method.clone(),
+            "endpoint" => endpoint,
+            "outcome" => kind.unwrap_or("success")
+        )
+        .increment(1);
+        if let Some(kind) = kind {
+            counter!(ERRORS, "method" => method.clone(), "endpoint" => endpoint, "kind" => kind)
+                .increment(1);
+        }
+        if exchange.attempt > 1 {
+            counter!(RETRIES, "method" => method.clone(), "endpoint" => endpoint).increment(1);
+        }
+        histogram!(DURATION, "method" => method, "endpoint" => endpoint)
+            .record(exchange.elapsed.as_secs_f64());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CrabraceClient, RetryPolicy};
+    use metrics::{
+        Counter, CounterFn, Gauge, Histogram, Key, KeyName, Metadata, Recorder, SharedString,
+    };
+    use parking_lot::Mutex;
+    use std::collections::BTreeMap;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    /// Counter totals and histogram sample counts by `name{labels}`
+    #[derive(Default)]
+    struct TestRecorder {
+        values: Arc<Mutex<BTreeMap<String, u64>>>,
+    }
+
+    struct Value {
+        key: String,
+        values: Arc<Mutex<BTreeMap<String, u64>>>,
+    }
+
+    impl CounterFn for Value {
+        fn increment(&self, value: u64) {
+            *self.values.lock().entry(self.key.clone()).or_default() += value;
+        }
+
+        fn absolute(&self, value: u64) {
+            self.values.lock().insert(self.key.clone(), value);
+        }
+    }
+
+    impl metrics::HistogramFn for Value {
+        fn record(&self, _value: f64) {
+            self.increment(1);
+        }
+    }
+
+    impl TestRecorder {
+        fn value(&self, key: &Key) -> Arc<Value> {
+            let labels: Vec<_> = key
+                .labels()
+                .map(|label| format!("{}={}", label.key(), label.value()))
+                .collect();
+            Arc::new(Value {
+                key: format!("{}{{{}}}", key.name(), labels.join(",")),
+                values: self.values.clone(),
+            })
+        }
+    }
+
+    impl Recorder for TestRecorder {
+        fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+        fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+            Counter::from_arc(self.value(key))
+        }
+
+        fn register_gauge(&self, _: &Key, _: &Metadata<'_>) -> Gauge {
+            Gauge::noop()
+        }
+
+        fn register_histogram(&self, key: &Key, _: &Metadata<'_>) -> Histogram {
+            Histogram::from_arc(self.value(key))
+        }
+    }
+
+    #[test]
+    fn test_client_metrics() {
+        let mut server = mockito::Server::new();
+        server
+            .mock("GET", "/v1/providers/openai")
+            .with_status(503)
+            .expect(1)
+            .create();
+        server
+            .mock("GET", "/v1/providers/openai")
+            .with_body(r#"{"name":"OpenAI","id":"openai","type":"openai","models":[]}"#)
+            .create();
+
+        let client = CrabraceClient::builder(server.url())
+            .retry(RetryPolicy {
+                base_delay: Duration::from_millis(1),
+                ..RetryPolicy::default()
+            })
+            .interceptor(ClientMetrics)
+            .build()
+            .unwrap();
+
+        // The recorder is only installed on this thread, so the request
+        // runs on it
+        let recorder = TestRecorder::default();
+        let provider = metrics::with_local_recorder(&recorder, || {
+            ClientMetrics::describe();
+            tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .unwrap()
+                .block_on(client.get_provider("openai"))
+        });
+        assert!(provider.unwrap().is_some());
+
+        let endpoint = "endpoint=/v1/providers/{id}";
+        let values = recorder.values.lock();
+        assert_eq!(
+            values[&format!("{REQUESTS}{{method=GET,{endpoint},outcome=server_error}}")],
+            1
+        );
+        assert_eq!(
+            values[&format!("{REQUESTS}{{method=GET,{endpoint},outcome=success}}")],
+            1
+        );
+        assert_eq!(
+            values[&format!("{ERRORS}{{method=GET,{endpoint},kind=server_error}}")],
+            1
+        );
+        assert_eq!(values[&format!("{RETRIES}{{method=GET,{endpoint}}}")], 1);
+        assert_eq!(values[&format!("{DURATION}{{method=GET,{endpoint}}}")], 2);
+    }
+}
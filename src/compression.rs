@@ -0,0 +1,133 @@
+//! Response compression
+//!
+//! Builds the `CompressionLayer` from configuration: which encodings to
+//! offer (negotiated against `Accept-Encoding`), the minimum body size worth
+//! compressing, and the compression level.
+
+use tower_http::compression::predicate::{NotForContentType, Predicate, SizeAbove};
+use tower_http::compression::{CompressionLayer, CompressionLevel};
+
+use crate::config::CompressionConfig;
+
+/// Encodings that can be offered
+pub const SUPPORTED_ALGORITHMS: [&str; 3] = ["gzip", "br", "zstd"];
+
+/// Parse a configured level: `fastest`, `default`, `best` or an integer
+pub fn parse_level(level: &str) -> Option<CompressionLevel> {
+    match level.trim().to_ascii_lowercase().as_str() {
+        "fastest" => Some(CompressionLevel::Fastest),
+        "default" => Some(CompressionLevel::Default),
+        "best" => Some(CompressionLevel::Best),
+        other => other.parse().ok().map(CompressionLevel::Precise),
+    }
+}
+
+/// Build the compression layer (`None` when disabled or no encoding is offered)
+///
+/// Images, gRPC and server-sent events are never compressed.
+pub fn build_compression_layer(
+    enabled: bool,
+    config: &CompressionConfig,
+) -> Option<CompressionLayer<impl Predicate>> {
+    if !enabled || config.algorithms.is_empty() {
+        return None;
+    }
+
+    let offers = |name: &str| {
+        config
+            .algorithms
+            .iter()
+            .any(|a| a.eq_ignore_ascii_case(name))
+    };
+    let predicate = SizeAbove::new(config.min_size_bytes)
+        .and(NotForContentType::GRPC)
+        .and(NotForContentType::IMAGES)
+        .and(NotForContentType::SSE);
+
+    Some(
+        CompressionLayer::new()
+            .gzip(offers("gzip"))
+            .br(offers("br"))
+            .zstd(offers("zstd"))
+            .quality(parse_level(&config.level).unwrap_or_default())
+            .compress_when(predicate),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::{header, Request};
+    use axum::routing::get;
+    use axum::Router;
+    use tower::ServiceExt;
+
+    fn app(config: &CompressionConfig) -> Router {
+        Router::new()
+            .route("/small", get(|| async { "tiny" }))
+            .route("/large", get(|| async { "crab ".repeat(1000) }))
+            .layer(build_compression_layer(true, config).unwrap())
+    }
+
+    async fn encoding(app: Router, uri: &str, accept: &str) -> Option<String> {
+        let response = app
+            .oneshot(
+                Request::get(uri)
+                    .header(header::ACCEPT_ENCODING, accept)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        response
+            .headers()
+            .get(header::CONTENT_ENCODING)
+            .map(|v| v.to_str().unwrap().to_string())
+    }
+
+    #[test]
+    fn test_parse_level() {
+        assert!(matches!(parse_level("Best"), Some(CompressionLevel::Best)));
+        assert!(matches!(
+            parse_level("4"),
+            Some(CompressionLevel::Precise(4))
+        ));
+        assert!(parse_level("loud").is_none());
+    }
+
+    #[test]
+    fn test_disabled() {
+        let config = CompressionConfig::default();
+        assert!(build_compression_layer(false, &config).is_none());
+
+        let none = CompressionConfig {
+            algorithms: Vec::new(),
+            ..config
+        };
+        assert!(build_compression_layer(true, &none).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_negotiation() {
+        let app = app(&CompressionConfig {
+            algorithms: vec!["gzip".into(), "br".into()],
+            ..Default::default()
+        });
+
+        assert_eq!(
+            encoding(app.clone(), "/large", "br").await.as_deref(),
+            Some("br")
+        );
+        assert_eq!(
+            encoding(app.clone(), "/large", "zstd, gzip")
+                .await
+                .as_deref(),
+            Some("gzip")
+        );
+        // zstd is not offered
+        assert_eq!(encoding(app.clone(), "/large", "zstd").await, None);
+        // Below the size threshold
+        assert_eq!(encoding(app, "/small", "gzip").await, None);
+    }
+}
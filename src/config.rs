@@ -1,5 +1,6 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::net::SocketAddr;
 use std::path::Path;
 use tracing::Level;
@@ -18,6 +19,38 @@ pub struct Config {
 
     /// Security configuration
     pub security: SecurityConfig,
+
+    /// API documentation configuration
+    #[serde(default)]
+    pub docs: DocsConfig,
+
+    /// Health probe configuration
+    #[serde(default)]
+    pub health: HealthConfig,
+
+    /// GraphQL query limits (`graphql` feature)
+    #[serde(default)]
+    pub graphql: GraphqlConfig,
+
+    /// Admin API configuration
+    #[serde(default)]
+    pub admin: AdminConfig,
+
+    /// Provider catalog sources
+    #[serde(default)]
+    pub providers: ProvidersConfig,
+
+    /// Remote catalog refresh
+    #[serde(default)]
+    pub registry: RegistryConfig,
+
+    /// Named tenant catalogs derived from the main catalog (tenant name to config)
+    #[serde(default)]
+    pub tenants: HashMap<String, TenantConfig>,
+
+    /// Currency conversion of reported costs
+    #[serde(default)]
+    pub currency: CurrencyConfig,
 }
 
 /// Server configuration
@@ -35,9 +68,126 @@ pub struct ServerConfig {
     #[serde(default = "default_true")]
     pub compression: bool,
 
+    /// Encodings, size threshold and level used when compression is enabled
+    #[serde(default)]
+    pub compression_options: CompressionConfig,
+
     /// Request timeout in seconds
     #[serde(default = "default_timeout")]
     pub timeout_seconds: u64,
+
+    /// Serve the unversioned (pre-`/v1`) API routes as deprecated aliases
+    #[serde(default = "default_true")]
+    pub legacy_routes: bool,
+
+    /// HTTP caching configuration for catalog responses
+    #[serde(default)]
+    pub cache: CacheConfig,
+
+    /// Native TLS termination
+    #[serde(default)]
+    pub tls: TlsConfig,
+
+    /// Path prefix all routes are served under (e.g. `/crabrace`; empty for the root)
+    #[serde(default)]
+    pub base_path: String,
+
+    /// Maximum request body size in bytes (413 beyond this)
+    #[serde(default = "default_max_body_bytes")]
+    pub max_body_bytes: usize,
+
+    /// Additional listeners; route groups they serve move off `host:port`
+    #[serde(default)]
+    pub listeners: Vec<ListenerConfig>,
+}
+
+/// Groups of routes that can be assigned to a listener
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RouteGroup {
+    /// Catalog API (`/v1/...` and legacy aliases)
+    Api,
+    /// `/health`, `/version` and the liveness/readiness probes
+    Health,
+    /// `/openapi.json` and Swagger UI
+    Docs,
+    /// Prometheus metrics endpoint
+    Metrics,
+    /// Admin API (`/admin/...`)
+    Admin,
+}
+
+impl RouteGroup {
+    /// Every route group, as served by a single listener
+    pub const ALL: [RouteGroup; 5] = [
+        RouteGroup::Api,
+        RouteGroup::Health,
+        RouteGroup::Docs,
+        RouteGroup::Metrics,
+        RouteGroup::Admin,
+    ];
+}
+
+/// An additional listener serving a subset of the routes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListenerConfig {
+    /// Socket address to bind, e.g. `127.0.0.1:9090`
+    pub address: String,
+
+    /// Route groups served on this listener
+    pub routes: Vec<RouteGroup>,
+}
+
+/// TLS configuration (rustls)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TlsConfig {
+    /// Serve HTTPS instead of plain HTTP
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// PEM certificate chain
+    #[serde(default)]
+    pub cert_path: Option<String>,
+
+    /// PEM private key (PKCS#8, PKCS#1 or SEC1)
+    #[serde(default)]
+    pub key_path: Option<String>,
+
+    /// Reload the certificate and key when the files change
+    #[serde(default)]
+    pub reload_on_change: bool,
+}
+
+/// Response compression settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompressionConfig {
+    /// Encodings to offer, negotiated against `Accept-Encoding` (gzip, br, zstd)
+    #[serde(default = "default_compression_algorithms")]
+    pub algorithms: Vec<String>,
+
+    /// Responses smaller than this many bytes are sent uncompressed
+    #[serde(default = "default_compression_min_size")]
+    pub min_size_bytes: u16,
+
+    /// Compression level: `fastest`, `default`, `best` or an algorithm-specific integer
+    #[serde(default = "default_compression_level")]
+    pub level: String,
+}
+
+/// HTTP caching configuration (`Cache-Control` on catalog responses)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheConfig {
+    /// Send `Cache-Control` headers on successful catalog responses
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// `max-age` directive in seconds
+    #[serde(default = "default_cache_max_age")]
+    pub max_age_seconds: u64,
+
+    /// Optional `stale-while-revalidate` directive in seconds
+    #[serde(default)]
+    pub stale_while_revalidate_seconds: Option<u64>,
 }
 
 /// Logging configuration
@@ -54,6 +204,22 @@ pub struct LoggingConfig {
     /// Show target in logs
     #[serde(default)]
     pub show_target: bool,
+
+    /// Access log configuration
+    #[serde(default)]
+    pub access_log: AccessLogConfig,
+}
+
+/// Access log configuration (one JSON line per request)
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct AccessLogConfig {
+    /// Enable access logging
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Append to this file instead of writing to stdout
+    #[serde(default)]
+    pub file: Option<String>,
 }
 
 /// Metrics configuration
@@ -68,6 +234,226 @@ pub struct MetricsConfig {
     pub path: String,
 }
 
+/// API documentation configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocsConfig {
+    /// Serve the OpenAPI specification at /openapi.json
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Serve Swagger UI at /docs
+    #[serde(default)]
+    pub swagger_ui: bool,
+}
+
+/// GraphQL query limits
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphqlConfig {
+    /// Deepest nesting of fields a query may have
+    #[serde(default = "default_graphql_max_depth")]
+    pub max_depth: usize,
+
+    /// Highest complexity a query may have: one per field, times ten for
+    /// every list of providers or models it is in
+    #[serde(default = "default_graphql_max_complexity")]
+    pub max_complexity: usize,
+}
+
+/// Health probe configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthConfig {
+    /// Liveness probe path (process is up)
+    #[serde(default = "default_liveness_path")]
+    pub liveness_path: String,
+
+    /// Readiness probe path (registry loaded and configuration valid)
+    #[serde(default = "default_readiness_path")]
+    pub readiness_path: String,
+}
+
+/// Provider catalog sources beyond the embedded configurations
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProvidersConfig {
+    /// Directory of extra provider JSON files loaded at startup
+    /// (also settable with `CRABRACE_PROVIDERS_DIR`)
+    #[serde(default)]
+    pub dir: Option<String>,
+
+    /// Provider IDs to serve; empty serves every provider
+    #[serde(default)]
+    pub enabled: Vec<String>,
+
+    /// Provider IDs never served, applied after `enabled`
+    #[serde(default)]
+    pub disabled: Vec<String>,
+
+    /// Periodic sync of the embedded providers from upstream Catwalk
+    #[serde(default)]
+    pub upstream: UpstreamConfig,
+
+    /// Extra provider ID aliases (alias = canonical ID), added to the
+    /// built-in table (e.g. `google` = `gemini`) and overriding it
+    #[serde(default)]
+    pub aliases: HashMap<String, String>,
+
+    /// Refuse to start if any embedded provider config has a problem,
+    /// instead of skipping configs that fail to parse (on by default when
+    /// `CI` is `true` or `1`)
+    #[serde(default)]
+    pub strict: bool,
+}
+
+/// Remote catalog configuration (e.g. edge replicas fed by a central instance)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegistryConfig {
+    /// URL of a catalog (JSON array of providers) that replaces the embedded one
+    #[serde(default)]
+    pub remote_url: Option<String>,
+
+    /// Like `remote_url`, but also accepts object storage locations
+    /// (`s3://bucket/key`, `gs://bucket/key`)
+    #[serde(default)]
+    pub source: Option<String>,
+
+    /// Seconds between conditional fetches of the remote catalog
+    #[serde(default = "default_refresh_interval")]
+    pub refresh_interval_seconds: u64,
+
+    /// Catalog shared with other instances through Redis
+    #[serde(default)]
+    pub redis: RedisConfig,
+}
+
+/// Shared catalog configuration (requires the `redis` feature)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RedisConfig {
+    /// Redis URL (e.g. `redis://redis:6379/0`); unset keeps the catalog per instance
+    #[serde(default)]
+    pub url: Option<String>,
+
+    /// Prefix of the catalog key (`<prefix>:catalog`) and invalidation channel
+    /// (`<prefix>:invalidate`)
+    #[serde(default = "default_redis_key_prefix")]
+    pub key_prefix: String,
+
+    /// Seconds between full comparisons with the shared catalog, catching
+    /// invalidations missed while disconnected
+    #[serde(default = "default_redis_resync_interval")]
+    pub resync_interval_seconds: u64,
+}
+
+impl RegistryConfig {
+    /// Location of the remote catalog, from `source` or `remote_url`
+    pub fn remote_source(&self) -> Option<&str> {
+        self.source.as_deref().or(self.remote_url.as_deref())
+    }
+}
+
+/// Upstream Catwalk sync configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpstreamConfig {
+    /// Periodically fetch upstream provider definitions
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Base URL of the Catwalk provider configs; `<url>/<id>.json` is fetched per provider
+    #[serde(default = "default_upstream_url")]
+    pub url: String,
+
+    /// Seconds between syncs
+    #[serde(default = "default_upstream_interval")]
+    pub interval_seconds: u64,
+}
+
+impl ProvidersConfig {
+    /// Whether the provider with this ID may be served
+    pub fn allows(&self, id: &str) -> bool {
+        (self.enabled.is_empty() || self.enabled.iter().any(|e| e == id))
+            && !self.disabled.iter().any(|d| d == id)
+    }
+}
+
+/// A tenant catalog, served under `/v1/t/<name>` or with an `X-Tenant` header
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TenantConfig {
+    /// Directory of provider JSON files deep-merged over the main catalog
+    /// (e.g. negotiated prices) or added to it
+    #[serde(default)]
+    pub dir: Option<String>,
+
+    /// Provider IDs the tenant sees; empty keeps every provider
+    #[serde(default)]
+    pub enabled: Vec<String>,
+
+    /// Provider IDs hidden from the tenant, applied after `enabled`
+    #[serde(default)]
+    pub disabled: Vec<String>,
+
+    /// Approved model IDs; empty keeps every model. Providers left without
+    /// models are hidden.
+    #[serde(default)]
+    pub models: Vec<String>,
+}
+
+impl TenantConfig {
+    /// Whether the tenant may see the provider with this ID
+    pub fn allows_provider(&self, id: &str) -> bool {
+        (self.enabled.is_empty() || self.enabled.iter().any(|e| e == id))
+            && !self.disabled.iter().any(|d| d == id)
+    }
+
+    /// Whether the tenant may see the model with this ID
+    pub fn allows_model(&self, id: &str) -> bool {
+        self.models.is_empty() || self.models.iter().any(|m| m == id)
+    }
+}
+
+/// Admin API configuration (runtime catalog mutations)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AdminConfig {
+    /// Serve the `/admin` endpoints (requires authentication)
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Writable directory where mutated providers are persisted as
+    /// `<id>.json` and loaded from on startup
+    #[serde(default)]
+    pub overlay_dir: Option<String>,
+
+    /// Audit log of admin operations
+    #[serde(default)]
+    pub audit: AuditConfig,
+}
+
+/// Audit log configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditConfig {
+    /// Append entries as JSON lines to this file (in memory only when unset)
+    #[serde(default)]
+    pub file: Option<String>,
+
+    /// Number of recent entries kept for `GET /admin/audit`
+    #[serde(default = "default_audit_max_entries")]
+    pub max_entries: usize,
+}
+
+/// Currency conversion of cost endpoints (catalog prices stay in USD)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CurrencyConfig {
+    /// Units of each currency per US dollar (e.g. `EUR = 0.92`)
+    #[serde(default)]
+    pub rates: HashMap<String, f64>,
+
+    /// Exchange-rate API refreshing `rates`, answering `{"rates": {"EUR": 0.92, ...}}`
+    /// with USD as the base
+    #[serde(default)]
+    pub refresh_url: Option<String>,
+
+    /// Seconds between refreshes
+    #[serde(default = "default_currency_refresh_interval")]
+    pub refresh_interval_seconds: u64,
+}
+
 /// Security configuration
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct SecurityConfig {
@@ -79,6 +465,86 @@ pub struct SecurityConfig {
 
     /// Security headers configuration
     pub headers: SecurityHeadersConfig,
+
+    /// API authentication configuration
+    #[serde(default)]
+    pub auth: AuthConfig,
+}
+
+/// API authentication configuration
+///
+/// Keys from all configured sources are accepted.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AuthConfig {
+    /// Require an API key on every non-exempt route
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Inline API keys
+    #[serde(default)]
+    pub keys: Vec<String>,
+
+    /// File containing one API key per line (`#` starts a comment)
+    #[serde(default)]
+    pub keys_file: Option<String>,
+
+    /// Environment variable holding comma-separated API keys
+    #[serde(default)]
+    pub keys_env: Option<String>,
+
+    /// Request paths that do not require authentication (default: `/health`,
+    /// the health probes and the metrics endpoint, at their configured
+    /// paths; see [`Config::auth_exempt_paths`])
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exempt_paths: Option<Vec<String>>,
+
+    /// Bearer JWT validation (in addition to API keys)
+    #[serde(default)]
+    pub jwt: JwtConfig,
+}
+
+/// JWT authentication configuration
+///
+/// Verification keys come from `secret` (HMAC), `jwks_url`, or OIDC
+/// discovery on `issuer`, in that order of precedence.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JwtConfig {
+    /// Accept bearer JWTs
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Required `iss` claim; also the OIDC discovery base URL
+    #[serde(default)]
+    pub issuer: Option<String>,
+
+    /// Required `aud` claim
+    #[serde(default)]
+    pub audience: Option<String>,
+
+    /// JSON Web Key Set URL (skips OIDC discovery)
+    #[serde(default)]
+    pub jwks_url: Option<String>,
+
+    /// Shared HMAC secret for HS256/HS384/HS512 tokens
+    #[serde(default)]
+    pub secret: Option<String>,
+
+    /// Only algorithm accepted for tokens signed with `secret` (HS256, HS384
+    /// or HS512)
+    #[serde(default = "default_jwt_algorithm")]
+    pub algorithm: String,
+
+    /// Scope required for read-only catalog routes (any valid token if unset)
+    #[serde(default)]
+    pub read_scope: Option<String>,
+
+    /// Scope required for admin and mutation endpoints
+    #[serde(default = "default_jwt_admin_scope")]
+    pub admin_scope: String,
+
+    /// Minimum seconds between JWKS refetches for unknown key IDs
+    #[serde(default = "default_jwks_refresh_seconds")]
+    pub jwks_refresh_seconds: u64,
 }
 
 /// CORS configuration
@@ -162,6 +628,10 @@ fn default_timeout() -> u64 {
     30
 }
 
+fn default_cache_max_age() -> u64 {
+    300
+}
+
 fn default_log_level() -> String {
     "info".to_string()
 }
@@ -170,6 +640,79 @@ fn default_metrics_path() -> String {
     "/metrics".to_string()
 }
 
+fn default_compression_algorithms() -> Vec<String> {
+    vec!["gzip".to_string()]
+}
+
+fn default_compression_min_size() -> u16 {
+    1024
+}
+
+fn default_compression_level() -> String {
+    "default".to_string()
+}
+
+fn default_audit_max_entries() -> usize {
+    10_000
+}
+
+fn default_upstream_url() -> String {
+    "https://raw.githubusercontent.com/charmbracelet/catwalk/main/internal/providers/configs"
+        .to_string()
+}
+
+fn default_upstream_interval() -> u64 {
+    3600
+}
+
+fn default_refresh_interval() -> u64 {
+    300
+}
+
+fn default_currency_refresh_interval() -> u64 {
+    86400
+}
+
+fn default_redis_key_prefix() -> String {
+    "crabrace".to_string()
+}
+
+fn default_redis_resync_interval() -> u64 {
+    30
+}
+
+fn default_max_body_bytes() -> usize {
+    2 * 1024 * 1024
+}
+
+fn default_jwt_admin_scope() -> String {
+    "crabrace:admin".to_string()
+}
+
+fn default_jwt_algorithm() -> String {
+    "HS256".to_string()
+}
+
+fn default_jwks_refresh_seconds() -> u64 {
+    300
+}
+
+fn default_graphql_max_depth() -> usize {
+    10
+}
+
+fn default_graphql_max_complexity() -> usize {
+    1000
+}
+
+fn default_liveness_path() -> String {
+    "/livez".to_string()
+}
+
+fn default_readiness_path() -> String {
+    "/readyz".to_string()
+}
+
 fn default_cors_origins() -> Vec<String> {
     vec!["*".to_string()]
 }
@@ -179,7 +722,11 @@ fn default_cors_methods() -> Vec<String> {
 }
 
 fn default_cors_headers() -> Vec<String> {
-    vec!["Content-Type".to_string(), "Authorization".to_string()]
+    vec![
+        "Content-Type".to_string(),
+        "Authorization".to_string(),
+        "X-Api-Key".to_string(),
+    ]
 }
 
 fn default_cors_max_age() -> u64 {
@@ -200,7 +747,70 @@ impl Default for ServerConfig {
             host: default_host(),
             port: default_port(),
             compression: default_true(),
+            compression_options: CompressionConfig::default(),
             timeout_seconds: default_timeout(),
+            legacy_routes: default_true(),
+            cache: CacheConfig::default(),
+            tls: TlsConfig::default(),
+            base_path: String::new(),
+            max_body_bytes: default_max_body_bytes(),
+            listeners: Vec::new(),
+        }
+    }
+}
+
+impl ServerConfig {
+    /// Base path without a trailing slash (`""` when serving from the root)
+    pub fn base_path(&self) -> &str {
+        self.base_path.trim_end_matches('/')
+    }
+
+    /// Route groups served on `host:port` (those not claimed by another listener)
+    pub fn primary_routes(&self) -> Vec<RouteGroup> {
+        RouteGroup::ALL
+            .into_iter()
+            .filter(|group| !self.listeners.iter().any(|l| l.routes.contains(group)))
+            .collect()
+    }
+}
+
+impl Default for UpstreamConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            url: default_upstream_url(),
+            interval_seconds: default_upstream_interval(),
+        }
+    }
+}
+
+impl Default for RegistryConfig {
+    fn default() -> Self {
+        Self {
+            remote_url: None,
+            source: None,
+            refresh_interval_seconds: default_refresh_interval(),
+            redis: RedisConfig::default(),
+        }
+    }
+}
+
+impl Default for RedisConfig {
+    fn default() -> Self {
+        Self {
+            url: None,
+            key_prefix: default_redis_key_prefix(),
+            resync_interval_seconds: default_redis_resync_interval(),
+        }
+    }
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_age_seconds: default_cache_max_age(),
+            stale_while_revalidate_seconds: None,
         }
     }
 }
@@ -211,6 +821,7 @@ impl Default for LoggingConfig {
             level: default_log_level(),
             json_format: false,
             show_target: false,
+            access_log: AccessLogConfig::default(),
         }
     }
 }
@@ -224,6 +835,33 @@ impl Default for MetricsConfig {
     }
 }
 
+impl Default for DocsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_true(),
+            swagger_ui: false,
+        }
+    }
+}
+
+impl Default for GraphqlConfig {
+    fn default() -> Self {
+        Self {
+            max_depth: default_graphql_max_depth(),
+            max_complexity: default_graphql_max_complexity(),
+        }
+    }
+}
+
+impl Default for HealthConfig {
+    fn default() -> Self {
+        Self {
+            liveness_path: default_liveness_path(),
+            readiness_path: default_readiness_path(),
+        }
+    }
+}
+
 impl Default for CorsConfig {
     fn default() -> Self {
         Self {
@@ -246,6 +884,51 @@ impl Default for RateLimitConfig {
     }
 }
 
+impl Default for CompressionConfig {
+    fn default() -> Self {
+        Self {
+            algorithms: default_compression_algorithms(),
+            min_size_bytes: default_compression_min_size(),
+            level: default_compression_level(),
+        }
+    }
+}
+
+impl Default for CurrencyConfig {
+    fn default() -> Self {
+        Self {
+            rates: HashMap::new(),
+            refresh_url: None,
+            refresh_interval_seconds: default_currency_refresh_interval(),
+        }
+    }
+}
+
+impl Default for AuditConfig {
+    fn default() -> Self {
+        Self {
+            file: None,
+            max_entries: default_audit_max_entries(),
+        }
+    }
+}
+
+impl Default for JwtConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            issuer: None,
+            audience: None,
+            jwks_url: None,
+            secret: None,
+            algorithm: default_jwt_algorithm(),
+            read_scope: None,
+            admin_scope: default_jwt_admin_scope(),
+            jwks_refresh_seconds: default_jwks_refresh_seconds(),
+        }
+    }
+}
+
 impl Default for SecurityHeadersConfig {
     fn default() -> Self {
         Self {
@@ -259,6 +942,24 @@ impl Default for SecurityHeadersConfig {
 }
 
 impl Config {
+    /// Request paths that do not require authentication: the configured
+    /// `security.auth.exempt_paths`, else the health check, the probes and
+    /// the metrics endpoint wherever they are served
+    pub fn auth_exempt_paths(&self) -> Vec<String> {
+        if let Some(paths) = &self.security.auth.exempt_paths {
+            return paths.clone();
+        }
+        let mut paths = vec![
+            "/health".to_string(),
+            self.health.liveness_path.clone(),
+            self.health.readiness_path.clone(),
+        ];
+        if self.metrics.enabled {
+            paths.push(self.metrics.path.clone());
+        }
+        paths
+    }
+
     /// Load configuration from multiple sources with precedence:
     /// 1. Environment variables (highest priority)
     /// 2. Configuration file (if provided)
@@ -289,12 +990,22 @@ impl Config {
                 .try_parsing(true),
         );
 
-        let config = builder
+        let mut config: Config = builder
             .build()
             .context("Failed to build configuration")?
             .try_deserialize()
             .context("Failed to deserialize configuration")?;
 
+        // Shorthand for the extra providers directory
+        if let Ok(dir) = std::env::var("CRABRACE_PROVIDERS_DIR") {
+            config.providers.dir = Some(dir);
+        }
+
+        // CI runs catch broken provider configs before they ship
+        if std::env::var("CI").is_ok_and(|ci| ci == "true" || ci == "1") {
+            config.providers.strict = true;
+        }
+
         Ok(config)
     }
 
@@ -332,6 +1043,158 @@ impl Config {
             anyhow::bail!("Server timeout cannot be 0");
         }
 
+        // Validate compression
+        let compression = &self.server.compression_options;
+        if let Some(algorithm) = compression.algorithms.iter().find(|a| {
+            !crate::compression::SUPPORTED_ALGORITHMS
+                .iter()
+                .any(|s| s.eq_ignore_ascii_case(a))
+        }) {
+            anyhow::bail!(
+                "Unsupported compression algorithm '{}'. Supported: {}",
+                algorithm,
+                crate::compression::SUPPORTED_ALGORITHMS.join(", ")
+            );
+        }
+        if crate::compression::parse_level(&compression.level).is_none() {
+            anyhow::bail!(
+                "Invalid compression level '{}'. Use fastest, default, best or an integer",
+                compression.level
+            );
+        }
+
+        // Validate base path
+        let base_path = self.server.base_path();
+        if !base_path.is_empty()
+            && (!base_path.starts_with('/') || base_path.chars().any(char::is_whitespace))
+        {
+            anyhow::bail!(
+                "Server base_path '{}' must start with '/' and contain no whitespace",
+                self.server.base_path
+            );
+        }
+
+        // Validate body limit
+        if self.server.max_body_bytes == 0 {
+            anyhow::bail!("Server max_body_bytes cannot be 0");
+        }
+
+        // Validate GraphQL limits
+        if self.graphql.max_depth == 0 || self.graphql.max_complexity == 0 {
+            anyhow::bail!("GraphQL max_depth and max_complexity cannot be 0");
+        }
+
+        // Validate additional listeners
+        let mut claimed = Vec::new();
+        let mut addresses: Vec<SocketAddr> = self.socket_addr().into_iter().collect();
+        for listener in &self.server.listeners {
+            let addr: SocketAddr = listener
+                .address
+                .parse()
+                .with_context(|| format!("Invalid listener address: {}", listener.address))?;
+            if addresses.contains(&addr) {
+                anyhow::bail!("Listener address {} is bound more than once", addr);
+            }
+            addresses.push(addr);
+
+            if listener.routes.is_empty() {
+                anyhow::bail!("Listener {} has no routes", listener.address);
+            }
+            for group in &listener.routes {
+                if claimed.contains(group) {
+                    anyhow::bail!(
+                        "Route group {:?} is assigned to more than one listener",
+                        group
+                    );
+                }
+                claimed.push(*group);
+            }
+        }
+
+        // Validate provider aliases
+        for (alias, id) in &self.providers.aliases {
+            if alias.trim().is_empty() || id.trim().is_empty() {
+                anyhow::bail!("Provider aliases cannot be empty ('{}' = '{}')", alias, id);
+            }
+        }
+
+        // Validate tenants
+        for name in self.tenants.keys() {
+            if name == crate::providers::tenants::DEFAULT_TENANT
+                || !crate::providers::validate::is_valid_provider_id(name)
+            {
+                anyhow::bail!(
+                    "Invalid tenant name '{}': use letters, digits, '-', '_' or '.' and not '{}'",
+                    name,
+                    crate::providers::tenants::DEFAULT_TENANT
+                );
+            }
+        }
+
+        // Validate upstream sync
+        let upstream = &self.providers.upstream;
+        if upstream.enabled {
+            if !upstream.url.starts_with("http://") && !upstream.url.starts_with("https://") {
+                anyhow::bail!("Upstream sync url must be an http(s) URL: {}", upstream.url);
+            }
+            if upstream.interval_seconds == 0 {
+                anyhow::bail!("Upstream sync interval_seconds cannot be 0");
+            }
+        }
+
+        // Validate remote catalog
+        if let Some(url) = &self.registry.remote_url {
+            if !url.starts_with("http://") && !url.starts_with("https://") {
+                anyhow::bail!("Registry remote_url must be an http(s) URL: {}", url);
+            }
+            if self.registry.source.is_some() {
+                anyhow::bail!("Registry remote_url and source cannot both be set");
+            }
+        }
+        if let Some(source) = &self.registry.source {
+            crate::storage::CatalogLocation::parse(source)?;
+        }
+        if self.registry.remote_source().is_some() {
+            if self.registry.refresh_interval_seconds == 0 {
+                anyhow::bail!("Registry refresh_interval_seconds cannot be 0");
+            }
+            if upstream.enabled {
+                anyhow::bail!(
+                    "A remote registry catalog and providers.upstream cannot both be enabled"
+                );
+            }
+        }
+
+        // Validate shared registry
+        if let Some(url) = &self.registry.redis.url {
+            if !cfg!(feature = "redis") {
+                anyhow::bail!(
+                    "registry.redis.url requires crabrace built with the 'redis' feature"
+                );
+            }
+            if !url.starts_with("redis://") {
+                anyhow::bail!("Registry redis url must be a redis:// URL: {}", url);
+            }
+            if self.registry.redis.resync_interval_seconds == 0 {
+                anyhow::bail!("Registry redis resync_interval_seconds cannot be 0");
+            }
+        }
+
+        // Validate currency conversion
+        for (currency, rate) in &self.currency.rates {
+            if !rate.is_finite() || *rate <= 0.0 {
+                anyhow::bail!("Exchange rate for '{}' must be a positive number", currency);
+            }
+        }
+        if let Some(url) = &self.currency.refresh_url {
+            if !url.starts_with("http://") && !url.starts_with("https://") {
+                anyhow::bail!("Currency refresh_url must be an http(s) URL: {}", url);
+            }
+            if self.currency.refresh_interval_seconds == 0 {
+                anyhow::bail!("Currency refresh_interval_seconds cannot be 0");
+            }
+        }
+
         // Validate log level
         let valid_levels = ["trace", "debug", "info", "warn", "error"];
         if !valid_levels.contains(&self.logging.level.to_lowercase().as_str()) {
@@ -342,6 +1205,33 @@ impl Config {
             );
         }
 
+        // Validate rate limiting
+        if self.security.rate_limit.enabled
+            && (self.security.rate_limit.requests_per_period == 0
+                || self.security.rate_limit.period_seconds == 0)
+        {
+            anyhow::bail!("Rate limit requests_per_period and period_seconds must be non-zero");
+        }
+
+        // Validate admin API
+        if self.admin.enabled && !self.security.auth.enabled {
+            anyhow::bail!("The admin API requires security.auth to be enabled");
+        }
+
+        // Validate TLS
+        if self.server.tls.enabled
+            && (self.server.tls.cert_path.is_none() || self.server.tls.key_path.is_none())
+        {
+            anyhow::bail!("TLS requires both cert_path and key_path");
+        }
+
+        // Validate probe paths
+        for path in [&self.health.liveness_path, &self.health.readiness_path] {
+            if !path.starts_with('/') {
+                anyhow::bail!("Health probe path '{}' must start with '/'", path);
+            }
+        }
+
         Ok(())
     }
 }
@@ -358,6 +1248,8 @@ mod tests {
         assert!(config.server.compression);
         assert_eq!(config.logging.level, "info");
         assert!(config.metrics.enabled);
+        assert!(!config.server.cache.enabled);
+        assert_eq!(config.server.cache.max_age_seconds, 300);
     }
 
     #[test]
@@ -400,8 +1292,72 @@ mod tests {
 
         config.server.timeout_seconds = 30;
 
+        // Invalid GraphQL limit
+        config.graphql.max_depth = 0;
+        assert!(config.validate().is_err());
+
+        config.graphql.max_depth = 10;
+
         // Invalid log level
         config.logging.level = "invalid".to_string();
         assert!(config.validate().is_err());
+
+        config.logging.level = "info".to_string();
+
+        // Invalid exchange rate
+        config.currency.rates.insert("EUR".to_string(), 0.0);
+        assert!(config.validate().is_err());
+
+        config.currency.rates.insert("EUR".to_string(), 0.92);
+        assert!(config.validate().is_ok());
+
+        // Invalid probe path
+        config.health.readiness_path = "readyz".to_string();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_providers_allows() {
+        let mut providers = ProvidersConfig::default();
+        assert!(providers.allows("openai"));
+
+        providers.enabled = vec!["openai".into(), "anthropic".into()];
+        providers.disabled = vec!["anthropic".into()];
+        assert!(providers.allows("openai"));
+        assert!(!providers.allows("anthropic"));
+        assert!(!providers.allows("gemini"));
+    }
+
+    #[test]
+    fn test_listeners() {
+        let mut config = Config::default();
+        assert_eq!(config.server.primary_routes(), RouteGroup::ALL.to_vec());
+
+        config.server.listeners.push(ListenerConfig {
+            address: "127.0.0.1:9090".to_string(),
+            routes: vec![RouteGroup::Metrics, RouteGroup::Admin],
+        });
+        assert!(config.validate().is_ok());
+        assert_eq!(
+            config.server.primary_routes(),
+            vec![RouteGroup::Api, RouteGroup::Health, RouteGroup::Docs]
+        );
+
+        // A route group can only live on one extra listener
+        config.server.listeners.push(ListenerConfig {
+            address: "127.0.0.1:9091".to_string(),
+            routes: vec![RouteGroup::Admin],
+        });
+        assert!(config.validate().is_err());
+
+        // Addresses must be distinct
+        config.server.listeners[1] = ListenerConfig {
+            address: "127.0.0.1:9090".to_string(),
+            routes: vec![RouteGroup::Docs],
+        };
+        assert!(config.validate().is_err());
+
+        config.server.listeners[1].address = "not-an-address".to_string();
+        assert!(config.validate().is_err());
     }
 }
@@ -0,0 +1,197 @@
+//! Conversion of USD prices to other currencies
+//!
+//! Catalog prices are always in USD. Cost endpoints (`/estimate`,
+//! `/models/compare`, `/recommend`) accept a `currency` query parameter and
+//! convert their amounts with the rates configured under `[currency.rates]`
+//! (units of the currency per US dollar). When `currency.refresh_url` is set,
+//! the rates are periodically replaced by those of an exchange-rate API
+//! answering `{"rates": {"EUR": 0.92, ...}}` with USD as the base; a failed
+//! refresh keeps the current rates.
+
+use anyhow::{Context, Result};
+use parking_lot::RwLock;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use crate::config::CurrencyConfig;
+
+pub use crate::models::estimate::BASE_CURRENCY;
+
+/// A currency to report costs in and its rate against USD
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conversion {
+    /// ISO 4217 code (e.g. `EUR`)
+    pub currency: String,
+
+    /// Units of `currency` per US dollar
+    pub rate: f64,
+}
+
+impl Conversion {
+    /// Convert an amount in USD
+    pub fn apply(&self, usd: f64) -> f64 {
+        usd * self.rate
+    }
+}
+
+impl Default for Conversion {
+    fn default() -> Self {
+        Self {
+            currency: BASE_CURRENCY.to_string(),
+            rate: 1.0,
+        }
+    }
+}
+
+/// Exchange-rate API response
+#[derive(Debug, Deserialize)]
+struct RatesResponse {
+    rates: BTreeMap<String, f64>,
+}
+
+/// Exchange rates against USD, optionally refreshed from an API
+#[derive(Debug, Default)]
+pub struct ExchangeRates {
+    /// Units per US dollar, by upper-case currency code
+    rates: RwLock<BTreeMap<String, f64>>,
+    refresh_url: Option<String>,
+    refresh_interval: Duration,
+    http: reqwest::Client,
+}
+
+impl ExchangeRates {
+    /// Build the rate table from configuration
+    pub fn from_config(config: &CurrencyConfig) -> Result<Self> {
+        Ok(Self {
+            rates: RwLock::new(normalized(config.rates.clone())),
+            refresh_url: config.refresh_url.clone(),
+            refresh_interval: Duration::from_secs(config.refresh_interval_seconds),
+            http: reqwest::Client::builder()
+                .timeout(Duration::from_secs(30))
+                .build()?,
+        })
+    }
+
+    /// URL of the exchange-rate API, if the rates are refreshed
+    pub fn refresh_url(&self) -> Option<&str> {
+        self.refresh_url.as_deref()
+    }
+
+    /// Conversion to a currency (case-insensitive), if its rate is known
+    pub fn conversion(&self, currency: &str) -> Option<Conversion> {
+        let currency = currency.trim().to_ascii_uppercase();
+        let rate = if currency == BASE_CURRENCY {
+            1.0
+        } else {
+            *self.rates.read().get(&currency)?
+        };
+        Some(Conversion { currency, rate })
+    }
+
+    /// Codes of every supported currency, USD included, sorted
+    pub fn currencies(&self) -> Vec<String> {
+        let mut currencies: Vec<String> = self.rates.read().keys().cloned().collect();
+        if !currencies.iter().any(|c| c == BASE_CURRENCY) {
+            currencies.push(BASE_CURRENCY.to_string());
+            currencies.sort();
+        }
+        currencies
+    }
+
+    /// Fetch the rates once, updating every currency the API publishes
+    ///
+    /// Returns the number of rates received.
+    pub async fn refresh(&self) -> Result<usize> {
+        let url = self
+            .refresh_url
+            .as_deref()
+            .context("No exchange-rate refresh URL configured")?;
+        let response: RatesResponse = self
+            .http
+            .get(url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .with_context(|| format!("Failed to fetch exchange rates from {}", url))?
+            .json()
+            .await
+            .with_context(|| format!("Invalid exchange rates from {}", url))?;
+
+        let fetched = normalized(response.rates);
+        let count = fetched.len();
+        self.rates.write().extend(fetched);
+        Ok(count)
+    }
+
+    /// Refresh the rates in the background, starting now
+    pub fn spawn(self: Arc<Self>) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(self.refresh_interval);
+            loop {
+                interval.tick().await;
+                match self.refresh().await {
+                    Ok(count) => info!("Refreshed {} exchange rates", count),
+                    Err(e) => warn!("{:#}", e),
+                }
+            }
+        })
+    }
+}
+
+/// Upper-case the codes and drop rates that are not positive numbers
+fn normalized(rates: impl IntoIterator<Item = (String, f64)>) -> BTreeMap<String, f64> {
+    rates
+        .into_iter()
+        .filter(|(_, rate)| rate.is_finite() && *rate > 0.0)
+        .map(|(currency, rate)| (currency.trim().to_ascii_uppercase(), rate))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn rates(refresh_url: Option<String>) -> ExchangeRates {
+        ExchangeRates::from_config(&CurrencyConfig {
+            rates: [("eur".to_string(), 0.5), ("GBP".to_string(), 0.75)].into(),
+            refresh_url,
+            ..CurrencyConfig::default()
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_conversion() {
+        let rates = rates(None);
+        let eur = rates.conversion("Eur").unwrap();
+        assert_eq!(eur.currency, "EUR");
+        assert_eq!(eur.apply(3.0), 1.5);
+        assert_eq!(rates.conversion("usd").unwrap(), Conversion::default());
+        assert!(rates.conversion("JPY").is_none());
+        assert_eq!(rates.currencies(), ["EUR", "GBP", "USD"]);
+    }
+
+    #[tokio::test]
+    async fn test_refresh() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/latest")
+            .with_header("content-type", "application/json")
+            .with_body(json!({ "base": "USD", "rates": { "EUR": 0.25, "JPY": 150.0 } }).to_string())
+            .create_async()
+            .await;
+
+        let rates = rates(Some(format!("{}/latest", server.url())));
+        let count = rates.refresh().await.unwrap();
+        assert_eq!(count, 2);
+        assert_eq!(rates.conversion("EUR").unwrap().rate, 0.25);
+        assert_eq!(rates.conversion("JPY").unwrap().rate, 150.0);
+        // Currencies missing upstream keep their configured rate
+        assert_eq!(rates.conversion("GBP").unwrap().rate, 0.75);
+    }
+}
@@ -0,0 +1,323 @@
+//! Offline fallback for [`CrabraceClient`](crate::CrabraceClient)
+//!
+//! When the server cannot be reached (connection errors, timeouts or 5xx
+//! responses once retries are exhausted), a client with a fallback answers
+//! from a local catalog instead of failing:
+//!
+//! 1. a snapshot file, rewritten whenever `get_providers()` gets the
+//!    catalog from the server (not from the client's response cache);
+//! 2. the catalog compiled into the crate (feature `embedded-data`).
+//!
+//! The `*_with_freshness` client methods report which of them answered.
+
+use anyhow::{Context, Result};
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::models::estimate::{self, EstimateRequest, EstimateResponse};
+use crate::{read_estimate, read_model, read_provider, read_providers, Model, Provider};
+
+/// Where an answer came from, and how old its data is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Freshness {
+    /// From the server (possibly through the client's response cache)
+    Live,
+
+    /// From the snapshot file, saved from the server at `saved_at`
+    Snapshot { saved_at: DateTime<Utc> },
+
+    /// From the catalog compiled into this crate, built at `built_at`
+    Embedded { built_at: Option<DateTime<Utc>> },
+}
+
+impl Freshness {
+    /// Whether the server answered
+    pub fn is_live(&self) -> bool {
+        matches!(self, Freshness::Live)
+    }
+
+    /// When the data was known to be current (`None` when live, or for an
+    /// embedded catalog of unknown build time)
+    pub fn as_of(&self) -> Option<DateTime<Utc>> {
+        match *self {
+            Freshness::Live => None,
+            Freshness::Snapshot { saved_at } => Some(saved_at),
+            Freshness::Embedded { built_at } => built_at,
+        }
+    }
+}
+
+/// A client answer along with its [`Freshness`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fetched<T> {
+    pub value: T,
+    pub freshness: Freshness,
+}
+
+impl<T> Fetched<T> {
+    pub(crate) fn live(value: T) -> Self {
+        Self {
+            value,
+            freshness: Freshness::Live,
+        }
+    }
+
+    /// Transform the value, keeping its freshness
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Fetched<U> {
+        Fetched {
+            value: f(self.value),
+            freshness: self.freshness,
+        }
+    }
+}
+
+/// Contents of a snapshot file
+#[derive(Serialize, Deserialize)]
+struct Snapshot {
+    saved_at: DateTime<Utc>,
+    providers: Vec<Provider>,
+}
+
+/// What a request with a fallback configured came to
+enum Outcome {
+    /// The server answered (any status but a 5xx)
+    Answered(StatusCode, Bytes),
+
+    /// The server could not be reached
+    Unreachable(anyhow::Error),
+}
+
+/// The local catalogs a client falls back to
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Fallback {
+    pub(crate) snapshot: Option<PathBuf>,
+    #[cfg(feature = "embedded-data")]
+    pub(crate) embedded: bool,
+}
+
+impl Fallback {
+    fn is_enabled(&self) -> bool {
+        #[cfg(feature = "embedded-data")]
+        if self.embedded {
+            return true;
+        }
+        self.snapshot.is_some()
+    }
+
+    /// Tell an unreachable server from an answer, or pass the error on
+    /// when there is nothing to fall back to
+    fn classify(&self, result: Result<(StatusCode, Bytes)>) -> Result<Outcome> {
+        match result {
+            Ok((status, _)) if status.is_server_error() && self.is_enabled() => Ok(
+                Outcome::Unreachable(anyhow::anyhow!("Server answered HTTP {}", status)),
+            ),
+            Ok((status, body)) => Ok(Outcome::Answered(status, body)),
+            Err(e) if e.is::<reqwest::Error>() && self.is_enabled() => Ok(Outcome::Unreachable(e)),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Providers from a `/v1/providers` request, else from a local catalog
+    pub(crate) fn providers_from(
+        &self,
+        result: Result<(StatusCode, Bytes)>,
+    ) -> Result<Fetched<Vec<Provider>>> {
+        match self.classify(result)? {
+            Outcome::Answered(status, body) => read_providers(status, &body).map(Fetched::live),
+            Outcome::Unreachable(cause) => self.providers(cause),
+        }
+    }
+
+    /// Provider from a `/v1/providers/{id}` request, else from a local catalog
+    pub(crate) fn provider_from(
+        &self,
+        id: &str,
+        result: Result<(StatusCode, Bytes)>,
+    ) -> Result<Fetched<Option<Provider>>> {
+        match self.classify(result)? {
+            Outcome::Answered(status, body) => read_provider(id, status, &body).map(Fetched::live),
+            Outcome::Unreachable(cause) => Ok(self
+                .providers(cause)?
+                .map(|providers| find_provider(providers, id))),
+        }
+    }
+
+    /// Model from a `/v1/providers/{provider_id}/models/{model_id}` request,
+    /// else from a local catalog
+    pub(crate) fn model_from(
+        &self,
+        provider_id: &str,
+        model_id: &str,
+        result: Result<(StatusCode, Bytes)>,
+    ) -> Result<Fetched<Option<Model>>> {
+        match self.classify(result)? {
+            Outcome::Answered(status, body) => {
+                read_model(provider_id, model_id, status, &body).map(Fetched::live)
+            }
+            Outcome::Unreachable(cause) => Ok(self.providers(cause)?.map(|providers| {
+                find_provider(providers, provider_id)
+                    .and_then(|provider| provider.get_model(model_id).cloned())
+            })),
+        }
+    }
+
+    /// Estimate from a `/v1/estimate` request, else priced locally against
+    /// a local catalog
+    pub(crate) fn estimate_from(
+        &self,
+        request: &EstimateRequest,
+        result: Result<(StatusCode, Bytes)>,
+    ) -> Result<Fetched<EstimateResponse>> {
+        match self.classify(result)? {
+            Outcome::Answered(status, body) => {
+                read_estimate(request, status, &body).map(Fetched::live)
+            }
+            Outcome::Unreachable(cause) => {
+                let providers = self.providers(cause)?;
+                let model = find_provider(providers.value, &request.provider_id)
+                    .and_then(|provider| provider.get_model(&request.model_id).cloned())
+                    .with_context(|| {
+                        format!(
+                            "Model '{}' of provider '{}' not found",
+                            request.model_id, request.provider_id
+                        )
+                    })?;
+                estimate::validate(&model, request)?;
+                Ok(Fetched {
+                    value: estimate::price(&model, request),
+                    freshness: providers.freshness,
+                })
+            }
+        }
+    }
+
+    /// Record the providers the server just returned in the snapshot file
+    ///
+    /// Only for answers from the server: the snapshot is dated now.
+    #[cfg(any(feature = "blocking", target_arch = "wasm32"))]
+    pub(crate) fn save(&self, providers: &[Provider]) -> Result<()> {
+        let Some((path, json)) = self.snapshot_json(providers)? else {
+            return Ok(());
+        };
+        // Write then rename, so a crash never leaves a truncated snapshot
+        let temp = path.with_extension("tmp");
+        std::fs::write(&temp, json)
+            .and_then(|()| std::fs::rename(&temp, path))
+            .with_context(|| format!("Failed to save catalog snapshot {}", path.display()))
+    }
+
+    /// [`save`](Self::save) without blocking the async runtime
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) async fn save_async(&self, providers: &[Provider]) -> Result<()> {
+        let Some((path, json)) = self.snapshot_json(providers)? else {
+            return Ok(());
+        };
+        let temp = path.with_extension("tmp");
+        let written = match tokio::fs::write(&temp, json).await {
+            Ok(()) => tokio::fs::rename(&temp, path).await,
+            Err(e) => Err(e),
+        };
+        written.with_context(|| format!("Failed to save catalog snapshot {}", path.display()))
+    }
+
+    /// Browsers have no files to save to
+    #[cfg(target_arch = "wasm32")]
+    pub(crate) async fn save_async(&self, providers: &[Provider]) -> Result<()> {
+        self.save(providers)
+    }
+
+    /// The snapshot file and its new contents, if one is configured
+    fn snapshot_json(&self, providers: &[Provider]) -> Result<Option<(&Path, Vec<u8>)>> {
+        let Some(path) = &self.snapshot else {
+            return Ok(None);
+        };
+        let snapshot = Snapshot {
+            saved_at: Utc::now(),
+            providers: providers.to_vec(),
+        };
+        Ok(Some((path, serde_json::to_vec(&snapshot)?)))
+    }
+
+    /// Every provider from the first local catalog available
+    fn providers(&self, cause: anyhow::Error) -> Result<Fetched<Vec<Provider>>> {
+        if let Some(snapshot) = self.snapshot.as_deref().and_then(read_snapshot) {
+            return Ok(Fetched {
+                value: snapshot.providers,
+                freshness: Freshness::Snapshot {
+                    saved_at: snapshot.saved_at,
+                },
+            });
+        }
+
+        #[cfg(feature = "embedded-data")]
+        if self.embedded {
+            return Ok(Fetched {
+                value: crate::LocalCatalog::new()?.get_providers()?,
+                freshness: Freshness::Embedded {
+                    built_at: build_timestamp(),
+                },
+            });
+        }
+
+        Err(cause.context("Server unreachable and no fallback catalog available"))
+    }
+}
+
+/// A snapshot file, if it exists and is readable
+fn read_snapshot(path: &Path) -> Option<Snapshot> {
+    let json = std::fs::read(path).ok()?;
+    serde_json::from_slice(&json).ok()
+}
+
+/// Build time of the crate, for the embedded catalog (see `build.rs`)
+#[cfg(feature = "embedded-data")]
+fn build_timestamp() -> Option<DateTime<Utc>> {
+    env!("CRABRACE_BUILD_TIMESTAMP")
+        .parse()
+        .ok()
+        .and_then(|secs| DateTime::from_timestamp(secs, 0))
+}
+
+/// A provider by ID, or by built-in alias when they are compiled in
+fn find_provider(providers: Vec<Provider>, id: &str) -> Option<Provider> {
+    #[cfg(feature = "embedded-data")]
+    let id = crate::providers::local::canonical_id(id);
+    providers.into_iter().find(|provider| provider.id == id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_snapshot() {
+        let dir = std::env::temp_dir().join(format!("crabrace-snapshot-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let fallback = Fallback {
+            snapshot: Some(dir.join("catalog.json")),
+            #[cfg(feature = "embedded-data")]
+            embedded: false,
+        };
+
+        let providers = vec![Provider::new(
+            "Test".to_string(),
+            "test".to_string(),
+            "openai".to_string(),
+        )];
+        fallback.save_async(&providers).await.unwrap();
+        let fetched = fallback.providers(anyhow::anyhow!("offline")).unwrap();
+        assert_eq!(fetched.value[0].id, "test");
+        assert!(!fetched.freshness.is_live());
+        assert!(fetched.freshness.as_of().unwrap() <= Utc::now());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+        let error = fallback.providers(anyhow::anyhow!("offline")).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Server unreachable and no fallback catalog available"
+        );
+    }
+}
@@ -0,0 +1,388 @@
+//! Request and response hooks for [`CrabraceClient`](crate::CrabraceClient)
+//!
+//! An [`Interceptor`] sees every HTTP attempt the client makes, retries
+//! included: it can add headers before a request is sent (tracing context,
+//! credentials from a token provider) and observe how each attempt ended
+//! (latency, status, errors). Interceptors run in the order they were
+//! registered, and are shared by clones of a client.
+//!
+//! For request counts and latencies, [`on_response`] turns a closure into
+//! an interceptor, and the `client-metrics` feature adds
+//! [`ClientMetrics`](crate::client_metrics::ClientMetrics), which reports
+//! through the `metrics` crate.
+
+use reqwest::header::HeaderMap;
+use reqwest::{Method, StatusCode, Url};
+use std::sync::Arc;
+use std::time::Duration;
+
+// `std::time::Instant` panics in browsers
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+#[cfg(target_arch = "wasm32")]
+use web_time::Instant;
+
+/// A request about to be sent
+#[derive(Debug)]
+pub struct OutgoingRequest<'a> {
+    pub method: &'a Method,
+    pub url: &'a Url,
+
+    /// Headers of this request, which are sent as modified (the client's
+    /// default headers and user-agent are added afterwards)
+    pub headers: &'a mut HeaderMap,
+
+    /// 1 for the first attempt, incremented on every retry
+    pub attempt: u32,
+}
+
+/// How one attempt ended
+#[derive(Debug)]
+pub struct Exchange<'a> {
+    pub method: &'a Method,
+    pub url: &'a Url,
+    pub attempt: u32,
+
+    /// Status of the response, or the error that prevented one
+    pub outcome: Result<StatusCode, &'a reqwest::Error>,
+
+    /// Time from sending the request to receiving the response headers
+    pub elapsed: Duration,
+}
+
+impl Exchange<'_> {
+    /// The API route requested, with IDs replaced by placeholders (e.g.
+    /// `/v1/providers/{id}`), to group attempts by without one group per
+    /// provider or model
+    ///
+    /// `other` for paths the client does not request.
+    pub fn endpoint(&self) -> &'static str {
+        let path = self.url.path();
+        if path.ends_with("/health") {
+            return "/health";
+        }
+        // The server may be mounted under a path prefix
+        let Some(start) = path.find("/v1/") else {
+            return "other";
+        };
+        match path[start..].split('/').skip(2).collect::<Vec<_>>()[..] {
+            ["providers"] => "/v1/providers",
+            ["providers", "events"] => "/v1/providers/events",
+            ["providers", _] => "/v1/providers/{id}",
+            ["providers", _, "models", _] => "/v1/providers/{id}/models/{model_id}",
+            ["estimate"] => "/v1/estimate",
+            _ => "other",
+        }
+    }
+
+    /// Why the attempt failed, `None` if it got a success or redirect
+    /// status
+    ///
+    /// One of `client_error` (4xx), `server_error` (5xx), `timeout`,
+    /// `connect` (the server could not be reached) or `request` (any other
+    /// error sending the request).
+    pub fn error_kind(&self) -> Option<&'static str> {
+        match self.outcome {
+            Ok(status) if status.is_client_error() => Some("client_error"),
+            Ok(status) if status.is_server_error() => Some("server_error"),
+            Ok(_) => None,
+            Err(error) if error.is_timeout() => Some("timeout"),
+            Err(error) if crate::retry::is_connect(error) => Some("connect"),
+            Err(_) => Some("request"),
+        }
+    }
+}
+
+/// Hooks run around every HTTP attempt of a client
+///
+/// Both methods do nothing by default.
+///
+/// # Example
+///
+/// ```
+/// use crabrace::intercept::{Exchange, Interceptor};
+/// use crabrace::CrabraceClient;
+///
+/// struct LogLatency;
+///
+/// impl Interceptor for LogLatency {
+///     fn on_response(&self, exchange: &Exchange<'_>) {
+///         eprintln!("{} {} took {:?}", exchange.method, exchange.url, exchange.elapsed);
+///     }
+/// }
+///
+/// let client = CrabraceClient::new("http://localhost:8080").with_interceptor(LogLatency);
+/// ```
+pub trait Interceptor: Send + Sync {
+    /// Called before each attempt is sent
+    fn on_request(&self, _request: &mut OutgoingRequest<'_>) {}
+
+    /// Called once each attempt has a response or failed
+    fn on_response(&self, _exchange: &Exchange<'_>) {}
+}
+
+/// Interceptors shared with the caller, e.g. to read what they recorded
+impl<T: Interceptor + ?Sized> Interceptor for Arc<T> {
+    fn on_request(&self, request: &mut OutgoingRequest<'_>) {
+        (**self).on_request(request);
+    }
+
+    fn on_response(&self, exchange: &Exchange<'_>) {
+        (**self).on_response(exchange);
+    }
+}
+
+/// An interceptor calling `f` once each attempt has a response or failed
+///
+/// # Example
+///
+/// ```
+/// use crabrace::intercept::on_response;
+/// use crabrace::CrabraceClient;
+///
+/// let client = CrabraceClient::new("http://localhost:8080").with_interceptor(on_response(
+///     |exchange| {
+///         if let Some(kind) = exchange.error_kind() {
+///             eprintln!("{} failed: {}", exchange.endpoint(), kind);
+///         }
+///     },
+/// ));
+/// ```
+pub fn on_response<F>(f: F) -> OnResponse<F>
+where
+    F: Fn(&Exchange<'_>) + Send + Sync,
+{
+    OnResponse(f)
+}
+
+/// The interceptor returned by [`on_response`]
+#[derive(Clone)]
+pub struct OnResponse<F>(F);
+
+impl<F> std::fmt::Debug for OnResponse<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("OnResponse")
+    }
+}
+
+impl<F> Interceptor for OnResponse<F>
+where
+    F: Fn(&Exchange<'_>) + Send + Sync,
+{
+    fn on_response(&self, exchange: &Exchange<'_>) {
+        (self.0)(exchange);
+    }
+}
+
+/// The interceptors of a client
+#[derive(Clone, Default)]
+pub(crate) struct Interceptors(Vec<Arc<dyn Interceptor>>);
+
+impl std::fmt::Debug for Interceptors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Interceptors")
+            .field("count", &self.0.len())
+            .finish()
+    }
+}
+
+impl Interceptors {
+    pub(crate) fn push(&mut self, interceptor: impl Interceptor + 'static) {
+        self.0.push(Arc::new(interceptor));
+    }
+
+    /// Run the request hooks on a request about to be sent, and start
+    /// timing it
+    pub(crate) fn before(
+        &self,
+        method: &Method,
+        url: &Url,
+        headers: &mut HeaderMap,
+        attempt: u32,
+    ) -> Instant {
+        let mut request = OutgoingRequest {
+            method,
+            url,
+            headers,
+            attempt,
+        };
+        for interceptor in &self.0 {
+            interceptor.on_request(&mut request);
+        }
+        Instant::now()
+    }
+
+    /// Run the response hooks on how an attempt started at `sent_at` ended
+    pub(crate) fn after(
+        &self,
+        method: &Method,
+        url: &Url,
+        attempt: u32,
+        outcome: Result<StatusCode, &reqwest::Error>,
+        sent_at: Instant,
+    ) {
+        if self.0.is_empty() {
+            return;
+        }
+        let exchange = Exchange {
+            method,
+            url,
+            attempt,
+            outcome,
+            elapsed: sent_at.elapsed(),
+        };
+        for interceptor in &self.0 {
+            interceptor.on_response(&exchange);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CrabraceClient, RetryPolicy};
+    use parking_lot::Mutex;
+
+    /// Tags requests and records how every attempt ended
+    #[derive(Default)]
+    struct Recorder {
+        attempts: Mutex<Vec<(u32, Option<StatusCode>)>>,
+    }
+
+    impl Interceptor for Recorder {
+        fn on_request(&self, request: &mut OutgoingRequest<'_>) {
+            request
+                .headers
+                .insert("x-trace-id", request.attempt.to_string().parse().unwrap());
+        }
+
+        fn on_response(&self, exchange: &Exchange<'_>) {
+            assert_eq!(exchange.url.path(), "/v1/providers");
+            let status = exchange.outcome.as_ref().ok().copied();
+            self.attempts.lock().push((exchange.attempt, status));
+        }
+    }
+
+    #[test]
+    fn test_endpoint() {
+        for (url, endpoint) in [
+            ("http://localhost/v1/providers", "/v1/providers"),
+            (
+                "http://localhost/v1/providers/events",
+                "/v1/providers/events",
+            ),
+            (
+                "http://localhost/api/v1/providers/openai",
+                "/v1/providers/{id}",
+            ),
+            (
+                "http://localhost/v1/providers/openrouter/models/openai%2Fgpt-4o",
+                "/v1/providers/{id}/models/{model_id}",
+            ),
+            ("http://localhost/v1/estimate", "/v1/estimate"),
+            ("http://localhost/health", "/health"),
+            ("http://localhost/v1/models", "other"),
+            ("http://localhost/metrics", "other"),
+        ] {
+            let url = Url::parse(url).unwrap();
+            let exchange = Exchange {
+                method: &Method::GET,
+                url: &url,
+                attempt: 1,
+                outcome: Ok(StatusCode::OK),
+                elapsed: Duration::ZERO,
+            };
+            assert_eq!(exchange.endpoint(), endpoint, "{}", url);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_interceptors() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/v1/providers")
+            .match_header("x-trace-id", "1")
+            .with_status(503)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/v1/providers")
+            .match_header("x-trace-id", "2")
+            .with_body("[]")
+            .create_async()
+            .await;
+
+        let recorder = Arc::new(Recorder::default());
+        let client = CrabraceClient::builder(server.url())
+            .retry(RetryPolicy {
+                base_delay: Duration::from_millis(1),
+                ..RetryPolicy::default()
+            })
+            .interceptor(recorder.clone())
+            .build()
+            .unwrap();
+        assert!(client.get_providers().await.unwrap().is_empty());
+        assert_eq!(
+            *recorder.attempts.lock(),
+            vec![
+                (1, Some(StatusCode::SERVICE_UNAVAILABLE)),
+                (2, Some(StatusCode::OK))
+            ]
+        );
+        assert!(format!("{:?}", client).contains("count: 1"));
+    }
+
+    #[tokio::test]
+    async fn test_on_response() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/api/v1/providers/missing")
+            .with_status(404)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/api/v1/providers/openai/models/gpt-4o")
+            .with_status(500)
+            .create_async()
+            .await;
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let record = {
+            let seen = seen.clone();
+            on_response(move |exchange| {
+                seen.lock()
+                    .push((exchange.endpoint(), exchange.error_kind()));
+            })
+        };
+        let client = CrabraceClient::builder(format!("{}/api", server.url()))
+            .retry(RetryPolicy::none())
+            .interceptor(record)
+            .build()
+            .unwrap();
+        assert!(client.get_provider("missing").await.unwrap().is_none());
+        assert!(client.get_model("openai", "gpt-4o").await.is_err());
+        assert_eq!(
+            *seen.lock(),
+            vec![
+                ("/v1/providers/{id}", Some("client_error")),
+                ("/v1/providers/{id}/models/{model_id}", Some("server_error")),
+            ]
+        );
+
+        // Nothing listens on port 1
+        seen.lock().clear();
+        let client = CrabraceClient::builder("http://127.0.0.1:1")
+            .retry(RetryPolicy::none())
+            .interceptor(on_response({
+                let seen = seen.clone();
+                move |exchange| {
+                    seen.lock()
+                        .push((exchange.endpoint(), exchange.error_kind()))
+                }
+            }))
+            .build()
+            .unwrap();
+        assert!(client.get_providers().await.is_err());
+        assert_eq!(*seen.lock(), vec![("/v1/providers", Some("connect"))]);
+    }
+}
@@ -29,23 +29,143 @@
 //! }
 //! ```
 
+#[cfg(feature = "server")]
+pub mod access_log;
+#[cfg(feature = "server")]
+pub mod api;
+#[cfg(feature = "server")]
+pub mod audit;
+#[cfg(feature = "server")]
+pub mod auth;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+pub mod builder;
+mod cache;
+pub mod catalog;
+#[cfg(feature = "client-metrics")]
+pub mod client_metrics;
+#[cfg(feature = "server")]
+pub mod compression;
+#[cfg(feature = "server")]
 pub mod config;
+#[cfg(feature = "server")]
+pub mod currency;
+pub mod fallback;
+pub mod intercept;
+#[cfg(feature = "server")]
 pub mod metrics;
 pub mod models;
+#[cfg(not(target_arch = "wasm32"))]
+mod pinning;
+#[cfg(feature = "embedded-data")]
 pub mod providers;
+pub mod retry;
+#[cfg(feature = "server")]
 pub mod security;
+pub mod source;
+#[cfg(feature = "server")]
+pub mod storage;
+mod stream;
+#[cfg(feature = "server")]
+pub mod sync;
+#[cfg(feature = "server")]
+pub mod tls;
+pub mod watch;
 
+pub use builder::CrabraceClientBuilder;
+pub use catalog::{Capability, CatalogExt};
+#[cfg(feature = "client-metrics")]
+pub use client_metrics::ClientMetrics;
+#[cfg(feature = "server")]
 pub use config::Config;
-pub use models::provider::{Model, Provider};
+pub use fallback::{Fetched, Freshness};
+pub use intercept::Interceptor;
+pub use models::estimate::{EstimateRequest, EstimateResponse, Usage};
+pub use models::provider::{
+    CostBreakdown, CostUsage, ImagePrice, Lifecycle, Modality, Model, ModelKind, Provider,
+    ProviderModel, RateLimits,
+};
+#[cfg(feature = "embedded-data")]
+pub use providers::local::LocalCatalog;
+pub use retry::RetryPolicy;
+pub use source::{MockSource, ProviderSource};
+pub use watch::CatalogRevision;
 
 use anyhow::Result;
-use reqwest::Client as HttpClient;
+use bytes::Bytes;
+use cache::ResponseCache;
+use fallback::Fallback;
+use futures_util::future::Either;
+use futures_util::{Stream, StreamExt};
+use intercept::Interceptors;
+use reqwest::header::{HeaderMap, ACCEPT, ETAG, IF_NONE_MATCH};
+use reqwest::{Client as HttpClient, Method, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::time::Duration;
+use watch::Watcher;
+
+#[cfg(target_arch = "wasm32")]
+use gloo_timers::future::sleep;
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::time::sleep;
+
+/// Events of a change feed connection (not `Send` in browsers, where
+/// nothing is)
+#[cfg(not(target_arch = "wasm32"))]
+type Events = Pin<Box<dyn Stream<Item = Result<stream::SseEvent>> + Send>>;
+#[cfg(target_arch = "wasm32")]
+type Events = Pin<Box<dyn Stream<Item = Result<stream::SseEvent>>>>;
 
 /// Crabrace HTTP client for querying provider information
+///
+/// Catalog requests are retried according to a [`RetryPolicy`] (by default
+/// 3 attempts with exponential backoff on 5xx, 429 and connection errors).
+/// Responses can be cached in memory with [`with_cache_ttl`](Self::with_cache_ttl);
+/// responses with an `ETag` are always revalidated with `If-None-Match`
+/// rather than downloaded again. With a [snapshot file](Self::with_snapshot_file)
+/// or the [embedded catalog](Self::with_embedded_fallback) as fallback, the
+/// client keeps answering when the server cannot be reached.
 #[derive(Debug, Clone)]
 pub struct CrabraceClient {
     base_url: String,
     http_client: HttpClient,
+    retry: RetryPolicy,
+    cache: ResponseCache,
+    credential: Option<Credential>,
+    fallback: Fallback,
+    interceptors: Interceptors,
+}
+
+/// Credential attached to every request of an authenticated client
+#[derive(Clone)]
+enum Credential {
+    /// Sent as `X-Api-Key`
+    ApiKey(String),
+
+    /// Sent as `Authorization: Bearer`
+    Bearer(String),
+}
+
+/// Source of [`CrabraceClient::get_providers_stream`]
+enum ProvidersStream {
+    /// An NDJSON response, read as it arrives
+    Ndjson(reqwest::Response),
+
+    /// Providers read whole, from a JSON array or a fallback catalog
+    Loaded(Vec<Provider>),
+}
+
+impl std::fmt::Debug for Credential {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kind = match self {
+            Credential::ApiKey(_) => "ApiKey",
+            Credential::Bearer(_) => "Bearer",
+        };
+        f.debug_tuple(kind).field(&"<redacted>").finish()
+    }
 }
 
 impl CrabraceClient {
@@ -63,10 +183,19 @@ impl CrabraceClient {
     /// let client = CrabraceClient::new("http://localhost:8080");
     /// ```
     pub fn new(base_url: impl Into<String>) -> Self {
-        Self {
-            base_url: base_url.into(),
-            http_client: HttpClient::new(),
-        }
+        let http_client = HttpClient::builder()
+            .user_agent(builder::DEFAULT_USER_AGENT)
+            .build()
+            .expect("Failed to build HTTP client");
+        Self::with_client(base_url, http_client)
+    }
+
+    /// Start building a client with custom timeouts, headers, user-agent or
+    /// proxy
+    ///
+    /// See [`CrabraceClientBuilder`].
+    pub fn builder(base_url: impl Into<String>) -> CrabraceClientBuilder {
+        CrabraceClientBuilder::new(base_url)
     }
 
     /// Create a new client with a custom HTTP client
@@ -79,6 +208,220 @@ impl CrabraceClient {
         Self {
             base_url: base_url.into(),
             http_client,
+            retry: RetryPolicy::default(),
+            cache: ResponseCache::new(Duration::ZERO),
+            credential: None,
+            fallback: Fallback::default(),
+            interceptors: Interceptors::default(),
+        }
+    }
+
+    /// Replace the retry policy
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use crabrace::{CrabraceClient, RetryPolicy};
+    /// use std::time::Duration;
+    ///
+    /// let client = CrabraceClient::new("http://localhost:8080").with_retry(RetryPolicy {
+    ///     max_attempts: 5,
+    ///     base_delay: Duration::from_millis(500),
+    ///     ..RetryPolicy::default()
+    /// });
+    /// ```
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Cache successful responses in memory for `ttl`
+    ///
+    /// Repeated calls within the TTL (e.g. `get_providers()` on every
+    /// incoming request) are answered without reaching the server. Once the
+    /// TTL is over, bodies with an `ETag` are revalidated with a conditional
+    /// request, which costs the server nothing when the catalog is unchanged.
+    /// Clones of the client share the cache.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use crabrace::CrabraceClient;
+    /// use std::time::Duration;
+    ///
+    /// let client = CrabraceClient::new("http://localhost:8080")
+    ///     .with_cache_ttl(Duration::from_secs(300));
+    /// ```
+    pub fn with_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.cache = ResponseCache::new(ttl);
+        self
+    }
+
+    /// Authenticate every request with an API key, sent as `X-Api-Key`
+    ///
+    /// The key never appears in the client's `Debug` output.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use crabrace::CrabraceClient;
+    ///
+    /// let client = CrabraceClient::new("https://crabrace.example.com")
+    ///     .with_api_key(std::env::var("CRABRACE_API_KEY").unwrap_or_default());
+    /// ```
+    pub fn with_api_key(mut self, key: impl Into<String>) -> Self {
+        self.credential = Some(Credential::ApiKey(key.into()));
+        self
+    }
+
+    /// Authenticate every request with a bearer token (an API key or a JWT),
+    /// sent as `Authorization: Bearer <token>`
+    ///
+    /// The token never appears in the client's `Debug` output.
+    pub fn with_bearer_token(mut self, token: impl Into<String>) -> Self {
+        self.credential = Some(Credential::Bearer(token.into()));
+        self
+    }
+
+    /// Answer from a snapshot file when the server cannot be reached
+    ///
+    /// Every [`get_providers`](Self::get_providers) answered by the server
+    /// (rather than the [response cache](Self::with_cache_ttl)) rewrites
+    /// the file with the whole catalog. Connection errors, timeouts and 5xx
+    /// responses (once retries are exhausted) are then answered from it,
+    /// which the `*_with_freshness` methods report.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use crabrace::CrabraceClient;
+    ///
+    /// let client = CrabraceClient::new("http://localhost:8080")
+    ///     .with_snapshot_file("/var/cache/my-cli/catalog.json");
+    /// ```
+    pub fn with_snapshot_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.fallback.snapshot = Some(path.into());
+        self
+    }
+
+    /// Answer from the catalog compiled into the crate when the server
+    /// cannot be reached (and no snapshot file is available)
+    #[cfg(feature = "embedded-data")]
+    pub fn with_embedded_fallback(mut self) -> Self {
+        self.fallback.embedded = true;
+        self
+    }
+
+    /// Run `interceptor` around every request (see [`crate::intercept`])
+    pub fn with_interceptor(mut self, interceptor: impl Interceptor + 'static) -> Self {
+        self.interceptors.push(interceptor);
+        self
+    }
+
+    /// Drop every cached response and ETag, so the next calls download
+    /// everything again
+    pub fn invalidate(&self) {
+        self.cache.clear();
+    }
+
+    /// Request carrying the client's credential
+    fn request(&self, method: Method, url: &str) -> reqwest::RequestBuilder {
+        let request = self.http_client.request(method, url);
+        match &self.credential {
+            Some(Credential::ApiKey(key)) => request.header("x-api-key", key.as_str()),
+            Some(Credential::Bearer(token)) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+
+    /// Send a request, retrying failures the retry policy allows
+    ///
+    /// Only idempotent requests may be sent this way.
+    async fn send(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response> {
+        let mut attempt = 1;
+        loop {
+            let request = request.try_clone().expect("Request bodies are buffered");
+            let result = self.execute(request, attempt).await;
+            let retries_left = attempt < self.retry.max_attempts;
+            let delay = match &result {
+                Ok(response) if retries_left && self.retry.retries_status(response.status()) => {
+                    self.retry
+                        .delay(attempt, retry::retry_after(response.headers()))
+                }
+                Err(e) if retries_left && self.retry.retries_error(e) => {
+                    self.retry.delay(attempt, None)
+                }
+                _ => return Ok(result?),
+            };
+            sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    /// Send one attempt of a request through the client's interceptors
+    async fn execute(
+        &self,
+        request: reqwest::RequestBuilder,
+        attempt: u32,
+    ) -> reqwest::Result<reqwest::Response> {
+        let mut request = request.build()?;
+        let (method, url) = (request.method().clone(), request.url().clone());
+        let sent_at = self
+            .interceptors
+            .before(&method, &url, request.headers_mut(), attempt);
+        let result = self.http_client.execute(request).await;
+        let outcome = result.as_ref().map(|response| response.status());
+        self.interceptors
+            .after(&method, &url, attempt, outcome, sent_at);
+        result
+    }
+
+    /// Status and body of a POST request with a JSON body
+    async fn post(&self, url: &str, body: &impl Serialize) -> Result<(StatusCode, Bytes)> {
+        let response = self
+            .send(self.request(Method::POST, url).json(body))
+            .await?;
+        let status = response.status();
+        Ok((status, response.bytes().await?))
+    }
+
+    /// Status and body of a GET request, from the cache when fresh or not
+    /// modified since its ETag
+    async fn fetch(&self, url: &str) -> Result<(StatusCode, Bytes)> {
+        if let Some(body) = self.cache.get(url) {
+            return Ok((StatusCode::OK, body));
+        }
+        self.revalidate(url).await
+    }
+
+    /// Status and body of a GET request, from the cache only if not
+    /// modified since its ETag
+    async fn revalidate(&self, url: &str) -> Result<(StatusCode, Bytes)> {
+        let mut etag = self.cache.etag(url);
+        loop {
+            let mut request = self.request(Method::GET, url);
+            if let Some(etag) = &etag {
+                request = request.header(IF_NONE_MATCH, etag);
+            }
+            let response = self.send(request).await?;
+            let status = response.status();
+            if status == StatusCode::NOT_MODIFIED && etag.is_some() {
+                match self.cache.revalidate(url) {
+                    Some(body) => return Ok((StatusCode::OK, body)),
+                    // Invalidated in the meantime: ask for the body again
+                    None => {
+                        etag = None;
+                        continue;
+                    }
+                }
+            }
+
+            let new_etag = etag_of(response.headers());
+            let body = response.bytes().await?;
+            if status.is_success() {
+                self.cache.insert(url, body.clone(), new_etag);
+            }
+            return Ok((status, body));
         }
     }
 
@@ -114,15 +457,349 @@ impl CrabraceClient {
     /// # }
     /// ```
     pub async fn get_providers(&self) -> Result<Vec<Provider>> {
-        let url = format!("{}/providers", self.base_url);
-        let response = self.http_client.get(&url).send().await?;
+        Ok(self.get_providers_with_freshness().await?.value)
+    }
+
+    /// Get all providers, along with where they came from
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use crabrace::CrabraceClient;
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// let client = CrabraceClient::new("http://localhost:8080").with_snapshot_file("catalog.json");
+    /// let providers = client.get_providers_with_freshness().await?;
+    /// if let Some(as_of) = providers.freshness.as_of() {
+    ///     eprintln!("Server unreachable, using the catalog as of {}", as_of);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_providers_with_freshness(&self) -> Result<Fetched<Vec<Provider>>> {
+        let url = format!("{}/v1/providers", self.base_url);
+        // Cached copies are not news for the snapshot file
+        if let Some(body) = self.cache.get(&url) {
+            return read_providers(StatusCode::OK, &body).map(Fetched::live);
+        }
+        let providers = self.fallback.providers_from(self.revalidate(&url).await)?;
+        if providers.freshness.is_live() {
+            // A snapshot that cannot be written only costs offline answers later
+            let _ = self.fallback.save_async(&providers.value).await;
+        }
+        Ok(providers)
+    }
 
+    /// Stream all providers, parsed one at a time as they arrive
+    ///
+    /// Asks the server for NDJSON, so consumers can process the first
+    /// providers, or stop reading, before the whole catalog has been
+    /// downloaded. Servers without NDJSON support answer with the usual JSON
+    /// array, which is streamed once it has been read; so are fallback
+    /// catalogs when the server cannot be reached.
+    ///
+    /// Streamed responses bypass the response cache and, whatever their
+    /// format, do not update the snapshot file.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use crabrace::CrabraceClient;
+    /// use futures_util::{pin_mut, StreamExt};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// let client = CrabraceClient::new("http://localhost:8080");
+    /// let providers = client.get_providers_stream();
+    /// pin_mut!(providers);
+    /// while let Some(provider) = providers.next().await {
+    ///     let provider = provider?;
+    ///     if provider.get_model("gpt-4o").is_some() {
+    ///         println!("Found gpt-4o at {}", provider.name);
+    ///         break;
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_providers_stream(&self) -> impl Stream<Item = Result<Provider>> + '_ {
+        futures_util::stream::once(self.open_providers_stream()).flat_map(|opened| match opened {
+            Ok(ProvidersStream::Ndjson(response)) => {
+                Either::Left(stream::ndjson(response.bytes_stream()))
+            }
+            Ok(ProvidersStream::Loaded(providers)) => {
+                let providers: Vec<_> = providers.into_iter().map(Ok).collect();
+                Either::Right(futures_util::stream::iter(providers))
+            }
+            Err(e) => Either::Right(futures_util::stream::iter(vec![Err(e)])),
+        })
+    }
+
+    /// Request the providers as NDJSON, or read them whole when the server
+    /// answers otherwise
+    async fn open_providers_stream(&self) -> Result<ProvidersStream> {
+        let url = format!("{}/v1/providers", self.base_url);
+        let request = self
+            .request(Method::GET, &url)
+            .header(ACCEPT, stream::NDJSON);
+        let result = match self.send(request).await {
+            Ok(response)
+                if response.status().is_success() && stream::is_ndjson(response.headers()) =>
+            {
+                return Ok(ProvidersStream::Ndjson(response));
+            }
+            Ok(response) => {
+                let status = response.status();
+                response
+                    .bytes()
+                    .await
+                    .map(|body| (status, body))
+                    .map_err(Into::into)
+            }
+            Err(e) => Err(e),
+        };
+        let providers = self.fallback.providers_from(result)?.value;
+        Ok(ProvidersStream::Loaded(providers))
+    }
+
+    /// Follow the catalog: yield it now, then again every time it changes
+    ///
+    /// Listens to the server's change feed and re-fetches the providers
+    /// (revalidating the cached ones by ETag) whenever a new catalog
+    /// version is announced. When the connection is lost or refused the
+    /// error is yielded and the client reconnects after a backoff following
+    /// its [`RetryPolicy`]; versions announced again after reconnecting are
+    /// not yielded twice. The stream never ends on its own: drop it to stop
+    /// watching.
+    ///
+    /// Unlike [`get_providers`](Self::get_providers), this needs the server:
+    /// there is nothing to fall back to.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use crabrace::CrabraceClient;
+    /// use futures_util::{pin_mut, StreamExt};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let client = CrabraceClient::new("http://localhost:8080");
+    /// let catalogs = client.watch();
+    /// pin_mut!(catalogs);
+    /// while let Some(catalog) = catalogs.next().await {
+    ///     match catalog {
+    ///         Ok(catalog) => println!("Catalog version {}", catalog.version),
+    ///         Err(e) => eprintln!("Catalog watch interrupted: {}", e),
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    pub fn watch(&self) -> impl Stream<Item = Result<CatalogRevision>> + '_ {
+        let state: (Watcher, Option<Events>) = (Watcher::default(), None);
+        futures_util::stream::unfold(state, move |(mut watcher, mut events)| async move {
+            loop {
+                let Some(open) = events.as_mut() else {
+                    if let Some(delay) = watcher.backoff(&self.retry) {
+                        sleep(delay).await;
+                    }
+                    match self.open_events().await {
+                        Ok(opened) => events = Some(opened),
+                        Err(e) => {
+                            watcher.disconnected();
+                            return Some((Err(e), (watcher, None)));
+                        }
+                    }
+                    continue;
+                };
+
+                let event = match open.next().await {
+                    Some(Ok(event)) => event,
+                    Some(Err(e)) if !watch::timed_out(&e) => {
+                        watcher.disconnected();
+                        return Some((Err(e), (watcher, None)));
+                    }
+                    // Closed by the server, a proxy or the request timeout
+                    _ => {
+                        watcher.disconnected();
+                        events = None;
+                        continue;
+                    }
+                };
+                let announcement = match watcher.announced(&event) {
+                    Ok(Some(announcement)) => announcement,
+                    Ok(None) => continue,
+                    Err(e) => return Some((Err(e), (watcher, events))),
+                };
+
+                let url = format!("{}/v1/providers", self.base_url);
+                let providers = match self.revalidate(&url).await {
+                    Ok((status, body)) => read_providers(status, &body),
+                    Err(e) => Err(e),
+                };
+                return match providers {
+                    Ok(providers) => {
+                        let _ = self.fallback.save_async(&providers).await;
+                        let revision = watcher.revision(announcement, providers);
+                        Some((Ok(revision), (watcher, events)))
+                    }
+                    // Reconnecting announces the version again
+                    Err(e) => {
+                        watcher.disconnected();
+                        Some((Err(e), (watcher, None)))
+                    }
+                };
+            }
+        })
+    }
+
+    /// Connect to the change feed
+    async fn open_events(&self) -> Result<Events> {
+        let url = format!("{}/v1/providers/events", self.base_url);
+        let request = self
+            .request(Method::GET, &url)
+            .header(ACCEPT, stream::EVENT_STREAM);
+        // Reconnections have their own backoff
+        let response = self.execute(request, 1).await?;
         if !response.status().is_success() {
-            anyhow::bail!("Failed to get providers: HTTP {}", response.status());
+            anyhow::bail!("Failed to watch the catalog: HTTP {}", response.status());
+        }
+        if !stream::is_event_stream(response.headers()) {
+            anyhow::bail!("Failed to watch the catalog: the server sent no event stream");
         }
+        Ok(Box::pin(stream::sse(response.bytes_stream())))
+    }
 
-        let providers: Vec<Provider> = response.json().await?;
-        Ok(providers)
+    /// Get a single provider by ID
+    ///
+    /// # Returns
+    ///
+    /// `Some(Provider)` if the provider exists, `None` if the server returned 404
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use crabrace::CrabraceClient;
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// let client = CrabraceClient::new("http://localhost:8080");
+    /// if let Some(provider) = client.get_provider("anthropic").await? {
+    ///     println!("{} has {} models", provider.name, provider.models.len());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_provider(&self, id: &str) -> Result<Option<Provider>> {
+        Ok(self.get_provider_with_freshness(id).await?.value)
+    }
+
+    /// Get a single provider by ID, along with where it came from
+    pub async fn get_provider_with_freshness(&self, id: &str) -> Result<Fetched<Option<Provider>>> {
+        let url = api_url(&self.base_url, &["v1", "providers", id]);
+        self.fallback.provider_from(id, self.fetch(&url).await)
+    }
+
+    /// Get a single model of a provider
+    ///
+    /// Model IDs may contain slashes (e.g. `deepseek-ai/DeepSeek-R1-0528`).
+    ///
+    /// # Returns
+    ///
+    /// `Some(Model)` if the model exists, `None` if the server returned 404
+    /// for the provider or the model
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use crabrace::CrabraceClient;
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// let client = CrabraceClient::new("http://localhost:8080");
+    /// if let Some(model) = client.get_model("anthropic", "claude-sonnet-4-5-20250929").await? {
+    ///     println!("{}: ${}/1M input tokens", model.name, model.cost_per_1m_in);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_model(&self, provider_id: &str, model_id: &str) -> Result<Option<Model>> {
+        Ok(self
+            .get_model_with_freshness(provider_id, model_id)
+            .await?
+            .value)
+    }
+
+    /// Get a single model of a provider, along with where it came from
+    pub async fn get_model_with_freshness(
+        &self,
+        provider_id: &str,
+        model_id: &str,
+    ) -> Result<Fetched<Option<Model>>> {
+        let url = api_url(
+            &self.base_url,
+            &["v1", "providers", provider_id, "models", model_id],
+        );
+        let result = self.fetch(&url).await;
+        self.fallback.model_from(provider_id, model_id, result)
+    }
+
+    /// Estimate what some usage of a model costs, in USD
+    ///
+    /// The server prices the usage (`POST /v1/estimate`). When it cannot be
+    /// reached and the client has a [fallback](Self::with_embedded_fallback),
+    /// the usage is priced locally against the fallback catalog instead, with
+    /// the same rules.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for unknown models and for usage the model has no
+    /// price for (e.g. `batch` on a model without batch pricing).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use crabrace::{CrabraceClient, Usage};
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// let client = CrabraceClient::new("http://localhost:8080");
+    /// let usage = Usage {
+    ///     input_tokens: 120_000,
+    ///     output_tokens: 8_000,
+    ///     ..Usage::default()
+    /// };
+    /// let estimate = client
+    ///     .estimate_cost("anthropic", "claude-sonnet-4-5-20250929", usage)
+    ///     .await?;
+    /// println!("${:.4}", estimate.total_cost);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn estimate_cost(
+        &self,
+        provider_id: &str,
+        model_id: &str,
+        usage: Usage,
+    ) -> Result<EstimateResponse> {
+        Ok(self
+            .estimate_cost_with_freshness(provider_id, model_id, usage)
+            .await?
+            .value)
+    }
+
+    /// Estimate what some usage of a model costs, along with where its
+    /// prices came from
+    pub async fn estimate_cost_with_freshness(
+        &self,
+        provider_id: &str,
+        model_id: &str,
+        usage: Usage,
+    ) -> Result<Fetched<EstimateResponse>> {
+        let url = format!("{}/v1/estimate", self.base_url);
+        let request = EstimateRequest {
+            provider_id: provider_id.to_string(),
+            model_id: model_id.to_string(),
+            usage,
+        };
+        let result = self.post(&url, &request).await;
+        self.fallback.estimate_from(&request, result)
     }
 
     /// Check if the Crabrace server is healthy
@@ -146,11 +823,113 @@ impl CrabraceClient {
     /// ```
     pub async fn health_check(&self) -> Result<bool> {
         let url = format!("{}/health", self.base_url);
-        let response = self.http_client.get(&url).send().await?;
+        let response = self.execute(self.request(Method::GET, &url), 1).await?;
         Ok(response.status().is_success())
     }
 }
 
+/// `base_url` followed by path segments, each percent-encoded so IDs with
+/// `/`, `?` or `#` stay within their segment
+fn api_url(base_url: &str, segments: &[&str]) -> String {
+    match reqwest::Url::parse(base_url) {
+        Ok(mut url) if !url.cannot_be_a_base() => {
+            url.path_segments_mut()
+                .expect("Base URLs have path segments")
+                .pop_if_empty()
+                .extend(segments);
+            url.into()
+        }
+        // Left for the request to report
+        _ => format!("{}/{}", base_url, segments.join("/")),
+    }
+}
+
+/// Deserialize a JSON response body
+fn parse<T: DeserializeOwned>(body: &[u8]) -> Result<T> {
+    Ok(serde_json::from_slice(body)?)
+}
+
+/// `ETag` of a response, if any
+fn etag_of(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(ETAG)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+}
+
+/// Providers from a `/v1/providers` response
+fn read_providers(status: StatusCode, body: &[u8]) -> Result<Vec<Provider>> {
+    if !status.is_success() {
+        anyhow::bail!("Failed to get providers: HTTP {}", status);
+    }
+
+    parse(body)
+}
+
+/// Provider from a `/v1/providers/{id}` response
+fn read_provider(id: &str, status: StatusCode, body: &[u8]) -> Result<Option<Provider>> {
+    if status == StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+
+    if !status.is_success() {
+        anyhow::bail!("Failed to get provider '{}': HTTP {}", id, status);
+    }
+
+    parse(body).map(Some)
+}
+
+/// Model from a `/v1/providers/{provider_id}/models/{model_id}` response
+fn read_model(
+    provider_id: &str,
+    model_id: &str,
+    status: StatusCode,
+    body: &[u8],
+) -> Result<Option<Model>> {
+    if status == StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+
+    if !status.is_success() {
+        anyhow::bail!(
+            "Failed to get model '{}' of provider '{}': HTTP {}",
+            model_id,
+            provider_id,
+            status
+        );
+    }
+
+    parse(body).map(Some)
+}
+
+/// Cost estimate from a `/v1/estimate` response
+fn read_estimate(
+    request: &EstimateRequest,
+    status: StatusCode,
+    body: &[u8],
+) -> Result<EstimateResponse> {
+    if !status.is_success() {
+        // The server says why it rejected the request (unknown model, unpriced usage)
+        let reason = serde_json::from_slice::<serde_json::Value>(body)
+            .ok()
+            .and_then(|error| {
+                error["error"]
+                    .as_str()
+                    .map(|reason| format!(" ({})", reason))
+            })
+            .unwrap_or_default();
+        anyhow::bail!(
+            "Failed to estimate cost of model '{}' of provider '{}': HTTP {}{}",
+            request.model_id,
+            request.provider_id,
+            status,
+            reason
+        );
+    }
+
+    parse(body)
+}
+
 impl Default for CrabraceClient {
     fn default() -> Self {
         Self::new("http://localhost:8080")
@@ -172,4 +951,447 @@ mod tests {
         let client = CrabraceClient::default();
         assert_eq!(client.base_url, "http://localhost:8080");
     }
+
+    #[tokio::test]
+    async fn test_get_provider() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/v1/providers/test")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"name":"Test","id":"test","type":"test","models":[]}"#)
+            .create_async()
+            .await;
+
+        let client = CrabraceClient::new(server.url());
+        let provider = client.get_provider("test").await.unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(provider.unwrap().id, "test");
+    }
+
+    #[test]
+    fn test_api_url() {
+        for base_url in ["http://localhost:8080", "http://localhost:8080/"] {
+            assert_eq!(
+                api_url(base_url, &["v1", "providers", "a b/c?d#e%"]),
+                "http://localhost:8080/v1/providers/a%20b%2Fc%3Fd%23e%25"
+            );
+        }
+        assert_eq!(
+            api_url("https://example.com/crabrace", &["v1", "providers", "x"]),
+            "https://example.com/crabrace/v1/providers/x"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_provider_not_found() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/v1/providers/missing")
+            .with_status(404)
+            .create_async()
+            .await;
+
+        let client = CrabraceClient::new(server.url());
+        assert!(client.get_provider("missing").await.unwrap().is_none());
+    }
+
+    fn fast_retries(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts,
+            base_delay: std::time::Duration::from_millis(1),
+            ..RetryPolicy::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retries_server_errors() {
+        let mut server = mockito::Server::new_async().await;
+        let failures = server
+            .mock("GET", "/v1/providers")
+            .with_status(503)
+            .with_header("retry-after", "0")
+            .expect(2)
+            .create_async()
+            .await;
+        let success = server
+            .mock("GET", "/v1/providers")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body("[]")
+            .create_async()
+            .await;
+
+        let client = CrabraceClient::new(server.url()).with_retry(fast_retries(3));
+        assert!(client.get_providers().await.unwrap().is_empty());
+        failures.assert_async().await;
+        success.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_max_attempts() {
+        let mut server = mockito::Server::new_async().await;
+        let failures = server
+            .mock("GET", "/v1/providers")
+            .with_status(500)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let client = CrabraceClient::new(server.url()).with_retry(fast_retries(2));
+        let error = client.get_providers().await.unwrap_err();
+        assert!(error.to_string().contains("HTTP 500"));
+        failures.assert_async().await;
+
+        // Client errors are never retried
+        let not_found = server
+            .mock("GET", "/v1/providers/missing")
+            .with_status(404)
+            .expect(1)
+            .create_async()
+            .await;
+        assert!(client.get_provider("missing").await.unwrap().is_none());
+        not_found.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_get_model() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/v1/providers/test/models/org%2Fmodel-1")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"id":"org/model-1","name":"Model 1","cost_per_1m_in":1.0,
+                "cost_per_1m_out":2.0,"context_window":8192,"default_max_tokens":1024}"#,
+            )
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/v1/providers/test/models/missing")
+            .with_status(404)
+            .create_async()
+            .await;
+
+        let client = CrabraceClient::new(server.url());
+        let model = client.get_model("test", "org/model-1").await.unwrap();
+        mock.assert_async().await;
+        assert_eq!(model.unwrap().context_window, 8192);
+        assert!(client.get_model("test", "missing").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_cached_responses() {
+        let mut server = mockito::Server::new_async().await;
+        let providers = server
+            .mock("GET", "/v1/providers")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body("[]")
+            .expect(2)
+            .create_async()
+            .await;
+        let missing = server
+            .mock("GET", "/v1/providers/missing")
+            .with_status(404)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let client = CrabraceClient::new(server.url()).with_cache_ttl(Duration::from_secs(60));
+        client.get_providers().await.unwrap();
+        client.clone().get_providers().await.unwrap();
+
+        // Errors are not cached
+        assert!(client.get_provider("missing").await.unwrap().is_none());
+        assert!(client.get_provider("missing").await.unwrap().is_none());
+        missing.assert_async().await;
+
+        client.invalidate();
+        client.get_providers().await.unwrap();
+        providers.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_conditional_requests() {
+        let mut server = mockito::Server::new_async().await;
+        let body = r#"[{"name":"Test","id":"test","type":"test","models":[]}]"#;
+        let first = server
+            .mock("GET", "/v1/providers")
+            .match_header("if-none-match", mockito::Matcher::Missing)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_header("etag", "\"v1\"")
+            .with_body(body)
+            .expect(1)
+            .create_async()
+            .await;
+        let not_modified = server
+            .mock("GET", "/v1/providers")
+            .match_header("if-none-match", "\"v1\"")
+            .with_status(304)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let client = CrabraceClient::new(server.url());
+        for _ in 0..3 {
+            let providers = client.get_providers().await.unwrap();
+            assert_eq!(providers[0].id, "test");
+        }
+        first.assert_async().await;
+        not_modified.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_credentials() {
+        let mut server = mockito::Server::new_async().await;
+        let api_key = server
+            .mock("GET", "/v1/providers")
+            .match_header("x-api-key", "key-secret")
+            .with_body("[]")
+            .create_async()
+            .await;
+        let bearer = server
+            .mock("GET", "/health")
+            .match_header("authorization", "Bearer token-secret")
+            .create_async()
+            .await;
+
+        let client = CrabraceClient::new(server.url()).with_api_key("key-secret");
+        client.get_providers().await.unwrap();
+        api_key.assert_async().await;
+        assert!(!format!("{:?}", client).contains("key-secret"));
+
+        let client = CrabraceClient::new(server.url()).with_bearer_token("token-secret");
+        assert!(client.health_check().await.unwrap());
+        bearer.assert_async().await;
+        assert!(!format!("{:?}", client).contains("token-secret"));
+    }
+
+    #[tokio::test]
+    async fn test_providers_stream() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/v1/providers")
+            .match_header("accept", "application/x-ndjson")
+            .with_header("content-type", "application/x-ndjson")
+            .with_body(concat!(
+                r#"{"name":"One","id":"one","type":"test","models":[]}"#,
+                "\n",
+                r#"{"name":"Two","id":"two","type":"test","models":[]}"#,
+                "\n",
+                "not json\n",
+            ))
+            .create_async()
+            .await;
+
+        let client = CrabraceClient::new(server.url());
+        let providers: Vec<_> = client.get_providers_stream().collect().await;
+        assert_eq!(providers.len(), 3);
+        assert_eq!(providers[1].as_ref().unwrap().id, "two");
+        assert!(providers[2].is_err());
+
+        // Stopping early
+        let mut providers = Box::pin(client.get_providers_stream());
+        let first = providers.next().await.unwrap();
+        assert_eq!(first.unwrap().id, "one");
+
+        // Servers without NDJSON support answer with a JSON array
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/v1/providers")
+            .with_header("content-type", "application/json")
+            .with_body(r#"[{"name":"One","id":"one","type":"test","models":[]}]"#)
+            .create_async()
+            .await;
+        let client = CrabraceClient::new(server.url());
+        let providers: Vec<_> = client.get_providers_stream().collect().await;
+        assert_eq!(providers.len(), 1);
+        assert_eq!(providers[0].as_ref().unwrap().id, "one");
+    }
+
+    #[tokio::test]
+    async fn test_watch() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/v1/providers/events")
+            .match_header("accept", "text/event-stream")
+            .with_header("content-type", "text/event-stream")
+            .with_body(concat!(
+                ": keep-alive\n\n",
+                "event: catalog\nid: 1\ndata: {\"version\":1,\"hash\":\"a\"}\n\n",
+                "event: catalog\nid: 1\ndata: {\"version\":1,\"hash\":\"a\"}\n\n",
+                "event: catalog\nid: 2\ndata: {\"version\":2,\"hash\":\"b\"}\n\n",
+            ))
+            .create_async()
+            .await;
+        let providers = server
+            .mock("GET", "/v1/providers")
+            .with_body(r#"[{"name":"One","id":"one","type":"test","models":[]}]"#)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let client = CrabraceClient::new(server.url()).with_cache_ttl(Duration::from_secs(60));
+        let revisions: Vec<_> = client.watch().take(2).collect().await;
+        let revisions: Vec<_> = revisions.into_iter().map(Result::unwrap).collect();
+        assert_eq!((revisions[0].version, revisions[0].hash.as_str()), (1, "a"));
+        assert_eq!((revisions[1].version, revisions[1].hash.as_str()), (2, "b"));
+        assert_eq!(revisions[1].providers[0].id, "one");
+        // Announced changes bypass the cache TTL
+        providers.assert_async().await;
+
+        // Failed connections are reported, then retried
+        let client = CrabraceClient::new("http://127.0.0.1:1").with_retry(RetryPolicy {
+            base_delay: Duration::from_millis(1),
+            ..RetryPolicy::default()
+        });
+        let errors: Vec<_> = client.watch().take(2).collect().await;
+        assert!(errors.iter().all(Result::is_err));
+    }
+
+    #[tokio::test]
+    async fn test_estimate_cost() {
+        let mut server = mockito::Server::new_async().await;
+        let usage = Usage {
+            input_tokens: 1_000_000,
+            ..Usage::default()
+        };
+        let model = Model::new("m".to_string(), "M".to_string(), 3.0, 15.0, 200_000, 4096);
+        let request = EstimateRequest {
+            provider_id: "test".to_string(),
+            model_id: "m".to_string(),
+            usage: usage.clone(),
+        };
+        let expected = models::estimate::price(&model, &request);
+        let estimate = server
+            .mock("POST", "/v1/estimate")
+            .match_body(mockito::Matcher::PartialJsonString(
+                r#"{"provider_id":"test","model_id":"m","input_tokens":1000000}"#.to_string(),
+            ))
+            .with_header("content-type", "application/json")
+            .with_body(serde_json::to_string(&expected).unwrap())
+            .create_async()
+            .await;
+        server
+            .mock("POST", "/v1/estimate")
+            .match_body(mockito::Matcher::PartialJsonString(
+                r#"{"batch":true}"#.to_string(),
+            ))
+            .with_status(400)
+            .with_body(r#"{"error":"Model 'm' has no batch pricing","code":"bad_request"}"#)
+            .create_async()
+            .await;
+
+        let client = CrabraceClient::new(server.url());
+        let result = client
+            .estimate_cost("test", "m", usage.clone())
+            .await
+            .unwrap();
+        assert_eq!(result, expected);
+        assert_eq!(result.total_cost, 3.0);
+        estimate.assert_async().await;
+
+        let batch = Usage {
+            batch: true,
+            ..usage
+        };
+        let error = client.estimate_cost("test", "m", batch).await.unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Failed to estimate cost of model 'm' of provider 'test': \
+             HTTP 400 Bad Request (Model 'm' has no batch pricing)"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_fallback() {
+        let mut server = mockito::Server::new_async().await;
+        let live = server
+            .mock("GET", "/v1/providers")
+            .with_header("content-type", "application/json")
+            .with_body(r#"[{"name":"Test","id":"test","type":"test","models":[]}]"#)
+            .create_async()
+            .await;
+
+        let snapshot =
+            std::env::temp_dir().join(format!("crabrace-client-{}.json", std::process::id()));
+        let client = CrabraceClient::new(server.url())
+            .with_retry(RetryPolicy::none())
+            .with_snapshot_file(&snapshot);
+
+        // Only answers from the server itself are recorded
+        let cached = client.clone().with_cache_ttl(Duration::from_secs(60));
+        cached.get_providers().await.unwrap();
+        std::fs::remove_file(&snapshot).unwrap();
+        cached.get_providers().await.unwrap();
+        let streamed: Vec<_> = client.get_providers_stream().collect().await;
+        assert_eq!(streamed.len(), 1);
+        assert!(!snapshot.exists());
+
+        let providers = client.get_providers_with_freshness().await.unwrap();
+        assert!(providers.freshness.is_live());
+        assert!(snapshot.exists());
+
+        live.remove_async().await;
+        server
+            .mock("GET", mockito::Matcher::Any)
+            .with_status(503)
+            .create_async()
+            .await;
+        let providers = client.get_providers_with_freshness().await.unwrap();
+        assert!(matches!(providers.freshness, Freshness::Snapshot { .. }));
+        assert_eq!(providers.value[0].id, "test");
+        let provider = client.get_provider_with_freshness("test").await.unwrap();
+        assert_eq!(provider.value.unwrap().id, "test");
+
+        // Without a fallback, the error surfaces
+        let client = CrabraceClient::new(server.url()).with_retry(RetryPolicy::none());
+        assert!(client.get_providers().await.is_err());
+        std::fs::remove_file(&snapshot).unwrap();
+    }
+
+    #[cfg(feature = "embedded-data")]
+    #[tokio::test]
+    async fn test_embedded_fallback() {
+        // Nothing listens on port 1
+        let client = CrabraceClient::new("http://127.0.0.1:1")
+            .with_retry(RetryPolicy::none())
+            .with_embedded_fallback();
+        let model = client
+            .get_model_with_freshness("anthropic", "claude-sonnet-4-5-20250929")
+            .await
+            .unwrap();
+        assert!(matches!(model.freshness, Freshness::Embedded { .. }));
+        assert!(model.value.is_some());
+        assert!(client.get_provider("google").await.unwrap().is_some());
+
+        // Estimates are priced locally, with the server's rules
+        let usage = Usage {
+            input_tokens: 1_000_000,
+            output_tokens: 1_000_000,
+            ..Usage::default()
+        };
+        let estimate = client
+            .estimate_cost_with_freshness("anthropic", "claude-sonnet-4-5-20250929", usage)
+            .await
+            .unwrap();
+        assert!(matches!(estimate.freshness, Freshness::Embedded { .. }));
+        assert_eq!(estimate.value.total_cost, 18.0);
+        let usage = Usage {
+            batch: true,
+            ..Usage::default()
+        };
+        let error = client
+            .estimate_cost("openai", "tts-1", usage)
+            .await
+            .unwrap_err();
+        assert_eq!(error.to_string(), "Model 'tts-1' has no batch pricing");
+        assert!(client
+            .estimate_cost("anthropic", "missing", Usage::default())
+            .await
+            .is_err());
+    }
 }
@@ -1,26 +1,21 @@
 use anyhow::Result;
-use axum::{
-    extract::State,
-    http::StatusCode,
-    response::{IntoResponse, Response},
-    routing::get,
-    Json, Router,
-};
-use prometheus::{Encoder, TextEncoder};
+use axum::Router;
+use axum_server::tls_rustls::RustlsConfig;
+use std::net::SocketAddr;
+use std::path::PathBuf;
 use std::sync::Arc;
-use tower_http::{
-    compression::CompressionLayer,
-    trace::{DefaultMakeSpan, TraceLayer},
-};
+use tokio::task::JoinSet;
+use tower_http::trace::{DefaultMakeSpan, TraceLayer};
 use tracing::info;
 
-use crabrace::{metrics, providers::registry::ProviderRegistry, security, Config};
-
-/// Application state shared across handlers
-#[derive(Clone)]
-struct AppState {
-    registry: Arc<ProviderRegistry>,
-}
+use crabrace::access_log::AccessLogger;
+use crabrace::api::{self, AppState};
+use crabrace::audit::AuditLog;
+use crabrace::auth::Authenticator;
+use crabrace::currency::ExchangeRates;
+use crabrace::providers::{registry::ProviderRegistry, sources, tenants::Tenants};
+use crabrace::sync::UpstreamSync;
+use crabrace::{compression, metrics, security, tls, Config};
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -48,6 +43,15 @@ async fn main() -> Result<()> {
         config.server.host, config.server.port, config.logging.level
     );
 
+    // Strict mode refuses to start on any problem in the embedded configs
+    if config.providers.strict {
+        let report = ProviderRegistry::validate_embedded();
+        if !report.is_empty() {
+            anyhow::bail!("Provider configuration validation failed: {}", report);
+        }
+        info!("Strict provider validation passed");
+    }
+
     // Initialize provider registry
     let registry = Arc::new(ProviderRegistry::new()?);
     info!(
@@ -56,132 +60,210 @@ async fn main() -> Result<()> {
         registry.model_count()
     );
 
-    let state = AppState { registry };
+    let authenticator = Authenticator::from_config(&config.security.auth)?;
+    if let Some(auth) = &authenticator {
+        info!(
+            "Authentication enabled: {} API keys, JWT {}, exempt paths {:?}",
+            auth.key_count(),
+            if auth.jwt_enabled() { "on" } else { "off" },
+            config.auth_exempt_paths()
+        );
+    }
 
-    // Build application routes
-    let mut app = Router::new()
-        .route("/providers", get(providers_handler))
-        .route("/health", get(health_handler));
+    // Providers directory, admin overlay and provider filter
+    sources::apply_local(&registry, &config)?;
+    if !config.providers.aliases.is_empty() {
+        registry.add_aliases(config.providers.aliases.clone());
+        info!("Provider aliases: {:?}", config.providers.aliases);
+    }
 
-    // Add metrics endpoint if enabled
-    if config.metrics.enabled {
-        app = app.route(&config.metrics.path, get(metrics_handler));
-        info!("Metrics endpoint enabled at {}", config.metrics.path);
+    // Tenant catalogs follow every change to the main catalog
+    let tenants = Tenants::build(&registry, &config)?;
+    if !tenants.is_empty() {
+        info!("Tenants: {:?}", tenants.names());
+        tenants.clone().spawn(registry.clone());
     }
 
-    // Add state to router
-    let mut app = app.with_state(state);
+    // Keep the catalog current with upstream Catwalk or a remote catalog
+    if let Some(sync) = UpstreamSync::from_config(&config)? {
+        info!(
+            "Upstream sync enabled: {} every {:?}",
+            sync.url(),
+            sync.interval()
+        );
+        sync.spawn(registry.clone());
+    }
 
-    // Add tracing layer
-    app = app.layer(
-        TraceLayer::new_for_http()
-            .make_span_with(DefaultMakeSpan::new().level(config.tracing_level())),
-    );
+    // Demote models on their deprecation and retirement dates
+    registry.clone().spawn_lifecycle_updates();
 
-    // Add security middleware layers
+    // Share the catalog (and admin edits) with the other instances
+    #[cfg(feature = "redis")]
+    if let Some(shared) = crabrace::providers::shared::SharedRegistry::from_config(&config)? {
+        info!("Shared registry enabled: {}", shared.url());
+        shared.spawn(registry.clone());
+    }
 
-    // CORS
-    if let Some(cors_layer) = security::build_cors_layer(&config.security.cors) {
-        app = app.layer(cors_layer);
+    if config.metrics.enabled {
+        metrics::spawn_registry_gauges(registry.clone());
+    }
+
+    // Exchange rates for costs reported in other currencies
+    let currencies = Arc::new(ExchangeRates::from_config(&config.currency)?);
+    info!("Cost currencies: {}", currencies.currencies().join(", "));
+    if let Some(url) = currencies.refresh_url() {
         info!(
-            "CORS enabled: origins={:?}",
-            config.security.cors.allowed_origins
+            "Exchange rates refreshed from {} every {}s",
+            url, config.currency.refresh_interval_seconds
         );
+        currencies.clone().spawn();
     }
 
-    // Rate limiting (temporarily disabled due to tower_governor 0.4.3 compatibility)
-    // TODO: Re-enable after upgrading to tower_governor 0.8.0+
-    if let Some(rate_limit_layer) =
-        security::build_rate_limit_layer::<()>(&config.security.rate_limit)
-    {
-        app = app.layer(rate_limit_layer);
+    let overlay_dir = config.admin.overlay_dir.as_ref().map(PathBuf::from);
+    let audit = AuditLog::from_config(&config.admin.audit)?;
+
+    let state = AppState::new(registry)
+        .with_auth(authenticator)
+        .with_overlay_dir(overlay_dir)
+        .with_audit(audit)
+        .with_tenants(tenants)
+        .with_currencies(currencies);
+
+    // One router per listener; extra listeners take their route groups off the primary
+    let mut listeners = vec![(config.socket_addr()?, api::router(&config, state.clone()))];
+    for listener in &config.server.listeners {
+        let addr: SocketAddr = listener.address.parse()?;
+        info!("Listener {} serves {:?}", addr, listener.routes);
+        listeners.push((
+            addr,
+            api::router_for(&config, state.clone(), &listener.routes),
+        ));
+    }
+
+    if config.security.rate_limit.enabled {
         info!(
-            "Rate limiting enabled: {} requests per {} seconds",
+            "Rate limiting enabled: {} requests per {} seconds per client IP",
             config.security.rate_limit.requests_per_period,
             config.security.rate_limit.period_seconds
         );
     }
 
-    // Security headers
-    let security_headers = security::build_security_headers_layers(&config.security.headers);
-    if !security_headers.is_empty() {
-        for layer in security_headers {
-            app = app.layer(layer);
-        }
+    let access_logger = AccessLogger::from_config(&config.logging.access_log)?;
+    if access_logger.is_some() {
+        info!(
+            "Access logging enabled: {}",
+            config
+                .logging
+                .access_log
+                .file
+                .as_deref()
+                .unwrap_or("stdout")
+        );
+    }
+
+    if config.security.cors.enabled {
+        info!(
+            "CORS enabled: origins={:?}",
+            config.security.cors.allowed_origins
+        );
+    }
+
+    if config.security.headers.enabled {
         info!("Security headers enabled");
     }
 
-    // Add compression if enabled
     if config.server.compression {
-        app = app.layer(CompressionLayer::new());
-        info!("HTTP compression enabled");
+        info!(
+            "HTTP compression enabled: {:?} above {} bytes, level {}",
+            config.server.compression_options.algorithms,
+            config.server.compression_options.min_size_bytes,
+            config.server.compression_options.level
+        );
     }
 
-    // Start server
-    let addr = config.socket_addr()?;
-    info!("Server listening on {}", addr);
+    // Start servers
+    let tls_config = tls::load(&config.server.tls).await?;
+    if tls_config.is_some() && config.server.tls.reload_on_change {
+        info!("TLS certificate reload on change enabled");
+    }
 
-    let listener = tokio::net::TcpListener::bind(addr).await?;
-    axum::serve(listener, app).await?;
+    let mut servers = JoinSet::new();
+    for (addr, app) in listeners {
+        let app = with_middleware(&config, app, access_logger.clone());
+        servers.spawn(serve(addr, app, tls_config.clone()));
+    }
+
+    // Any listener failing stops the process
+    while let Some(result) = servers.join_next().await {
+        result??;
+    }
 
     Ok(())
 }
 
-/// GET /providers - Returns all AI providers and their models
-async fn providers_handler(State(state): State<AppState>) -> Response {
-    // Increment Prometheus counter
-    metrics::increment_providers_requests();
-
-    match state.registry.get_all() {
-        Ok(providers) => {
-            info!(
-                "Returned {} providers with {} total models",
-                providers.len(),
-                providers.iter().map(|p| p.models.len()).sum::<usize>()
-            );
-            (StatusCode::OK, Json(providers)).into_response()
-        }
-        Err(e) => {
-            tracing::error!("Failed to get providers: {}", e);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({
-                    "error": "Failed to retrieve providers"
-                })),
-            )
-                .into_response()
-        }
+/// Wrap a listener's router in the shared middleware stack
+fn with_middleware(
+    config: &Config,
+    mut app: Router,
+    access_logger: Option<AccessLogger>,
+) -> Router {
+    // Add tracing layer
+    app = app.layer(
+        TraceLayer::new_for_http()
+            .make_span_with(DefaultMakeSpan::new().level(config.tracing_level())),
+    );
+
+    // Rate limiting (inside the access log so rejected requests are logged)
+    if let Some(rate_limit_layer) = security::build_rate_limit_layer(&config.security.rate_limit) {
+        app = app.layer(rate_limit_layer);
     }
-}
 
-/// GET /health - Health check endpoint
-async fn health_handler() -> Response {
-    (StatusCode::OK, "OK").into_response()
+    // Access log (outside the handlers so latency covers the whole stack)
+    if let Some(access_logger) = access_logger {
+        app = access_logger.layer(app);
+    }
+
+    // Add security middleware layers
+
+    // CORS
+    if let Some(cors_layer) = security::build_cors_layer(&config.security.cors) {
+        app = app.layer(cors_layer);
+    }
+
+    // Security headers
+    for layer in security::build_security_headers_layers(&config.security.headers) {
+        app = app.layer(layer);
+    }
+
+    // Add compression if enabled
+    if let Some(compression_layer) = compression::build_compression_layer(
+        config.server.compression,
+        &config.server.compression_options,
+    ) {
+        app = app.layer(compression_layer);
+    }
+
+    app
 }
 
-/// GET /metrics - Prometheus metrics endpoint
-async fn metrics_handler() -> Response {
-    let encoder = TextEncoder::new();
-    let metric_families = prometheus::gather();
-    let mut buffer = Vec::new();
-
-    match encoder.encode(&metric_families, &mut buffer) {
-        Ok(_) => (
-            StatusCode::OK,
-            [(
-                axum::http::header::CONTENT_TYPE,
-                "text/plain; version=0.0.4",
-            )],
-            buffer,
+/// Serve a router on one address, over TLS when configured
+async fn serve(addr: SocketAddr, app: Router, tls_config: Option<RustlsConfig>) -> Result<()> {
+    if let Some(tls_config) = tls_config {
+        info!("Server listening on https://{}", addr);
+
+        axum_server::bind_rustls(addr, tls_config)
+            .serve(app.into_make_service_with_connect_info::<SocketAddr>())
+            .await?;
+    } else {
+        info!("Server listening on {}", addr);
+
+        let listener = tokio::net::TcpListener::bind(addr).await?;
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<SocketAddr>(),
         )
-            .into_response(),
-        Err(e) => {
-            tracing::error!("Failed to encode metrics: {}", e);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "Failed to encode metrics",
-            )
-                .into_response()
-        }
+        .await?;
     }
+
+    Ok(())
 }
@@ -2,48 +2,222 @@
 //!
 //! This module defines and exports Prometheus metrics used throughout the application.
 
+use axum::extract::{MatchedPath, Request};
+use axum::middleware::Next;
+use axum::response::Response;
 use once_cell::sync::Lazy;
-use prometheus::{register_int_counter, IntCounter};
+use prometheus::{
+    register_gauge, register_histogram_vec, register_int_counter_vec, register_int_gauge,
+    register_int_gauge_vec, Gauge, HistogramVec, IntCounterVec, IntGauge, IntGaugeVec,
+};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-/// Total number of requests to the /providers endpoint
-pub static PROVIDERS_REQUESTS_TOTAL: Lazy<IntCounter> = Lazy::new(|| {
-    register_int_counter!(
-        "crabrace_providers_requests_total",
-        "Total number of requests to the providers endpoint"
+use crate::providers::registry::ProviderRegistry;
+
+/// `path` label used for requests that did not match any route
+pub const UNMATCHED_PATH: &str = "unmatched";
+
+/// Total number of HTTP requests, by route, method and status code
+pub static HTTP_REQUESTS_TOTAL: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "crabrace_http_requests_total",
+        "Total number of HTTP requests",
+        &["path", "method", "status"]
+    )
+    .expect("Failed to register http_requests_total counter")
+});
+
+/// HTTP request latency in seconds, by route and method
+pub static HTTP_REQUEST_DURATION_SECONDS: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "crabrace_http_request_duration_seconds",
+        "HTTP request latency in seconds",
+        &["path", "method"],
+        vec![0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5]
+    )
+    .expect("Failed to register http_request_duration_seconds histogram")
+});
+
+/// Number of providers in the catalog being served
+pub static PROVIDERS_LOADED: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "crabrace_providers_loaded",
+        "Number of providers in the catalog being served"
+    )
+    .expect("Failed to register providers_loaded gauge")
+});
+
+/// Number of models per provider in the catalog being served
+pub static MODELS_LOADED: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "crabrace_models_loaded",
+        "Number of models per provider in the catalog being served",
+        &["provider"]
+    )
+    .expect("Failed to register models_loaded gauge")
+});
+
+/// Unix time the catalog being served was loaded or last changed
+pub static REGISTRY_VERSION_TIMESTAMP: Lazy<Gauge> = Lazy::new(|| {
+    register_gauge!(
+        "crabrace_registry_version_timestamp",
+        "Unix time the catalog being served was loaded or last changed"
+    )
+    .expect("Failed to register registry_version_timestamp gauge")
+});
+
+/// Embedded provider configurations that failed to parse, by provider
+pub static PROVIDER_LOAD_FAILURES_TOTAL: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "crabrace_provider_load_failures_total",
+        "Embedded provider configurations that failed to parse",
+        &["provider"]
     )
-    .expect("Failed to register providers_requests_total counter")
+    .expect("Failed to register provider_load_failures_total counter")
 });
 
-/// Increment the providers request counter
+/// Set the registry gauges from the catalog being served
+pub fn observe_registry(registry: &ProviderRegistry) {
+    let providers = registry.providers();
+    PROVIDERS_LOADED.set(providers.len() as i64);
+    // Reset so removed providers do not keep reporting their last count
+    MODELS_LOADED.reset();
+    for provider in providers.iter() {
+        MODELS_LOADED
+            .with_label_values(&[&provider.id])
+            .set(provider.models.len() as i64);
+    }
+    REGISTRY_VERSION_TIMESTAMP.set(registry.loaded_at().timestamp_millis() as f64 / 1000.0);
+}
+
+/// Export the registry gauges and keep them current
+///
+/// Counts the load failures of the initial load, then updates the gauges
+/// on every catalog change (see [`ProviderRegistry::subscribe`]).
+pub fn spawn_registry_gauges(registry: Arc<ProviderRegistry>) {
+    for failure in registry.load_failures() {
+        PROVIDER_LOAD_FAILURES_TOTAL
+            .with_label_values(&[&failure.provider])
+            .inc();
+    }
+
+    let mut updates = registry.subscribe();
+    observe_registry(&registry);
+    tokio::spawn(async move {
+        while updates.changed().await.is_ok() {
+            observe_registry(&registry);
+        }
+    });
+}
+
+/// Record one completed HTTP request
 #[inline]
-pub fn increment_providers_requests() {
-    PROVIDERS_REQUESTS_TOTAL.inc();
+pub fn observe_http_request(path: &str, method: &str, status: u16, duration: Duration) {
+    HTTP_REQUESTS_TOTAL
+        .with_label_values(&[path, method, &status.to_string()])
+        .inc();
+    HTTP_REQUEST_DURATION_SECONDS
+        .with_label_values(&[path, method])
+        .observe(duration.as_secs_f64());
+}
+
+/// Middleware recording request counts and latencies
+///
+/// Requests are labeled with the matched route template (e.g.
+/// `/v1/providers/:id`) rather than the raw path, keeping label cardinality
+/// bounded.
+pub async fn track_http_metrics(request: Request, next: Next) -> Response {
+    let start = Instant::now();
+    let path = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| UNMATCHED_PATH.to_string());
+    let method = request.method().to_string();
+
+    let response = next.run(request).await;
+
+    observe_http_request(&path, &method, response.status().as_u16(), start.elapsed());
+    response
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::api::test_support::get;
+
+    fn request_count(path: &str, status: &str) -> u64 {
+        HTTP_REQUESTS_TOTAL
+            .with_label_values(&[path, "GET", status])
+            .get()
+    }
 
     #[test]
-    fn test_metrics_registration() {
-        // Just accessing the lazy static will register the metric
-        let initial = PROVIDERS_REQUESTS_TOTAL.get();
+    fn test_observe_http_request() {
+        let path = "/test/observe";
+        let initial = request_count(path, "200");
+        let initial_samples = HTTP_REQUEST_DURATION_SECONDS
+            .with_label_values(&[path, "GET"])
+            .get_sample_count();
 
-        // Increment
-        increment_providers_requests();
+        observe_http_request(path, "GET", 200, Duration::from_millis(3));
 
-        // Verify increment
-        assert_eq!(PROVIDERS_REQUESTS_TOTAL.get(), initial + 1);
+        assert_eq!(request_count(path, "200"), initial + 1);
+        assert_eq!(
+            HTTP_REQUEST_DURATION_SECONDS
+                .with_label_values(&[path, "GET"])
+                .get_sample_count(),
+            initial_samples + 1
+        );
     }
 
-    #[test]
-    fn test_multiple_increments() {
-        let initial = PROVIDERS_REQUESTS_TOTAL.get();
+    #[tokio::test]
+    async fn test_registry_gauges() {
+        let registry = Arc::new(ProviderRegistry::new().unwrap());
+        spawn_registry_gauges(registry.clone());
+        assert_eq!(PROVIDERS_LOADED.get(), registry.count() as i64);
+        let openai = registry.get_by_id("openai").unwrap().unwrap();
+        assert_eq!(
+            MODELS_LOADED.with_label_values(&["openai"]).get(),
+            openai.models.len() as i64
+        );
+        assert!(REGISTRY_VERSION_TIMESTAMP.get() > 0.0);
 
-        for _ in 0..5 {
-            increment_providers_requests();
+        // Gauges follow catalog changes
+        registry.remove_provider("openai").unwrap();
+        for _ in 0..100 {
+            if PROVIDERS_LOADED.get() == registry.count() as i64 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
         }
+        assert_eq!(PROVIDERS_LOADED.get(), registry.count() as i64);
+        let exported = prometheus::gather()
+            .into_iter()
+            .find(|family| family.get_name() == "crabrace_models_loaded")
+            .unwrap();
+        assert!(exported
+            .get_metric()
+            .iter()
+            .all(|m| m.get_label()[0].get_value() != "openai"));
+    }
+
+    #[tokio::test]
+    async fn test_middleware_uses_route_template() {
+        let initial = request_count("/v1/providers/:id", "404");
+
+        get("/v1/providers/definitely-missing").await;
+
+        assert!(request_count("/v1/providers/:id", "404") > initial);
+    }
+
+    #[tokio::test]
+    async fn test_middleware_unmatched_route() {
+        let initial = request_count(UNMATCHED_PATH, "404");
+
+        get("/no/such/route").await;
 
-        assert_eq!(PROVIDERS_REQUESTS_TOTAL.get(), initial + 5);
+        assert!(request_count(UNMATCHED_PATH, "404") > initial);
     }
 }
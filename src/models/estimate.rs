@@ -0,0 +1,216 @@
+//! Cost estimates
+//!
+//! The request and response bodies of `POST /v1/estimate`, and the pricing
+//! the server applies to them. Clients price requests locally with the same
+//! code when the server cannot be reached.
+
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::provider::{CostUsage, Model};
+
+/// Currency of every price in the catalog
+pub const BASE_CURRENCY: &str = "USD";
+
+/// Usage to price: tokens, images, audio and flat fees
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct Usage {
+    /// Uncached input tokens
+    #[serde(default)]
+    pub input_tokens: u64,
+
+    /// Output tokens, reasoning excluded
+    #[serde(default)]
+    pub output_tokens: u64,
+
+    /// Raw prompt text, tokenized with the model's tokenizer and added to `input_tokens`
+    #[serde(default)]
+    pub input_text: Option<String>,
+
+    /// Raw completion text, tokenized with the model's tokenizer and added to `output_tokens`
+    #[serde(default)]
+    pub output_text: Option<String>,
+
+    /// Reasoning (thinking) tokens
+    #[serde(default)]
+    pub reasoning_tokens: u64,
+
+    /// Input tokens served from the prompt cache
+    #[serde(default)]
+    pub cached_input_tokens: u64,
+
+    /// Generated images (image models)
+    #[serde(default)]
+    pub images: u64,
+
+    /// Size of the generated images (the model's first price tier when omitted)
+    #[serde(default)]
+    pub image_size: Option<String>,
+
+    /// Quality tier of the generated images
+    #[serde(default)]
+    pub image_quality: Option<String>,
+
+    /// Minutes of audio transcribed (speech-to-text models)
+    #[serde(default)]
+    pub audio_minutes: f64,
+
+    /// Characters synthesized (text-to-speech models)
+    #[serde(default)]
+    pub characters: u64,
+
+    /// Requests made, for models charging a flat fee per request
+    #[serde(default)]
+    pub requests: u64,
+
+    /// Server-side tool calls (e.g. web searches), for models charging per call
+    #[serde(default)]
+    pub tool_calls: u64,
+
+    /// Price tokens at the model's batch API rates
+    #[serde(default)]
+    pub batch: bool,
+}
+
+/// Usage to price for one model
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct EstimateRequest {
+    /// Provider offering the model
+    pub provider_id: String,
+
+    /// Model to price
+    pub model_id: String,
+
+    #[serde(flatten)]
+    pub usage: Usage,
+}
+
+/// Cost breakdown for one estimate request (amounts in `currency`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct EstimateResponse {
+    pub provider_id: String,
+    pub model_id: String,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub reasoning_tokens: u64,
+    pub cached_input_tokens: u64,
+    pub images: u64,
+    pub audio_minutes: f64,
+    pub characters: u64,
+    pub requests: u64,
+    pub tool_calls: u64,
+    pub batch: bool,
+    pub input_cost: f64,
+    pub output_cost: f64,
+    pub cached_input_cost: f64,
+    pub reasoning_cost: f64,
+    pub image_cost: f64,
+    pub audio_cost: f64,
+    pub surcharge_cost: f64,
+    pub total_cost: f64,
+    pub currency: String,
+}
+
+/// Reject usage the model has no price for
+///
+/// Unpriced images, audio, characters or batch tokens would otherwise be
+/// silently priced at nothing by [`price`].
+pub fn validate(model: &Model, request: &EstimateRequest) -> Result<()> {
+    let usage = &request.usage;
+    if token_counts(model, usage).is_none() {
+        bail!("Token counts are too large");
+    }
+    if usage.images > 0
+        && model
+            .image_price(usage.image_size.as_deref(), usage.image_quality.as_deref())
+            .is_none()
+    {
+        bail!(
+            "Model '{}' has no image price for size {} and quality {}",
+            request.model_id,
+            usage.image_size.as_deref().unwrap_or("(any)"),
+            usage.image_quality.as_deref().unwrap_or("(any)")
+        );
+    }
+    if !usage.audio_minutes.is_finite() || usage.audio_minutes < 0.0 {
+        bail!("audio_minutes must be a non-negative number");
+    }
+    if usage.audio_minutes > 0.0 && model.cost_per_audio_minute.is_none() {
+        bail!("Model '{}' has no price per audio minute", request.model_id);
+    }
+    if usage.batch && model.cost_per_1m_in_batch.is_none() && model.cost_per_1m_out_batch.is_none()
+    {
+        bail!("Model '{}' has no batch pricing", request.model_id);
+    }
+    if usage.characters > 0 && model.cost_per_1m_characters.is_none() {
+        bail!("Model '{}' has no price per character", request.model_id);
+    }
+    Ok(())
+}
+
+/// Input and output tokens of a usage, given as counts and as text (`None`
+/// when they overflow)
+fn token_counts(model: &Model, usage: &Usage) -> Option<(u64, u64)> {
+    let count = |text: &Option<String>| text.as_deref().map_or(0, |t| model.estimate_tokens(t));
+    Some((
+        usage.input_tokens.checked_add(count(&usage.input_text))?,
+        usage.output_tokens.checked_add(count(&usage.output_text))?,
+    ))
+}
+
+/// Price a request against a known model using [`Model::calculate_cost`],
+/// [`Model::calculate_image_cost`] and [`Model::calculate_audio_cost`]
+///
+/// Text is counted with [`Model::estimate_tokens`]. Images and audio the
+/// model has no price for cost nothing, and token counts too large to add
+/// up saturate; [`validate`] rejects them.
+pub fn price(model: &Model, request: &EstimateRequest) -> EstimateResponse {
+    let usage = &request.usage;
+    let (input_tokens, output_tokens) = token_counts(model, usage).unwrap_or((u64::MAX, u64::MAX));
+    let tokens = model.calculate_cost(&CostUsage {
+        reasoning_tokens: usage.reasoning_tokens,
+        requests: usage.requests,
+        tool_calls: usage.tool_calls,
+        batch: usage.batch,
+        ..CostUsage::tokens(input_tokens, output_tokens)
+    });
+    let cached = model.calculate_cost(&CostUsage {
+        input_tokens: usage.cached_input_tokens,
+        use_cache: true,
+        batch: usage.batch,
+        ..CostUsage::default()
+    });
+    let image_cost = model
+        .calculate_image_cost(
+            usage.images,
+            usage.image_size.as_deref(),
+            usage.image_quality.as_deref(),
+        )
+        .unwrap_or(0.0);
+    let audio_cost = model.calculate_audio_cost(usage.audio_minutes, usage.characters);
+
+    EstimateResponse {
+        provider_id: request.provider_id.clone(),
+        model_id: request.model_id.clone(),
+        input_tokens,
+        output_tokens,
+        reasoning_tokens: usage.reasoning_tokens,
+        cached_input_tokens: usage.cached_input_tokens,
+        images: usage.images,
+        audio_minutes: usage.audio_minutes,
+        characters: usage.characters,
+        requests: usage.requests,
+        tool_calls: usage.tool_calls,
+        batch: usage.batch,
+        input_cost: tokens.input,
+        output_cost: tokens.output,
+        cached_input_cost: cached.total,
+        reasoning_cost: tokens.reasoning,
+        image_cost,
+        audio_cost,
+        surcharge_cost: tokens.surcharge,
+        total_cost: tokens.total + cached.total + image_cost + audio_cost,
+        currency: BASE_CURRENCY.to_string(),
+    }
+}
@@ -1 +1,3 @@
+pub mod estimate;
 pub mod provider;
+mod tokens;
@@ -1,8 +1,12 @@
+use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use utoipa::ToSchema;
 
 /// Represents an AI inference provider (e.g., Anthropic, OpenAI, etc.)
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
 pub struct Provider {
     /// Display name of the provider (e.g., "Anthropic", "OpenAI")
     pub name: String,
@@ -34,13 +38,331 @@ pub struct Provider {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub default_headers: Option<HashMap<String, String>>,
 
+    /// Rate limits shared by every model, by account tier; models may list their own
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub limits: Vec<RateLimits>,
+
+    /// Regions every model is served from (e.g. "eu-west-1"); models may list their own
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub regions: Vec<String>,
+
+    /// Curation tags applying to every model (e.g. "local", "aggregator")
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+
     /// List of models available from this provider
     #[serde(default)]
     pub models: Vec<Model>,
 }
 
+/// Kind of content a model accepts or produces
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, ToSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Modality {
+    Text,
+    Image,
+    Audio,
+    Video,
+    Pdf,
+}
+
+impl Modality {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Text => "text",
+            Self::Image => "image",
+            Self::Audio => "audio",
+            Self::Video => "video",
+            Self::Pdf => "pdf",
+        }
+    }
+
+    /// Whether the modality counts as an attachment (see [`Model::supports_attachments`])
+    pub fn is_attachment(self) -> bool {
+        matches!(self, Self::Image | Self::Pdf)
+    }
+
+    /// Input modalities implied by the `supports_attachments` flag of configs without modalities
+    pub fn legacy_inputs(supports_attachments: bool) -> Vec<Self> {
+        if supports_attachments {
+            vec![Self::Text, Self::Image]
+        } else {
+            vec![Self::Text]
+        }
+    }
+}
+
+impl fmt::Display for Modality {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Modality {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "text" => Ok(Self::Text),
+            "image" => Ok(Self::Image),
+            "audio" => Ok(Self::Audio),
+            "video" => Ok(Self::Video),
+            "pdf" => Ok(Self::Pdf),
+            _ => Err(format!(
+                "Unknown modality '{}' (expected text, image, audio, video or pdf)",
+                s
+            )),
+        }
+    }
+}
+
+/// What a model is for
+///
+/// Listings describe chat models unless another kind is asked for, so
+/// Catwalk clients never see embedding or media models.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    ToSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum ModelKind {
+    #[default]
+    Chat,
+    Embedding,
+    Rerank,
+    Image,
+    Audio,
+}
+
+impl ModelKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Chat => "chat",
+            Self::Embedding => "embedding",
+            Self::Rerank => "rerank",
+            Self::Image => "image",
+            Self::Audio => "audio",
+        }
+    }
+
+    /// Whether models of this kind return vectors or scores rather than content
+    pub fn has_no_output(self) -> bool {
+        matches!(self, Self::Embedding | Self::Rerank)
+    }
+}
+
+impl fmt::Display for ModelKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ModelKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "chat" => Ok(Self::Chat),
+            "embedding" => Ok(Self::Embedding),
+            "rerank" => Ok(Self::Rerank),
+            "image" => Ok(Self::Image),
+            "audio" => Ok(Self::Audio),
+            _ => Err(format!(
+                "Unknown model kind '{}' (expected chat, embedding, rerank, image or audio)",
+                s
+            )),
+        }
+    }
+}
+
+/// Where a model is in its provider's lifecycle, derived from its dates
+///
+/// Deprecated and retired models are left out of listings unless asked for.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    ToSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Lifecycle {
+    #[default]
+    Active,
+    Deprecated,
+    Retired,
+}
+
+impl Lifecycle {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Active => "active",
+            Self::Deprecated => "deprecated",
+            Self::Retired => "retired",
+        }
+    }
+}
+
+impl fmt::Display for Lifecycle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Cost of token usage by kind (all amounts in USD)
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct CostBreakdown {
+    /// Uncached input tokens
+    pub input: f64,
+
+    /// Output tokens, reasoning excluded
+    pub output: f64,
+
+    /// Input tokens served from the prompt cache
+    pub cached: f64,
+
+    /// Reasoning (thinking) tokens
+    pub reasoning: f64,
+
+    /// Per-request and per-tool-call fees
+    pub surcharge: f64,
+
+    /// Sum of the above
+    pub total: f64,
+}
+
+impl CostBreakdown {
+    fn new(input: f64, output: f64, cached: f64, reasoning: f64, surcharge: f64) -> Self {
+        Self {
+            input,
+            output,
+            cached,
+            reasoning,
+            surcharge,
+            total: input + output + cached + reasoning + surcharge,
+        }
+    }
+
+    /// Total cost (USD)
+    pub fn total(&self) -> f64 {
+        self.total
+    }
+}
+
+/// Usage priced by [`Model::calculate_cost`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CostUsage {
+    pub input_tokens: u64,
+
+    /// Output tokens, reasoning excluded
+    pub output_tokens: u64,
+
+    /// Reasoning (thinking) tokens
+    pub reasoning_tokens: u64,
+
+    /// Requests charged the model's `cost_per_request`
+    pub requests: u64,
+
+    /// Tool calls charged the model's `cost_per_tool_call`
+    pub tool_calls: u64,
+
+    /// Price tokens at the cached rates, when the model has them
+    pub use_cache: bool,
+
+    /// Price tokens at the batch rates, when the model has them
+    pub batch: bool,
+}
+
+impl CostUsage {
+    /// Input and output tokens at realtime, uncached prices
+    pub fn tokens(input_tokens: u64, output_tokens: u64) -> Self {
+        Self {
+            input_tokens,
+            output_tokens,
+            ..Self::default()
+        }
+    }
+}
+
+/// Rate limits for an account tier
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct RateLimits {
+    /// Account tier (e.g. "tier-1"); omitted when the provider has a single tier
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tier: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requests_per_minute: Option<u64>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tokens_per_minute: Option<u64>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tokens_per_day: Option<u64>,
+}
+
+impl RateLimits {
+    /// Whether the limits apply to a requested tier (`None` matches any)
+    pub fn matches(&self, tier: Option<&str>) -> bool {
+        tier.map_or(true, |tier| {
+            self.tier
+                .as_deref()
+                .is_some_and(|t| t.eq_ignore_ascii_case(tier))
+        })
+    }
+}
+
+/// Price of one generated image at a size and quality tier
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct ImagePrice {
+    /// Image size (e.g. "1024x1024")
+    pub size: String,
+
+    /// Quality tier (e.g. "low", "high"); omitted when the model has a single tier
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quality: Option<String>,
+
+    /// Cost per image (USD)
+    pub cost: f64,
+}
+
+impl ImagePrice {
+    /// Whether the tier matches a requested size and quality (`None` matches any)
+    pub fn matches(&self, size: Option<&str>, quality: Option<&str>) -> bool {
+        size.map_or(true, |size| self.size.eq_ignore_ascii_case(size))
+            && quality.map_or(true, |quality| {
+                self.quality
+                    .as_deref()
+                    .is_some_and(|q| q.eq_ignore_ascii_case(quality))
+            })
+    }
+}
+
 /// Represents an AI model with its capabilities and pricing
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+///
+/// Deserialized through [`ModelDef`] so configurations written before
+/// modalities existed still load (see [`Modality::legacy_inputs`]).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+#[serde(from = "ModelDef")]
 pub struct Model {
     /// Unique model identifier (e.g., "claude-sonnet-4-5-20250929")
     pub id: String,
@@ -48,7 +370,52 @@ pub struct Model {
     /// Human-readable model name (e.g., "Claude Sonnet 4.5")
     pub name: String,
 
-    /// Cost per 1 million input tokens (USD)
+    /// What the model is for (chat unless stated otherwise)
+    #[serde(default)]
+    pub model_kind: ModelKind,
+
+    /// Product line the model belongs to across snapshots and generations
+    /// (e.g. "claude-sonnet", "gpt-4o")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub family: Option<String>,
+
+    /// ID of the model this one succeeds, within the same provider
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous_model_id: Option<String>,
+
+    /// ID of the reference model this one is a serving variant of, within the
+    /// same provider
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_model_id: Option<String>,
+
+    /// How this variant is served differently from its base model
+    /// (e.g. "speculative" for speculative decoding)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variant: Option<String>,
+
+    /// Precision the weights are served at (e.g. "fp8", "int4", "q4_K_M")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quantization: Option<String>,
+
+    /// Whether the weights are published and may be redistributed (Llama, Qwen, DeepSeek...)
+    #[serde(default)]
+    pub open_weights: bool,
+
+    /// License the weights are published under, as a Hugging Face license tag
+    /// (e.g. "apache-2.0", "llama3.3")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>,
+
+    /// Curation tags (e.g. "frontier", "cheap", "coding", "long-context")
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+
+    /// Published benchmark scores as percentages, by benchmark
+    /// (e.g. "mmlu_pro", "swe_bench_verified", "gpqa_diamond")
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub benchmarks: HashMap<String, f64>,
+
+    /// Cost per 1 million input tokens (USD); the only price of embedding models
     pub cost_per_1m_in: f64,
 
     /// Cost per 1 million output tokens (USD)
@@ -62,12 +429,87 @@ pub struct Model {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub cost_per_1m_out_cached: Option<f64>,
 
+    /// Cost per 1 million input tokens submitted through the batch API (USD)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost_per_1m_in_batch: Option<f64>,
+
+    /// Cost per 1 million output tokens generated through the batch API (USD)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost_per_1m_out_batch: Option<f64>,
+
+    /// Cost per 1 million reasoning (thinking) tokens (USD), when billed
+    /// differently from output tokens
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost_per_1m_reasoning: Option<f64>,
+
+    /// Cost per generated image, by size and quality tier (image models)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cost_per_image: Vec<ImagePrice>,
+
+    /// Cost per minute of input audio (USD) - for speech-to-text models
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost_per_audio_minute: Option<f64>,
+
+    /// Cost per 1 million input characters (USD) - for text-to-speech models
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost_per_1m_characters: Option<f64>,
+
+    /// Flat fee per request (USD), on top of token costs - for grounded or
+    /// search-enabled models
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost_per_request: Option<f64>,
+
+    /// Fee per server-side tool call, such as a web search (USD)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost_per_tool_call: Option<f64>,
+
     /// Maximum context window size in tokens
     pub context_window: u64,
 
-    /// Default maximum output tokens
+    /// Suggested maximum output tokens for a request
     pub default_max_tokens: u64,
 
+    /// Largest number of output tokens the provider accepts in a request
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_output_tokens: Option<u64>,
+
+    /// Size of the vectors returned by an embedding model
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dimensions: Option<u64>,
+
+    /// Largest number of tokens accepted in a single input (embedding and rerank models)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_input_tokens: Option<u64>,
+
+    /// Tokenizer the model counts tokens with: a tiktoken encoding
+    /// ("o200k_base", "cl100k_base") or a vendor tokenizer ("claude", "llama3")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tokenizer: Option<String>,
+
+    /// Voices a text-to-speech model can speak with
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub voices: Vec<String>,
+
+    /// Languages an audio model understands or speaks (BCP 47 tags, e.g. "en-US")
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub languages: Vec<String>,
+
+    /// Rate limits by account tier, when they differ from the provider's
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub limits: Vec<RateLimits>,
+
+    /// Regions the model is served from, when they differ from the provider's
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub regions: Vec<String>,
+
+    /// Median time to first token in milliseconds, from published or measured data
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub median_ttft_ms: Option<u64>,
+
+    /// Median output speed in tokens per second, from published or measured data
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub median_tokens_per_second: Option<f64>,
+
     /// Whether the model supports extended thinking/reasoning
     #[serde(default)]
     pub can_reason: bool,
@@ -80,9 +522,270 @@ pub struct Model {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub default_reasoning_effort: Option<String>,
 
-    /// Whether the model supports image/attachment inputs
+    /// Whether the model accepts image or document inputs
+    ///
+    /// Kept for Catwalk clients; derived from `input_modalities` when a
+    /// configuration omits it.
     #[serde(default)]
     pub supports_attachments: bool,
+
+    /// Whether the model supports tool (function) calling
+    #[serde(default)]
+    pub supports_tools: bool,
+
+    /// Whether the model can request several tool calls in one response
+    #[serde(default)]
+    pub supports_parallel_tool_calls: bool,
+
+    /// Whether the provider can constrain responses to a JSON schema
+    #[serde(default)]
+    pub supports_structured_output: bool,
+
+    /// Whether responses can be streamed as they are generated
+    #[serde(default)]
+    pub supports_streaming: bool,
+
+    /// Kinds of content the model accepts
+    pub input_modalities: Vec<Modality>,
+
+    /// Kinds of content the model produces
+    pub output_modalities: Vec<Modality>,
+
+    /// Date the model's training data ends (the first of the month when
+    /// the provider only publishes a month)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>, format = Date)]
+    pub knowledge_cutoff: Option<NaiveDate>,
+
+    /// Date the provider made the model generally available
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>, format = Date)]
+    pub released_at: Option<NaiveDate>,
+
+    /// Whether the provider has deprecated the model
+    #[serde(default)]
+    pub deprecated: bool,
+
+    /// Date the provider deprecated (or will deprecate) the model
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>, format = Date)]
+    pub deprecated_at: Option<NaiveDate>,
+
+    /// Date the model is (or was) retired by the provider
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>, format = Date)]
+    pub retires_at: Option<NaiveDate>,
+
+    /// Same as `retires_at`; kept for older clients
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>, format = Date)]
+    pub deprecation_date: Option<NaiveDate>,
+
+    /// Lifecycle status as of the day the catalog was last refreshed
+    ///
+    /// Derived from `deprecated` and the dates above (see
+    /// [`Model::lifecycle_on`]); the registry re-derives it on every change
+    /// and hourly, so values in provider files are overridden.
+    #[serde(default)]
+    pub lifecycle: Lifecycle,
+
+    /// ID of the model the provider recommends instead
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replacement_model_id: Option<String>,
+}
+
+/// Wire format of [`Model`], where modalities and `supports_attachments` are optional,
+/// and the retirement date may be given under either name
+#[derive(Deserialize)]
+struct ModelDef {
+    id: String,
+    name: String,
+    #[serde(default)]
+    model_kind: ModelKind,
+    #[serde(default)]
+    family: Option<String>,
+    #[serde(default)]
+    previous_model_id: Option<String>,
+    #[serde(default)]
+    base_model_id: Option<String>,
+    #[serde(default)]
+    variant: Option<String>,
+    #[serde(default)]
+    quantization: Option<String>,
+    #[serde(default)]
+    open_weights: bool,
+    #[serde(default)]
+    license: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    benchmarks: HashMap<String, f64>,
+    cost_per_1m_in: f64,
+    cost_per_1m_out: f64,
+    #[serde(default)]
+    cost_per_1m_in_cached: Option<f64>,
+    #[serde(default)]
+    cost_per_1m_out_cached: Option<f64>,
+    #[serde(default)]
+    cost_per_1m_in_batch: Option<f64>,
+    #[serde(default)]
+    cost_per_1m_out_batch: Option<f64>,
+    #[serde(default)]
+    cost_per_1m_reasoning: Option<f64>,
+    #[serde(default)]
+    cost_per_image: Vec<ImagePrice>,
+    #[serde(default)]
+    cost_per_audio_minute: Option<f64>,
+    #[serde(default)]
+    cost_per_1m_characters: Option<f64>,
+    #[serde(default)]
+    cost_per_request: Option<f64>,
+    #[serde(default)]
+    cost_per_tool_call: Option<f64>,
+    context_window: u64,
+    default_max_tokens: u64,
+    #[serde(default)]
+    max_output_tokens: Option<u64>,
+    #[serde(default)]
+    dimensions: Option<u64>,
+    #[serde(default)]
+    max_input_tokens: Option<u64>,
+    #[serde(default)]
+    tokenizer: Option<String>,
+    #[serde(default)]
+    voices: Vec<String>,
+    #[serde(default)]
+    languages: Vec<String>,
+    #[serde(default)]
+    limits: Vec<RateLimits>,
+    #[serde(default)]
+    regions: Vec<String>,
+    #[serde(default)]
+    median_ttft_ms: Option<u64>,
+    #[serde(default)]
+    median_tokens_per_second: Option<f64>,
+    #[serde(default)]
+    can_reason: bool,
+    #[serde(default)]
+    has_reasoning_efforts: bool,
+    #[serde(default)]
+    default_reasoning_effort: Option<String>,
+    #[serde(default)]
+    supports_attachments: Option<bool>,
+    #[serde(default)]
+    supports_tools: bool,
+    #[serde(default)]
+    supports_parallel_tool_calls: bool,
+    #[serde(default)]
+    supports_structured_output: bool,
+    #[serde(default)]
+    supports_streaming: bool,
+    #[serde(default)]
+    input_modalities: Option<Vec<Modality>>,
+    #[serde(default)]
+    output_modalities: Option<Vec<Modality>>,
+    #[serde(default)]
+    knowledge_cutoff: Option<NaiveDate>,
+    #[serde(default)]
+    released_at: Option<NaiveDate>,
+    #[serde(default)]
+    deprecated: bool,
+    #[serde(default)]
+    deprecated_at: Option<NaiveDate>,
+    #[serde(default)]
+    retires_at: Option<NaiveDate>,
+    #[serde(default)]
+    deprecation_date: Option<NaiveDate>,
+    #[serde(default)]
+    lifecycle: Lifecycle,
+    #[serde(default)]
+    replacement_model_id: Option<String>,
+}
+
+impl From<ModelDef> for Model {
+    fn from(def: ModelDef) -> Self {
+        let input_modalities = def
+            .input_modalities
+            .unwrap_or_else(|| Modality::legacy_inputs(def.supports_attachments == Some(true)));
+        Self {
+            supports_attachments: def
+                .supports_attachments
+                .unwrap_or_else(|| input_modalities.iter().any(|m| m.is_attachment())),
+            input_modalities,
+            output_modalities: def.output_modalities.unwrap_or_else(|| {
+                if def.model_kind.has_no_output() {
+                    Vec::new()
+                } else {
+                    vec![Modality::Text]
+                }
+            }),
+            id: def.id,
+            name: def.name,
+            model_kind: def.model_kind,
+            family: def.family,
+            previous_model_id: def.previous_model_id,
+            base_model_id: def.base_model_id,
+            variant: def.variant,
+            quantization: def.quantization,
+            open_weights: def.open_weights,
+            license: def.license,
+            tags: def.tags,
+            benchmarks: def.benchmarks,
+            cost_per_1m_in: def.cost_per_1m_in,
+            cost_per_1m_out: def.cost_per_1m_out,
+            cost_per_1m_in_cached: def.cost_per_1m_in_cached,
+            cost_per_1m_out_cached: def.cost_per_1m_out_cached,
+            cost_per_1m_in_batch: def.cost_per_1m_in_batch,
+            cost_per_1m_out_batch: def.cost_per_1m_out_batch,
+            cost_per_1m_reasoning: def.cost_per_1m_reasoning,
+            cost_per_image: def.cost_per_image,
+            cost_per_audio_minute: def.cost_per_audio_minute,
+            cost_per_1m_characters: def.cost_per_1m_characters,
+            cost_per_request: def.cost_per_request,
+            cost_per_tool_call: def.cost_per_tool_call,
+            context_window: def.context_window,
+            default_max_tokens: def.default_max_tokens,
+            max_output_tokens: def.max_output_tokens,
+            dimensions: def.dimensions,
+            max_input_tokens: def.max_input_tokens,
+            tokenizer: def.tokenizer,
+            voices: def.voices,
+            languages: def.languages,
+            limits: def.limits,
+            regions: def.regions,
+            median_ttft_ms: def.median_ttft_ms,
+            median_tokens_per_second: def.median_tokens_per_second,
+            can_reason: def.can_reason,
+            has_reasoning_efforts: def.has_reasoning_efforts,
+            default_reasoning_effort: def.default_reasoning_effort,
+            supports_tools: def.supports_tools,
+            supports_parallel_tool_calls: def.supports_parallel_tool_calls,
+            supports_structured_output: def.supports_structured_output,
+            supports_streaming: def.supports_streaming,
+            knowledge_cutoff: def.knowledge_cutoff,
+            released_at: def.released_at,
+            deprecated: def.deprecated,
+            deprecated_at: def.deprecated_at,
+            retires_at: def.retires_at.or(def.deprecation_date),
+            deprecation_date: def.deprecation_date.or(def.retires_at),
+            lifecycle: def.lifecycle,
+            replacement_model_id: def.replacement_model_id,
+        }
+    }
+}
+
+/// A model annotated with the ID of the provider offering it
+///
+/// Used by cross-provider endpoints that return models outside of their
+/// provider's nested `models` array.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct ProviderModel {
+    /// ID of the provider offering this model
+    pub provider_id: String,
+
+    /// The model itself (fields are flattened into the same JSON object)
+    #[serde(flatten)]
+    pub model: Model,
 }
 
 impl Provider {
@@ -97,6 +800,9 @@ impl Provider {
             default_large_model_id: None,
             default_small_model_id: None,
             default_headers: None,
+            limits: Vec::new(),
+            regions: Vec::new(),
+            tags: Vec::new(),
             models: Vec::new(),
         }
     }
@@ -124,6 +830,45 @@ impl Provider {
         self.models.iter().find(|m| m.id == model_id)
     }
 
+    /// Regions one of the provider's models is served from: its own, else the provider's
+    pub fn regions_of<'a>(&'a self, model: &'a Model) -> &'a [String] {
+        if model.regions.is_empty() {
+            &self.regions
+        } else {
+            &model.regions
+        }
+    }
+
+    /// Whether one of the provider's models is served from a region
+    pub fn available_in(&self, model: &Model, region: &str) -> bool {
+        self.regions_of(model)
+            .iter()
+            .any(|r| r.eq_ignore_ascii_case(region))
+    }
+
+    /// Whether one of the provider's models, or the provider itself, has a tag
+    pub fn has_tag(&self, model: &Model, tag: &str) -> bool {
+        model
+            .tags
+            .iter()
+            .chain(&self.tags)
+            .any(|t| t.eq_ignore_ascii_case(tag))
+    }
+
+    /// Rate limits for one of the provider's models at an account tier
+    /// (`None` for the first listed): the model's own, else the provider's
+    pub fn rate_limits<'a>(
+        &'a self,
+        model: &'a Model,
+        tier: Option<&str>,
+    ) -> Option<&'a RateLimits> {
+        model
+            .limits
+            .iter()
+            .chain(&self.limits)
+            .find(|limits| limits.matches(tier))
+    }
+
     /// Get the default large model
     pub fn default_large_model(&self) -> Option<&Model> {
         self.default_large_model_id
@@ -152,43 +897,184 @@ impl Model {
         Self {
             id,
             name,
+            model_kind: ModelKind::Chat,
+            family: None,
+            previous_model_id: None,
+            base_model_id: None,
+            variant: None,
+            quantization: None,
+            open_weights: false,
+            license: None,
+            tags: Vec::new(),
+            benchmarks: HashMap::new(),
             cost_per_1m_in,
             cost_per_1m_out,
             cost_per_1m_in_cached: None,
             cost_per_1m_out_cached: None,
+            cost_per_1m_in_batch: None,
+            cost_per_1m_out_batch: None,
+            cost_per_1m_reasoning: None,
+            cost_per_image: Vec::new(),
+            cost_per_audio_minute: None,
+            cost_per_1m_characters: None,
+            cost_per_request: None,
+            cost_per_tool_call: None,
             context_window,
             default_max_tokens,
+            max_output_tokens: None,
+            dimensions: None,
+            max_input_tokens: None,
+            tokenizer: None,
+            voices: Vec::new(),
+            languages: Vec::new(),
+            limits: Vec::new(),
+            regions: Vec::new(),
+            median_ttft_ms: None,
+            median_tokens_per_second: None,
             can_reason: false,
             has_reasoning_efforts: false,
             default_reasoning_effort: None,
             supports_attachments: false,
+            supports_tools: false,
+            supports_parallel_tool_calls: false,
+            supports_structured_output: false,
+            supports_streaming: false,
+            input_modalities: vec![Modality::Text],
+            output_modalities: vec![Modality::Text],
+            knowledge_cutoff: None,
+            released_at: None,
+            deprecated: false,
+            deprecated_at: None,
+            retires_at: None,
+            deprecation_date: None,
+            lifecycle: Lifecycle::Active,
+            replacement_model_id: None,
         }
     }
 
-    /// Calculate cost for a given number of input and output tokens
+    /// Calculate cost for a given number of input, output and reasoning tokens
     ///
-    /// Returns the total cost in USD
-    /// If use_cache is true and cached pricing is available, uses cached pricing
-    pub fn calculate_cost(&self, input_tokens: u64, output_tokens: u64, use_cache: bool) -> f64 {
-        let input_cost = if use_cache && self.cost_per_1m_in_cached.is_some() {
-            (input_tokens as f64 / 1_000_000.0) * self.cost_per_1m_in_cached.unwrap()
-        } else {
-            (input_tokens as f64 / 1_000_000.0) * self.cost_per_1m_in
+    /// Returns the cost in USD, broken down by kind of token
+    /// If use_cache is true and cached pricing is available, uses cached pricing;
+    /// otherwise, if batch is true and batch pricing is available, uses batch pricing.
+    /// Reasoning tokens are billed at `cost_per_1m_reasoning` when set, else at the
+    /// output rate. Requests and tool calls add the model's flat `cost_per_request`
+    /// and `cost_per_tool_call` fees, if any.
+    pub fn calculate_cost(&self, usage: &CostUsage) -> CostBreakdown {
+        let CostUsage {
+            input_tokens,
+            output_tokens,
+            reasoning_tokens,
+            requests,
+            tool_calls,
+            use_cache,
+            batch,
+        } = *usage;
+
+        let input_rate = match (self.cost_per_1m_in_cached, self.cost_per_1m_in_batch) {
+            (Some(cached), _) if use_cache => cached,
+            (_, Some(batch_rate)) if batch => batch_rate,
+            _ => self.cost_per_1m_in,
         };
 
-        let output_cost = if use_cache && self.cost_per_1m_out_cached.is_some() {
-            (output_tokens as f64 / 1_000_000.0) * self.cost_per_1m_out_cached.unwrap()
-        } else {
-            (output_tokens as f64 / 1_000_000.0) * self.cost_per_1m_out
+        let output_rate = match (self.cost_per_1m_out_cached, self.cost_per_1m_out_batch) {
+            (Some(cached), _) if use_cache => cached,
+            (_, Some(batch_rate)) if batch => batch_rate,
+            _ => self.cost_per_1m_out,
         };
 
-        input_cost + output_cost
+        let reasoning_rate = self.cost_per_1m_reasoning.unwrap_or(output_rate);
+
+        let input_cost = (input_tokens as f64 / 1_000_000.0) * input_rate;
+        let output_cost = (output_tokens as f64 / 1_000_000.0) * output_rate;
+        let reasoning_cost = (reasoning_tokens as f64 / 1_000_000.0) * reasoning_rate;
+        let surcharge = requests as f64 * self.cost_per_request.unwrap_or(0.0)
+            + tool_calls as f64 * self.cost_per_tool_call.unwrap_or(0.0);
+
+        if use_cache && self.cost_per_1m_in_cached.is_some() {
+            CostBreakdown::new(0.0, output_cost, input_cost, reasoning_cost, surcharge)
+        } else {
+            CostBreakdown::new(input_cost, output_cost, 0.0, reasoning_cost, surcharge)
+        }
+    }
+
+    /// Price tier for generated images of the given size and quality
+    ///
+    /// `None` matches any size or quality, so the first listed tier is the
+    /// model's default.
+    pub fn image_price(&self, size: Option<&str>, quality: Option<&str>) -> Option<&ImagePrice> {
+        self.cost_per_image
+            .iter()
+            .find(|tier| tier.matches(size, quality))
+    }
+
+    /// Calculate the cost of generating a number of images
+    ///
+    /// Returns the total cost in USD, or `None` when the model has no price
+    /// for that size and quality.
+    pub fn calculate_image_cost(
+        &self,
+        images: u64,
+        size: Option<&str>,
+        quality: Option<&str>,
+    ) -> Option<f64> {
+        self.image_price(size, quality)
+            .map(|tier| images as f64 * tier.cost)
+    }
+
+    /// Calculate the cost of transcribing audio and synthesizing speech
+    ///
+    /// Returns the total cost in USD; usage the model has no price for costs nothing.
+    pub fn calculate_audio_cost(&self, audio_minutes: f64, characters: u64) -> f64 {
+        let transcription = audio_minutes * self.cost_per_audio_minute.unwrap_or(0.0);
+        let speech = (characters as f64 / 1_000_000.0) * self.cost_per_1m_characters.unwrap_or(0.0);
+        transcription + speech
+    }
+
+    /// Whether the model accepts the given kind of input
+    pub fn accepts(&self, modality: Modality) -> bool {
+        self.input_modalities.contains(&modality)
+    }
+
+    /// Whether the model produces the given kind of output
+    pub fn produces(&self, modality: Modality) -> bool {
+        self.output_modalities.contains(&modality)
+    }
+
+    /// Lifecycle status on a given day
+    ///
+    /// A model is retired from its `retires_at` date and deprecated when
+    /// flagged `deprecated` or from its `deprecated_at` date.
+    pub fn lifecycle_on(&self, today: NaiveDate) -> Lifecycle {
+        if self.retires_at.is_some_and(|date| date <= today) {
+            Lifecycle::Retired
+        } else if self.deprecated || self.deprecated_at.is_some_and(|date| date <= today) {
+            Lifecycle::Deprecated
+        } else {
+            Lifecycle::Active
+        }
+    }
+
+    /// Whether the model is neither deprecated nor retired
+    ///
+    /// Uses the derived `lifecycle`, which the registry keeps current, and
+    /// the `deprecated` flag.
+    pub fn is_active(&self) -> bool {
+        self.lifecycle == Lifecycle::Active && !self.deprecated
     }
 
     /// Check if the given token count fits within the context window
     pub fn fits_in_context(&self, tokens: u64) -> bool {
         tokens <= self.context_window
     }
+
+    /// Score on a benchmark (case-insensitive), if published
+    pub fn benchmark(&self, name: &str) -> Option<f64> {
+        self.benchmarks
+            .iter()
+            .find(|(benchmark, _)| benchmark.eq_ignore_ascii_case(name))
+            .map(|(_, score)| *score)
+    }
 }
 
 #[cfg(test)]
@@ -220,7 +1106,9 @@ mod tests {
         );
 
         // Test with 100k input and 50k output tokens (no caching)
-        let cost = model.calculate_cost(100_000, 50_000, false);
+        let cost = model
+            .calculate_cost(&CostUsage::tokens(100_000, 50_000))
+            .total();
         // (100k / 1M * $3) + (50k / 1M * $15) = $0.30 + $0.75 = $1.05
         assert_eq!(cost, 1.05);
     }
@@ -239,11 +1127,125 @@ mod tests {
         model.cost_per_1m_out_cached = Some(0.3);
 
         // Test with caching
-        let cost = model.calculate_cost(100_000, 50_000, true);
+        let cost = model
+            .calculate_cost(&CostUsage {
+                use_cache: true,
+                ..CostUsage::tokens(100_000, 50_000)
+            })
+            .total();
         // (100k / 1M * $0.3) + (50k / 1M * $0.3) = $0.03 + $0.015 = $0.045
         assert_eq!(cost, 0.045);
     }
 
+    #[test]
+    fn test_model_cost_calculation_with_batch() {
+        let mut model = Model::new(
+            "test-model".to_string(),
+            "Test Model".to_string(),
+            3.0,
+            15.0,
+            200_000,
+            5000,
+        );
+
+        // Without batch pricing, batch requests cost the realtime price
+        assert_eq!(
+            model
+                .calculate_cost(&CostUsage {
+                    batch: true,
+                    ..CostUsage::tokens(100_000, 50_000)
+                })
+                .total(),
+            1.05
+        );
+
+        model.cost_per_1m_in_batch = Some(1.5);
+        model.cost_per_1m_out_batch = Some(7.5);
+        // (100k / 1M * $1.5) + (50k / 1M * $7.5) = $0.15 + $0.375 = $0.525
+        assert_eq!(
+            model
+                .calculate_cost(&CostUsage {
+                    batch: true,
+                    ..CostUsage::tokens(100_000, 50_000)
+                })
+                .total(),
+            0.525
+        );
+        assert_eq!(
+            model
+                .calculate_cost(&CostUsage::tokens(100_000, 50_000))
+                .total(),
+            1.05
+        );
+    }
+
+    #[test]
+    fn test_model_cost_calculation_with_reasoning() {
+        let mut model = Model::new(
+            "test-model".to_string(),
+            "Test Model".to_string(),
+            3.0,
+            15.0,
+            200_000,
+            5000,
+        );
+
+        // Reasoning tokens are billed as output unless priced separately
+        let cost = model.calculate_cost(&CostUsage {
+            reasoning_tokens: 250_000,
+            ..CostUsage::default()
+        });
+        assert_eq!(cost.reasoning, 3.75);
+        assert_eq!(cost.output, 0.0);
+
+        model.cost_per_1m_reasoning = Some(3.5);
+        let cost = model.calculate_cost(&CostUsage {
+            reasoning_tokens: 250_000,
+            ..CostUsage::tokens(500_000, 100_000)
+        });
+        assert_eq!(
+            cost,
+            CostBreakdown {
+                input: 1.5,
+                output: 1.5,
+                cached: 0.0,
+                reasoning: 0.875,
+                surcharge: 0.0,
+                total: 3.875,
+            }
+        );
+        assert_eq!(cost.total(), 3.875);
+    }
+
+    #[test]
+    fn test_model_cost_calculation_with_surcharges() {
+        let mut model = Model::new(
+            "test-model".to_string(),
+            "Test Model".to_string(),
+            3.0,
+            15.0,
+            200_000,
+            5000,
+        );
+
+        let surcharged = CostUsage {
+            requests: 10,
+            tool_calls: 4,
+            ..CostUsage::tokens(100_000, 50_000)
+        };
+        // Requests and tool calls are free unless the model charges for them
+        let cost = model.calculate_cost(&surcharged);
+        assert_eq!(cost.surcharge, 0.0);
+        assert_eq!(cost.total(), 1.05);
+
+        model.cost_per_request = Some(0.035);
+        model.cost_per_tool_call = Some(0.01);
+        // 10 * $0.035 + 4 * $0.01 = $0.39
+        let cost = model.calculate_cost(&surcharged);
+        assert_eq!(cost.surcharge, 0.39);
+        assert_eq!(cost.total(), 1.44);
+    }
+
     #[test]
     fn test_context_window() {
         let model = Model::new(
@@ -278,6 +1280,136 @@ mod tests {
         assert!(model.can_reason);
     }
 
+    #[test]
+    fn test_modalities_from_legacy_configs() {
+        let model = |extra: serde_json::Value| -> Model {
+            let mut json = serde_json::json!({
+                "id": "m",
+                "name": "M",
+                "cost_per_1m_in": 1.0,
+                "cost_per_1m_out": 1.0,
+                "context_window": 1000,
+                "default_max_tokens": 100
+            });
+            json.as_object_mut()
+                .unwrap()
+                .extend(extra.as_object().unwrap().clone());
+            serde_json::from_value(json).unwrap()
+        };
+
+        let legacy = model(serde_json::json!({"supports_attachments": true}));
+        assert_eq!(legacy.input_modalities, [Modality::Text, Modality::Image]);
+        assert_eq!(legacy.output_modalities, [Modality::Text]);
+        assert!(!model(serde_json::json!({})).accepts(Modality::Image));
+
+        let explicit = model(serde_json::json!({
+            "input_modalities": ["text", "audio", "pdf"],
+            "output_modalities": ["text", "image"]
+        }));
+        assert!(explicit.supports_attachments);
+        assert!(explicit.accepts(Modality::Audio));
+        assert!(explicit.produces(Modality::Image));
+
+        // Serialized models load back unchanged
+        let json = serde_json::to_string(&explicit).unwrap();
+        assert_eq!(serde_json::from_str::<Model>(&json).unwrap(), explicit);
+        assert_eq!("PDF".parse::<Modality>().unwrap(), Modality::Pdf);
+        assert!("smell".parse::<Modality>().is_err());
+    }
+
+    #[test]
+    fn test_image_cost() {
+        let tier = |size: &str, quality: Option<&str>, cost: f64| ImagePrice {
+            size: size.to_string(),
+            quality: quality.map(str::to_string),
+            cost,
+        };
+        let mut model = Model::new("img".to_string(), "Img".to_string(), 5.0, 40.0, 1000, 0);
+        assert_eq!(model.calculate_image_cost(1, None, None), None);
+
+        model.cost_per_image = vec![
+            tier("1024x1024", Some("low"), 0.01),
+            tier("1024x1024", Some("high"), 0.15),
+            tier("1536x1024", Some("high"), 0.25),
+        ];
+        assert_eq!(model.calculate_image_cost(4, None, None), Some(0.04));
+        assert_eq!(
+            model.calculate_image_cost(4, Some("1536X1024"), Some("HIGH")),
+            Some(1.0)
+        );
+        assert_eq!(model.calculate_image_cost(2, None, Some("high")), Some(0.3));
+        assert_eq!(model.calculate_image_cost(1, Some("512x512"), None), None);
+    }
+
+    #[test]
+    fn test_audio_cost() {
+        let mut model = Model::new("tts".to_string(), "TTS".to_string(), 0.0, 0.0, 4096, 0);
+        assert_eq!(model.calculate_audio_cost(10.0, 1_000_000), 0.0);
+
+        model.cost_per_audio_minute = Some(0.006);
+        model.cost_per_1m_characters = Some(15.0);
+        assert_eq!(model.calculate_audio_cost(0.0, 200_000), 3.0);
+        assert_eq!(model.calculate_audio_cost(100.0, 0), 0.6);
+    }
+
+    #[test]
+    fn test_embedding_model() {
+        let model: Model = serde_json::from_value(serde_json::json!({
+            "id": "embed",
+            "name": "Embed",
+            "model_kind": "embedding",
+            "cost_per_1m_in": 0.02,
+            "cost_per_1m_out": 0.0,
+            "context_window": 8191,
+            "default_max_tokens": 0,
+            "dimensions": 1536,
+            "max_input_tokens": 8191
+        }))
+        .unwrap();
+        assert_eq!(model.model_kind, ModelKind::Embedding);
+        assert_eq!(model.dimensions, Some(1536));
+        assert_eq!(model.input_modalities, [Modality::Text]);
+        assert!(model.output_modalities.is_empty());
+
+        // Chat is the default kind
+        let chat = Model::new("m".to_string(), "M".to_string(), 1.0, 1.0, 1000, 100);
+        let json = serde_json::to_value(&chat).unwrap();
+        assert_eq!(json["model_kind"], "chat");
+        assert!(json.get("dimensions").is_none());
+        assert_eq!("Rerank".parse::<ModelKind>().unwrap(), ModelKind::Rerank);
+        assert!("speech".parse::<ModelKind>().is_err());
+    }
+
+    #[test]
+    fn test_lifecycle() {
+        let date = |s: &str| s.parse::<NaiveDate>().unwrap();
+        let mut model: Model = serde_json::from_value(serde_json::json!({
+            "id": "old",
+            "name": "Old",
+            "cost_per_1m_in": 1.0,
+            "cost_per_1m_out": 2.0,
+            "context_window": 8000,
+            "default_max_tokens": 1000,
+            "deprecated_at": "2025-06-01",
+            "deprecation_date": "2025-12-01"
+        }))
+        .unwrap();
+        assert_eq!(model.retires_at, Some(date("2025-12-01")));
+        assert_eq!(model.lifecycle_on(date("2025-05-31")), Lifecycle::Active);
+        assert_eq!(
+            model.lifecycle_on(date("2025-06-01")),
+            Lifecycle::Deprecated
+        );
+        assert_eq!(model.lifecycle_on(date("2025-12-01")), Lifecycle::Retired);
+
+        assert!(model.is_active());
+        model.lifecycle = Lifecycle::Retired;
+        assert!(!model.is_active());
+        let json = serde_json::to_value(&model).unwrap();
+        assert_eq!(json["lifecycle"], "retired");
+        assert_eq!(json["deprecation_date"], "2025-12-01");
+    }
+
     #[test]
     fn test_provider_with_models() {
         let model = Model::new(
@@ -300,6 +1432,45 @@ mod tests {
         assert_eq!(provider.models[0].id, "test-model");
     }
 
+    #[test]
+    fn test_rate_limits() {
+        let tier = |tier: &str, requests_per_minute: u64| RateLimits {
+            tier: Some(tier.to_string()),
+            requests_per_minute: Some(requests_per_minute),
+            ..RateLimits::default()
+        };
+        let mut model = Model::new("m".to_string(), "M".to_string(), 1.0, 1.0, 1000, 100);
+        model.limits = vec![tier("tier-1", 500)];
+        let mut provider = Provider::new("P".to_string(), "p".to_string(), "p".to_string());
+        provider.limits = vec![tier("tier-1", 50), tier("tier-2", 1000)];
+
+        // The model's own limits win, the provider's fill in other tiers
+        let limits = |t| provider.rate_limits(&model, t).unwrap().requests_per_minute;
+        assert_eq!(limits(Some("Tier-1")), Some(500));
+        assert_eq!(limits(Some("tier-2")), Some(1000));
+        assert_eq!(limits(None), Some(500));
+        assert!(provider.rate_limits(&model, Some("tier-5")).is_none());
+    }
+
+    #[test]
+    fn test_provider_model_serialization() {
+        let entry = ProviderModel {
+            provider_id: "test".to_string(),
+            model: Model::new(
+                "test-model".to_string(),
+                "Test Model".to_string(),
+                3.0,
+                15.0,
+                200_000,
+                5000,
+            ),
+        };
+
+        let json = serde_json::to_value(&entry).unwrap();
+        assert_eq!(json["provider_id"], "test");
+        assert_eq!(json["id"], "test-model");
+    }
+
     #[test]
     fn test_default_models() {
         let large_model = Model::new(
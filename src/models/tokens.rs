@@ -0,0 +1,80 @@
+//! Token estimation from raw text
+//!
+//! Models name the tokenizer they count tokens with in `tokenizer`. With the
+//! `tiktoken` feature, text for models using an OpenAI encoding
+//! (`o200k_base`, `cl100k_base`, `p50k_base`, `r50k_base`) is tokenized
+//! exactly; every other tokenizer falls back to a characters-per-token
+//! ratio, which is close enough to budget costs and context windows.
+
+use super::provider::Model;
+
+/// Characters per token for English text when nothing better is known
+const DEFAULT_CHARS_PER_TOKEN: f64 = 4.0;
+
+/// Characters per token by tokenizer, for tokenizers that cannot be run locally
+fn chars_per_token(tokenizer: Option<&str>) -> f64 {
+    match tokenizer {
+        Some("claude") => 3.5,
+        Some("gemini") | Some("llama3") => 4.2,
+        _ => DEFAULT_CHARS_PER_TOKEN,
+    }
+}
+
+/// Exact token count with a tiktoken encoding, if `tokenizer` is one
+#[cfg(feature = "tiktoken")]
+fn tiktoken_count(tokenizer: Option<&str>, text: &str) -> Option<u64> {
+    let bpe = match tokenizer? {
+        "o200k_base" => tiktoken_rs::o200k_base_singleton(),
+        "cl100k_base" => tiktoken_rs::cl100k_base_singleton(),
+        "p50k_base" => tiktoken_rs::p50k_base_singleton(),
+        "r50k_base" => tiktoken_rs::r50k_base_singleton(),
+        _ => return None,
+    };
+    let count = bpe.lock().encode_ordinary(text).len();
+    Some(count as u64)
+}
+
+#[cfg(not(feature = "tiktoken"))]
+fn tiktoken_count(_tokenizer: Option<&str>, _text: &str) -> Option<u64> {
+    None
+}
+
+impl Model {
+    /// Estimate the number of tokens `text` takes for this model
+    ///
+    /// Exact for OpenAI encodings when built with the `tiktoken` feature,
+    /// otherwise derived from the text length.
+    pub fn estimate_tokens(&self, text: &str) -> u64 {
+        let tokenizer = self.tokenizer.as_deref();
+        if let Some(count) = tiktoken_count(tokenizer, text) {
+            return count;
+        }
+        (text.chars().count() as f64 / chars_per_token(tokenizer)).ceil() as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn model(tokenizer: Option<&str>) -> Model {
+        let mut model = Model::new("m".to_string(), "M".to_string(), 1.0, 1.0, 1000, 100);
+        model.tokenizer = tokenizer.map(str::to_string);
+        model
+    }
+
+    #[test]
+    fn test_estimate_tokens_heuristic() {
+        let text = "a".repeat(70);
+        assert_eq!(model(None).estimate_tokens(&text), 18);
+        assert_eq!(model(Some("claude")).estimate_tokens(&text), 20);
+        assert_eq!(model(None).estimate_tokens(""), 0);
+    }
+
+    #[cfg(feature = "tiktoken")]
+    #[test]
+    fn test_estimate_tokens_tiktoken() {
+        let model = model(Some("o200k_base"));
+        assert_eq!(model.estimate_tokens("Hello, world!"), 4);
+    }
+}
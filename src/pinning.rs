@@ -0,0 +1,163 @@
+//! Certificate pinning for [`CrabraceClient`](crate::CrabraceClient)
+//!
+//! A pinned client only talks to servers presenting one of the pinned
+//! certificates, identified by the SHA-256 fingerprint of the leaf
+//! certificate (as printed by `openssl x509 -noout -fingerprint -sha256`).
+//!
+//! The fingerprint is checked during the TLS handshake, after the usual
+//! chain and host name validation, so nothing (credentials included) is
+//! sent to a server presenting another certificate. Pinned clients use
+//! rustls for this, through [`Pins::tls_config`], instead of the platform's
+//! TLS library.
+
+use anyhow::{bail, Context, Result};
+use rustls_client::client::{ServerCertVerified, ServerCertVerifier, WebPkiVerifier};
+use rustls_client::{Certificate, ClientConfig, RootCertStore, ServerName};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+/// SHA-256 fingerprints of the certificates a client accepts (any
+/// certificate when empty)
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Pins(Arc<[[u8; 32]]>);
+
+impl Pins {
+    /// Parse fingerprints written as 64 hex digits, optionally separated by
+    /// colons
+    pub(crate) fn parse(fingerprints: &[String]) -> Result<Self> {
+        let pins = fingerprints
+            .iter()
+            .map(|fingerprint| parse_fingerprint(fingerprint))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self(pins.into()))
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Fail unless a DER certificate is pinned
+    pub(crate) fn check(&self, certificate: &[u8]) -> Result<()> {
+        if self.is_empty() {
+            return Ok(());
+        }
+        let fingerprint: [u8; 32] = Sha256::digest(certificate).into();
+        if !self.0.contains(&fingerprint) {
+            bail!(
+                "The server certificate (SHA-256 {}) is not pinned",
+                fingerprint
+                    .iter()
+                    .map(|byte| format!("{:02X}", byte))
+                    .collect::<Vec<_>>()
+                    .join(":")
+            );
+        }
+        Ok(())
+    }
+
+    /// TLS settings for a pinned client trusting the system's roots (if
+    /// `system_roots`) and the certificates of the `root_certificates` PEM
+    /// bundles
+    pub(crate) fn tls_config(
+        &self,
+        root_certificates: &[Vec<u8>],
+        system_roots: bool,
+    ) -> Result<ClientConfig> {
+        let mut roots = RootCertStore::empty();
+        if system_roots {
+            let certificates = rustls_native_certs::load_native_certs()
+                .context("Failed to load the system's root certificates")?;
+            let certificates: Vec<_> = certificates.into_iter().map(|cert| cert.0).collect();
+            roots.add_parsable_certificates(&certificates);
+        }
+        for pem in root_certificates {
+            let certificates =
+                rustls_pemfile::certs(&mut pem.as_slice()).context("Invalid root certificate")?;
+            for certificate in certificates {
+                roots
+                    .add(&Certificate(certificate))
+                    .context("Invalid root certificate")?;
+            }
+        }
+
+        let verifier = PinnedVerifier {
+            webpki: WebPkiVerifier::new(roots, None),
+            pins: self.clone(),
+        };
+        Ok(ClientConfig::builder()
+            .with_safe_defaults()
+            .with_custom_certificate_verifier(Arc::new(verifier))
+            .with_no_client_auth())
+    }
+}
+
+/// Validates server certificates as usual, then requires a pinned leaf
+struct PinnedVerifier {
+    webpki: WebPkiVerifier,
+    pins: Pins,
+}
+
+impl ServerCertVerifier for PinnedVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &Certificate,
+        intermediates: &[Certificate],
+        server_name: &ServerName,
+        scts: &mut dyn Iterator<Item = &[u8]>,
+        ocsp_response: &[u8],
+        now: SystemTime,
+    ) -> Result<ServerCertVerified, rustls_client::Error> {
+        let verified = self.webpki.verify_server_cert(
+            end_entity,
+            intermediates,
+            server_name,
+            scts,
+            ocsp_response,
+            now,
+        )?;
+        self.pins
+            .check(&end_entity.0)
+            .map_err(|error| rustls_client::Error::General(error.to_string()))?;
+        Ok(verified)
+    }
+}
+
+fn parse_fingerprint(fingerprint: &str) -> Result<[u8; 32]> {
+    let digits: Vec<u8> = fingerprint.bytes().filter(|&c| c != b':').collect();
+    if digits.len() != 64 || !digits.iter().all(u8::is_ascii_hexdigit) {
+        bail!(
+            "Invalid certificate fingerprint '{}': expected the 64 hex digits of a SHA-256 hash",
+            fingerprint
+        );
+    }
+    let mut bytes = [0; 32];
+    for (byte, pair) in bytes.iter_mut().zip(digits.chunks(2)) {
+        let pair = std::str::from_utf8(pair).expect("Hex digits are ASCII");
+        *byte = u8::from_str_radix(pair, 16).expect("Hex digits were checked");
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pins() {
+        let colons = ["AB"; 32].join(":");
+        let plain = "ab".repeat(32);
+        let pins = Pins::parse(&[colons, plain]).unwrap();
+        assert_eq!(pins.0[..], [[0xab; 32], [0xab; 32]]);
+        assert!(Pins::default().is_empty());
+        assert!(Pins::default().check(b"certificate").is_ok());
+        assert!(pins.check(b"certificate").is_err());
+
+        for invalid in ["ab", &"zz".repeat(32), &"é".repeat(32)] {
+            let error = Pins::parse(&[invalid.to_string()]).unwrap_err();
+            assert!(error
+                .to_string()
+                .starts_with("Invalid certificate fingerprint"));
+        }
+    }
+}
@@ -0,0 +1,283 @@
+//! Changelog of catalog changes between registry versions
+//!
+//! After every mutation the registry diffs the new catalog against a compact
+//! record of the previous one (provider IDs and per-model prices) and logs
+//! provider and model additions and removals plus price changes under the
+//! new version, so downstream caches can apply deltas instead of
+//! re-downloading the catalog. Only the most recent [`CAPACITY`] changes
+//! are kept.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use utoipa::ToSchema;
+
+use crate::{Model, Provider};
+
+/// Number of changes retained
+pub const CAPACITY: usize = 1000;
+
+/// What changed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    ProviderAdded,
+    ProviderRemoved,
+    ModelAdded,
+    ModelRemoved,
+    PriceChanged,
+}
+
+/// Per-million-token prices of a model
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct Prices {
+    pub cost_per_1m_in: f64,
+
+    pub cost_per_1m_out: f64,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cost_per_1m_in_cached: Option<f64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cost_per_1m_out_cached: Option<f64>,
+}
+
+impl From<&Model> for Prices {
+    fn from(model: &Model) -> Self {
+        Self {
+            cost_per_1m_in: model.cost_per_1m_in,
+            cost_per_1m_out: model.cost_per_1m_out,
+            cost_per_1m_in_cached: model.cost_per_1m_in_cached,
+            cost_per_1m_out_cached: model.cost_per_1m_out_cached,
+        }
+    }
+}
+
+/// One change between two registry versions
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct Change {
+    /// Registry version that introduced the change
+    pub version: u64,
+
+    pub kind: ChangeKind,
+
+    pub provider_id: String,
+
+    /// Set for model changes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model_id: Option<String>,
+
+    /// Prices before the change (removed models and price changes)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old_prices: Option<Prices>,
+
+    /// Prices after the change (added models and price changes)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new_prices: Option<Prices>,
+
+    #[schema(value_type = String, format = DateTime)]
+    pub changed_at: DateTime<Utc>,
+}
+
+/// What the changelog remembers about a catalog, for diffing
+#[derive(Debug, Default)]
+struct CatalogState {
+    providers: BTreeSet<String>,
+    /// (provider ID, model ID) to prices; the first duplicate wins
+    models: BTreeMap<(String, String), Prices>,
+}
+
+impl CatalogState {
+    fn of<'a>(providers: impl IntoIterator<Item = &'a Provider>) -> Self {
+        let mut state = Self::default();
+        for provider in providers {
+            state.providers.insert(provider.id.clone());
+            for model in &provider.models {
+                state
+                    .models
+                    .entry((provider.id.clone(), model.id.clone()))
+                    .or_insert_with(|| Prices::from(model));
+            }
+        }
+        state
+    }
+}
+
+/// Bounded log of catalog changes
+#[derive(Debug)]
+pub struct Changelog {
+    state: CatalogState,
+    changes: VecDeque<Change>,
+    /// Every change after this version is retained
+    complete_since: u64,
+}
+
+impl Changelog {
+    /// Start a changelog at `version` for the given catalog
+    pub fn new<'a>(version: u64, providers: impl IntoIterator<Item = &'a Provider>) -> Self {
+        Self {
+            state: CatalogState::of(providers),
+            changes: VecDeque::new(),
+            complete_since: version,
+        }
+    }
+
+    /// Diff `providers` against the previous catalog and log the changes under `version`
+    pub fn record<'a>(&mut self, version: u64, providers: impl IntoIterator<Item = &'a Provider>) {
+        let new = CatalogState::of(providers);
+        let old = std::mem::replace(&mut self.state, new);
+        let new = &self.state;
+        let changed_at = Utc::now();
+        let change =
+            |kind, provider_id: &str, model_id: Option<&str>, old_prices, new_prices| Change {
+                version,
+                kind,
+                provider_id: provider_id.to_string(),
+                model_id: model_id.map(str::to_string),
+                old_prices,
+                new_prices,
+                changed_at,
+            };
+
+        let mut changes = Vec::new();
+        for id in new.providers.difference(&old.providers) {
+            changes.push(change(ChangeKind::ProviderAdded, id, None, None, None));
+        }
+        for id in old.providers.difference(&new.providers) {
+            changes.push(change(ChangeKind::ProviderRemoved, id, None, None, None));
+        }
+        for ((provider_id, model_id), prices) in &new.models {
+            match old.models.get(&(provider_id.clone(), model_id.clone())) {
+                None => changes.push(change(
+                    ChangeKind::ModelAdded,
+                    provider_id,
+                    Some(model_id),
+                    None,
+                    Some(prices.clone()),
+                )),
+                Some(old_prices) if old_prices != prices => changes.push(change(
+                    ChangeKind::PriceChanged,
+                    provider_id,
+                    Some(model_id),
+                    Some(old_prices.clone()),
+                    Some(prices.clone()),
+                )),
+                Some(_) => {}
+            }
+        }
+        for ((provider_id, model_id), prices) in &old.models {
+            if !new
+                .models
+                .contains_key(&(provider_id.clone(), model_id.clone()))
+            {
+                changes.push(change(
+                    ChangeKind::ModelRemoved,
+                    provider_id,
+                    Some(model_id),
+                    Some(prices.clone()),
+                    None,
+                ));
+            }
+        }
+
+        self.changes.extend(changes);
+        while self.changes.len() > CAPACITY {
+            if let Some(dropped) = self.changes.pop_front() {
+                self.complete_since = self.complete_since.max(dropped.version);
+            }
+        }
+    }
+
+    /// Changes made after version `since`, oldest first
+    ///
+    /// Returns `Err` with the oldest version deltas can still be requested
+    /// from when changes after `since` are no longer retained.
+    pub fn since(&self, since: u64) -> Result<Vec<Change>, u64> {
+        if since < self.complete_since {
+            return Err(self.complete_since);
+        }
+        Ok(self
+            .changes
+            .iter()
+            .filter(|change| change.version > since)
+            .cloned()
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn provider(id: &str, models: &[(&str, f64)]) -> Provider {
+        let mut provider = Provider::new(id.to_string(), id.to_string(), "openai".to_string());
+        for &(model_id, cost) in models {
+            let model = Model::new(model_id.to_string(), model_id.to_string(), cost, 1.0, 1, 1);
+            provider.models.push(model);
+        }
+        provider
+    }
+
+    #[test]
+    fn test_record_changes() {
+        let mut changelog = Changelog::new(1, &[provider("a", &[("m1", 1.0), ("m2", 2.0)])]);
+        changelog.record(
+            2,
+            &[
+                provider("a", &[("m1", 1.5), ("m3", 3.0)]),
+                provider("b", &[]),
+            ],
+        );
+        changelog.record(3, &[provider("a", &[("m1", 1.5), ("m3", 3.0)])]);
+
+        let summary: Vec<_> = changelog
+            .since(1)
+            .unwrap()
+            .iter()
+            .map(|c| (c.version, c.kind, c.provider_id.clone(), c.model_id.clone()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (2, ChangeKind::ProviderAdded, "b".to_string(), None),
+                (
+                    2,
+                    ChangeKind::PriceChanged,
+                    "a".to_string(),
+                    Some("m1".to_string())
+                ),
+                (
+                    2,
+                    ChangeKind::ModelAdded,
+                    "a".to_string(),
+                    Some("m3".to_string())
+                ),
+                (
+                    2,
+                    ChangeKind::ModelRemoved,
+                    "a".to_string(),
+                    Some("m2".to_string())
+                ),
+                (3, ChangeKind::ProviderRemoved, "b".to_string(), None),
+            ]
+        );
+
+        let price = &changelog.since(1).unwrap()[1];
+        assert_eq!(price.old_prices.as_ref().unwrap().cost_per_1m_in, 1.0);
+        assert_eq!(price.new_prices.as_ref().unwrap().cost_per_1m_in, 1.5);
+        assert_eq!(changelog.since(2).unwrap().len(), 1);
+        assert!(changelog.since(3).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_expired_changes() {
+        let mut changelog = Changelog::new(1, &[]);
+        let models: Vec<String> = (0..=CAPACITY).map(|i| format!("m{}", i)).collect();
+        let models: Vec<(&str, f64)> = models.iter().map(|id| (id.as_str(), 1.0)).collect();
+        changelog.record(2, &[provider("a", &models)]);
+        changelog.record(3, &[]);
+
+        assert_eq!(changelog.since(1), Err(3));
+        assert_eq!(changelog.since(3).unwrap().len(), 0);
+    }
+}
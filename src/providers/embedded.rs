@@ -0,0 +1,67 @@
+//! Provider configurations compiled into the crate
+//!
+//! Every `configs/*.json` file must be listed in [`EMBEDDED_CONFIGS`]; the
+//! build fails otherwise. The server loads them into the
+//! [`ProviderRegistry`](super::registry::ProviderRegistry) at startup and
+//! [`LocalCatalog`](super::local::LocalCatalog) serves them in-process.
+
+/// Embedded provider configuration files
+/// These JSON files contain provider and model metadata
+const ANTHROPIC_CONFIG: &str = include_str!("configs/anthropic.json");
+const OPENAI_CONFIG: &str = include_str!("configs/openai.json");
+const GEMINI_CONFIG: &str = include_str!("configs/gemini.json");
+const AZURE_CONFIG: &str = include_str!("configs/azure.json");
+const BEDROCK_CONFIG: &str = include_str!("configs/bedrock.json");
+const VERTEXAI_CONFIG: &str = include_str!("configs/vertexai.json");
+const XAI_CONFIG: &str = include_str!("configs/xai.json");
+const ZAI_CONFIG: &str = include_str!("configs/zai.json");
+const GROQ_CONFIG: &str = include_str!("configs/groq.json");
+const OPENROUTER_CONFIG: &str = include_str!("configs/openrouter.json");
+const CEREBRAS_CONFIG: &str = include_str!("configs/cerebras.json");
+const VENICE_CONFIG: &str = include_str!("configs/venice.json");
+const CHUTES_CONFIG: &str = include_str!("configs/chutes.json");
+const DEEPSEEK_CONFIG: &str = include_str!("configs/deepseek.json");
+const HUGGINGFACE_CONFIG: &str = include_str!("configs/huggingface.json");
+const AIHUBMIX_CONFIG: &str = include_str!("configs/aihubmix.json");
+const OLLAMA_CONFIG: &str = include_str!("configs/ollama.json");
+const LMSTUDIO_CONFIG: &str = include_str!("configs/lmstudio.json");
+
+/// Display name, file name and contents of every embedded configuration, in load order
+pub(crate) const EMBEDDED_CONFIGS: &[(&str, &str, &str)] = &[
+    ("Anthropic", "anthropic.json", ANTHROPIC_CONFIG),
+    ("OpenAI", "openai.json", OPENAI_CONFIG),
+    ("Gemini", "gemini.json", GEMINI_CONFIG),
+    ("Azure", "azure.json", AZURE_CONFIG),
+    ("Bedrock", "bedrock.json", BEDROCK_CONFIG),
+    ("VertexAI", "vertexai.json", VERTEXAI_CONFIG),
+    ("xAI", "xai.json", XAI_CONFIG),
+    ("zAI", "zai.json", ZAI_CONFIG),
+    ("Groq", "groq.json", GROQ_CONFIG),
+    ("OpenRouter", "openrouter.json", OPENROUTER_CONFIG),
+    ("Cerebras", "cerebras.json", CEREBRAS_CONFIG),
+    ("Venice", "venice.json", VENICE_CONFIG),
+    ("Chutes", "chutes.json", CHUTES_CONFIG),
+    ("DeepSeek", "deepseek.json", DEEPSEEK_CONFIG),
+    ("HuggingFace", "huggingface.json", HUGGINGFACE_CONFIG),
+    ("AIHubMix", "aihubmix.json", AIHUBMIX_CONFIG),
+    ("Ollama", "ollama.json", OLLAMA_CONFIG),
+    ("LM Studio", "lmstudio.json", LMSTUDIO_CONFIG),
+];
+
+/// Built-in provider ID aliases (alias, canonical ID) for names used by other tools
+pub const DEFAULT_ALIASES: &[(&str, &str)] = &[
+    ("google", "gemini"),
+    ("google-ai", "gemini"),
+    ("vertex", "vertexai"),
+    ("google-vertex", "vertexai"),
+    ("aws", "bedrock"),
+    ("amazon-bedrock", "bedrock"),
+    ("azure-openai", "azure"),
+    ("x-ai", "xai"),
+    ("grok", "xai"),
+    ("z-ai", "zai"),
+    ("zhipu", "zai"),
+    ("hf", "huggingface"),
+    ("hugging-face", "huggingface"),
+    ("lm-studio", "lmstudio"),
+];
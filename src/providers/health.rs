@@ -0,0 +1,167 @@
+use serde::{Deserialize, Serialize};
+
+use super::registry::LoadFailure;
+use crate::Provider;
+
+/// Health of a provider or of the whole catalog
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthStatus {
+    /// No issues found
+    Ok,
+    /// Usable, but with data issues (e.g. a default model ID that does not resolve)
+    Degraded,
+    /// Not usable (e.g. a provider with no models or a config that failed to parse)
+    Unhealthy,
+}
+
+/// Integrity report over the loaded catalog
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntegrityReport {
+    /// Worst status across all providers and load failures
+    pub status: HealthStatus,
+
+    /// Per-provider results, in catalog order
+    pub providers: Vec<ProviderHealth>,
+
+    /// Embedded provider configurations that failed to parse
+    pub load_failures: Vec<LoadFailure>,
+}
+
+/// Integrity check result for a single provider
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderHealth {
+    pub id: String,
+    pub status: HealthStatus,
+    pub model_count: usize,
+
+    /// Human readable descriptions of the problems found
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub issues: Vec<String>,
+}
+
+impl ProviderHealth {
+    /// Check a single provider's data
+    pub fn check(provider: &Provider) -> Self {
+        let mut status = HealthStatus::Ok;
+        let mut issues = Vec::new();
+
+        if provider.models.is_empty() {
+            status = HealthStatus::Unhealthy;
+            issues.push("provider has no models".to_string());
+        }
+
+        for (field, model_id) in [
+            ("default_large_model_id", &provider.default_large_model_id),
+            ("default_small_model_id", &provider.default_small_model_id),
+        ] {
+            if let Some(model_id) = model_id {
+                if provider.get_model(model_id).is_none() {
+                    status = status.max(HealthStatus::Degraded);
+                    issues.push(format!("{} '{}' does not match any model", field, model_id));
+                }
+            }
+        }
+
+        Self {
+            id: provider.id.clone(),
+            status,
+            model_count: provider.models.len(),
+            issues,
+        }
+    }
+}
+
+impl IntegrityReport {
+    /// Check every provider and fold in configuration load failures
+    pub fn check<'a>(
+        providers: impl IntoIterator<Item = &'a Provider>,
+        load_failures: Vec<LoadFailure>,
+    ) -> Self {
+        let providers: Vec<ProviderHealth> =
+            providers.into_iter().map(ProviderHealth::check).collect();
+
+        let mut status = providers
+            .iter()
+            .map(|p| p.status)
+            .max()
+            .unwrap_or(HealthStatus::Unhealthy);
+        if !load_failures.is_empty() {
+            status = HealthStatus::Unhealthy;
+        }
+
+        Self {
+            status,
+            providers,
+            load_failures,
+        }
+    }
+
+    /// Whether the catalog can serve traffic (healthy or degraded)
+    pub fn is_serving(&self) -> bool {
+        self.status != HealthStatus::Unhealthy
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Model;
+
+    fn provider(id: &str) -> Provider {
+        Provider::new(id.to_string(), id.to_string(), "openai".to_string())
+    }
+
+    fn model(id: &str) -> Model {
+        Model::new(id.to_string(), id.to_string(), 1.0, 2.0, 8_000, 1_000)
+    }
+
+    #[test]
+    fn test_healthy_provider() {
+        let mut p = provider("a").with_model(model("m1"));
+        p.default_large_model_id = Some("m1".to_string());
+
+        let health = ProviderHealth::check(&p);
+        assert_eq!(health.status, HealthStatus::Ok);
+        assert!(health.issues.is_empty());
+    }
+
+    #[test]
+    fn test_unresolved_default_model() {
+        let mut p = provider("a").with_model(model("m1"));
+        p.default_small_model_id = Some("missing".to_string());
+
+        let health = ProviderHealth::check(&p);
+        assert_eq!(health.status, HealthStatus::Degraded);
+        assert!(health.issues[0].contains("default_small_model_id"));
+    }
+
+    #[test]
+    fn test_provider_without_models() {
+        let health = ProviderHealth::check(&provider("empty"));
+        assert_eq!(health.status, HealthStatus::Unhealthy);
+    }
+
+    #[test]
+    fn test_report_status() {
+        let providers = vec![provider("a").with_model(model("m1"))];
+        let report = IntegrityReport::check(&providers, Vec::new());
+        assert_eq!(report.status, HealthStatus::Ok);
+        assert!(report.is_serving());
+
+        let failure = LoadFailure {
+            provider: "Broken".to_string(),
+            file: "broken.json".to_string(),
+            path: "models[0]".to_string(),
+            error: "expected value".to_string(),
+        };
+        let report = IntegrityReport::check(&providers, vec![failure]);
+        assert_eq!(report.status, HealthStatus::Unhealthy);
+        assert!(!report.is_serving());
+
+        assert_eq!(
+            IntegrityReport::check(&[], Vec::new()).status,
+            HealthStatus::Unhealthy
+        );
+    }
+}
@@ -0,0 +1,130 @@
+//! The embedded catalog, without a server (feature `embedded-data`)
+//!
+//! [`LocalCatalog`] parses the provider configs compiled into the crate and
+//! answers the same queries as [`CrabraceClient`](crate::CrabraceClient),
+//! in-process and without network access. It holds the catalog a server of
+//! the same version starts from, before upstream syncs, overlays or admin
+//! edits are applied.
+
+use anyhow::{Context, Result};
+
+use super::embedded::{DEFAULT_ALIASES, EMBEDDED_CONFIGS};
+use crate::{Model, Provider};
+
+/// Every embedded provider, queried in-process
+///
+/// # Example
+///
+/// ```
+/// use crabrace::LocalCatalog;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let catalog = LocalCatalog::new()?;
+/// if let Some(model) = catalog.get_model("anthropic", "claude-sonnet-4-5-20250929")? {
+///     println!("{}: ${}/1M input tokens", model.name, model.cost_per_1m_in);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct LocalCatalog {
+    providers: Vec<Provider>,
+}
+
+impl LocalCatalog {
+    /// Parse every embedded provider config
+    ///
+    /// The build validates the configs, so this only fails if the crate
+    /// itself is broken.
+    pub fn new() -> Result<Self> {
+        let providers = EMBEDDED_CONFIGS
+            .iter()
+            .map(|&(_, file, config)| {
+                serde_json::from_str(config)
+                    .with_context(|| format!("Invalid embedded provider config {}", file))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { providers })
+    }
+
+    /// Every provider, in catalog order
+    pub fn providers(&self) -> &[Provider] {
+        &self.providers
+    }
+
+    /// A provider by ID or built-in alias (e.g. `google` for `gemini`)
+    pub fn provider(&self, id: &str) -> Option<&Provider> {
+        let id = canonical_id(id);
+        self.providers.iter().find(|provider| provider.id == id)
+    }
+
+    /// Get all providers and their models, like
+    /// [`CrabraceClient::get_providers`](crate::CrabraceClient::get_providers)
+    pub fn get_providers(&self) -> Result<Vec<Provider>> {
+        Ok(self.providers.clone())
+    }
+
+    /// Get a single provider by ID, like
+    /// [`CrabraceClient::get_provider`](crate::CrabraceClient::get_provider)
+    pub fn get_provider(&self, id: &str) -> Result<Option<Provider>> {
+        Ok(self.provider(id).cloned())
+    }
+
+    /// Get a single model of a provider, like
+    /// [`CrabraceClient::get_model`](crate::CrabraceClient::get_model)
+    pub fn get_model(&self, provider_id: &str, model_id: &str) -> Result<Option<Model>> {
+        Ok(self
+            .provider(provider_id)
+            .and_then(|provider| provider.get_model(model_id))
+            .cloned())
+    }
+}
+
+/// Provider ID a built-in alias stands for, or `id` itself
+pub(crate) fn canonical_id(id: &str) -> &str {
+    let alias = id.to_ascii_lowercase();
+    DEFAULT_ALIASES
+        .iter()
+        .find(|&&(name, _)| name == alias)
+        .map_or(id, |&(_, canonical)| canonical)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_local_catalog() {
+        let catalog = LocalCatalog::new().unwrap();
+        assert_eq!(catalog.providers().len(), EMBEDDED_CONFIGS.len());
+
+        assert_eq!(
+            catalog.get_provider("Google").unwrap().unwrap().id,
+            "gemini"
+        );
+        assert!(catalog.get_provider("missing").unwrap().is_none());
+
+        let model = catalog
+            .get_model("anthropic", "claude-sonnet-4-5-20250929")
+            .unwrap()
+            .unwrap();
+        assert_eq!(model.id, "claude-sonnet-4-5-20250929");
+        assert!(catalog.get_model("anthropic", "missing").unwrap().is_none());
+    }
+
+    #[cfg(feature = "server")]
+    #[test]
+    fn test_matches_registry() {
+        let registry = crate::providers::registry::ProviderRegistry::new().unwrap();
+        let ids = |providers: &[Provider]| -> Vec<(String, usize)> {
+            providers
+                .iter()
+                .map(|provider| (provider.id.clone(), provider.models.len()))
+                .collect()
+        };
+        assert_eq!(
+            ids(&LocalCatalog::new().unwrap().get_providers().unwrap()),
+            ids(&registry.get_all().unwrap())
+        );
+    }
+}
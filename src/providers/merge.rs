@@ -0,0 +1,115 @@
+//! Deep-merge of partial provider configurations
+//!
+//! A patch only needs the provider `id` plus the fields it changes. Objects
+//! are merged key by key, `models` are matched by their `id` (unknown models
+//! are appended), and any other value, arrays included, replaces the
+//! original.
+
+use anyhow::{bail, Result};
+use serde_json::Value;
+
+/// Merge `patch` into `base` in place
+pub fn merge_provider(base: &mut Value, patch: Value) -> Result<()> {
+    let (Value::Object(base), Value::Object(patch)) = (base, patch) else {
+        bail!("Provider configuration must be a JSON object");
+    };
+
+    for (key, value) in patch {
+        match (key.as_str(), base.get_mut(&key)) {
+            ("models", Some(Value::Array(models))) => merge_models(models, value)?,
+            (_, Some(existing)) => merge_value(existing, value),
+            (_, None) => {
+                base.insert(key, value);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Merge a patch's `models` array into the base models by ID
+fn merge_models(models: &mut Vec<Value>, patch: Value) -> Result<()> {
+    let Value::Array(patches) = patch else {
+        bail!("Provider 'models' must be an array");
+    };
+
+    for patch in patches {
+        let Some(id) = patch.get("id").and_then(Value::as_str).map(str::to_string) else {
+            bail!("Every model in a provider patch needs an 'id'");
+        };
+        match models
+            .iter_mut()
+            .find(|model| model.get("id").and_then(Value::as_str) == Some(id.as_str()))
+        {
+            Some(model) => merge_value(model, patch),
+            None => models.push(patch),
+        }
+    }
+    Ok(())
+}
+
+/// Recursively merge objects; anything else is replaced
+fn merge_value(base: &mut Value, patch: Value) {
+    match (base, patch) {
+        (Value::Object(base), Value::Object(patch)) => {
+            for (key, value) in patch {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_value(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, patch) => *base = patch,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_merge_provider() {
+        let mut base = json!({
+            "id": "azure",
+            "api_endpoint": "https://azure.example.com",
+            "default_headers": {"a": "1"},
+            "models": [
+                {"id": "gpt-4o", "cost_per_1m_in": 2.5, "cost_per_1m_out": 10.0},
+                {"id": "gpt-4o-mini", "cost_per_1m_in": 0.15}
+            ]
+        });
+
+        merge_provider(
+            &mut base,
+            json!({
+                "id": "azure",
+                "api_endpoint": "https://gateway.internal",
+                "default_headers": {"b": "2"},
+                "models": [
+                    {"id": "gpt-4o", "cost_per_1m_in": 2.0},
+                    {"id": "o3", "cost_per_1m_in": 1.0}
+                ]
+            }),
+        )
+        .unwrap();
+
+        assert_eq!(base["api_endpoint"], "https://gateway.internal");
+        assert_eq!(base["default_headers"], json!({"a": "1", "b": "2"}));
+
+        let models = base["models"].as_array().unwrap();
+        assert_eq!(models.len(), 3);
+        assert_eq!(models[0]["cost_per_1m_in"], 2.0);
+        assert_eq!(models[0]["cost_per_1m_out"], 10.0);
+        assert_eq!(models[1]["id"], "gpt-4o-mini");
+        assert_eq!(models[2]["id"], "o3");
+    }
+
+    #[test]
+    fn test_merge_rejects_models_without_id() {
+        let mut base = json!({"id": "azure", "models": []});
+        let patch = json!({"id": "azure", "models": [{"name": "nameless"}]});
+        assert!(merge_provider(&mut base, patch).is_err());
+    }
+}
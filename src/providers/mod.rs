@@ -1 +1,22 @@
+#[cfg(feature = "server")]
+pub mod changelog;
+pub mod embedded;
+#[cfg(feature = "server")]
+pub mod health;
+pub mod local;
+#[cfg(feature = "server")]
+pub mod merge;
+#[cfg(feature = "server")]
 pub mod registry;
+#[cfg(feature = "server")]
+pub mod search;
+#[cfg(feature = "redis")]
+pub mod shared;
+#[cfg(feature = "server")]
+pub mod sources;
+#[cfg(feature = "server")]
+pub mod stats;
+#[cfg(feature = "server")]
+pub mod tenants;
+#[cfg(feature = "server")]
+pub mod validate;
@@ -1,40 +1,235 @@
-use crate::Provider;
-use anyhow::Result;
-use parking_lot::RwLock;
+use super::changelog::{Change, Changelog};
+use super::embedded::EMBEDDED_CONFIGS;
+use super::health::IntegrityReport;
+use super::search::SearchMode;
+use super::stats::CatalogStats;
+use super::validate::{self, Problem, ValidationReport};
+use crate::{Model, Provider, ProviderModel};
+use anyhow::{Context, Result};
+use arc_swap::ArcSwap;
+use chrono::{DateTime, NaiveDate, Utc};
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use utoipa::ToSchema;
 
-/// Embedded provider configuration files
-/// These JSON files contain provider and model metadata
-const ANTHROPIC_CONFIG: &str = include_str!("configs/anthropic.json");
-const OPENAI_CONFIG: &str = include_str!("configs/openai.json");
-const GEMINI_CONFIG: &str = include_str!("configs/gemini.json");
-const AZURE_CONFIG: &str = include_str!("configs/azure.json");
-const BEDROCK_CONFIG: &str = include_str!("configs/bedrock.json");
-const VERTEXAI_CONFIG: &str = include_str!("configs/vertexai.json");
-const XAI_CONFIG: &str = include_str!("configs/xai.json");
-const ZAI_CONFIG: &str = include_str!("configs/zai.json");
-const GROQ_CONFIG: &str = include_str!("configs/groq.json");
-const OPENROUTER_CONFIG: &str = include_str!("configs/openrouter.json");
-const CEREBRAS_CONFIG: &str = include_str!("configs/cerebras.json");
-const VENICE_CONFIG: &str = include_str!("configs/venice.json");
-const CHUTES_CONFIG: &str = include_str!("configs/chutes.json");
-const DEEPSEEK_CONFIG: &str = include_str!("configs/deepseek.json");
-const HUGGINGFACE_CONFIG: &str = include_str!("configs/huggingface.json");
-const AIHUBMIX_CONFIG: &str = include_str!("configs/aihubmix.json");
-const OLLAMA_CONFIG: &str = include_str!("configs/ollama.json");
-const LMSTUDIO_CONFIG: &str = include_str!("configs/lmstudio.json");
+pub use super::embedded::DEFAULT_ALIASES;
+
+/// An embedded provider configuration that failed to parse
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoadFailure {
+    /// Display name of the provider
+    pub provider: String,
+
+    /// Embedded file name (e.g. `openai.json`)
+    #[serde(default)]
+    pub file: String,
+
+    /// JSON path where parsing stopped (e.g. `models[3].context_window`)
+    #[serde(default)]
+    pub path: String,
+
+    /// Parse error
+    pub error: String,
+}
+
+/// Self-contained copy of the catalog, for promoting it between instances
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RegistrySnapshot {
+    /// Registry version of the exporting instance
+    pub registry_version: u64,
+
+    /// Hex-encoded SHA-256 of `providers`, checked on import
+    pub dataset_hash: String,
+
+    #[schema(value_type = String, format = DateTime)]
+    pub exported_at: DateTime<Utc>,
+
+    pub providers: Vec<Provider>,
+}
+
+impl RegistrySnapshot {
+    /// Check that the providers still hash to `dataset_hash`
+    pub fn verify(&self) -> Result<()> {
+        let actual = ProviderRegistry::compute_dataset_hash(&self.providers)?;
+        if actual != self.dataset_hash {
+            anyhow::bail!(
+                "Snapshot dataset_hash {} does not match its providers ({})",
+                self.dataset_hash,
+                actual
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Published to subscribers (see [`ProviderRegistry::subscribe`]) whenever the catalog changes
+#[derive(Debug, Clone, PartialEq)]
+pub struct CatalogUpdate {
+    /// Registry version after the change
+    pub version: u64,
+
+    /// Hex-encoded SHA-256 of the catalog after the change
+    pub dataset_hash: String,
+
+    pub provider_count: usize,
+
+    pub model_count: usize,
+
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Search index entry pointing at one model in the provider list
+struct ModelIndexEntry {
+    provider: usize,
+    model: usize,
+    /// Lowercased model ID, used for matching
+    id: String,
+    /// Lowercased model name, used for matching
+    name: String,
+}
+
+/// Exact-match lookup tables into the provider list
+///
+/// When IDs are duplicated the first occurrence wins, matching a linear scan.
+#[derive(Default)]
+struct LookupIndex {
+    /// Provider ID to its position in the provider list
+    providers: HashMap<String, usize>,
+    /// (provider ID, model ID) to the provider and model positions
+    models: HashMap<(String, String), (usize, usize)>,
+    /// Model ID to every provider and model position offering it, in catalog order
+    offers: HashMap<String, Vec<(usize, usize)>>,
+}
+
+impl LookupIndex {
+    fn build(providers: &[Arc<Provider>]) -> Self {
+        let mut index = Self::default();
+        for (provider_idx, provider) in providers.iter().enumerate() {
+            index
+                .providers
+                .entry(provider.id.clone())
+                .or_insert(provider_idx);
+            for (model_idx, model) in provider.models.iter().enumerate() {
+                index
+                    .models
+                    .entry((provider.id.clone(), model.id.clone()))
+                    .or_insert((provider_idx, model_idx));
+                index
+                    .offers
+                    .entry(model.id.clone())
+                    .or_default()
+                    .push((provider_idx, model_idx));
+            }
+        }
+        index
+    }
+}
+
+/// Immutable catalog state, swapped as a whole on every mutation
+///
+/// Readers load the current catalog without locking; mutations build a new
+/// one, sharing the providers they did not touch.
+#[derive(Default)]
+struct Catalog {
+    providers: Arc<[Arc<Provider>]>,
+    model_index: Vec<ModelIndexEntry>,
+    /// Provider and model lookup by ID
+    lookup: LookupIndex,
+    /// SHA-256 of the catalog, identifying the dataset being served
+    dataset_hash: String,
+}
+
+/// How often model lifecycles are re-derived from their dates
+const LIFECYCLE_REFRESH_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// Today's date (UTC), against which model lifecycles are derived
+fn today() -> NaiveDate {
+    Utc::now().date_naive()
+}
+
+/// Set every model's lifecycle as of `today`, copying only the providers that change
+fn with_lifecycles(providers: Vec<Arc<Provider>>, today: NaiveDate) -> Vec<Arc<Provider>> {
+    providers
+        .into_iter()
+        .map(|mut provider| {
+            if provider
+                .models
+                .iter()
+                .any(|m| m.lifecycle != m.lifecycle_on(today))
+            {
+                for model in &mut Arc::make_mut(&mut provider).models {
+                    model.lifecycle = model.lifecycle_on(today);
+                }
+            }
+            provider
+        })
+        .collect()
+}
+
+impl Catalog {
+    /// Index the providers, deriving model lifecycles as of today
+    fn build(providers: Vec<Arc<Provider>>) -> Result<Self> {
+        let providers = with_lifecycles(providers, today());
+        Ok(Self {
+            model_index: ProviderRegistry::build_model_index(&providers),
+            lookup: LookupIndex::build(&providers),
+            dataset_hash: ProviderRegistry::compute_dataset_hash(&providers)?,
+            providers: providers.into(),
+        })
+    }
+}
 
 /// Provider registry that manages all available AI providers
 pub struct ProviderRegistry {
-    providers: Arc<RwLock<Vec<Provider>>>,
+    catalog: Arc<ArcSwap<Catalog>>,
+    /// Serializes mutations, so none is lost to a concurrent one
+    write_lock: Arc<Mutex<()>>,
+    /// Embedded configurations that failed to parse on the last load
+    load_failures: Arc<RwLock<Vec<LoadFailure>>>,
+    /// Incremented on every runtime mutation of the catalog
+    version: Arc<AtomicU64>,
+    /// Additions, removals and price changes per version
+    changelog: Arc<RwLock<Changelog>>,
+    /// When the catalog being served was loaded or last changed
+    loaded_at: Arc<RwLock<DateTime<Utc>>>,
+    /// Change notifications for subscribers
+    updates: Arc<watch::Sender<CatalogUpdate>>,
+    /// Lowercased alias to canonical provider ID
+    aliases: Arc<RwLock<HashMap<String, String>>>,
 }
 
 impl ProviderRegistry {
     /// Create a new provider registry and load all providers
     pub fn new() -> Result<Self> {
         let registry = Self {
-            providers: Arc::new(RwLock::new(Vec::new())),
+            catalog: Arc::new(ArcSwap::from_pointee(Catalog::default())),
+            write_lock: Arc::new(Mutex::new(())),
+            load_failures: Arc::new(RwLock::new(Vec::new())),
+            version: Arc::new(AtomicU64::new(1)),
+            changelog: Arc::new(RwLock::new(Changelog::new(1, &[]))),
+            loaded_at: Arc::new(RwLock::new(Utc::now())),
+            updates: Arc::new(watch::Sender::new(CatalogUpdate {
+                version: 1,
+                dataset_hash: String::new(),
+                provider_count: 0,
+                model_count: 0,
+                updated_at: Utc::now(),
+            })),
+            aliases: Arc::new(RwLock::new(HashMap::new())),
         };
+        registry.add_aliases(
+            DEFAULT_ALIASES
+                .iter()
+                .map(|&(alias, id)| (alias.to_string(), id.to_string())),
+        );
 
         registry.load_providers()?;
         Ok(registry)
@@ -42,78 +237,597 @@ impl ProviderRegistry {
 
     /// Load all provider configurations from embedded JSON files
     fn load_providers(&self) -> Result<()> {
-        let mut providers = self.providers.write();
-
-        // Helper macro to load a provider configuration
-        macro_rules! load_provider {
-            ($config:expr, $name:expr) => {
-                if let Ok(provider) = serde_json::from_str::<Provider>($config) {
-                    providers.push(provider);
-                } else {
-                    tracing::warn!("Failed to load {} provider configuration", $name);
+        let _write = self.write_lock.lock();
+        let mut providers = Vec::new();
+        let mut failures = Vec::new();
+
+        for &(name, file, config) in EMBEDDED_CONFIGS {
+            let mut deserializer = serde_json::Deserializer::from_str(config);
+            match serde_path_to_error::deserialize::<_, Provider>(&mut deserializer) {
+                Ok(provider) => providers.push(Arc::new(provider)),
+                Err(e) => {
+                    let path = e.path().to_string();
+                    let error = e.into_inner().to_string();
+                    tracing::warn!(
+                        "Failed to load {} provider configuration ({} at {}): {}",
+                        name,
+                        file,
+                        path,
+                        error
+                    );
+                    failures.push(LoadFailure {
+                        provider: name.to_string(),
+                        file: file.to_string(),
+                        path,
+                        error,
+                    });
                 }
-            };
-        }
-
-        // Load all provider configurations
-        load_provider!(ANTHROPIC_CONFIG, "Anthropic");
-        load_provider!(OPENAI_CONFIG, "OpenAI");
-        load_provider!(GEMINI_CONFIG, "Gemini");
-        load_provider!(AZURE_CONFIG, "Azure");
-        load_provider!(BEDROCK_CONFIG, "Bedrock");
-        load_provider!(VERTEXAI_CONFIG, "VertexAI");
-        load_provider!(XAI_CONFIG, "xAI");
-        load_provider!(ZAI_CONFIG, "zAI");
-        load_provider!(GROQ_CONFIG, "Groq");
-        load_provider!(OPENROUTER_CONFIG, "OpenRouter");
-        load_provider!(CEREBRAS_CONFIG, "Cerebras");
-        load_provider!(VENICE_CONFIG, "Venice");
-        load_provider!(CHUTES_CONFIG, "Chutes");
-        load_provider!(DEEPSEEK_CONFIG, "DeepSeek");
-        load_provider!(HUGGINGFACE_CONFIG, "HuggingFace");
-        load_provider!(AIHUBMIX_CONFIG, "AIHubMix");
-        load_provider!(OLLAMA_CONFIG, "Ollama");
-        load_provider!(LMSTUDIO_CONFIG, "LM Studio");
+            }
+        }
+
+        let catalog = Catalog::build(providers)?;
+        *self.load_failures.write() = failures;
+        *self.changelog.write() =
+            Changelog::new(self.version(), catalog.providers.iter().map(|p| &**p));
+        *self.loaded_at.write() = Utc::now();
+        self.catalog.store(Arc::new(catalog));
+        self.publish();
 
         Ok(())
     }
 
-    /// Get all providers
+    /// Strictly validate every embedded configuration
+    ///
+    /// Unlike loading, which skips a config that fails to parse, this
+    /// collects every problem in every file: parse errors with their JSON
+    /// path, unknown fields and semantic problems such as negative costs or
+    /// default model IDs that match no model.
+    pub fn validate_embedded() -> ValidationReport {
+        let mut report = ValidationReport::default();
+        for &(_, file, config) in EMBEDDED_CONFIGS {
+            report.add(file, validate::check_config(config));
+        }
+        report
+    }
+
+    /// Build the cross-provider model search index
+    fn build_model_index(providers: &[Arc<Provider>]) -> Vec<ModelIndexEntry> {
+        providers
+            .iter()
+            .enumerate()
+            .flat_map(|(provider_idx, provider)| {
+                provider
+                    .models
+                    .iter()
+                    .enumerate()
+                    .map(move |(model_idx, model)| ModelIndexEntry {
+                        provider: provider_idx,
+                        model: model_idx,
+                        id: model.id.to_lowercase(),
+                        name: model.name.to_lowercase(),
+                    })
+            })
+            .collect()
+    }
+
+    /// Hash the serialized catalog (hex-encoded SHA-256)
+    ///
+    /// Serializes through `serde_json::Value` so map keys (e.g. provider
+    /// `default_headers`) are hashed in sorted order.
+    fn compute_dataset_hash<P: Serialize>(providers: &[P]) -> Result<String> {
+        let bytes = serde_json::to_vec(&serde_json::to_value(providers)?)?;
+        Ok(Sha256::digest(&bytes)
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect())
+    }
+
+    /// Swap in a mutated provider list, bump the version and log the changes
+    ///
+    /// Callers must hold `write_lock`.
+    fn commit(&self, providers: Vec<Arc<Provider>>) -> Result<u64> {
+        let catalog = Catalog::build(providers)?;
+        let version = self.version.fetch_add(1, Ordering::SeqCst) + 1;
+        self.changelog
+            .write()
+            .record(version, catalog.providers.iter().map(|p| &**p));
+        *self.loaded_at.write() = Utc::now();
+        self.catalog.store(Arc::new(catalog));
+        self.publish();
+        Ok(version)
+    }
+
+    /// Notify subscribers of the catalog's new state
+    fn publish(&self) {
+        let catalog = self.catalog.load();
+        self.updates.send_replace(CatalogUpdate {
+            version: self.version(),
+            dataset_hash: catalog.dataset_hash.clone(),
+            provider_count: catalog.providers.len(),
+            model_count: catalog.providers.iter().map(|p| p.models.len()).sum(),
+            updated_at: self.loaded_at(),
+        });
+    }
+
+    /// Subscribe to catalog changes (reloads, syncs, admin edits, imports)
+    ///
+    /// The receiver always holds the latest [`CatalogUpdate`] and is marked
+    /// changed after every mutation. Updates that arrive faster than they
+    /// are read coalesce into the latest one; subscribers that need every
+    /// change can pass the last version they saw to [`changes_since`].
+    ///
+    /// [`changes_since`]: Self::changes_since
+    pub fn subscribe(&self) -> watch::Receiver<CatalogUpdate> {
+        self.updates.subscribe()
+    }
+
+    /// When the catalog being served was loaded or last changed
+    pub fn loaded_at(&self) -> DateTime<Utc> {
+        *self.loaded_at.read()
+    }
+
+    /// Changes made after version `since`, oldest first
+    ///
+    /// Returns `Err` with the oldest version changes can still be requested
+    /// from when some changes after `since` have been dropped from the log.
+    pub fn changes_since(&self, since: u64) -> std::result::Result<Vec<Change>, u64> {
+        self.changelog.read().since(since)
+    }
+
+    /// Catalog version, starting at 1 and incremented by every mutation
+    pub fn version(&self) -> u64 {
+        self.version.load(Ordering::SeqCst)
+    }
+
+    /// Add a new provider
+    ///
+    /// Fails without changing the catalog if the provider does not pass
+    /// [validation](super::validate::provider_problems) or its ID is taken.
+    pub fn add_provider(&self, provider: Provider) -> Result<()> {
+        ensure_valid(&provider.id, validate::provider_problems(&provider))?;
+
+        let _write = self.write_lock.lock();
+        let catalog = self.catalog.load();
+        if catalog.lookup.providers.contains_key(&provider.id) {
+            anyhow::bail!("Provider '{}' already exists", provider.id);
+        }
+        let mut providers = catalog.providers.to_vec();
+        providers.push(Arc::new(provider));
+        self.commit(providers)?;
+        Ok(())
+    }
+
+    /// Insert a provider, replacing any existing provider with the same ID
+    ///
+    /// Unlike [`add_provider`](Self::add_provider) this does not validate,
+    /// so partially filled configurations can still be loaded. Returns the
+    /// replaced provider.
+    pub fn upsert_provider(&self, provider: Provider) -> Result<Option<Provider>> {
+        let _write = self.write_lock.lock();
+        let catalog = self.catalog.load();
+        let mut providers = catalog.providers.to_vec();
+        let previous = match catalog.lookup.providers.get(&provider.id) {
+            Some(&position) => Some(std::mem::replace(
+                &mut providers[position],
+                Arc::new(provider),
+            )),
+            None => {
+                providers.push(Arc::new(provider));
+                None
+            }
+        };
+        self.commit(providers)?;
+        Ok(previous.map(|p| Provider::clone(&p)))
+    }
+
+    /// Remove a provider, returning it if it existed
+    pub fn remove_provider(&self, id: &str) -> Result<Option<Provider>> {
+        let _write = self.write_lock.lock();
+        let catalog = self.catalog.load();
+        let Some(&position) = catalog.lookup.providers.get(id) else {
+            return Ok(None);
+        };
+        let mut providers = catalog.providers.to_vec();
+        let removed = providers.remove(position);
+        self.commit(providers)?;
+        Ok(Some(Provider::clone(&removed)))
+    }
+
+    /// Modify a provider in place, returning `None` if it does not exist
+    ///
+    /// The update is applied to a copy, which is discarded and an error
+    /// returned if it does not pass validation or was renamed to a taken ID.
+    /// Otherwise the catalog is reindexed and its version bumped.
+    pub fn update_provider<T>(
+        &self,
+        id: &str,
+        update: impl FnOnce(&mut Provider) -> T,
+    ) -> Result<Option<T>> {
+        let _write = self.write_lock.lock();
+        let catalog = self.catalog.load();
+        let Some(&position) = catalog.lookup.providers.get(id) else {
+            return Ok(None);
+        };
+
+        let mut updated = Provider::clone(&catalog.providers[position]);
+        let result = update(&mut updated);
+        ensure_valid(id, validate::provider_problems(&updated))?;
+        if updated.id != id && catalog.lookup.providers.contains_key(&updated.id) {
+            anyhow::bail!("Provider '{}' already exists", updated.id);
+        }
+
+        let mut providers = catalog.providers.to_vec();
+        providers[position] = Arc::new(updated);
+        self.commit(providers)?;
+        Ok(Some(result))
+    }
+
+    /// Add a model to a provider, replacing any model with the same ID
+    ///
+    /// Fails if the provider does not exist or the model does not pass
+    /// [validation](super::validate::model_problems). Returns the replaced
+    /// model.
+    pub fn upsert_model(&self, provider_id: &str, model: Model) -> Result<Option<Model>> {
+        ensure_valid(&model.id, validate::model_problems(&model, ""))?;
+
+        self.update_provider(provider_id, |provider| {
+            match provider.models.iter_mut().find(|m| m.id == model.id) {
+                Some(existing) => Some(std::mem::replace(existing, model)),
+                None => {
+                    provider.models.push(model);
+                    None
+                }
+            }
+        })?
+        .with_context(|| format!("Provider '{}' not found", provider_id))
+    }
+
+    /// Export the current catalog as a snapshot
+    pub fn snapshot(&self) -> Result<RegistrySnapshot> {
+        let catalog = self.catalog.load();
+        Ok(RegistrySnapshot {
+            registry_version: self.version(),
+            dataset_hash: catalog.dataset_hash.clone(),
+            exported_at: Utc::now(),
+            providers: catalog
+                .providers
+                .iter()
+                .map(|p| Provider::clone(p))
+                .collect(),
+        })
+    }
+
+    /// Swap in a whole new catalog if its content differs from the current one
+    ///
+    /// Returns whether the catalog changed.
+    pub fn replace_all(&self, new: Vec<Provider>) -> Result<bool> {
+        let new = with_lifecycles(new.into_iter().map(Arc::new).collect(), today());
+        let hash = Self::compute_dataset_hash(&new)?;
+        let _write = self.write_lock.lock();
+        if hash == self.catalog.load().dataset_hash {
+            return Ok(false);
+        }
+        self.commit(new)?;
+        Ok(true)
+    }
+
+    /// Re-derive model lifecycles as of today, committing if any changed
+    ///
+    /// Returns whether the catalog changed.
+    pub fn refresh_lifecycles(&self) -> Result<bool> {
+        let _write = self.write_lock.lock();
+        let current = self.catalog.load().providers.clone();
+        let refreshed = with_lifecycles(current.to_vec(), today());
+        if current
+            .iter()
+            .zip(&refreshed)
+            .all(|(before, after)| Arc::ptr_eq(before, after))
+        {
+            return Ok(false);
+        }
+        self.commit(refreshed)?;
+        Ok(true)
+    }
+
+    /// Refresh model lifecycles in the background, so models are demoted
+    /// on their deprecation and retirement dates without a catalog change
+    pub fn spawn_lifecycle_updates(self: Arc<Self>) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(LIFECYCLE_REFRESH_INTERVAL);
+            loop {
+                interval.tick().await;
+                match self.refresh_lifecycles() {
+                    Ok(true) => {
+                        tracing::info!("Model lifecycles updated (version {})", self.version())
+                    }
+                    Ok(false) => {}
+                    Err(e) => tracing::warn!("Failed to update model lifecycles: {:#}", e),
+                }
+            }
+        })
+    }
+
+    /// Drop every provider for which `keep` returns false
+    ///
+    /// Returns the IDs of the removed providers.
+    pub fn retain_providers(&self, keep: impl Fn(&Provider) -> bool) -> Result<Vec<String>> {
+        let _write = self.write_lock.lock();
+        let (kept, removed): (Vec<_>, Vec<_>) = self
+            .catalog
+            .load()
+            .providers
+            .iter()
+            .cloned()
+            .partition(|p| keep(p));
+
+        if !removed.is_empty() {
+            self.commit(kept)?;
+        }
+        Ok(removed.into_iter().map(|p| p.id.clone()).collect())
+    }
+
+    /// Load provider files (`*.json`) from a directory, adding new providers
+    /// and replacing loaded providers with the same ID
+    ///
+    /// Returns the number of providers loaded.
+    pub fn load_dir(&self, dir: &Path) -> Result<usize> {
+        let paths = Self::json_files(dir)?;
+        for path in &paths {
+            let contents = std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            let provider: Provider = serde_json::from_str(&contents)
+                .with_context(|| format!("Invalid provider configuration {}", path.display()))?;
+            self.upsert_provider(provider)?;
+        }
+
+        Ok(paths.len())
+    }
+
+    /// Load provider files (`*.json`) from a directory, deep-merging files
+    /// whose `id` matches a loaded provider into it (see [`merge`])
+    ///
+    /// Files for unknown IDs must be complete providers. Returns the number
+    /// of providers loaded.
+    ///
+    /// [`merge`]: super::merge
+    pub fn merge_dir(&self, dir: &Path) -> Result<usize> {
+        let paths = Self::json_files(dir)?;
+        for path in &paths {
+            let contents = std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            let provider = serde_json::from_str(&contents)
+                .map_err(anyhow::Error::from)
+                .and_then(|patch| self.merged(patch))
+                .with_context(|| format!("Invalid provider configuration {}", path.display()))?;
+            self.upsert_provider(provider)?;
+        }
+
+        Ok(paths.len())
+    }
+
+    /// Apply a (possibly partial) provider configuration to the loaded provider with its ID
+    fn merged(&self, patch: serde_json::Value) -> Result<Provider> {
+        let id = patch
+            .get("id")
+            .and_then(|id| id.as_str())
+            .context("Provider configuration has no 'id'")?;
+
+        match self.get_by_canonical_id(id)? {
+            Some(existing) => {
+                let mut merged = serde_json::to_value(existing)?;
+                super::merge::merge_provider(&mut merged, patch)?;
+                Ok(serde_json::from_value(merged)?)
+            }
+            None => Ok(serde_json::from_value(patch)?),
+        }
+    }
+
+    /// Sorted `*.json` paths in a directory
+    fn json_files(dir: &Path) -> Result<Vec<PathBuf>> {
+        let mut paths: Vec<_> = std::fs::read_dir(dir)
+            .with_context(|| format!("Failed to read provider directory {}", dir.display()))?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .collect();
+        paths.sort();
+        Ok(paths)
+    }
+
+    /// Hex-encoded SHA-256 of the catalog currently being served
+    pub fn dataset_hash(&self) -> String {
+        self.catalog.load().dataset_hash.clone()
+    }
+
+    /// Embedded provider configurations that failed to parse
+    pub fn load_failures(&self) -> Vec<LoadFailure> {
+        self.load_failures.read().clone()
+    }
+
+    /// Get an owned copy of all providers
+    ///
+    /// Deep-copies the catalog; use [`providers`](Self::providers) to read it.
     pub fn get_all(&self) -> Result<Vec<Provider>> {
-        let providers = self.providers.read();
-        Ok(providers.clone())
+        Ok(self
+            .providers()
+            .iter()
+            .map(|p| Provider::clone(p))
+            .collect())
+    }
+
+    /// All providers, as a shared snapshot of the catalog being served
+    ///
+    /// Cheap to call: the snapshot is reference-counted rather than copied,
+    /// and later mutations do not affect it.
+    pub fn providers(&self) -> Arc<[Arc<Provider>]> {
+        self.catalog.load().providers.clone()
+    }
+
+    /// All providers together with the dataset hash of that same snapshot
+    pub fn providers_with_hash(&self) -> (Arc<[Arc<Provider>]>, String) {
+        let catalog = self.catalog.load();
+        (catalog.providers.clone(), catalog.dataset_hash.clone())
     }
 
     /// Get a specific provider by ID
+    ///
+    /// `id` may also be an alias (see [`resolve_id`](Self::resolve_id)).
     pub fn get_by_id(&self, id: &str) -> Result<Option<Provider>> {
-        let providers = self.providers.read();
-        Ok(providers.iter().find(|p| p.id == id).cloned())
+        self.get_by_canonical_id(&self.resolve_id(id))
     }
 
-    /// Get a specific model from a provider
+    /// Get a provider by its exact ID, ignoring aliases
+    pub fn get_by_canonical_id(&self, id: &str) -> Result<Option<Provider>> {
+        let catalog = self.catalog.load();
+        Ok(catalog
+            .lookup
+            .providers
+            .get(id)
+            .map(|&idx| Provider::clone(&catalog.providers[idx])))
+    }
+
+    /// Map a provider ID or alias to the provider ID it refers to
+    ///
+    /// Loaded provider IDs always win over aliases, and alias matching is
+    /// case-insensitive. Unknown IDs are returned unchanged.
+    pub fn resolve_id(&self, id: &str) -> String {
+        if self.catalog.load().lookup.providers.contains_key(id) {
+            return id.to_string();
+        }
+        self.aliases
+            .read()
+            .get(&id.to_ascii_lowercase())
+            .cloned()
+            .unwrap_or_else(|| id.to_string())
+    }
+
+    /// Add provider ID aliases (alias, canonical ID), replacing existing ones
+    pub fn add_aliases(&self, aliases: impl IntoIterator<Item = (String, String)>) {
+        let mut table = self.aliases.write();
+        for (alias, id) in aliases {
+            table.insert(alias.to_ascii_lowercase(), id);
+        }
+    }
+
+    /// Every alias and the provider ID it refers to
+    pub fn aliases(&self) -> HashMap<String, String> {
+        self.aliases.read().clone()
+    }
+
+    /// Get a specific model from a provider (the provider ID may be an alias)
     pub fn get_model(&self, provider_id: &str, model_id: &str) -> Result<Option<crate::Model>> {
-        let providers = self.providers.read();
-        Ok(providers
+        let key = (self.resolve_id(provider_id), model_id.to_string());
+        let catalog = self.catalog.load();
+        let providers = &catalog.providers;
+        Ok(catalog
+            .lookup
+            .models
+            .get(&key)
+            .map(|&(provider, model)| providers[provider].models[model].clone()))
+    }
+
+    /// Every provider offering a model with exactly this ID, in catalog order
+    ///
+    /// Aggregators (e.g. OpenRouter) often list the same model ID as the
+    /// vendor itself.
+    pub fn find_model(&self, model_id: &str) -> Result<Vec<ProviderModel>> {
+        let catalog = self.catalog.load();
+        let providers = &catalog.providers;
+        Ok(catalog
+            .lookup
+            .offers
+            .get(model_id)
+            .into_iter()
+            .flatten()
+            .map(|&(provider, model)| ProviderModel {
+                provider_id: providers[provider].id.clone(),
+                model: providers[provider].models[model].clone(),
+            })
+            .collect())
+    }
+
+    /// Search model IDs and names across all providers
+    ///
+    /// Matching is a case-insensitive substring search. Results are returned in
+    /// catalog order, each annotated with the ID of the provider offering it.
+    pub fn search_models(&self, query: &str) -> Result<Vec<ProviderModel>> {
+        self.search(query, SearchMode::Substring)
+    }
+
+    /// Search model IDs and names across all providers with the given mode
+    ///
+    /// A model matches if its ID or its name does; fuzzy results are ordered
+    /// best match first (ties in catalog order), others in catalog order.
+    pub fn search(&self, query: &str, mode: SearchMode) -> Result<Vec<ProviderModel>> {
+        let needle = query.trim().to_lowercase();
+        let catalog = self.catalog.load();
+
+        let mut hits: Vec<(u32, &ModelIndexEntry)> = catalog
+            .model_index
             .iter()
-            .find(|p| p.id == provider_id)
-            .and_then(|p| p.get_model(model_id))
-            .cloned())
+            .filter_map(|entry| {
+                let score = mode
+                    .score(&needle, &entry.id)
+                    .max(mode.score(&needle, &entry.name))?;
+                Some((score, entry))
+            })
+            .collect();
+        hits.sort_by_key(|&(score, _)| std::cmp::Reverse(score));
+
+        Ok(hits
+            .into_iter()
+            .map(|(_, entry)| {
+                let provider = &catalog.providers[entry.provider];
+                ProviderModel {
+                    provider_id: provider.id.clone(),
+                    model: provider.models[entry.model].clone(),
+                }
+            })
+            .collect())
     }
 
     /// Get the total number of providers
     pub fn count(&self) -> usize {
-        self.providers.read().len()
+        self.catalog.load().providers.len()
     }
 
     /// Get the total number of models across all providers
     pub fn model_count(&self) -> usize {
-        self.providers.read().iter().map(|p| p.models.len()).sum()
+        self.catalog
+            .load()
+            .providers
+            .iter()
+            .map(|p| p.models.len())
+            .sum()
+    }
+
+    /// Verify catalog integrity: configs parsed, providers have models and
+    /// default model IDs resolve
+    pub fn check_integrity(&self) -> Result<IntegrityReport> {
+        Ok(IntegrityReport::check(
+            self.providers().iter().map(|p| &**p),
+            self.load_failures(),
+        ))
+    }
+
+    /// Compute aggregate statistics over the catalog
+    pub fn stats(&self) -> Result<CatalogStats> {
+        Ok(CatalogStats::compute(&self.providers()))
     }
 }
 
+/// Turn validation problems into an error naming `what`
+fn ensure_valid(what: &str, problems: Vec<Problem>) -> Result<()> {
+    if problems.is_empty() {
+        return Ok(());
+    }
+    let problems: Vec<_> = problems.iter().map(Problem::to_string).collect();
+    anyhow::bail!("Invalid '{}': {}", what, problems.join("; "))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::Lifecycle;
 
     #[test]
     fn test_registry_creation() {
@@ -121,6 +835,12 @@ mod tests {
         assert!(registry.is_ok());
     }
 
+    #[test]
+    fn test_embedded_configs_pass_strict_validation() {
+        let report = ProviderRegistry::validate_embedded();
+        assert!(report.is_empty(), "{}", report);
+    }
+
     #[test]
     fn test_get_all_providers() {
         let registry = ProviderRegistry::new().unwrap();
@@ -128,6 +848,31 @@ mod tests {
         assert!(!providers.is_empty());
     }
 
+    #[test]
+    fn test_providers_snapshot_is_shared_and_stable() {
+        let registry = ProviderRegistry::new().unwrap();
+        let before = registry.providers();
+        assert_eq!(before.len(), registry.count());
+
+        registry
+            .update_provider("openai", |p| p.name = "Renamed".to_string())
+            .unwrap();
+        let after = registry.providers();
+
+        // Earlier snapshots keep the catalog they were taken from
+        let openai =
+            |providers: &[Arc<Provider>]| providers.iter().position(|p| p.id == "openai").unwrap();
+        assert_eq!(before[openai(&before)].name, "OpenAI");
+        assert_eq!(after[openai(&after)].name, "Renamed");
+
+        // Untouched providers are shared, not copied
+        assert!(Arc::ptr_eq(&before[0], &after[0]));
+        assert!(!Arc::ptr_eq(
+            &before[openai(&before)],
+            &after[openai(&after)]
+        ));
+    }
+
     #[test]
     fn test_provider_count() {
         let registry = ProviderRegistry::new().unwrap();
@@ -190,4 +935,423 @@ mod tests {
         let nonexistent = registry.get_by_id("nonexistent").unwrap();
         assert!(nonexistent.is_none());
     }
+
+    #[test]
+    fn test_search_models() {
+        let registry = ProviderRegistry::new().unwrap();
+
+        let results = registry.search_models("GPT-4").unwrap();
+        assert!(!results.is_empty());
+        assert!(results.iter().any(|r| r.provider_id == "openai"));
+        for result in &results {
+            let haystack = format!("{} {}", result.model.id, result.model.name).to_lowercase();
+            assert!(haystack.contains("gpt-4"));
+        }
+
+        assert!(registry
+            .search_models("definitely-not-a-model")
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_stats() {
+        let registry = ProviderRegistry::new().unwrap();
+        let stats = registry.stats().unwrap();
+
+        assert_eq!(stats.provider_count, registry.count());
+        assert_eq!(stats.model_count, registry.model_count());
+        assert_eq!(stats.providers.len(), registry.count());
+        assert!(stats.cheapest_model.is_some());
+        assert!(stats.largest_context_window.is_some());
+    }
+
+    #[test]
+    fn test_dataset_hash() {
+        let hash = ProviderRegistry::new().unwrap().dataset_hash();
+        assert_eq!(hash.len(), 64);
+        assert!(hash.chars().all(|c| c.is_ascii_hexdigit()));
+
+        // The embedded dataset hashes identically on every load
+        assert_eq!(hash, ProviderRegistry::new().unwrap().dataset_hash());
+    }
+
+    #[test]
+    fn test_no_load_failures() {
+        let registry = ProviderRegistry::new().unwrap();
+        assert!(registry.load_failures().is_empty());
+    }
+
+    #[test]
+    fn test_check_integrity() {
+        let registry = ProviderRegistry::new().unwrap();
+        let report = registry.check_integrity().unwrap();
+
+        assert!(report.is_serving());
+        assert!(report.load_failures.is_empty());
+        assert_eq!(report.providers.len(), registry.count());
+        assert!(report.providers.iter().all(|p| p.model_count > 0));
+    }
+
+    #[test]
+    fn test_mutations_bump_version() {
+        let registry = ProviderRegistry::new().unwrap();
+        let hash = registry.dataset_hash();
+        assert_eq!(registry.version(), 1);
+
+        let provider = Provider::new("Acme".into(), "acme".into(), "openai".into());
+        assert!(registry.upsert_provider(provider).unwrap().is_none());
+        assert_eq!(registry.version(), 2);
+        assert_eq!(registry.count(), 19);
+        assert_ne!(registry.dataset_hash(), hash);
+
+        let renamed = registry
+            .update_provider("acme", |p| {
+                p.name = "Acme Corp".into();
+                p.name.clone()
+            })
+            .unwrap();
+        assert_eq!(renamed.as_deref(), Some("Acme Corp"));
+        assert!(registry.update_provider("nope", |_| ()).unwrap().is_none());
+
+        assert!(registry.remove_provider("acme").unwrap().is_some());
+        assert!(registry.remove_provider("acme").unwrap().is_none());
+        assert_eq!(registry.version(), 4);
+        assert_eq!(registry.dataset_hash(), hash);
+    }
+
+    #[test]
+    fn test_lookup_follows_mutations() {
+        let registry = ProviderRegistry::new().unwrap();
+        let providers = registry.get_all().unwrap();
+        let last = providers.last().unwrap();
+        let model_id = last.models[0].id.clone();
+
+        // Removing the first provider shifts every position
+        registry.remove_provider(&providers[0].id).unwrap();
+        assert!(registry.get_by_id(&providers[0].id).unwrap().is_none());
+        assert_eq!(registry.get_by_id(&last.id).unwrap().unwrap().id, last.id);
+        assert_eq!(
+            registry.get_model(&last.id, &model_id).unwrap().unwrap().id,
+            model_id
+        );
+
+        registry
+            .update_provider(&last.id, |p| p.models.clear())
+            .unwrap();
+        assert!(registry.get_model(&last.id, &model_id).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_search_modes() {
+        let registry = ProviderRegistry::new().unwrap();
+
+        let prefix = registry
+            .search("claude-sonnet", SearchMode::Prefix)
+            .unwrap();
+        assert!(!prefix.is_empty());
+        assert!(prefix.iter().all(|r| {
+            r.model.id.to_lowercase().starts_with("claude-sonnet")
+                || r.model.name.to_lowercase().starts_with("claude-sonnet")
+        }));
+
+        // Name prefixes count too
+        let by_name = registry
+            .search("Claude Sonnet", SearchMode::Prefix)
+            .unwrap();
+        assert!(!by_name.is_empty());
+
+        let fuzzy = registry.search("cls45", SearchMode::Fuzzy).unwrap();
+        assert!(fuzzy
+            .iter()
+            .any(|r| r.model.id == "claude-sonnet-4-5-20250929"));
+        assert!(registry
+            .search("cls45", SearchMode::Substring)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_find_model() {
+        let registry = ProviderRegistry::new().unwrap();
+        let (provider_id, model_id) = {
+            let providers = registry.get_all().unwrap();
+            (providers[0].id.clone(), providers[0].models[0].id.clone())
+        };
+
+        // A second provider offering the same model
+        let mut reseller = Provider::new("Reseller".into(), "reseller".into(), "openai".into());
+        reseller.models = vec![registry
+            .get_model(&provider_id, &model_id)
+            .unwrap()
+            .unwrap()];
+        registry.upsert_provider(reseller).unwrap();
+
+        let offers = registry.find_model(&model_id).unwrap();
+        let providers: Vec<&str> = offers.iter().map(|o| o.provider_id.as_str()).collect();
+        assert_eq!(providers.first(), Some(&provider_id.as_str()));
+        assert_eq!(providers.last(), Some(&"reseller"));
+        assert!(offers.iter().all(|o| o.model.id == model_id));
+
+        assert!(registry.find_model("no-such-model").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_load_dir() {
+        let dir = std::env::temp_dir().join(format!("crabrace-overlay-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let registry = ProviderRegistry::new().unwrap();
+        let mut anthropic = registry.get_by_id("anthropic").unwrap().unwrap();
+        anthropic.name = "Anthropic (overlay)".into();
+        std::fs::write(
+            dir.join("anthropic.json"),
+            serde_json::to_string(&anthropic).unwrap(),
+        )
+        .unwrap();
+        std::fs::write(dir.join("README.txt"), "ignored").unwrap();
+
+        let mut gateway = anthropic.clone();
+        gateway.id = "internal-gateway".into();
+        gateway.name = "Internal Gateway".into();
+        std::fs::write(
+            dir.join("internal-gateway.json"),
+            serde_json::to_string(&gateway).unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(registry.load_dir(&dir).unwrap(), 2);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(registry.count(), 19);
+        let anthropic = registry.get_by_id("anthropic").unwrap().unwrap();
+        assert_eq!(anthropic.name, "Anthropic (overlay)");
+        assert!(registry.get_by_id("internal-gateway").unwrap().is_some());
+
+        // A missing directory is an error
+        assert!(registry.load_dir(&dir).is_err());
+    }
+
+    #[test]
+    fn test_snapshot_verify() {
+        let registry = ProviderRegistry::new().unwrap();
+        let mut snapshot = registry.snapshot().unwrap();
+        assert_eq!(snapshot.dataset_hash, registry.dataset_hash());
+        assert_eq!(snapshot.providers.len(), 18);
+        assert!(snapshot.verify().is_ok());
+
+        // Round-trips through JSON
+        let json = serde_json::to_string(&snapshot).unwrap();
+        let parsed: RegistrySnapshot = serde_json::from_str(&json).unwrap();
+        assert!(parsed.verify().is_ok());
+
+        snapshot.providers[0].models[0].cost_per_1m_in += 1.0;
+        assert!(snapshot.verify().is_err());
+    }
+
+    #[test]
+    fn test_replace_all() {
+        let registry = ProviderRegistry::new().unwrap();
+        let version = registry.version();
+        let mut providers = registry.get_all().unwrap();
+
+        assert!(!registry.replace_all(providers.clone()).unwrap());
+        assert_eq!(registry.version(), version);
+
+        providers.truncate(2);
+        assert!(registry.replace_all(providers).unwrap());
+        assert_eq!(registry.count(), 2);
+        assert_eq!(registry.version(), version + 1);
+    }
+
+    #[test]
+    fn test_lifecycles() {
+        let registry = ProviderRegistry::new().unwrap();
+        let opus = registry
+            .get_model("anthropic", "claude-3-opus-20240229")
+            .unwrap()
+            .unwrap();
+        assert_eq!(opus.lifecycle, Lifecycle::Retired);
+
+        // Lifecycles are already current, so a refresh changes nothing
+        let version = registry.version();
+        assert!(!registry.refresh_lifecycles().unwrap());
+        assert_eq!(registry.version(), version);
+
+        // Models pushed with a stale lifecycle get today's
+        let mut providers = registry.get_all().unwrap();
+        for model in &mut providers[0].models {
+            model.lifecycle = Lifecycle::Active;
+            model.deprecated_at = Some(NaiveDate::from_ymd_opt(2020, 1, 1).unwrap());
+        }
+        let id = providers[0].id.clone();
+        assert!(registry.replace_all(providers).unwrap());
+        let provider = registry.get_by_id(&id).unwrap().unwrap();
+        assert!(provider.models.iter().all(|m| !m.is_active()));
+    }
+
+    #[test]
+    fn test_retain_providers() {
+        let registry = ProviderRegistry::new().unwrap();
+        let version = registry.version();
+
+        let removed = registry
+            .retain_providers(|p| p.id != "openai" && p.id != "xai")
+            .unwrap();
+        assert_eq!(removed, vec!["openai", "xai"]);
+        assert_eq!(registry.count(), 16);
+        assert!(registry.get_by_id("openai").unwrap().is_none());
+        assert!(registry
+            .search_models("gpt")
+            .unwrap()
+            .iter()
+            .all(|m| m.provider_id != "openai"));
+        assert_eq!(registry.version(), version + 1);
+
+        // Nothing removed, nothing bumped
+        assert!(registry.retain_providers(|_| true).unwrap().is_empty());
+        assert_eq!(registry.version(), version + 1);
+    }
+
+    #[test]
+    fn test_merge_dir() {
+        let dir = std::env::temp_dir().join(format!("crabrace-merge-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let registry = ProviderRegistry::new().unwrap();
+        let original = registry.get_by_id("anthropic").unwrap().unwrap();
+        let model_id = original.models[0].id.clone();
+        std::fs::write(
+            dir.join("anthropic.json"),
+            serde_json::json!({
+                "id": "anthropic",
+                "api_endpoint": "https://gateway.internal/anthropic",
+                "models": [{"id": model_id, "cost_per_1m_in": 0.5}]
+            })
+            .to_string(),
+        )
+        .unwrap();
+
+        assert_eq!(registry.merge_dir(&dir).unwrap(), 1);
+
+        let anthropic = registry.get_by_id("anthropic").unwrap().unwrap();
+        assert_eq!(
+            anthropic.api_endpoint.as_deref(),
+            Some("https://gateway.internal/anthropic")
+        );
+        assert_eq!(anthropic.name, original.name);
+        assert_eq!(anthropic.models.len(), original.models.len());
+        assert_eq!(anthropic.models[0].cost_per_1m_in, 0.5);
+        assert_eq!(
+            anthropic.models[0].cost_per_1m_out,
+            original.models[0].cost_per_1m_out
+        );
+
+        // Partial files for unknown providers are rejected
+        std::fs::write(
+            dir.join("unknown.json"),
+            r#"{"id": "unknown", "api_endpoint": "https://x"}"#,
+        )
+        .unwrap();
+        let result = registry.merge_dir(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_subscribe() {
+        let registry = ProviderRegistry::new().unwrap();
+        let mut updates = registry.subscribe();
+        assert_eq!(updates.borrow().dataset_hash, registry.dataset_hash());
+        assert_eq!(updates.borrow().provider_count, registry.count());
+
+        registry.remove_provider("xai").unwrap();
+        registry.remove_provider("groq").unwrap();
+        updates.changed().await.unwrap();
+
+        // Both removals coalesce into the latest state
+        let update = updates.borrow_and_update().clone();
+        assert_eq!(update.version, registry.version());
+        assert_eq!(update.provider_count, registry.count());
+        assert_eq!(update.dataset_hash, registry.dataset_hash());
+        assert!(!updates.has_changed().unwrap());
+
+        // No-op mutations do not notify
+        registry.retain_providers(|_| true).unwrap();
+        assert!(!updates.has_changed().unwrap());
+    }
+
+    #[test]
+    fn test_programmatic_mutations() {
+        let registry = ProviderRegistry::new().unwrap();
+        let mut provider = registry.get_by_id("openai").unwrap().unwrap();
+        let first_model = provider.models[0].id.clone();
+
+        // Duplicate IDs and invalid providers are rejected
+        assert!(registry.add_provider(provider.clone()).is_err());
+        provider.id = "acme".to_string();
+        provider.models[0].cost_per_1m_in = -1.0;
+        let error = registry.add_provider(provider.clone()).unwrap_err();
+        assert!(error.to_string().contains("models[0].cost_per_1m_in"));
+
+        provider.models[0].cost_per_1m_in = 1.0;
+        registry.add_provider(provider).unwrap();
+        assert!(registry.get_by_id("acme").unwrap().is_some());
+
+        // Invalid updates and renames onto taken IDs are rolled back
+        let version = registry.version();
+        assert!(registry
+            .update_provider("acme", |p| p.models[0].context_window = 0)
+            .is_err());
+        assert!(registry
+            .update_provider("acme", |p| p.id = "anthropic".to_string())
+            .is_err());
+        assert_eq!(registry.version(), version);
+        let model = registry.get_model("acme", &first_model).unwrap().unwrap();
+        assert_ne!(model.context_window, 0);
+
+        // Models are replaced by ID or appended
+        let mut model = Model::new("acme-1".into(), "Acme 1".into(), 1.0, 2.0, 1000, 100);
+        assert!(registry
+            .upsert_model("acme", model.clone())
+            .unwrap()
+            .is_none());
+        model.cost_per_1m_out = 3.0;
+        let replaced = registry.upsert_model("acme", model).unwrap().unwrap();
+        assert_eq!(replaced.cost_per_1m_out, 2.0);
+        assert_eq!(
+            registry
+                .get_model("acme", "acme-1")
+                .unwrap()
+                .unwrap()
+                .cost_per_1m_out,
+            3.0
+        );
+        let model = Model::new("acme-2".into(), "Acme 2".into(), 1.0, 2.0, 1000, 100);
+        assert!(registry.upsert_model("missing", model).is_err());
+
+        assert!(registry.remove_provider("acme").unwrap().is_some());
+        assert!(registry.get_by_id("acme").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_aliases() {
+        let registry = ProviderRegistry::new().unwrap();
+        assert_eq!(registry.get_by_id("google").unwrap().unwrap().id, "gemini");
+        assert_eq!(registry.get_by_id("AWS").unwrap().unwrap().id, "bedrock");
+        assert_eq!(registry.resolve_id("x-ai"), "xai");
+        assert!(registry.get_by_canonical_id("google").unwrap().is_none());
+
+        let model = &registry.get_by_id("xai").unwrap().unwrap().models[0];
+        assert!(registry.get_model("x-ai", &model.id).unwrap().is_some());
+
+        // Configured aliases extend the table, but real IDs always win
+        registry.add_aliases([
+            ("claude".to_string(), "anthropic".to_string()),
+            ("openai".to_string(), "azure".to_string()),
+        ]);
+        assert_eq!(registry.resolve_id("claude"), "anthropic");
+        assert_eq!(registry.get_by_id("openai").unwrap().unwrap().id, "openai");
+        assert_eq!(registry.resolve_id("unknown"), "unknown");
+    }
 }
@@ -0,0 +1,117 @@
+//! Model search matching and scoring
+//!
+//! All matching is case-insensitive and runs against a model's ID and name
+//! separately. Fuzzy matching accepts the query characters in order with
+//! gaps (so `cs45` finds `claude-sonnet-4-5`) and scores consecutive runs
+//! and matches at word boundaries higher, in the spirit of fzf/skim.
+
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+/// How a search query is matched against model IDs and names
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchMode {
+    /// ID or name contains the query (results in catalog order)
+    #[default]
+    Substring,
+    /// ID or name starts with the query (results in catalog order)
+    Prefix,
+    /// Query characters appear in order (results best match first)
+    Fuzzy,
+}
+
+impl SearchMode {
+    /// Score `haystack` against an already lowercased `needle`
+    ///
+    /// Returns `None` when it does not match. Substring and prefix matches
+    /// all score the same, so only fuzzy results are reordered.
+    pub fn score(self, needle: &str, haystack: &str) -> Option<u32> {
+        match self {
+            SearchMode::Substring => haystack.contains(needle).then_some(0),
+            SearchMode::Prefix => haystack.starts_with(needle).then_some(0),
+            SearchMode::Fuzzy => fuzzy_score(needle, haystack),
+        }
+    }
+}
+
+/// Score bonus for each matched character
+const MATCH: u32 = 16;
+/// Extra bonus when a match directly follows the previous one
+const CONSECUTIVE: u32 = 8;
+/// Extra bonus when a match starts a word (after `-`, `/`, `.`, `_`, space)
+const WORD_START: u32 = 8;
+
+/// Greedy in-order subsequence match of `needle` in `haystack`
+fn fuzzy_score(needle: &str, haystack: &str) -> Option<u32> {
+    let mut score = 0;
+    let mut previous: Option<char> = None;
+    let mut last_matched = false;
+    let mut needle_chars = needle.chars().peekable();
+
+    for c in haystack.chars() {
+        let Some(&wanted) = needle_chars.peek() else {
+            break;
+        };
+        if c == wanted {
+            needle_chars.next();
+            score += MATCH;
+            if last_matched {
+                score += CONSECUTIVE;
+            }
+            if previous.map_or(true, |p| matches!(p, '-' | '/' | '.' | '_' | ' ')) {
+                score += WORD_START;
+            }
+            last_matched = true;
+        } else {
+            last_matched = false;
+        }
+        previous = Some(c);
+    }
+
+    if needle_chars.peek().is_some() {
+        return None;
+    }
+
+    // Prefer shorter haystacks among otherwise equal matches
+    let length_penalty = haystack.chars().count() as u32;
+    Some((score * 4).saturating_sub(length_penalty))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_substring_and_prefix() {
+        assert!(SearchMode::Substring
+            .score("sonnet", "claude-sonnet-4-5")
+            .is_some());
+        assert!(SearchMode::Prefix
+            .score("sonnet", "claude-sonnet-4-5")
+            .is_none());
+        assert!(SearchMode::Prefix
+            .score("claude", "claude-sonnet-4-5")
+            .is_some());
+    }
+
+    #[test]
+    fn test_fuzzy_matches_in_order() {
+        assert!(fuzzy_score("cs45", "claude-sonnet-4-5").is_some());
+        assert!(fuzzy_score("54sc", "claude-sonnet-4-5").is_none());
+        assert!(fuzzy_score("", "anything").is_some());
+    }
+
+    #[test]
+    fn test_fuzzy_ranking() {
+        // Consecutive and word-start matches beat scattered ones
+        let tight = fuzzy_score("gpt4", "gpt-4o").unwrap();
+        let loose = fuzzy_score("gpt4", "grok-mpt-v4").unwrap();
+        assert!(tight > loose);
+
+        // Shorter haystacks win ties
+        let short = fuzzy_score("o3", "o3").unwrap();
+        let long = fuzzy_score("o3", "o3-mini-high").unwrap();
+        assert!(short > long);
+    }
+}
@@ -0,0 +1,37 @@
+//! Local catalog sources applied on top of a base catalog
+//!
+//! The same steps run at startup over the embedded providers and again
+//! whenever the base catalog is refreshed from upstream, so local overrides
+//! survive a refresh.
+
+use anyhow::Result;
+use std::path::Path;
+use tracing::info;
+
+use super::registry::ProviderRegistry;
+use crate::Config;
+
+/// Apply the providers directory, the admin overlay and the provider filter
+pub fn apply_local(registry: &ProviderRegistry, config: &Config) -> Result<()> {
+    // Extra provider files (e.g. internal gateways) and partial overrides
+    if let Some(dir) = &config.providers.dir {
+        let loaded = registry.merge_dir(Path::new(dir))?;
+        info!("Loaded {} providers from {}", loaded, dir);
+    }
+
+    // Persisted admin mutations override the embedded catalog
+    if let Some(dir) = config.admin.overlay_dir.as_deref().map(Path::new) {
+        if dir.is_dir() {
+            let loaded = registry.load_dir(dir)?;
+            info!("Loaded {} providers from overlay {}", loaded, dir.display());
+        }
+    }
+
+    // Hide providers that are not approved for this instance
+    let removed = registry.retain_providers(|p| config.providers.allows(&p.id))?;
+    if !removed.is_empty() {
+        info!("Filtered out {} providers: {:?}", removed.len(), removed);
+    }
+
+    Ok(())
+}
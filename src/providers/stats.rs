@@ -0,0 +1,187 @@
+use serde::{Deserialize, Serialize};
+use std::borrow::Borrow;
+use utoipa::ToSchema;
+
+use crate::{Model, Provider};
+
+/// Aggregate statistics over the provider catalog
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct CatalogStats {
+    /// Number of providers
+    pub provider_count: usize,
+
+    /// Number of models across all providers
+    pub model_count: usize,
+
+    /// Number of models with zero input and output pricing (e.g. local runtimes)
+    pub free_model_count: usize,
+
+    /// Paid model with the lowest combined input + output price per 1M tokens
+    pub cheapest_model: Option<ModelSummary>,
+
+    /// Model with the highest combined input + output price per 1M tokens
+    pub most_expensive_model: Option<ModelSummary>,
+
+    /// Model with the largest context window
+    pub largest_context_window: Option<ModelSummary>,
+
+    /// Per-provider statistics, in catalog order
+    pub providers: Vec<ProviderStats>,
+}
+
+/// Identifies a model referenced by [`CatalogStats`]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct ModelSummary {
+    pub provider_id: String,
+    pub model_id: String,
+    pub name: String,
+    pub cost_per_1m_in: f64,
+    pub cost_per_1m_out: f64,
+    pub context_window: u64,
+}
+
+/// Statistics for a single provider
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct ProviderStats {
+    pub id: String,
+    pub model_count: usize,
+
+    /// Median input price per 1M tokens (absent when the provider has no models)
+    pub median_cost_per_1m_in: Option<f64>,
+
+    /// Median output price per 1M tokens (absent when the provider has no models)
+    pub median_cost_per_1m_out: Option<f64>,
+
+    /// Largest context window offered by the provider
+    pub max_context_window: Option<u64>,
+}
+
+impl ModelSummary {
+    fn new(provider: &Provider, model: &Model) -> Self {
+        Self {
+            provider_id: provider.id.clone(),
+            model_id: model.id.clone(),
+            name: model.name.clone(),
+            cost_per_1m_in: model.cost_per_1m_in,
+            cost_per_1m_out: model.cost_per_1m_out,
+            context_window: model.context_window,
+        }
+    }
+}
+
+/// Combined input + output price used to rank models by cost
+fn blended_cost(model: &Model) -> f64 {
+    model.cost_per_1m_in + model.cost_per_1m_out
+}
+
+fn is_free(model: &Model) -> bool {
+    model.cost_per_1m_in == 0.0 && model.cost_per_1m_out == 0.0
+}
+
+/// Median of a list of values (mean of the two middle values for even lengths)
+fn median(mut values: Vec<f64>) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(f64::total_cmp);
+
+    let mid = values.len() / 2;
+    if values.len() % 2 == 0 {
+        Some((values[mid - 1] + values[mid]) / 2.0)
+    } else {
+        Some(values[mid])
+    }
+}
+
+impl CatalogStats {
+    /// Compute statistics over a list of providers
+    pub fn compute<P: Borrow<Provider>>(providers: &[P]) -> Self {
+        let providers: Vec<&Provider> = providers.iter().map(Borrow::borrow).collect();
+        let models = || {
+            providers
+                .iter()
+                .flat_map(|p| p.models.iter().map(move |m| (p, m)))
+        };
+
+        let cheapest_model = models()
+            .filter(|(_, m)| !is_free(m))
+            .min_by(|(_, a), (_, b)| blended_cost(a).total_cmp(&blended_cost(b)))
+            .map(|(p, m)| ModelSummary::new(p, m));
+        let most_expensive_model = models()
+            .max_by(|(_, a), (_, b)| blended_cost(a).total_cmp(&blended_cost(b)))
+            .map(|(p, m)| ModelSummary::new(p, m));
+        let largest_context_window = models()
+            .max_by_key(|(_, m)| m.context_window)
+            .map(|(p, m)| ModelSummary::new(p, m));
+
+        let providers_stats = providers
+            .iter()
+            .map(|p| ProviderStats {
+                id: p.id.clone(),
+                model_count: p.models.len(),
+                median_cost_per_1m_in: median(p.models.iter().map(|m| m.cost_per_1m_in).collect()),
+                median_cost_per_1m_out: median(
+                    p.models.iter().map(|m| m.cost_per_1m_out).collect(),
+                ),
+                max_context_window: p.models.iter().map(|m| m.context_window).max(),
+            })
+            .collect();
+
+        Self {
+            provider_count: providers.len(),
+            model_count: models().count(),
+            free_model_count: models().filter(|(_, m)| is_free(m)).count(),
+            cheapest_model,
+            most_expensive_model,
+            largest_context_window,
+            providers: providers_stats,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn model(id: &str, cost_in: f64, cost_out: f64, context_window: u64) -> Model {
+        Model::new(
+            id.to_string(),
+            id.to_string(),
+            cost_in,
+            cost_out,
+            context_window,
+            4096,
+        )
+    }
+
+    #[test]
+    fn test_median() {
+        assert_eq!(median(vec![]), None);
+        assert_eq!(median(vec![3.0, 1.0, 2.0]), Some(2.0));
+        assert_eq!(median(vec![4.0, 1.0, 2.0, 3.0]), Some(2.5));
+    }
+
+    #[test]
+    fn test_compute() {
+        let providers = vec![
+            Provider::new("A".into(), "a".into(), "openai".into())
+                .with_model(model("cheap", 0.1, 0.2, 8_000))
+                .with_model(model("pricey", 10.0, 30.0, 128_000)),
+            Provider::new("B".into(), "b".into(), "openai".into())
+                .with_model(model("free", 0.0, 0.0, 1_000_000)),
+            Provider::new("C".into(), "c".into(), "openai".into()),
+        ];
+
+        let stats = CatalogStats::compute(&providers);
+        assert_eq!(stats.provider_count, 3);
+        assert_eq!(stats.model_count, 3);
+        assert_eq!(stats.free_model_count, 1);
+        assert_eq!(stats.cheapest_model.unwrap().model_id, "cheap");
+        assert_eq!(stats.most_expensive_model.unwrap().model_id, "pricey");
+        assert_eq!(stats.largest_context_window.unwrap().model_id, "free");
+
+        assert_eq!(stats.providers[0].median_cost_per_1m_out, Some(15.1));
+        assert_eq!(stats.providers[2].median_cost_per_1m_in, None);
+        assert_eq!(stats.providers[2].max_context_window, None);
+    }
+}
@@ -0,0 +1,224 @@
+//! Tenant catalogs derived from the main catalog
+//!
+//! Each tenant configured under `[tenants.<name>]` gets its own registry,
+//! built from the main catalog by merging the tenant's provider files over
+//! it (negotiated prices, private gateways) and dropping providers and
+//! models the tenant is not approved for. Tenant registries are rebuilt
+//! whenever the main catalog changes, so upstream syncs and admin edits
+//! reach every tenant.
+
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use super::registry::ProviderRegistry;
+use crate::config::TenantConfig;
+use crate::{Config, Provider};
+
+/// Name that selects the main catalog
+pub const DEFAULT_TENANT: &str = "default";
+
+/// A tenant's configuration and the catalog built from it
+#[derive(Clone)]
+struct Tenant {
+    config: TenantConfig,
+    registry: Arc<ProviderRegistry>,
+}
+
+/// Every configured tenant, by name
+#[derive(Clone, Default)]
+pub struct Tenants {
+    tenants: BTreeMap<String, Tenant>,
+}
+
+impl Tenants {
+    /// Build every configured tenant's catalog from the main registry
+    ///
+    /// Tenant registries share the main registry's provider aliases.
+    pub fn build(base: &ProviderRegistry, config: &Config) -> Result<Self> {
+        let mut tenants = BTreeMap::new();
+        for (name, tenant_config) in &config.tenants {
+            let registry = ProviderRegistry::new()?;
+            registry.add_aliases(base.aliases());
+            registry.replace_all(
+                catalog(base, tenant_config)
+                    .with_context(|| format!("Failed to build tenant '{}'", name))?,
+            )?;
+            tenants.insert(
+                name.clone(),
+                Tenant {
+                    config: tenant_config.clone(),
+                    registry: Arc::new(registry),
+                },
+            );
+        }
+        Ok(Self { tenants })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tenants.is_empty()
+    }
+
+    /// Registry of the named tenant
+    pub fn get(&self, name: &str) -> Option<&Arc<ProviderRegistry>> {
+        self.tenants.get(name).map(|tenant| &tenant.registry)
+    }
+
+    /// Tenant names, sorted
+    pub fn names(&self) -> Vec<&str> {
+        self.tenants.keys().map(String::as_str).collect()
+    }
+
+    /// Tenant names and registries, sorted by name
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Arc<ProviderRegistry>)> {
+        self.tenants
+            .iter()
+            .map(|(name, tenant)| (name.as_str(), &tenant.registry))
+    }
+
+    /// Rebuild every tenant's catalog from the main registry
+    ///
+    /// A tenant whose rebuild fails keeps its current catalog; the first
+    /// error is returned after every tenant has been tried.
+    pub fn refresh(&self, base: &ProviderRegistry) -> Result<()> {
+        let mut result = Ok(());
+        for (name, tenant) in &self.tenants {
+            let refreshed = catalog(base, &tenant.config)
+                .and_then(|providers| tenant.registry.replace_all(providers))
+                .with_context(|| format!("Failed to refresh tenant '{}'", name));
+            match refreshed {
+                Ok(true) => info!(
+                    "Tenant '{}' catalog updated (version {})",
+                    name,
+                    tenant.registry.version()
+                ),
+                Ok(false) => {}
+                Err(e) if result.is_ok() => result = Err(e),
+                Err(_) => {}
+            }
+        }
+        result
+    }
+
+    /// Rebuild the tenant catalogs in the background whenever the main registry changes
+    pub fn spawn(self, base: Arc<ProviderRegistry>) -> JoinHandle<()> {
+        let mut updates = base.subscribe();
+        tokio::spawn(async move {
+            while updates.changed().await.is_ok() {
+                if let Err(e) = self.refresh(&base) {
+                    warn!("{:#}", e);
+                }
+            }
+        })
+    }
+}
+
+/// A tenant's view of the main catalog
+fn catalog(base: &ProviderRegistry, config: &TenantConfig) -> Result<Vec<Provider>> {
+    let scratch = ProviderRegistry::new()?;
+    scratch.replace_all(base.get_all()?)?;
+    if let Some(dir) = &config.dir {
+        scratch.merge_dir(Path::new(dir))?;
+    }
+    scratch.retain_providers(|p| config.allows_provider(&p.id))?;
+
+    let mut providers = scratch.get_all()?;
+    if !config.models.is_empty() {
+        for provider in &mut providers {
+            provider.models.retain(|m| config.allows_model(&m.id));
+        }
+        providers.retain(|p| !p.models.is_empty());
+    }
+    Ok(providers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn config_with(name: &str, tenant: TenantConfig) -> Config {
+        let mut config = Config::default();
+        config.tenants.insert(name.to_string(), tenant);
+        config
+    }
+
+    #[test]
+    fn test_tenant_catalog() {
+        let dir = std::env::temp_dir().join(format!("crabrace-tenant-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("anthropic.json"),
+            json!({
+                "id": "anthropic",
+                "models": [{"id": "claude-sonnet-4-5-20250929", "cost_per_1m_in": 2.0}]
+            })
+            .to_string(),
+        )
+        .unwrap();
+
+        let base = ProviderRegistry::new().unwrap();
+        let config = config_with(
+            "team-a",
+            TenantConfig {
+                dir: Some(dir.to_string_lossy().to_string()),
+                models: vec![
+                    "claude-sonnet-4-5-20250929".to_string(),
+                    "gpt-4o-mini-2024-07-18".to_string(),
+                ],
+                disabled: vec!["azure".to_string()],
+                ..TenantConfig::default()
+            },
+        );
+        let tenants = Tenants::build(&base, &config).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let registry = tenants.get("team-a").unwrap();
+        let model = registry
+            .get_model("anthropic", "claude-sonnet-4-5-20250929")
+            .unwrap()
+            .unwrap();
+        assert_eq!(model.cost_per_1m_in, 2.0);
+        assert!(registry
+            .get_all()
+            .unwrap()
+            .iter()
+            .all(|p| p.id != "azure" && !p.models.is_empty()));
+        assert_eq!(
+            registry.get_by_id("openai").unwrap().unwrap().models.len(),
+            1
+        );
+
+        // The main catalog is untouched and aliases carry over
+        let base_model = base
+            .get_model("anthropic", "claude-sonnet-4-5-20250929")
+            .unwrap()
+            .unwrap();
+        assert_eq!(base_model.cost_per_1m_in, 3.0);
+        assert!(registry.get_by_id("google").unwrap().is_none());
+        assert_eq!(registry.resolve_id("google"), "gemini");
+    }
+
+    #[test]
+    fn test_refresh_follows_main_catalog() {
+        let base = ProviderRegistry::new().unwrap();
+        let config = config_with(
+            "team-b",
+            TenantConfig {
+                enabled: vec!["openai".to_string(), "anthropic".to_string()],
+                ..TenantConfig::default()
+            },
+        );
+        let tenants = Tenants::build(&base, &config).unwrap();
+        assert_eq!(tenants.get("team-b").unwrap().count(), 2);
+
+        base.remove_provider("openai").unwrap();
+        tenants.refresh(&base).unwrap();
+        let registry = tenants.get("team-b").unwrap();
+        assert_eq!(registry.count(), 1);
+        assert!(registry.get_by_id("openai").unwrap().is_none());
+    }
+}
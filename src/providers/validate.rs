@@ -0,0 +1,590 @@
+//! Provider configuration validation
+//!
+//! Checks beyond what deserialization enforces, shared by the admin API
+//! (which rejects the first problem) and the strict startup check (which
+//! reports every problem in every embedded config).
+
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::{Model, ModelKind, Provider, RateLimits};
+
+/// One problem found in a provider configuration
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Problem {
+    /// JSON path of the offending value (e.g. `models[3].cost_per_1m_in`), empty for the root
+    pub path: String,
+
+    pub message: String,
+}
+
+impl Problem {
+    fn new(path: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "{}: {}", self.path, self.message)
+        }
+    }
+}
+
+/// Provider IDs double as overlay file names
+pub fn is_valid_provider_id(id: &str) -> bool {
+    !id.is_empty()
+        && !id.starts_with('.')
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+/// Every problem with a provider and its models
+pub fn provider_problems(provider: &Provider) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    if !is_valid_provider_id(&provider.id) {
+        problems.push(Problem::new(
+            "id",
+            "Provider ID must be non-empty and contain only letters, digits, '-', '_' or '.'",
+        ));
+    }
+    if provider.name.trim().is_empty() || provider.provider_type.trim().is_empty() {
+        problems.push(Problem::new("", "Provider name and type are required"));
+    }
+    problems.extend(limits_problems(&provider.limits, "limits", &provider.id));
+    problems.extend(names_problems(
+        &provider.regions,
+        "region",
+        "regions",
+        &provider.id,
+    ));
+    problems.extend(names_problems(&provider.tags, "tag", "tags", &provider.id));
+
+    let mut ids = HashSet::new();
+    for (i, model) in provider.models.iter().enumerate() {
+        let path = format!("models[{}]", i);
+        problems.extend(model_problems(model, &path));
+        if !ids.insert(model.id.as_str()) {
+            problems.push(Problem::new(
+                format!("{}.id", path),
+                format!("Duplicate model ID '{}'", model.id),
+            ));
+        }
+    }
+
+    for (i, model) in provider.models.iter().enumerate() {
+        if let Some(replacement) = &model.replacement_model_id {
+            if provider.get_model(replacement).is_none() {
+                problems.push(Problem::new(
+                    format!("models[{}].replacement_model_id", i),
+                    format!("Replacement model '{}' does not exist", replacement),
+                ));
+            }
+        }
+        let references = [
+            ("previous_model_id", "Previous", &model.previous_model_id),
+            ("base_model_id", "Base", &model.base_model_id),
+        ];
+        for (field, role, reference) in references {
+            let Some(reference) = reference else {
+                continue;
+            };
+            let message = if reference == &model.id {
+                format!(
+                    "Model '{}' cannot be its own {} model",
+                    model.id,
+                    role.to_ascii_lowercase()
+                )
+            } else if provider.get_model(reference).is_none() {
+                format!("{} model '{}' does not exist", role, reference)
+            } else {
+                continue;
+            };
+            problems.push(Problem::new(format!("models[{}].{}", i, field), message));
+        }
+    }
+    problems
+}
+
+/// Every problem with a list of rate limits at `path`, owned by the provider or model `owner`
+fn limits_problems(limits: &[RateLimits], path: &str, owner: &str) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    let mut tiers = HashSet::new();
+    for (i, tier) in limits.iter().enumerate() {
+        let values = [
+            tier.requests_per_minute,
+            tier.tokens_per_minute,
+            tier.tokens_per_day,
+        ];
+        if values.iter().all(Option::is_none) || values.contains(&Some(0)) {
+            problems.push(Problem::new(
+                format!("{}[{}]", path, i),
+                format!("'{}' has rate limits that are missing or zero", owner),
+            ));
+        }
+        if !tiers.insert(tier.tier.as_deref().map(str::to_ascii_lowercase)) {
+            problems.push(Problem::new(
+                format!("{}[{}].tier", path, i),
+                format!("'{}' lists the same rate limit tier twice", owner),
+            ));
+        }
+    }
+    problems
+}
+
+/// Every problem with a list of names (regions, tags) at `path`, owned by the provider or model `owner`
+fn names_problems(names: &[String], noun: &str, path: &str, owner: &str) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    let mut seen = HashSet::new();
+    for (i, name) in names.iter().enumerate() {
+        if name.trim().is_empty() {
+            problems.push(Problem::new(
+                format!("{}[{}]", path, i),
+                format!("'{}' lists an empty {}", owner, noun),
+            ));
+        } else if !seen.insert(name.to_ascii_lowercase()) {
+            problems.push(Problem::new(
+                format!("{}[{}]", path, i),
+                format!("'{}' lists the {} '{}' twice", owner, noun, name),
+            ));
+        }
+    }
+    problems
+}
+
+/// Every problem with a model, with paths relative to `path`
+pub fn model_problems(model: &Model, path: &str) -> Vec<Problem> {
+    let at = |field: &str| {
+        if path.is_empty() {
+            field.to_string()
+        } else {
+            format!("{}.{}", path, field)
+        }
+    };
+
+    let mut problems = Vec::new();
+    if model.id.trim().is_empty() || model.name.trim().is_empty() {
+        problems.push(Problem::new(path, "Model ID and name are required"));
+    }
+
+    let costs = [
+        ("cost_per_1m_in", Some(model.cost_per_1m_in)),
+        ("cost_per_1m_out", Some(model.cost_per_1m_out)),
+        ("cost_per_1m_in_cached", model.cost_per_1m_in_cached),
+        ("cost_per_1m_out_cached", model.cost_per_1m_out_cached),
+        ("cost_per_1m_in_batch", model.cost_per_1m_in_batch),
+        ("cost_per_1m_out_batch", model.cost_per_1m_out_batch),
+        ("cost_per_1m_reasoning", model.cost_per_1m_reasoning),
+        ("cost_per_audio_minute", model.cost_per_audio_minute),
+        ("cost_per_1m_characters", model.cost_per_1m_characters),
+        ("cost_per_request", model.cost_per_request),
+        ("cost_per_tool_call", model.cost_per_tool_call),
+    ];
+    for (field, cost) in costs {
+        if cost.is_some_and(|cost| !cost.is_finite() || cost < 0.0) {
+            problems.push(Problem::new(
+                at(field),
+                format!("Model '{}' has a negative or non-finite cost", model.id),
+            ));
+        }
+    }
+
+    problems.extend(limits_problems(&model.limits, &at("limits"), &model.id));
+    problems.extend(names_problems(
+        &model.regions,
+        "region",
+        &at("regions"),
+        &model.id,
+    ));
+    problems.extend(names_problems(&model.tags, "tag", &at("tags"), &model.id));
+
+    let mut benchmarks: Vec<_> = model.benchmarks.iter().collect();
+    benchmarks.sort_by(|a, b| a.0.cmp(b.0));
+    let mut seen = HashSet::new();
+    for (name, score) in benchmarks {
+        let field = at(&format!("benchmarks.{}", name));
+        if name.trim().is_empty() {
+            problems.push(Problem::new(
+                field.clone(),
+                format!("Model '{}' has a benchmark without a name", model.id),
+            ));
+        } else if !seen.insert(name.to_ascii_lowercase()) {
+            problems.push(Problem::new(
+                field.clone(),
+                format!("Model '{}' lists the benchmark '{}' twice", model.id, name),
+            ));
+        }
+        if !(0.0..=100.0).contains(score) {
+            problems.push(Problem::new(
+                field,
+                format!("Model '{}' has a benchmark score outside 0-100", model.id),
+            ));
+        }
+    }
+
+    if model.median_ttft_ms == Some(0) {
+        problems.push(Problem::new(
+            at("median_ttft_ms"),
+            format!("Model '{}' has a zero median_ttft_ms", model.id),
+        ));
+    }
+    if model
+        .median_tokens_per_second
+        .is_some_and(|speed| !speed.is_finite() || speed <= 0.0)
+    {
+        problems.push(Problem::new(
+            at("median_tokens_per_second"),
+            format!(
+                "Model '{}' has a median_tokens_per_second that is not positive",
+                model.id
+            ),
+        ));
+    }
+
+    let mut tiers = HashSet::new();
+    for (i, tier) in model.cost_per_image.iter().enumerate() {
+        if !tier.cost.is_finite() || tier.cost < 0.0 {
+            problems.push(Problem::new(
+                at(&format!("cost_per_image[{}].cost", i)),
+                format!("Model '{}' has a negative or non-finite cost", model.id),
+            ));
+        }
+        if !tiers.insert((tier.size.to_ascii_lowercase(), tier.quality.clone())) {
+            problems.push(Problem::new(
+                at(&format!("cost_per_image[{}]", i)),
+                format!(
+                    "Model '{}' lists the image size '{}' and quality twice",
+                    model.id, tier.size
+                ),
+            ));
+        }
+    }
+
+    if model.context_window == 0 {
+        problems.push(Problem::new(
+            at("context_window"),
+            format!("Model '{}' must have a non-zero context window", model.id),
+        ));
+    }
+
+    if let Some(max_output_tokens) = model.max_output_tokens {
+        if model.default_max_tokens > max_output_tokens {
+            problems.push(Problem::new(
+                at("default_max_tokens"),
+                format!(
+                    "Model '{}' has default_max_tokens above max_output_tokens",
+                    model.id
+                ),
+            ));
+        }
+        if max_output_tokens > model.context_window {
+            problems.push(Problem::new(
+                at("max_output_tokens"),
+                format!(
+                    "Model '{}' has max_output_tokens above its context window",
+                    model.id
+                ),
+            ));
+        }
+    }
+
+    let modalities = [
+        ("input_modalities", &model.input_modalities, true),
+        (
+            "output_modalities",
+            &model.output_modalities,
+            !model.model_kind.has_no_output(),
+        ),
+    ];
+    for (field, modalities, required) in modalities {
+        if required && modalities.is_empty() {
+            problems.push(Problem::new(
+                at(field),
+                format!("Model '{}' must list at least one modality", model.id),
+            ));
+        }
+    }
+    if (model.model_kind == ModelKind::Embedding) != model.dimensions.is_some() {
+        problems.push(Problem::new(
+            at("dimensions"),
+            format!(
+                "Model '{}' must set dimensions if and only if it is an embedding model",
+                model.id
+            ),
+        ));
+    }
+    if model.license.is_some() && !model.open_weights {
+        problems.push(Problem::new(
+            at("license"),
+            format!(
+                "Model '{}' has a license but does not have open weights",
+                model.id
+            ),
+        ));
+    }
+    if model.deprecation_date != model.retires_at {
+        problems.push(Problem::new(
+            at("deprecation_date"),
+            format!(
+                "Model '{}' has different deprecation_date and retires_at",
+                model.id
+            ),
+        ));
+    }
+    let dates = [
+        ("released_at", model.released_at),
+        ("deprecated_at", model.deprecated_at),
+        ("retires_at", model.retires_at),
+    ];
+    for (i, &(field, date)) in dates.iter().enumerate() {
+        for &(earlier, earlier_date) in &dates[..i] {
+            if date
+                .zip(earlier_date)
+                .is_some_and(|(date, earlier_date)| date < earlier_date)
+            {
+                problems.push(Problem::new(
+                    at(field),
+                    format!("Model '{}' has {} before {}", model.id, field, earlier),
+                ));
+            }
+        }
+    }
+    if model.supports_parallel_tool_calls && !model.supports_tools {
+        problems.push(Problem::new(
+            at("supports_parallel_tool_calls"),
+            format!(
+                "Model '{}' supports parallel tool calls but not tools",
+                model.id
+            ),
+        ));
+    }
+    if model.supports_attachments != model.input_modalities.iter().any(|m| m.is_attachment()) {
+        problems.push(Problem::new(
+            at("supports_attachments"),
+            format!(
+                "Model '{}' supports_attachments disagrees with input_modalities (image or pdf)",
+                model.id
+            ),
+        ));
+    }
+    problems
+}
+
+/// Strictly check a provider configuration file's contents
+///
+/// Reports, in order: the parse error with its JSON path and expected type
+/// (parsing stops there), fields crabrace does not know (usually typos),
+/// the problems found by [`provider_problems`] and default model IDs that
+/// do not resolve.
+pub fn check_config(json: &str) -> Vec<Problem> {
+    let mut deserializer = serde_json::Deserializer::from_str(json);
+    let provider: Provider = match serde_path_to_error::deserialize(&mut deserializer) {
+        Ok(provider) => provider,
+        Err(e) => {
+            let path = e.path().to_string();
+            let path = if path == "." { String::new() } else { path };
+            return vec![Problem::new(path, e.into_inner().to_string())];
+        }
+    };
+
+    let mut problems = Vec::new();
+    if let (Ok(original), Ok(parsed)) = (
+        serde_json::from_str::<Value>(json),
+        serde_json::to_value(&provider),
+    ) {
+        unknown_fields("", &original, &parsed, &mut problems);
+    }
+    problems.extend(provider_problems(&provider));
+
+    let defaults = [
+        ("default_large_model_id", &provider.default_large_model_id),
+        ("default_small_model_id", &provider.default_small_model_id),
+    ];
+    for (field, model_id) in defaults {
+        if let Some(model_id) = model_id {
+            if provider.get_model(model_id).is_none() {
+                problems.push(Problem::new(
+                    field,
+                    format!("'{}' does not match any model", model_id),
+                ));
+            }
+        }
+    }
+    problems
+}
+
+/// Report non-null keys of `original` that did not survive a parse round trip
+fn unknown_fields(path: &str, original: &Value, parsed: &Value, problems: &mut Vec<Problem>) {
+    match (original, parsed) {
+        (Value::Object(original), Value::Object(parsed)) => {
+            for (key, value) in original {
+                let child = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", path, key)
+                };
+                match parsed.get(key) {
+                    Some(parsed) => unknown_fields(&child, value, parsed, problems),
+                    None if !value.is_null() => {
+                        problems.push(Problem::new(child, "Unknown field"));
+                    }
+                    None => {}
+                }
+            }
+        }
+        (Value::Array(original), Value::Array(parsed)) => {
+            for (i, (original, parsed)) in original.iter().zip(parsed).enumerate() {
+                unknown_fields(&format!("{}[{}]", path, i), original, parsed, problems);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Problems found across several configuration files
+#[derive(Debug, Default)]
+pub struct ValidationReport {
+    /// File name and its problems, for files with at least one problem
+    pub files: Vec<(String, Vec<Problem>)>,
+}
+
+impl ValidationReport {
+    /// Record a file's problems (files without problems are skipped)
+    pub fn add(&mut self, file: impl Into<String>, problems: Vec<Problem>) {
+        if !problems.is_empty() {
+            self.files.push((file.into(), problems));
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Total number of problems
+    pub fn problem_count(&self) -> usize {
+        self.files.iter().map(|(_, problems)| problems.len()).sum()
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} problems in {} provider configs",
+            self.problem_count(),
+            self.files.len()
+        )?;
+        for (file, problems) in &self.files {
+            for problem in problems {
+                write!(f, "\n  {}: {}", file, problem)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn config(models: Value) -> String {
+        json!({
+            "name": "Acme",
+            "id": "acme",
+            "type": "openai",
+            "default_large_model_id": "acme-1",
+            "models": models
+        })
+        .to_string()
+    }
+
+    fn model(id: &str) -> Value {
+        json!({
+            "id": id,
+            "name": "Acme 1",
+            "cost_per_1m_in": 1.0,
+            "cost_per_1m_out": 2.0,
+            "context_window": 128000,
+            "default_max_tokens": 4096
+        })
+    }
+
+    #[test]
+    fn test_valid_config() {
+        assert!(check_config(&config(json!([model("acme-1")]))).is_empty());
+    }
+
+    #[test]
+    fn test_parse_error_path() {
+        let mut bad = model("acme-1");
+        bad["context_window"] = json!("large");
+        let problems = check_config(&config(json!([model("acme-0"), bad])));
+
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].path, "models[1].context_window");
+        assert!(problems[0].message.contains("expected u64"));
+    }
+
+    #[test]
+    fn test_reports_every_problem() {
+        let mut typo = model("acme-1");
+        typo["cost_per_1m_inn"] = json!(1.0);
+        let mut negative = model("acme-1");
+        negative["cost_per_1m_out"] = json!(-2.0);
+        negative["max_output_tokens"] = json!(2048);
+        negative["median_tokens_per_second"] = json!(0.0);
+        let mut embedding = model("acme-embed");
+        embedding["model_kind"] = json!("embedding");
+        embedding["license"] = json!("apache-2.0");
+        let mut retired = model("acme-old");
+        retired["released_at"] = json!("2025-03-01");
+        retired["retires_at"] = json!("2025-01-01");
+        retired["previous_model_id"] = json!("acme-0");
+        retired["limits"] = json!([{"tier": "free", "requests_per_minute": 0}]);
+        retired["regions"] = json!(["us-east-1", "US-EAST-1"]);
+        retired["tags"] = json!([" "]);
+        retired["max_output_tokens"] = json!(200_000);
+        retired["base_model_id"] = json!("acme-old");
+        retired["benchmarks"] = json!({"gpqa_diamond": 120.0, "GPQA_Diamond": 50.0});
+        let mut json: Value =
+            serde_json::from_str(&config(json!([typo, negative, embedding, retired]))).unwrap();
+        json["default_large_model_id"] = json!("missing");
+
+        let problems: Vec<String> = check_config(&json.to_string())
+            .iter()
+            .map(Problem::to_string)
+            .collect();
+        assert_eq!(
+            problems,
+            vec![
+                "models[0].cost_per_1m_inn: Unknown field",
+                "models[1].cost_per_1m_out: Model 'acme-1' has a negative or non-finite cost",
+                "models[1].median_tokens_per_second: Model 'acme-1' has a median_tokens_per_second that is not positive",
+                "models[1].default_max_tokens: Model 'acme-1' has default_max_tokens above max_output_tokens",
+                "models[1].id: Duplicate model ID 'acme-1'",
+                "models[2].dimensions: Model 'acme-embed' must set dimensions if and only if it is an embedding model",
+                "models[2].license: Model 'acme-embed' has a license but does not have open weights",
+                "models[3].limits[0]: 'acme-old' has rate limits that are missing or zero",
+                "models[3].regions[1]: 'acme-old' lists the region 'US-EAST-1' twice",
+                "models[3].tags[0]: 'acme-old' lists an empty tag",
+                "models[3].benchmarks.gpqa_diamond: Model 'acme-old' lists the benchmark 'gpqa_diamond' twice",
+                "models[3].benchmarks.gpqa_diamond: Model 'acme-old' has a benchmark score outside 0-100",
+                "models[3].max_output_tokens: Model 'acme-old' has max_output_tokens above its context window",
+                "models[3].retires_at: Model 'acme-old' has retires_at before released_at",
+                "models[3].previous_model_id: Previous model 'acme-0' does not exist",
+                "models[3].base_model_id: Model 'acme-old' cannot be its own base model",
+                "default_large_model_id: 'missing' does not match any model",
+            ]
+        );
+    }
+}
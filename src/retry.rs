@@ -0,0 +1,178 @@
+//! Retry policy for [`CrabraceClient`](crate::CrabraceClient) requests
+//!
+//! Failed requests are retried with exponential backoff: the n-th retry
+//! waits `base_delay * 2^(n-1)`, capped at `max_delay`, and with jitter a
+//! random duration between half and all of that. A `Retry-After` header on
+//! the failed response (typically a 429 or 503) replaces the computed delay,
+//! still capped.
+
+use chrono::{DateTime, Utc};
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::StatusCode;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+/// When and how often the client retries a failed request
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// Attempts per request, the first included (1 disables retries)
+    pub max_attempts: u32,
+
+    /// Delay before the first retry, doubled for each further retry
+    pub base_delay: Duration,
+
+    /// Longest delay between two attempts, `Retry-After` included
+    pub max_delay: Duration,
+
+    /// Randomize each delay between half and all of its value, so clients
+    /// failing together do not retry together
+    pub jitter: bool,
+
+    /// Retry on 5xx responses
+    pub retry_on_server_errors: bool,
+
+    /// Retry on 429 Too Many Requests
+    pub retry_on_rate_limit: bool,
+
+    /// Retry when the server cannot be reached or the request times out
+    pub retry_on_connect_errors: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(200),
+            max_delay: Duration::from_secs(10),
+            jitter: true,
+            retry_on_server_errors: true,
+            retry_on_rate_limit: true,
+            retry_on_connect_errors: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Never retry
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    /// Whether a response with this status is worth retrying
+    pub fn retries_status(&self, status: StatusCode) -> bool {
+        (self.retry_on_server_errors && status.is_server_error())
+            || (self.retry_on_rate_limit && status == StatusCode::TOO_MANY_REQUESTS)
+    }
+
+    /// Whether a failed request is worth retrying
+    pub fn retries_error(&self, error: &reqwest::Error) -> bool {
+        self.retry_on_connect_errors && (is_connect(error) || error.is_timeout())
+    }
+
+    /// Delay before retry number `retry` (1 for the first), unless the
+    /// server asked for another one
+    pub fn delay(&self, retry: u32, retry_after: Option<Duration>) -> Duration {
+        if let Some(retry_after) = retry_after {
+            return retry_after.min(self.max_delay);
+        }
+
+        let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+        let delay = self.base_delay.saturating_mul(factor).min(self.max_delay);
+        if self.jitter {
+            delay.mul_f64(0.5 + random_fraction() / 2.0)
+        } else {
+            delay
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn is_connect(error: &reqwest::Error) -> bool {
+    error.is_connect()
+}
+
+/// Browsers do not tell connection failures apart: any failed fetch is one
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn is_connect(error: &reqwest::Error) -> bool {
+    error.is_request()
+}
+
+/// Delay requested by a `Retry-After` header, in seconds or as an HTTP date
+pub(crate) fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    parse_retry_after(headers.get(RETRY_AFTER)?.to_str().ok()?, Utc::now())
+}
+
+fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let at = DateTime::parse_from_rfc2822(value).ok()?;
+    Some((at.with_timezone(&Utc) - now).to_std().unwrap_or_default())
+}
+
+/// Uniformly distributed number in `[0, 1)`, from the std hasher's random keys
+fn random_fraction() -> f64 {
+    let bits = RandomState::new().build_hasher().finish();
+    (bits >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff() {
+        let policy = RetryPolicy {
+            jitter: false,
+            max_delay: Duration::from_millis(500),
+            ..RetryPolicy::default()
+        };
+        assert_eq!(policy.delay(1, None), Duration::from_millis(200));
+        assert_eq!(policy.delay(2, None), Duration::from_millis(400));
+        assert_eq!(policy.delay(3, None), Duration::from_millis(500));
+        assert_eq!(
+            policy.delay(1, Some(Duration::from_secs(60))),
+            Duration::from_millis(500)
+        );
+
+        let jittered = RetryPolicy::default().delay(2, None);
+        assert!(jittered >= Duration::from_millis(200) && jittered <= Duration::from_millis(400));
+    }
+
+    #[test]
+    fn test_retryable_statuses() {
+        let policy = RetryPolicy {
+            retry_on_rate_limit: false,
+            ..RetryPolicy::default()
+        };
+        assert!(policy.retries_status(StatusCode::BAD_GATEWAY));
+        assert!(!policy.retries_status(StatusCode::TOO_MANY_REQUESTS));
+        assert!(!policy.retries_status(StatusCode::NOT_FOUND));
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        let now = DateTime::parse_from_rfc3339("2015-10-21T07:28:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(
+            parse_retry_after("120", now),
+            Some(Duration::from_secs(120))
+        );
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:28:30 GMT", now),
+            Some(Duration::from_secs(30))
+        );
+        // Dates in the past mean "now"
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:00:00 GMT", now),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+}
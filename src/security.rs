@@ -1,7 +1,15 @@
 use crate::config::{CorsConfig, RateLimitConfig, SecurityHeadersConfig};
-use axum::http::{header, HeaderValue, Method, StatusCode};
+use axum::extract::ConnectInfo;
+use axum::http::{header, HeaderValue, Method, Request, StatusCode};
 use axum::response::{IntoResponse, Response};
-use std::time::Duration;
+use futures_util::future::{self, Either, Ready};
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use tower::{Layer, Service};
 use tower_http::cors::{AllowOrigin, CorsLayer};
 use tower_http::set_header::SetResponseHeaderLayer;
 
@@ -41,6 +49,9 @@ pub fn build_cors_layer(config: &CorsConfig) -> Option<CorsLayer> {
         .collect();
     cors = cors.allow_headers(headers);
 
+    // Let browser clients read the request ID for support reports
+    cors = cors.expose_headers([crate::api::request_id::X_REQUEST_ID]);
+
     // Configure max age
     cors = cors.max_age(Duration::from_secs(config.max_age_seconds));
 
@@ -49,17 +60,180 @@ pub fn build_cors_layer(config: &CorsConfig) -> Option<CorsLayer> {
 
 /// Build rate limiting middleware layer from configuration
 ///
-/// Note: This function currently returns None due to type compatibility issues
-/// with tower_governor 0.4.3. Rate limiting will be re-enabled after upgrading
-/// to a newer version that exposes the necessary types publicly.
-///
-/// TODO: Upgrade to tower_governor 0.8.0+ and re-implement rate limiting
-pub fn build_rate_limit_layer<T>(_config: &RateLimitConfig) -> Option<T> {
-    // Temporarily disabled due to tower_governor 0.4.3 type visibility issues
-    // The GovernorLayer requires 2 generic arguments but the rate limiter types
-    // (DefaultDirectRateLimiter, DefaultKeyedStateStore) are private.
-    // This will be fixed when upgrading to tower_governor 0.8.0+
-    None
+/// Requests are limited per client IP with a token bucket holding
+/// `requests_per_period` tokens and refilling at
+/// `requests_per_period / period_seconds` tokens per second. The client IP is
+/// read from [`ConnectInfo`], so the server must be started with
+/// `into_make_service_with_connect_info::<SocketAddr>()`; requests without it
+/// share a single bucket. At most [`MAX_TRACKED_CLIENTS`] clients are
+/// tracked, so a flood from many addresses cannot exhaust memory.
+pub fn build_rate_limit_layer(config: &RateLimitConfig) -> Option<RateLimitLayer> {
+    if !config.enabled || config.requests_per_period == 0 || config.period_seconds == 0 {
+        return None;
+    }
+
+    Some(RateLimitLayer {
+        limiter: Arc::new(RateLimiter::new(
+            config.requests_per_period,
+            Duration::from_secs(config.period_seconds),
+        )),
+    })
+}
+
+/// Clients a [`RateLimiter`] keeps buckets for; beyond it, the least
+/// recently seen clients are forgotten (and start again with a full bucket)
+pub const MAX_TRACKED_CLIENTS: usize = 100_000;
+
+/// Token bucket state for one client
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+/// Per-IP token bucket rate limiter
+#[derive(Debug)]
+pub struct RateLimiter {
+    capacity: f64,
+    refill_per_second: f64,
+    period: Duration,
+    max_clients: usize,
+    state: Mutex<LimiterState>,
+}
+
+#[derive(Debug)]
+struct LimiterState {
+    buckets: HashMap<IpAddr, Bucket>,
+    last_pruned: Instant,
+}
+
+impl RateLimiter {
+    /// Allow `requests` per `period` for each client
+    pub fn new(requests: u32, period: Duration) -> Self {
+        Self {
+            capacity: f64::from(requests),
+            refill_per_second: f64::from(requests) / period.as_secs_f64(),
+            period,
+            max_clients: MAX_TRACKED_CLIENTS,
+            state: Mutex::new(LimiterState {
+                buckets: HashMap::new(),
+                last_pruned: Instant::now(),
+            }),
+        }
+    }
+
+    /// Take a token for `ip`, or return how long until one is available
+    pub fn check(&self, ip: IpAddr) -> Result<(), Duration> {
+        self.check_at(ip, Instant::now())
+    }
+
+    fn check_at(&self, ip: IpAddr, now: Instant) -> Result<(), Duration> {
+        let mut state = self.state.lock();
+
+        // Buckets idle for a whole period are full again and can be dropped
+        if now.saturating_duration_since(state.last_pruned) >= self.period {
+            let period = self.period;
+            state
+                .buckets
+                .retain(|_, bucket| now.saturating_duration_since(bucket.updated) < period);
+            state.last_pruned = now;
+        }
+        if state.buckets.len() >= self.max_clients && !state.buckets.contains_key(&ip) {
+            state.evict(now, self.period, self.max_clients);
+        }
+
+        let bucket = state.buckets.entry(ip).or_insert(Bucket {
+            tokens: self.capacity,
+            updated: now,
+        });
+
+        let elapsed = now.saturating_duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.refill_per_second).min(self.capacity);
+        bucket.updated = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            let wait = (1.0 - bucket.tokens) / self.refill_per_second;
+            Err(Duration::from_secs_f64(wait))
+        }
+    }
+}
+
+impl LimiterState {
+    /// Make room for new clients: drop idle buckets, then the least
+    /// recently updated ones down to 90% of `max_clients`, so that evicting
+    /// does not happen on every new client of a flood
+    fn evict(&mut self, now: Instant, period: Duration, max_clients: usize) {
+        self.buckets
+            .retain(|_, bucket| now.saturating_duration_since(bucket.updated) < period);
+        self.last_pruned = now;
+
+        let keep = max_clients - (max_clients / 10).max(1);
+        if self.buckets.len() <= keep {
+            return;
+        }
+        let mut updated: Vec<Instant> = self.buckets.values().map(|b| b.updated).collect();
+        let evicted = updated.len() - keep;
+        let (_, &mut cutoff, _) = updated.select_nth_unstable(evicted - 1);
+        self.buckets.retain(|_, bucket| bucket.updated > cutoff);
+    }
+}
+
+/// Layer applying [`RateLimiter`] to every request
+#[derive(Debug, Clone)]
+pub struct RateLimitLayer {
+    limiter: Arc<RateLimiter>,
+}
+
+impl<S> Layer<S> for RateLimitLayer {
+    type Service = RateLimit<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RateLimit {
+            inner,
+            limiter: self.limiter.clone(),
+        }
+    }
+}
+
+/// Middleware service produced by [`RateLimitLayer`]
+#[derive(Debug, Clone)]
+pub struct RateLimit<S> {
+    inner: S,
+    limiter: Arc<RateLimiter>,
+}
+
+impl<S, B> Service<Request<B>> for RateLimit<S>
+where
+    S: Service<Request<B>, Response = Response>,
+{
+    type Response = Response;
+    type Error = S::Error;
+    type Future = Either<S::Future, Ready<Result<Response, S::Error>>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<B>) -> Self::Future {
+        let ip = request
+            .extensions()
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip())
+            .unwrap_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED));
+
+        match self.limiter.check(ip) {
+            Ok(()) => Either::Left(self.inner.call(request)),
+            Err(retry_after) => {
+                tracing::debug!("Rate limit exceeded for {}", ip);
+                Either::Right(future::ready(Ok(
+                    RateLimitError { retry_after }.into_response()
+                )))
+            }
+        }
+    }
 }
 
 /// Build security headers middleware layers from configuration
@@ -112,12 +286,17 @@ pub fn build_security_headers_layers(
 }
 
 /// Custom rate limit error response
-pub struct RateLimitError;
+pub struct RateLimitError {
+    /// Time until the client may retry (sent as `Retry-After`, rounded up)
+    pub retry_after: Duration,
+}
 
 impl IntoResponse for RateLimitError {
     fn into_response(self) -> Response {
+        let retry_after = self.retry_after.as_secs_f64().ceil().max(1.0) as u64;
         (
             StatusCode::TOO_MANY_REQUESTS,
+            [(header::RETRY_AFTER, retry_after.to_string())],
             "Too many requests. Please try again later.",
         )
             .into_response()
@@ -149,17 +328,106 @@ mod tests {
             enabled: false,
             ..Default::default()
         };
-        // Rate limiting is temporarily disabled, so this always returns None
-        let result: Option<()> = build_rate_limit_layer(&config);
-        assert!(result.is_none());
+        assert!(build_rate_limit_layer(&config).is_none());
     }
 
     #[test]
     fn test_rate_limit_layer_enabled() {
         let config = RateLimitConfig::default();
-        // Rate limiting is temporarily disabled, so this always returns None
-        let result: Option<()> = build_rate_limit_layer(&config);
-        assert!(result.is_none());
+        assert!(build_rate_limit_layer(&config).is_some());
+    }
+
+    #[test]
+    fn test_token_bucket() {
+        let limiter = RateLimiter::new(2, Duration::from_secs(10));
+        let ip: IpAddr = "10.0.0.1".parse().unwrap();
+        let other: IpAddr = "10.0.0.2".parse().unwrap();
+        let start = Instant::now();
+
+        assert!(limiter.check_at(ip, start).is_ok());
+        assert!(limiter.check_at(ip, start).is_ok());
+        let retry_after = limiter.check_at(ip, start).unwrap_err();
+        assert!(retry_after <= Duration::from_secs(5));
+
+        // Buckets are per IP
+        assert!(limiter.check_at(other, start).is_ok());
+
+        // One token refills every 5 seconds
+        assert!(limiter.check_at(ip, start + Duration::from_secs(5)).is_ok());
+        assert!(limiter
+            .check_at(ip, start + Duration::from_secs(5))
+            .is_err());
+    }
+
+    #[test]
+    fn test_idle_buckets_pruned() {
+        let limiter = RateLimiter::new(1, Duration::from_secs(1));
+        let start = Instant::now();
+        for i in 0..10u8 {
+            let _ = limiter.check_at(IpAddr::V4(Ipv4Addr::new(10, 0, 0, i)), start);
+        }
+        assert_eq!(limiter.state.lock().buckets.len(), 10);
+
+        let later = start + Duration::from_secs(2);
+        let _ = limiter.check_at(IpAddr::V4(Ipv4Addr::LOCALHOST), later);
+        assert_eq!(limiter.state.lock().buckets.len(), 1);
+    }
+
+    #[test]
+    fn test_tracked_clients_capped() {
+        let mut limiter = RateLimiter::new(1, Duration::from_secs(60));
+        limiter.max_clients = 10;
+        let start = Instant::now();
+        let client = |i: u8| IpAddr::V4(Ipv4Addr::new(10, 0, 0, i));
+        for i in 0..10u8 {
+            let at = start + Duration::from_millis(u64::from(i));
+            assert!(limiter.check_at(client(i), at).is_ok());
+        }
+        assert_eq!(limiter.state.lock().buckets.len(), 10);
+
+        // A new client evicts the least recently seen ones
+        let later = start + Duration::from_secs(1);
+        assert!(limiter.check_at(client(10), later).is_ok());
+        let state = limiter.state.lock();
+        assert_eq!(state.buckets.len(), 10);
+        assert!(!state.buckets.contains_key(&client(0)));
+        assert!(state.buckets.contains_key(&client(9)));
+        drop(state);
+
+        // Clients still tracked keep their empty bucket
+        assert!(limiter.check_at(client(9), later).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_middleware() {
+        use axum::body::Body;
+        use axum::routing::get;
+        use axum::Router;
+        use tower::ServiceExt;
+
+        let config = RateLimitConfig {
+            enabled: true,
+            requests_per_period: 1,
+            period_seconds: 60,
+        };
+        let app = Router::new()
+            .route("/", get(|| async { "ok" }))
+            .layer(build_rate_limit_layer(&config).unwrap());
+
+        let request = || {
+            let mut request = Request::get("/").body(Body::empty()).unwrap();
+            request
+                .extensions_mut()
+                .insert(ConnectInfo(SocketAddr::from(([192, 0, 2, 1], 4000))));
+            request
+        };
+
+        let response = app.clone().oneshot(request()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = app.oneshot(request()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert!(response.headers().contains_key(header::RETRY_AFTER));
     }
 
     #[test]
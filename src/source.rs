@@ -0,0 +1,231 @@
+//! Where providers come from
+//!
+//! [`ProviderSource`] is the query surface shared by [`CrabraceClient`]
+//! (a server), [`LocalCatalog`](crate::LocalCatalog) (the embedded catalog)
+//! and [`MockSource`] (a fixed list, for tests). Code taking any source
+//! instead of the concrete client can be tested without a server.
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+
+use crate::{CrabraceClient, Model, Provider};
+
+/// A catalog of providers that can be queried asynchronously
+///
+/// Futures are `Send`, except in browsers (`wasm32`) where nothing is.
+///
+/// # Example
+///
+/// ```
+/// use crabrace::{MockSource, Provider, ProviderSource};
+///
+/// async fn model_count(source: &impl ProviderSource) -> anyhow::Result<usize> {
+///     let providers = source.get_providers().await?;
+///     Ok(providers.iter().map(|provider| provider.models.len()).sum())
+/// }
+///
+/// # #[tokio::main]
+/// # async fn main() -> anyhow::Result<()> {
+/// let provider = Provider::new("Test".to_string(), "test".to_string(), "openai".to_string());
+/// assert_eq!(model_count(&MockSource::new(vec![provider])).await?, 0);
+/// # Ok(())
+/// # }
+/// ```
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+pub trait ProviderSource {
+    /// Get all providers and their models
+    async fn get_providers(&self) -> Result<Vec<Provider>>;
+
+    /// Get a single provider by ID (`None` if unknown)
+    async fn get_provider(&self, id: &str) -> Result<Option<Provider>>;
+
+    /// Get a single model of a provider (`None` if either is unknown)
+    async fn get_model(&self, provider_id: &str, model_id: &str) -> Result<Option<Model>>;
+
+    /// Whether the source can currently answer queries
+    async fn health(&self) -> Result<bool>;
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl ProviderSource for CrabraceClient {
+    async fn get_providers(&self) -> Result<Vec<Provider>> {
+        CrabraceClient::get_providers(self).await
+    }
+
+    async fn get_provider(&self, id: &str) -> Result<Option<Provider>> {
+        CrabraceClient::get_provider(self, id).await
+    }
+
+    async fn get_model(&self, provider_id: &str, model_id: &str) -> Result<Option<Model>> {
+        CrabraceClient::get_model(self, provider_id, model_id).await
+    }
+
+    async fn health(&self) -> Result<bool> {
+        self.health_check().await
+    }
+}
+
+/// The embedded catalog is always available
+#[cfg(feature = "embedded-data")]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl ProviderSource for crate::LocalCatalog {
+    async fn get_providers(&self) -> Result<Vec<Provider>> {
+        crate::LocalCatalog::get_providers(self)
+    }
+
+    async fn get_provider(&self, id: &str) -> Result<Option<Provider>> {
+        crate::LocalCatalog::get_provider(self, id)
+    }
+
+    async fn get_model(&self, provider_id: &str, model_id: &str) -> Result<Option<Model>> {
+        crate::LocalCatalog::get_model(self, provider_id, model_id)
+    }
+
+    async fn health(&self) -> Result<bool> {
+        Ok(true)
+    }
+}
+
+/// A fixed list of providers, for testing code that takes a
+/// [`ProviderSource`]
+///
+/// Providers and models are looked up by exact ID. A source built
+/// [`with_error`](Self::with_error) fails every query, to exercise error
+/// handling.
+#[derive(Debug, Clone)]
+pub struct MockSource {
+    providers: Vec<Provider>,
+    healthy: bool,
+    error: Option<String>,
+}
+
+impl MockSource {
+    /// A healthy source answering with `providers`
+    pub fn new(providers: Vec<Provider>) -> Self {
+        Self {
+            providers,
+            healthy: true,
+            error: None,
+        }
+    }
+
+    /// Report the given health
+    pub fn with_health(mut self, healthy: bool) -> Self {
+        self.healthy = healthy;
+        self
+    }
+
+    /// Fail every query with `message`
+    pub fn with_error(mut self, message: impl Into<String>) -> Self {
+        self.error = Some(message.into());
+        self
+    }
+
+    /// The configured providers, or the configured error
+    fn providers(&self) -> Result<&[Provider]> {
+        match &self.error {
+            Some(message) => Err(anyhow!("{}", message)),
+            None => Ok(&self.providers),
+        }
+    }
+}
+
+impl Default for MockSource {
+    fn default() -> Self {
+        Self::new(Vec::new())
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl ProviderSource for MockSource {
+    async fn get_providers(&self) -> Result<Vec<Provider>> {
+        Ok(self.providers()?.to_vec())
+    }
+
+    async fn get_provider(&self, id: &str) -> Result<Option<Provider>> {
+        Ok(self
+            .providers()?
+            .iter()
+            .find(|provider| provider.id == id)
+            .cloned())
+    }
+
+    async fn get_model(&self, provider_id: &str, model_id: &str) -> Result<Option<Model>> {
+        let provider = self.get_provider(provider_id).await?;
+        Ok(provider.and_then(|provider| provider.get_model(model_id).cloned()))
+    }
+
+    async fn health(&self) -> Result<bool> {
+        self.providers()?;
+        Ok(self.healthy)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Downstream-style code, generic over the source
+    async fn reasoning_models(source: &dyn ProviderSource) -> Result<Vec<String>> {
+        let providers = source.get_providers().await?;
+        Ok(providers
+            .iter()
+            .flat_map(|provider| &provider.models)
+            .filter(|model| model.can_reason)
+            .map(|model| model.id.clone())
+            .collect())
+    }
+
+    #[tokio::test]
+    async fn test_mock_source() {
+        let mut provider =
+            Provider::new("Test".to_string(), "test".to_string(), "openai".to_string());
+        let mut model = Model::new("m".to_string(), "M".to_string(), 1.0, 2.0, 8192, 1024);
+        model.can_reason = true;
+        provider.models.push(model);
+
+        let source = MockSource::new(vec![provider]);
+        assert_eq!(reasoning_models(&source).await.unwrap(), vec!["m"]);
+        assert!(source.get_model("test", "m").await.unwrap().is_some());
+        assert!(source.get_provider("missing").await.unwrap().is_none());
+        assert!(source.health().await.unwrap());
+        assert!(!source.clone().with_health(false).health().await.unwrap());
+
+        let failing = source.with_error("offline");
+        let error = failing.get_providers().await.unwrap_err();
+        assert_eq!(error.to_string(), "offline");
+        assert!(failing.health().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_client_source() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/v1/providers")
+            .with_body(r#"[{"name":"Test","id":"test","type":"test","models":[]}]"#)
+            .create_async()
+            .await;
+        server.mock("GET", "/health").create_async().await;
+
+        let source: Box<dyn ProviderSource> = Box::new(CrabraceClient::new(server.url()));
+        assert!(reasoning_models(source.as_ref()).await.unwrap().is_empty());
+        assert!(source.health().await.unwrap());
+    }
+
+    #[cfg(feature = "embedded-data")]
+    #[tokio::test]
+    async fn test_local_catalog_source() {
+        let catalog = crate::LocalCatalog::new().unwrap();
+        let sources: [&dyn ProviderSource; 2] = [&catalog, &MockSource::default()];
+        assert!(!reasoning_models(sources[0]).await.unwrap().is_empty());
+        assert!(reasoning_models(sources[1]).await.unwrap().is_empty());
+        assert!(ProviderSource::get_provider(&catalog, "google")
+            .await
+            .unwrap()
+            .is_some());
+    }
+}
@@ -0,0 +1,235 @@
+//! NDJSON response bodies, parsed as they arrive
+//!
+//! The server streams listings as one JSON document per line when asked for
+//! `application/x-ndjson`. [`ndjson`] turns the chunks of such a body into
+//! a stream of items, so consumers can handle the first ones (or stop)
+//! before the rest has been downloaded.
+//!
+//! The change feed is a `text/event-stream` body instead: [`SseParser`]
+//! reads its events line by line, and [`sse`] turns a body into a stream
+//! of them.
+
+use anyhow::Result;
+use bytes::Bytes;
+use futures_util::stream::{self, Stream, StreamExt};
+use reqwest::header::{HeaderMap, CONTENT_TYPE};
+use serde::de::DeserializeOwned;
+
+/// Media type of NDJSON bodies
+pub(crate) const NDJSON: &str = "application/x-ndjson";
+
+/// Media type of server-sent event streams
+pub(crate) const EVENT_STREAM: &str = "text/event-stream";
+
+/// Whether a response body is NDJSON
+pub(crate) fn is_ndjson(headers: &HeaderMap) -> bool {
+    has_type(headers, NDJSON)
+}
+
+/// Whether a response body is a server-sent event stream
+pub(crate) fn is_event_stream(headers: &HeaderMap) -> bool {
+    has_type(headers, EVENT_STREAM)
+}
+
+fn has_type(headers: &HeaderMap, media_type: &str) -> bool {
+    headers
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with(media_type))
+}
+
+/// Items of an NDJSON body, one per non-blank line
+///
+/// The stream ends after the first error, whether reading the body or
+/// parsing a line failed.
+pub(crate) fn ndjson<T, S>(chunks: S) -> impl Stream<Item = Result<T>>
+where
+    T: DeserializeOwned,
+    S: Stream<Item = reqwest::Result<Bytes>>,
+{
+    let state = (Box::pin(chunks), Vec::new(), false);
+    stream::unfold(state, |(mut chunks, mut buffer, mut done)| async move {
+        loop {
+            if let Some(end) = buffer.iter().position(|&byte| byte == b'\n') {
+                let line: Vec<u8> = buffer.drain(..=end).collect();
+                if is_blank(&line) {
+                    continue;
+                }
+                let item = parse(&line);
+                if item.is_err() {
+                    buffer.clear();
+                    done = true;
+                }
+                return Some((item, (chunks, buffer, done)));
+            }
+            if done {
+                // The last line need not end with a newline
+                if is_blank(&buffer) {
+                    return None;
+                }
+                let item = parse(&std::mem::take(&mut buffer));
+                return Some((item, (chunks, buffer, done)));
+            }
+
+            match chunks.next().await {
+                Some(Ok(chunk)) => buffer.extend_from_slice(&chunk),
+                Some(Err(e)) => {
+                    buffer.clear();
+                    return Some((Err(e.into()), (chunks, buffer, true)));
+                }
+                None => done = true,
+            }
+        }
+    })
+}
+
+/// One server-sent event
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct SseEvent {
+    /// Event type (`message` when unnamed)
+    pub(crate) event: String,
+
+    /// Data lines, joined with newlines
+    pub(crate) data: String,
+}
+
+/// Reads server-sent events from the lines of an event stream
+///
+/// Only `event` and `data` fields are kept; comments (keep-alives) and
+/// other fields are skipped.
+#[derive(Debug, Default)]
+pub(crate) struct SseParser {
+    event: String,
+    data: Vec<String>,
+}
+
+impl SseParser {
+    /// Feed one line, with or without its line ending, and get the event it
+    /// completes, if any
+    pub(crate) fn line(&mut self, line: &str) -> Option<SseEvent> {
+        let line = line.trim_end_matches(['\r', '\n']);
+        if line.is_empty() {
+            let event = std::mem::take(&mut self.event);
+            let data = std::mem::take(&mut self.data);
+            // Events without data are not dispatched
+            if data.is_empty() {
+                return None;
+            }
+            return Some(SseEvent {
+                event: if event.is_empty() {
+                    "message".to_string()
+                } else {
+                    event
+                },
+                data: data.join("\n"),
+            });
+        }
+
+        let (field, value) = line.split_once(':').unwrap_or((line, ""));
+        let value = value.strip_prefix(' ').unwrap_or(value);
+        match field {
+            "event" => self.event = value.to_string(),
+            "data" => self.data.push(value.to_string()),
+            _ => {}
+        }
+        None
+    }
+}
+
+/// Events of a `text/event-stream` body
+///
+/// The stream ends with the body, or after an error reading it. An event
+/// left incomplete at the end of the body is dropped.
+pub(crate) fn sse<S>(chunks: S) -> impl Stream<Item = Result<SseEvent>>
+where
+    S: Stream<Item = reqwest::Result<Bytes>>,
+{
+    let state = (Box::pin(chunks), Vec::new(), SseParser::default(), false);
+    stream::unfold(
+        state,
+        |(mut chunks, mut buffer, mut parser, done)| async move {
+            if done {
+                return None;
+            }
+            loop {
+                if let Some(end) = buffer.iter().position(|&byte| byte == b'\n') {
+                    let line: Vec<u8> = buffer.drain(..=end).collect();
+                    if let Some(event) = parser.line(&String::from_utf8_lossy(&line)) {
+                        return Some((Ok(event), (chunks, buffer, parser, false)));
+                    }
+                    continue;
+                }
+
+                match chunks.next().await? {
+                    Ok(chunk) => buffer.extend_from_slice(&chunk),
+                    Err(e) => return Some((Err(e.into()), (chunks, buffer, parser, true))),
+                }
+            }
+        },
+    )
+}
+
+/// Deserialize one line
+fn parse<T: DeserializeOwned>(line: &[u8]) -> Result<T> {
+    Ok(serde_json::from_slice(line)?)
+}
+
+fn is_blank(line: &[u8]) -> bool {
+    line.iter().all(u8::is_ascii_whitespace)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn parse_chunks(chunks: &[&'static str]) -> Vec<Result<u64>> {
+        let chunks = chunks
+            .iter()
+            .map(|chunk| Ok(Bytes::from_static(chunk.as_bytes())));
+        ndjson(stream::iter(chunks.collect::<Vec<_>>()))
+            .collect()
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_ndjson() {
+        // Lines split across chunks, blank lines, no final newline
+        let items = parse_chunks(&["1\n2", "2\n\n", "3"]).await;
+        let items: Vec<u64> = items.into_iter().map(Result::unwrap).collect();
+        assert_eq!(items, vec![1, 22, 3]);
+
+        // Nothing is read past an invalid line
+        let items = parse_chunks(&["1\nx\n3\n"]).await;
+        assert_eq!(items.len(), 2);
+        assert!(items[1].is_err());
+
+        assert!(parse_chunks(&[]).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_sse() {
+        let chunks = [
+            ": keep-alive\n\nevent: catalog\r\nid: 2\r\ndata: {\"version\"",
+            ":2}\r\n\r\ndata: a\ndata:b\n\nevent: empty\n\n",
+            "data: incomplete\n",
+        ];
+        let chunks = chunks.map(|chunk| Ok(Bytes::from_static(chunk.as_bytes())));
+        let events: Vec<SseEvent> = sse(stream::iter(chunks))
+            .map(Result::unwrap)
+            .collect()
+            .await;
+        assert_eq!(
+            events,
+            vec![
+                SseEvent {
+                    event: "catalog".to_string(),
+                    data: r#"{"version":2}"#.to_string(),
+                },
+                SseEvent {
+                    event: "message".to_string(),
+                    data: "a\nb".to_string(),
+                },
+            ]
+        );
+    }
+}
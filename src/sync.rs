@@ -0,0 +1,668 @@
+//! Periodic catalog sync from an upstream source
+//!
+//! A background task periodically fetches the base catalog, either the
+//! Catwalk definition of every embedded provider or a whole catalog from a
+//! remote URL (e.g. another crabrace instance) or object storage (see
+//! [`storage`](crate::storage)), already in crabrace's schema. Catwalk
+//! definitions are converted to crabrace's schema. The local sources
+//! (providers directory, admin overlay, provider filter) are then
+//! re-applied on top. The registry is only swapped when the
+//! resulting catalog hash differs, so unchanged upstream data costs nothing
+//! downstream.
+//!
+//! Until the first successful sync the embedded catalog is served, and a
+//! failed sync keeps the last good catalog.
+
+use anyhow::{Context, Result};
+use parking_lot::Mutex;
+use reqwest::header::{ETAG, IF_NONE_MATCH};
+use reqwest::StatusCode;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use crate::providers::registry::ProviderRegistry;
+use crate::providers::sources;
+use crate::storage::CatalogLocation;
+use crate::{Config, Lifecycle, Modality, Model, ModelKind, Provider};
+
+/// Provider definition as published by Catwalk
+///
+/// Catwalk leaves unknown values empty (`""`) rather than absent and has
+/// fields crabrace does not serve, so every field is optional here.
+#[derive(Debug, Deserialize)]
+struct CatwalkProvider {
+    #[serde(default)]
+    name: String,
+    id: String,
+    #[serde(default, rename = "type")]
+    provider_type: String,
+    #[serde(default)]
+    api_key: Option<String>,
+    #[serde(default)]
+    api_endpoint: Option<String>,
+    #[serde(default)]
+    default_large_model_id: Option<String>,
+    #[serde(default)]
+    default_small_model_id: Option<String>,
+    #[serde(default)]
+    default_headers: Option<HashMap<String, String>>,
+    #[serde(default)]
+    models: Vec<CatwalkModel>,
+}
+
+/// Model definition as published by Catwalk
+#[derive(Debug, Deserialize)]
+struct CatwalkModel {
+    id: String,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    cost_per_1m_in: f64,
+    #[serde(default)]
+    cost_per_1m_out: f64,
+    #[serde(default)]
+    cost_per_1m_in_cached: Option<f64>,
+    #[serde(default)]
+    cost_per_1m_out_cached: Option<f64>,
+    #[serde(default)]
+    context_window: u64,
+    #[serde(default)]
+    default_max_tokens: u64,
+    #[serde(default)]
+    can_reason: bool,
+    #[serde(default)]
+    has_reasoning_efforts: bool,
+    #[serde(default)]
+    reasoning_levels: Vec<String>,
+    #[serde(default)]
+    default_reasoning_effort: Option<String>,
+    #[serde(default)]
+    supports_attachments: bool,
+}
+
+/// Treat Catwalk's empty strings as missing values
+fn non_empty(value: Option<String>) -> Option<String> {
+    value.filter(|v| !v.is_empty())
+}
+
+impl From<CatwalkModel> for Model {
+    fn from(model: CatwalkModel) -> Self {
+        Self {
+            name: if model.name.is_empty() {
+                model.id.clone()
+            } else {
+                model.name
+            },
+            id: model.id,
+            model_kind: ModelKind::Chat,
+            family: None,
+            previous_model_id: None,
+            base_model_id: None,
+            variant: None,
+            quantization: None,
+            open_weights: false,
+            license: None,
+            tags: Vec::new(),
+            benchmarks: HashMap::new(),
+            cost_per_1m_in: model.cost_per_1m_in,
+            cost_per_1m_out: model.cost_per_1m_out,
+            cost_per_1m_in_cached: model.cost_per_1m_in_cached,
+            cost_per_1m_out_cached: model.cost_per_1m_out_cached,
+            cost_per_1m_in_batch: None,
+            cost_per_1m_out_batch: None,
+            cost_per_1m_reasoning: None,
+            cost_per_image: Vec::new(),
+            cost_per_audio_minute: None,
+            cost_per_1m_characters: None,
+            cost_per_request: None,
+            cost_per_tool_call: None,
+            context_window: model.context_window,
+            default_max_tokens: model.default_max_tokens,
+            max_output_tokens: None,
+            dimensions: None,
+            max_input_tokens: None,
+            tokenizer: None,
+            voices: Vec::new(),
+            languages: Vec::new(),
+            limits: Vec::new(),
+            regions: Vec::new(),
+            median_ttft_ms: None,
+            median_tokens_per_second: None,
+            can_reason: model.can_reason,
+            has_reasoning_efforts: model.has_reasoning_efforts
+                || !model.reasoning_levels.is_empty(),
+            default_reasoning_effort: non_empty(model.default_reasoning_effort),
+            supports_attachments: model.supports_attachments,
+            // Catwalk only lists models Crush can drive, which requires tool calling
+            // and streaming
+            supports_tools: true,
+            supports_parallel_tool_calls: false,
+            supports_structured_output: false,
+            supports_streaming: true,
+            input_modalities: Modality::legacy_inputs(model.supports_attachments),
+            output_modalities: vec![Modality::Text],
+            knowledge_cutoff: None,
+            released_at: None,
+            deprecated: false,
+            deprecated_at: None,
+            retires_at: None,
+            deprecation_date: None,
+            lifecycle: Lifecycle::Active,
+            replacement_model_id: None,
+        }
+    }
+}
+
+impl From<CatwalkProvider> for Provider {
+    fn from(provider: CatwalkProvider) -> Self {
+        Self {
+            name: provider.name,
+            id: provider.id,
+            provider_type: provider.provider_type,
+            api_key: non_empty(provider.api_key),
+            api_endpoint: non_empty(provider.api_endpoint),
+            default_large_model_id: non_empty(provider.default_large_model_id),
+            default_small_model_id: non_empty(provider.default_small_model_id),
+            default_headers: provider.default_headers.filter(|h| !h.is_empty()),
+            limits: Vec::new(),
+            regions: Vec::new(),
+            tags: Vec::new(),
+            models: provider.models.into_iter().map(Model::from).collect(),
+        }
+    }
+}
+
+/// Response header carrying the hex-encoded SHA-256 of a remote catalog body
+pub const X_CHECKSUM_SHA256: &str = "x-checksum-sha256";
+
+/// Where the base catalog comes from
+enum Source {
+    /// Catwalk provider configs, fetched as `<url>/<id>.json` per embedded provider
+    Catwalk { url: String },
+
+    /// A whole catalog (JSON array of providers), fetched conditionally on its ETag
+    Remote {
+        url: String,
+        location: CatalogLocation,
+        etag: Mutex<Option<String>>,
+    },
+}
+
+/// A freshly fetched base catalog
+struct Fetched {
+    providers: Vec<Provider>,
+    etag: Option<String>,
+}
+
+/// Periodic sync of the catalog from an upstream source
+pub struct UpstreamSync {
+    http: reqwest::Client,
+    source: Source,
+    interval: Duration,
+    config: Config,
+}
+
+impl UpstreamSync {
+    /// Build the sync task from configuration (`None` when disabled)
+    ///
+    /// A remote registry catalog takes the place of the Catwalk sync.
+    pub fn from_config(config: &Config) -> Result<Option<Self>> {
+        let upstream = &config.providers.upstream;
+        let (source, interval) = match config.registry.remote_source() {
+            Some(url) => (
+                Source::Remote {
+                    url: url.to_string(),
+                    location: CatalogLocation::parse(url)?,
+                    etag: Mutex::new(None),
+                },
+                config.registry.refresh_interval_seconds,
+            ),
+            None if upstream.enabled => (
+                Source::Catwalk {
+                    url: upstream.url.trim_end_matches('/').to_string(),
+                },
+                upstream.interval_seconds,
+            ),
+            None => return Ok(None),
+        };
+
+        Ok(Some(Self {
+            http: reqwest::Client::builder()
+                .timeout(Duration::from_secs(30))
+                .build()?,
+            source,
+            interval: Duration::from_secs(interval),
+            config: config.clone(),
+        }))
+    }
+
+    /// URL of the upstream source
+    pub fn url(&self) -> &str {
+        match &self.source {
+            Source::Catwalk { url } | Source::Remote { url, .. } => url,
+        }
+    }
+
+    /// Time between syncs
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Fetch upstream once and swap the registry's catalog if it changed
+    ///
+    /// Providers Catwalk does not publish (404) keep their embedded
+    /// definition; any other failure aborts the sync and leaves the catalog
+    /// untouched. Returns whether the catalog changed.
+    pub async fn sync_once(&self, registry: &ProviderRegistry) -> Result<bool> {
+        let fetched = match &self.source {
+            Source::Catwalk { url } => Some(self.fetch_catwalk(url).await?),
+            Source::Remote {
+                url,
+                location,
+                etag,
+            } => {
+                let current = etag.lock().clone();
+                self.fetch_remote(url, location, current).await?
+            }
+        };
+        let Some(fetched) = fetched else {
+            return Ok(false);
+        };
+
+        let base = ProviderRegistry::new()?;
+        base.replace_all(fetched.providers)?;
+        sources::apply_local(&base, &self.config)?;
+        let changed = registry.replace_all(base.get_all()?)?;
+
+        // Only remember the ETag once the catalog has been applied
+        if let Source::Remote { etag, .. } = &self.source {
+            *etag.lock() = fetched.etag;
+        }
+
+        if changed {
+            info!(
+                "Upstream sync updated the catalog from {} (version {})",
+                self.url(),
+                registry.version()
+            );
+        }
+        Ok(changed)
+    }
+
+    /// Embedded providers, each replaced by its Catwalk definition when published
+    async fn fetch_catwalk(&self, url: &str) -> Result<Fetched> {
+        let mut providers = ProviderRegistry::new()?.get_all()?;
+        for provider in &mut providers {
+            if let Some(upstream) = self.fetch_catwalk_provider(url, &provider.id).await? {
+                *provider = upstream;
+            }
+        }
+        Ok(Fetched {
+            providers,
+            etag: None,
+        })
+    }
+
+    /// Fetch a remote catalog (`None` when unchanged since `etag`)
+    ///
+    /// When the response carries an `X-Checksum-Sha256` header, the body
+    /// must hash to it.
+    async fn fetch_remote(
+        &self,
+        url: &str,
+        location: &CatalogLocation,
+        etag: Option<String>,
+    ) -> Result<Option<Fetched>> {
+        let mut request = location.request(&self.http).await?;
+        if let Some(etag) = &etag {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+
+        let response = request
+            .send()
+            .await
+            .with_context(|| format!("Failed to fetch {}", url))?;
+        if response.status() == StatusCode::NOT_MODIFIED {
+            return Ok(None);
+        }
+
+        let response = response
+            .error_for_status()
+            .with_context(|| format!("Failed to fetch {}", url))?;
+        let etag = response
+            .headers()
+            .get(ETAG)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        let checksum = response
+            .headers()
+            .get(X_CHECKSUM_SHA256)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_lowercase);
+        let body = response
+            .bytes()
+            .await
+            .with_context(|| format!("Failed to fetch {}", url))?;
+
+        if let Some(expected) = checksum {
+            let actual: String = Sha256::digest(&body)
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect();
+            if actual != expected {
+                anyhow::bail!(
+                    "Checksum mismatch for {}: expected {}, got {}",
+                    url,
+                    expected,
+                    actual
+                );
+            }
+        }
+
+        let providers: Vec<Provider> =
+            serde_json::from_slice(&body).with_context(|| format!("Invalid catalog at {}", url))?;
+        Ok(Some(Fetched { providers, etag }))
+    }
+
+    /// Fetch one provider's Catwalk definition (`None` if not published)
+    async fn fetch_catwalk_provider(&self, base_url: &str, id: &str) -> Result<Option<Provider>> {
+        let url = format!("{}/{}.json", base_url, id);
+        let response = self
+            .http
+            .get(&url)
+            .send()
+            .await
+            .with_context(|| format!("Failed to fetch {}", url))?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        let provider: CatwalkProvider = response
+            .error_for_status()
+            .with_context(|| format!("Failed to fetch {}", url))?
+            .json()
+            .await
+            .with_context(|| format!("Invalid provider definition at {}", url))?;
+        if provider.id != id {
+            anyhow::bail!(
+                "{} describes provider '{}', expected '{}'",
+                url,
+                provider.id,
+                id
+            );
+        }
+        Ok(Some(provider.into()))
+    }
+
+    /// Run the sync on its interval in the background, starting immediately
+    pub fn spawn(self, registry: Arc<ProviderRegistry>) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(self.interval);
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.sync_once(&registry).await {
+                    warn!("Upstream sync failed, keeping the current catalog: {:#}", e);
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn config_for(url: String) -> Config {
+        let mut config = Config::default();
+        config.providers.upstream.enabled = true;
+        config.providers.upstream.url = url;
+        config
+    }
+
+    #[test]
+    fn test_catwalk_conversion() {
+        let provider: CatwalkProvider = serde_json::from_value(json!({
+            "name": "Anthropic",
+            "id": "anthropic",
+            "type": "anthropic",
+            "api_key": "$ANTHROPIC_API_KEY",
+            "api_endpoint": "",
+            "default_headers": {},
+            "models": [{
+                "id": "claude-new",
+                "name": "Claude New",
+                "cost_per_1m_in": 3.0,
+                "cost_per_1m_out": 15.0,
+                "context_window": 200000,
+                "default_max_tokens": 64000,
+                "can_reason": true,
+                "reasoning_levels": ["low", "high"],
+                "default_reasoning_effort": "",
+                "options": {}
+            }]
+        }))
+        .unwrap();
+
+        let provider = Provider::from(provider);
+        assert_eq!(provider.api_key.as_deref(), Some("$ANTHROPIC_API_KEY"));
+        assert_eq!(provider.api_endpoint, None);
+        assert_eq!(provider.default_headers, None);
+
+        let model = &provider.models[0];
+        assert!(model.has_reasoning_efforts);
+        assert_eq!(model.default_reasoning_effort, None);
+        assert_eq!(model.cost_per_1m_in_cached, None);
+    }
+
+    #[tokio::test]
+    async fn test_sync_once() {
+        let mut server = mockito::Server::new_async().await;
+        let registry = ProviderRegistry::new().unwrap();
+
+        let mut anthropic = registry.get_by_id("anthropic").unwrap().unwrap();
+        anthropic.models[0].cost_per_1m_in = 1.0;
+        let mut mocks = vec![
+            server
+                .mock("GET", "/anthropic.json")
+                .with_body(serde_json::to_string(&anthropic).unwrap())
+                .create_async()
+                .await,
+        ];
+        for provider in registry.get_all().unwrap() {
+            if provider.id != "anthropic" {
+                let path = format!("/{}.json", provider.id);
+                mocks.push(
+                    server
+                        .mock("GET", path.as_str())
+                        .with_status(404)
+                        .create_async()
+                        .await,
+                );
+            }
+        }
+
+        let sync = UpstreamSync::from_config(&config_for(server.url()))
+            .unwrap()
+            .unwrap();
+        let version = registry.version();
+
+        assert!(sync.sync_once(&registry).await.unwrap());
+        assert_eq!(registry.version(), version + 1);
+        assert_eq!(registry.count(), 18);
+        let synced = registry.get_by_id("anthropic").unwrap().unwrap();
+        assert_eq!(synced.models[0].cost_per_1m_in, 1.0);
+
+        // Same upstream content: no swap
+        assert!(!sync.sync_once(&registry).await.unwrap());
+        assert_eq!(registry.version(), version + 1);
+        assert_eq!(mocks.len(), 18);
+    }
+
+    #[tokio::test]
+    async fn test_remote_catalog() {
+        let mut server = mockito::Server::new_async().await;
+        let registry = ProviderRegistry::new().unwrap();
+
+        let mut providers = registry.get_all().unwrap();
+        providers.truncate(3);
+        // Fields Catwalk does not have survive the sync
+        let model = &mut providers[0].models[0];
+        model.model_kind = ModelKind::Embedding;
+        model.supports_tools = false;
+        model.supports_streaming = false;
+        model.deprecated = true;
+        model.retires_at = "2030-01-31".parse().ok();
+        model.deprecation_date = model.retires_at;
+        model.lifecycle = Lifecycle::Deprecated;
+        model.replacement_model_id = Some("successor".to_string());
+        model.input_modalities = vec![Modality::Text, Modality::Audio];
+        model.cost_per_1m_reasoning = Some(42.0);
+        let body = serde_json::to_string(&providers).unwrap();
+        let checksum: String = Sha256::digest(body.as_bytes())
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+
+        let full = server
+            .mock("GET", "/v1/providers")
+            .match_header("if-none-match", mockito::Matcher::Missing)
+            .with_header("etag", "\"v1\"")
+            .with_header(X_CHECKSUM_SHA256, &checksum)
+            .with_body(&body)
+            .create_async()
+            .await;
+        let not_modified = server
+            .mock("GET", "/v1/providers")
+            .match_header("if-none-match", "\"v1\"")
+            .with_status(304)
+            .create_async()
+            .await;
+
+        let mut config = Config::default();
+        config.registry.remote_url = Some(format!("{}/v1/providers", server.url()));
+        let sync = UpstreamSync::from_config(&config).unwrap().unwrap();
+
+        assert!(sync.sync_once(&registry).await.unwrap());
+        assert_eq!(registry.count(), 3);
+        let synced = registry.get_by_id(&providers[0].id).unwrap().unwrap();
+        assert_eq!(synced.models[0], providers[0].models[0]);
+        let version = registry.version();
+
+        assert!(!sync.sync_once(&registry).await.unwrap());
+        assert_eq!(registry.version(), version);
+
+        full.assert_async().await;
+        not_modified.assert_async().await;
+    }
+
+    /// Environment variables set for the lifetime of the guard, restored
+    /// (or removed) when it is dropped, even if the test fails
+    struct EnvGuard(Vec<(String, Option<std::ffi::OsString>)>);
+
+    impl EnvGuard {
+        fn set(vars: &[(&str, &str)]) -> Self {
+            Self(
+                vars.iter()
+                    .map(|(name, value)| {
+                        let previous = std::env::var_os(name);
+                        std::env::set_var(name, value);
+                        (name.to_string(), previous)
+                    })
+                    .collect(),
+            )
+        }
+    }
+
+    impl Drop for EnvGuard {
+        fn drop(&mut self) {
+            for (name, previous) in &self.0 {
+                match previous {
+                    Some(value) => std::env::set_var(name, value),
+                    None => std::env::remove_var(name),
+                }
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_s3_source() {
+        let mut server = mockito::Server::new_async().await;
+        let registry = ProviderRegistry::new().unwrap();
+        let mut providers = registry.get_all().unwrap();
+        providers.truncate(2);
+        let mock = server
+            .mock("GET", "/pricing/daily/catalog.json")
+            .match_header(
+                "authorization",
+                mockito::Matcher::Regex(
+                    r"^AWS4-HMAC-SHA256 Credential=AKIDTEST/\d{8}/eu-west-1/s3/aws4_request, SignedHeaders=host;x-amz-content-sha256;x-amz-date, Signature=[0-9a-f]{64}$"
+                        .to_string(),
+                ),
+            )
+            .with_body(serde_json::to_string(&providers).unwrap())
+            .create_async()
+            .await;
+
+        let _env = EnvGuard::set(&[
+            ("AWS_ENDPOINT_URL", &server.url()),
+            ("AWS_REGION", "eu-west-1"),
+            ("AWS_ACCESS_KEY_ID", "AKIDTEST"),
+            ("AWS_SECRET_ACCESS_KEY", "secret"),
+            ("AWS_SESSION_TOKEN", ""),
+        ]);
+
+        let mut config = Config::default();
+        config.registry.source = Some("s3://pricing/daily/catalog.json".to_string());
+        config.validate().unwrap();
+        let sync = UpstreamSync::from_config(&config).unwrap().unwrap();
+        assert_eq!(sync.url(), "s3://pricing/daily/catalog.json");
+
+        assert!(sync.sync_once(&registry).await.unwrap());
+        assert_eq!(registry.count(), 2);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_remote_checksum_mismatch() {
+        let mut server = mockito::Server::new_async().await;
+        let _mock = server
+            .mock("GET", "/catalog.json")
+            .with_header(X_CHECKSUM_SHA256, "00")
+            .with_body("[]")
+            .create_async()
+            .await;
+
+        let mut config = Config::default();
+        config.registry.remote_url = Some(format!("{}/catalog.json", server.url()));
+        let sync = UpstreamSync::from_config(&config).unwrap().unwrap();
+
+        let registry = ProviderRegistry::new().unwrap();
+        assert!(sync.sync_once(&registry).await.is_err());
+        assert_eq!(registry.count(), 18);
+    }
+
+    #[tokio::test]
+    async fn test_sync_failure_keeps_catalog() {
+        let mut server = mockito::Server::new_async().await;
+        let _mock = server
+            .mock("GET", mockito::Matcher::Any)
+            .with_status(500)
+            .create_async()
+            .await;
+
+        let registry = ProviderRegistry::new().unwrap();
+        let hash = registry.dataset_hash();
+        let sync = UpstreamSync::from_config(&config_for(server.url()))
+            .unwrap()
+            .unwrap();
+
+        assert!(sync.sync_once(&registry).await.is_err());
+        assert_eq!(registry.dataset_hash(), hash);
+    }
+}
@@ -0,0 +1,149 @@
+//! Native TLS termination
+//!
+//! Loads a PEM certificate chain and private key with rustls so the server
+//! can run without a TLS-terminating reverse proxy. Certificates can be
+//! reloaded in place when the files change (e.g. after cert-manager renews
+//! them), without dropping existing connections.
+
+use anyhow::{Context, Result};
+use axum_server::tls_rustls::RustlsConfig;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tracing::{info, warn};
+
+use crate::config::TlsConfig;
+
+/// How often certificate files are checked for changes
+const RELOAD_POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Load the rustls configuration (`None` when TLS is disabled)
+///
+/// Starts a background reload task when `reload_on_change` is set.
+pub async fn load(config: &TlsConfig) -> Result<Option<RustlsConfig>> {
+    if !config.enabled {
+        return Ok(None);
+    }
+
+    let (cert, key) = paths(config)?;
+
+    // Only the ring provider is compiled in; ignore an already installed default
+    let _ = rustls::crypto::ring::default_provider().install_default();
+
+    let rustls_config = RustlsConfig::from_pem_file(&cert, &key)
+        .await
+        .with_context(|| {
+            format!(
+                "Failed to load TLS certificate {} and key {}",
+                cert.display(),
+                key.display()
+            )
+        })?;
+
+    if config.reload_on_change {
+        spawn_reload(rustls_config.clone(), cert, key);
+    }
+
+    Ok(Some(rustls_config))
+}
+
+/// Certificate and key paths from configuration
+fn paths(config: &TlsConfig) -> Result<(PathBuf, PathBuf)> {
+    let cert = config
+        .cert_path
+        .as_deref()
+        .context("TLS is enabled but no cert_path is configured")?;
+    let key = config
+        .key_path
+        .as_deref()
+        .context("TLS is enabled but no key_path is configured")?;
+    Ok((PathBuf::from(cert), PathBuf::from(key)))
+}
+
+/// Latest modification time of the certificate and key files
+fn modified(cert: &Path, key: &Path) -> Option<SystemTime> {
+    let cert = std::fs::metadata(cert).and_then(|m| m.modified()).ok()?;
+    let key = std::fs::metadata(key).and_then(|m| m.modified()).ok()?;
+    Some(cert.max(key))
+}
+
+/// Poll the certificate files and reload them when they change
+///
+/// A failed reload keeps serving the previous certificate.
+fn spawn_reload(rustls_config: RustlsConfig, cert: PathBuf, key: PathBuf) {
+    tokio::spawn(async move {
+        let mut last = modified(&cert, &key);
+        let mut interval = tokio::time::interval(RELOAD_POLL_INTERVAL);
+        interval.tick().await;
+
+        loop {
+            interval.tick().await;
+
+            let current = modified(&cert, &key);
+            if current.is_none() || current == last {
+                continue;
+            }
+
+            match rustls_config.reload_from_pem_file(&cert, &key).await {
+                Ok(()) => {
+                    info!("Reloaded TLS certificate from {}", cert.display());
+                    last = current;
+                }
+                Err(e) => warn!("Failed to reload TLS certificate: {}", e),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Write a self-signed certificate and key into a temporary directory
+    fn self_signed(name: &str) -> (PathBuf, PathBuf) {
+        let dir =
+            std::env::temp_dir().join(format!("crabrace-tls-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let certified = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+        let cert = dir.join("cert.pem");
+        let key = dir.join("key.pem");
+        std::fs::write(&cert, certified.cert.pem()).unwrap();
+        std::fs::write(&key, certified.key_pair.serialize_pem()).unwrap();
+        (cert, key)
+    }
+
+    fn config(cert: &Path, key: &Path) -> TlsConfig {
+        TlsConfig {
+            enabled: true,
+            cert_path: Some(cert.to_string_lossy().into_owned()),
+            key_path: Some(key.to_string_lossy().into_owned()),
+            reload_on_change: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_disabled() {
+        assert!(load(&TlsConfig::default()).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_load() {
+        let (cert, key) = self_signed("load");
+        assert!(load(&config(&cert, &key)).await.unwrap().is_some());
+        std::fs::remove_dir_all(cert.parent().unwrap()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_load_errors() {
+        let missing = TlsConfig {
+            enabled: true,
+            ..Default::default()
+        };
+        assert!(load(&missing).await.is_err());
+
+        let (cert, key) = self_signed("invalid");
+        std::fs::write(&key, "not a key").unwrap();
+        assert!(load(&config(&cert, &key)).await.is_err());
+        std::fs::remove_dir_all(cert.parent().unwrap()).unwrap();
+    }
+}
@@ -0,0 +1,137 @@
+//! Following catalog changes
+//!
+//! [`CrabraceClient::watch`](crate::CrabraceClient::watch) stays connected
+//! to the server's change feed (`GET /v1/providers/events`, a stream of
+//! server-sent events) and yields the whole catalog again every time it
+//! changes, so long-lived processes pick up new models and prices without
+//! a restart. Lost connections are re-established with the client's
+//! [retry](crate::RetryPolicy) backoff.
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::time::Duration;
+
+use crate::stream::SseEvent;
+use crate::{Provider, RetryPolicy};
+
+/// The catalog as of one registry version
+#[derive(Debug, Clone, PartialEq)]
+pub struct CatalogRevision {
+    /// Registry version, incremented by every change on the server
+    pub version: u64,
+
+    /// Hex-encoded SHA-256 of the catalog, as sent in `X-Catalog-Version`
+    pub hash: String,
+
+    pub providers: Vec<Provider>,
+}
+
+/// Data of a `catalog` event
+#[derive(Debug, Deserialize)]
+pub(crate) struct Announcement {
+    version: u64,
+    hash: String,
+}
+
+/// What a watch remembers across connections
+#[derive(Debug, Default)]
+pub(crate) struct Watcher {
+    /// Hash of the catalog last yielded
+    hash: Option<String>,
+
+    /// Connections lost or refused since the last `catalog` event
+    failures: u32,
+}
+
+impl Watcher {
+    /// The catalog announced by an event, unless it is the one last
+    /// yielded (e.g. after reconnecting) or the event is about something
+    /// else
+    pub(crate) fn announced(&mut self, event: &SseEvent) -> Result<Option<Announcement>> {
+        if event.event != "catalog" {
+            return Ok(None);
+        }
+        let announcement: Announcement =
+            serde_json::from_str(&event.data).context("Invalid catalog event")?;
+        self.failures = 0;
+        Ok((self.hash.as_ref() != Some(&announcement.hash)).then_some(announcement))
+    }
+
+    /// The revision to yield for an announcement, once its providers were
+    /// fetched
+    pub(crate) fn revision(
+        &mut self,
+        announcement: Announcement,
+        providers: Vec<Provider>,
+    ) -> CatalogRevision {
+        self.hash = Some(announcement.hash.clone());
+        CatalogRevision {
+            version: announcement.version,
+            hash: announcement.hash,
+            providers,
+        }
+    }
+
+    /// Record a lost or refused connection
+    pub(crate) fn disconnected(&mut self) {
+        self.failures = self.failures.saturating_add(1);
+    }
+
+    /// How long to wait before connecting (nothing for the first connection)
+    pub(crate) fn backoff(&self, retry: &RetryPolicy) -> Option<Duration> {
+        (self.failures > 0).then(|| retry.delay(self.failures, None))
+    }
+}
+
+/// Whether reading an event stream stopped on the client's request timeout,
+/// which only means the connection has to be re-established
+pub(crate) fn timed_out(error: &anyhow::Error) -> bool {
+    error
+        .downcast_ref::<reqwest::Error>()
+        .is_some_and(reqwest::Error::is_timeout)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn catalog_event(version: u64, hash: &str) -> SseEvent {
+        SseEvent {
+            event: "catalog".to_string(),
+            data: format!(r#"{{"version":{},"hash":"{}"}}"#, version, hash),
+        }
+    }
+
+    #[test]
+    fn test_watcher() {
+        let retry = RetryPolicy {
+            jitter: false,
+            ..RetryPolicy::default()
+        };
+        let mut watcher = Watcher::default();
+        assert_eq!(watcher.backoff(&retry), None);
+        watcher.disconnected();
+        watcher.disconnected();
+        assert_eq!(watcher.backoff(&retry), Some(retry.delay(2, None)));
+
+        let announcement = watcher.announced(&catalog_event(1, "a")).unwrap().unwrap();
+        assert_eq!(watcher.backoff(&retry), None);
+        let revision = watcher.revision(announcement, Vec::new());
+        assert_eq!((revision.version, revision.hash.as_str()), (1, "a"));
+
+        // The same catalog announced again, as on every reconnection
+        assert!(watcher.announced(&catalog_event(1, "a")).unwrap().is_none());
+        assert!(watcher.announced(&catalog_event(2, "b")).unwrap().is_some());
+
+        let other = SseEvent {
+            event: "message".to_string(),
+            data: "x".to_string(),
+        };
+        assert!(watcher.announced(&other).unwrap().is_none());
+        let invalid = SseEvent {
+            data: "x".to_string(),
+            ..catalog_event(3, "c")
+        };
+        assert!(watcher.announced(&invalid).is_err());
+    }
+}